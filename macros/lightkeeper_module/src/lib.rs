@@ -14,6 +14,7 @@ use syn;
 //     name="name",
 //     version="1.0",
 //     description="description",
+//     default_timeout=600,
 //     settings={
 //         parameter1_key => "parameter1_description",
 //         parameter2_key => "parameter2_description"
@@ -28,6 +29,7 @@ struct ModuleArgs {
     settings: HashMap<String, String>,
     secrets: HashMap<String, String>,
     uses_sudo: Option<bool>,
+    default_timeout: Option<u64>,
 }
 
 impl syn::parse::Parse for ModuleArgs {
@@ -40,6 +42,7 @@ impl syn::parse::Parse for ModuleArgs {
         let mut settings = HashMap::new();
         let mut secrets = HashMap::new();
         let mut uses_sudo = None;
+        let mut default_timeout = None;
 
         while !input.is_empty() {
             let key: syn::Ident = input.parse()?;
@@ -63,6 +66,9 @@ impl syn::parse::Parse for ModuleArgs {
                 "uses_sudo" => {
                     uses_sudo = Some(input.parse::<syn::LitBool>()?.value);
                 }
+                "default_timeout" => {
+                    default_timeout = Some(input.parse::<syn::LitInt>()?.base10_parse::<u64>()?);
+                }
                 "settings" => {
                     let content;
                     syn::braced!(content in input);
@@ -107,6 +113,7 @@ impl syn::parse::Parse for ModuleArgs {
             settings: settings,
             secrets: secrets,
             uses_sudo: uses_sudo,
+            default_timeout: default_timeout,
         })
     }
 }
@@ -119,6 +126,10 @@ pub fn monitoring_module(args: TokenStream, input: TokenStream) -> TokenStream {
     let module_version = args_parsed.version;
     let module_description = args_parsed.description;
     let uses_sudo = args_parsed.uses_sudo.unwrap_or(false);
+    let default_timeout = match args_parsed.default_timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
     let settings = args_parsed.settings.iter().map(|(key, value)| {
         quote! {
             (#key.to_string(), #value.to_string())
@@ -147,6 +158,7 @@ pub fn monitoring_module(args: TokenStream, input: TokenStream) -> TokenStream {
                         parent_module: None,
                         is_stateless: true,
                         uses_sudo: #uses_sudo,
+                        default_timeout: #default_timeout,
                     }
                 }
 
@@ -181,6 +193,10 @@ pub fn monitoring_extension_module(args: TokenStream, input: TokenStream) -> Tok
     let parent_module_name = args_parsed.parent_module_name;
     let parent_module_version = args_parsed.parent_module_version;
     let uses_sudo = args_parsed.uses_sudo.unwrap_or(false);
+    let default_timeout = match args_parsed.default_timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
     let settings = args_parsed.settings.iter().map(|(key, value)| {
         quote! {
             (#key.to_string(), #value.to_string())
@@ -209,6 +225,7 @@ pub fn monitoring_extension_module(args: TokenStream, input: TokenStream) -> Tok
                         parent_module: Some(ModuleSpecification::monitor(#parent_module_name, #parent_module_version)),
                         is_stateless: true,
                         uses_sudo: #uses_sudo,
+                        default_timeout: #default_timeout,
                     }
                 }
 
@@ -240,6 +257,10 @@ pub fn command_module(args: TokenStream, input: TokenStream) -> TokenStream {
     let module_version = args_parsed.version;
     let module_description = args_parsed.description;
     let uses_sudo = args_parsed.uses_sudo.unwrap_or(false);
+    let default_timeout = match args_parsed.default_timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
     let settings = args_parsed.settings.iter().map(|(key, value)| {
         quote! {
             (#key.to_string(), #value.to_string())
@@ -266,6 +287,7 @@ pub fn command_module(args: TokenStream, input: TokenStream) -> TokenStream {
                     parent_module: None,
                     is_stateless: true,
                     uses_sudo: #uses_sudo,
+                    default_timeout: #default_timeout,
                 }
             }
 
@@ -295,6 +317,10 @@ pub fn connection_module(args: TokenStream, input: TokenStream) -> TokenStream {
     let module_version = args_parsed.version;
     let module_description = args_parsed.description;
     let uses_sudo = args_parsed.uses_sudo.unwrap_or(false);
+    let default_timeout = match args_parsed.default_timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
     let settings = args_parsed.settings.iter().map(|(key, value)| {
         quote! {
             (#key.to_string(), #value.to_string())
@@ -328,6 +354,7 @@ pub fn connection_module(args: TokenStream, input: TokenStream) -> TokenStream {
                     parent_module: None,
                     is_stateless: false,
                     uses_sudo: #uses_sudo,
+                    default_timeout: #default_timeout,
                 }
             }
 
@@ -356,6 +383,10 @@ pub fn stateless_connection_module(args: TokenStream, input: TokenStream) -> Tok
     let module_version = args_parsed.version;
     let module_description = args_parsed.description;
     let uses_sudo = args_parsed.uses_sudo.unwrap_or(false);
+    let default_timeout = match args_parsed.default_timeout {
+        Some(timeout) => quote! { Some(#timeout) },
+        None => quote! { None },
+    };
     let settings = args_parsed.settings.iter().map(|(key, value)| {
         quote! {
             (#key.to_string(), #value.to_string())
@@ -391,6 +422,7 @@ pub fn stateless_connection_module(args: TokenStream, input: TokenStream) -> Tok
                         parent_module: None,
                         is_stateless: true,
                         uses_sudo: #uses_sudo,
+                        default_timeout: #default_timeout,
                     }
                 }
 
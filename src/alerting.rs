@@ -0,0 +1,18 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod alert;
+pub use alert::{Alert, AlertKind};
+
+pub mod alert_tracker;
+pub use alert_tracker::AlertTracker;
+
+pub mod alert_manager;
+pub use alert_manager::AlertManager;
+
+pub mod sink_worker;
+pub use sink_worker::SinkWorker;
+
+pub mod sinks;
@@ -0,0 +1,76 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::enums::Criticality;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AlertKind {
+    /// Criticality rose to alerting level or changed between alerting levels.
+    Firing,
+    /// Still at the same alerting level after the repeat interval.
+    Repeated,
+    /// Returned back to normal.
+    Resolved,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub host_name: String,
+    pub monitor_id: String,
+    /// Label of the multivalue data point. Empty for single-value monitors.
+    pub label: String,
+    pub criticality: Criticality,
+    pub previous_criticality: Criticality,
+    pub value: String,
+    pub description: String,
+    pub time: chrono::DateTime<chrono::Utc>,
+}
+
+impl Alert {
+    /// Short one-line summary, e.g. for email subjects and notification titles.
+    pub fn subject(&self) -> String {
+        let target = if self.label.is_empty() {
+            self.monitor_id.clone()
+        }
+        else {
+            format!("{} ({})", self.monitor_id, self.label)
+        };
+
+        match self.kind {
+            AlertKind::Resolved => format!("[{}] Resolved: {}", self.host_name, target),
+            AlertKind::Firing | AlertKind::Repeated => format!("[{}] {}: {}", self.host_name, self.criticality, target),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let mut lines = vec![
+            format!("Host: {}", self.host_name),
+            format!("Monitor: {}", self.monitor_id),
+        ];
+
+        if !self.label.is_empty() {
+            lines.push(format!("Label: {}", self.label));
+        }
+
+        lines.push(format!("Criticality: {} (was {})", self.criticality, self.previous_criticality));
+
+        if !self.value.is_empty() {
+            lines.push(format!("Value: {}", self.value));
+        }
+
+        if !self.description.is_empty() {
+            lines.push(format!("Description: {}", self.description));
+        }
+
+        lines.push(format!("Time: {}", self.time.to_rfc3339()));
+        lines.join("\n")
+    }
+}
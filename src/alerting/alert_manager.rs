@@ -0,0 +1,136 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::configuration::Alerting;
+use crate::frontend::UIUpdate;
+use crate::maintenance::Silence;
+
+use super::{Alert, AlertTracker, SinkWorker};
+
+
+/// Observes host state updates and sends alerts to the configured sinks.
+/// Works without a frontend so it can be used in headless setups.
+#[derive(Default)]
+pub struct AlertManager {
+    config: Alerting,
+    update_sender_prototype: Option<mpsc::Sender<UIUpdate>>,
+    update_receiver: Option<mpsc::Receiver<UIUpdate>>,
+    receiver_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl AlertManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn configure(&mut self, config: &Alerting) {
+        self.stop();
+
+        self.config = config.clone();
+
+        let (sender, receiver) = mpsc::channel::<UIUpdate>();
+        self.update_sender_prototype = Some(sender);
+        self.update_receiver = Some(receiver);
+    }
+
+    /// Register the sender as an observer in HostManager.
+    pub fn new_update_sender(&self) -> mpsc::Sender<UIUpdate> {
+        self.update_sender_prototype.clone().unwrap()
+    }
+
+    pub fn start_processing_updates(&mut self) {
+        let thread = Self::_start_processing_updates(self.config.clone(), self.update_receiver.take().unwrap());
+        self.receiver_thread = Some(thread);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(thread) = self.receiver_thread.take() {
+            if self.new_update_sender().send(UIUpdate::Stop()).is_err() {
+                log::warn!("Couldn't stop thread, it may have already stopped.");
+            }
+
+            if thread.join().is_err() {
+                log::warn!("Thread had paniced");
+            }
+        }
+    }
+
+    fn _start_processing_updates(config: Alerting, receiver: mpsc::Receiver<UIUpdate>) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            log::debug!("Started processing alerts with {} sink(s)", config.sinks.len());
+
+            let mut tracker = AlertTracker::new(&config);
+            let workers = config.sinks.iter().cloned().map(SinkWorker::new).collect::<Vec<_>>();
+            // Latest known silences. Host name is the key.
+            let mut silences = HashMap::<String, Vec<Silence>>::new();
+
+            loop {
+                let update = match receiver.recv() {
+                    Ok(update) => update,
+                    Err(error) => {
+                        log::error!("Stopped alert receiver thread: {}", error);
+                        break;
+                    }
+                };
+
+                let display_data = match update {
+                    UIUpdate::Host(display_data) => display_data,
+                    UIUpdate::Stop() => break,
+                    _ => continue,
+                };
+
+                // Silences by maintenance windows and ad-hoc silences are already resolved by HostManager.
+                let host_state = &display_data.host_state;
+                let known_silences = silences.get(&host_state.host.name).map(Vec::as_slice).unwrap_or_default();
+                let silences_changed = known_silences != host_state.silences.as_slice();
+
+                // When a silence ends, problems that still persist are alerted without waiting for new data.
+                let monitors_to_check = if silences_changed {
+                    silences.insert(host_state.host.name.clone(), host_state.silences.clone());
+                    host_state.monitor_data.values().collect::<Vec<_>>()
+                }
+                else if let Some((_, monitoring_data)) = &display_data.new_monitoring_data {
+                    vec![monitoring_data]
+                }
+                else {
+                    continue;
+                };
+
+                for monitoring_data in monitors_to_check {
+                    let Some(data_point) = monitoring_data.values.back() else {
+                        continue;
+                    };
+
+                    let alerts = tracker.process_unsilenced(
+                        &host_state.host.name,
+                        &monitoring_data.monitor_id,
+                        data_point,
+                        chrono::Utc::now(),
+                        |label| host_state.is_silenced(&monitoring_data.monitor_id, label),
+                    );
+
+                    for alert in alerts.iter() {
+                        Self::dispatch(&workers, alert);
+                    }
+                }
+            }
+
+            log::debug!("Stopping alert receiver thread");
+        })
+    }
+
+    /// Sinks are sent to in the background. Queued alerts are still sent after stopping.
+    fn dispatch(workers: &[SinkWorker], alert: &Alert) {
+        log::info!("Alert ({}): {}", alert.kind, alert.subject());
+
+        for worker in workers.iter() {
+            worker.send(alert);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::configuration::Alerting;
+use crate::enums::Criticality;
+use crate::module::monitoring::DataPoint;
+
+use super::{Alert, AlertKind};
+
+
+/// Detects criticality transitions per host, monitor and multivalue label.
+/// Only transitions produce alerts, so repeated identical data points are deduplicated.
+pub struct AlertTracker {
+    min_criticality: Criticality,
+    repeat_interval: u64,
+    send_resolved: bool,
+    /// Currently active alerts. Key is host name, monitor ID and label.
+    active: HashMap<(String, String, String), ActiveAlert>,
+}
+
+struct ActiveAlert {
+    criticality: Criticality,
+    last_sent: DateTime<Utc>,
+}
+
+impl AlertTracker {
+    pub fn new(config: &Alerting) -> Self {
+        AlertTracker {
+            min_criticality: config.min_criticality,
+            repeat_interval: config.repeat_interval,
+            send_resolved: config.send_resolved,
+            active: HashMap::new(),
+        }
+    }
+
+    /// Processes a new data point and returns the alerts that should be sent.
+    /// Multivalue data points are tracked per label.
+    pub fn process(&mut self, host_name: &str, monitor_id: &str, data_point: &DataPoint, now: DateTime<Utc>) -> Vec<Alert> {
+        self.process_unsilenced(host_name, monitor_id, data_point, now, |_| false)
+    }
+
+    /// Like `process`, but values with a silenced label are left untouched.
+    /// Their state is kept as is, so a problem that persists after the silence ends will still be alerted.
+    pub fn process_unsilenced(
+        &mut self,
+        host_name: &str,
+        monitor_id: &str,
+        data_point: &DataPoint,
+        now: DateTime<Utc>,
+        is_silenced: impl Fn(&str) -> bool,
+    ) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+
+        let current = if data_point.multivalue.is_empty() {
+            vec![(String::new(), data_point)]
+        }
+        else {
+            data_point.multivalue.iter().map(|child| (child.label.clone(), child)).collect::<Vec<_>>()
+        };
+        let current = current.into_iter()
+            .filter(|(label, _)| !is_silenced(label))
+            .collect::<Vec<_>>();
+
+        // Values that are no longer reported are considered resolved. Only multivalue results list all labels,
+        // e.g. error data points don't have children even though the labels still exist.
+        let disappeared = self.active.keys()
+            .filter(|(host, monitor, label)| {
+                !data_point.multivalue.is_empty() &&
+                host == host_name && monitor == monitor_id && !is_silenced(label) &&
+                !current.iter().any(|(current_label, _)| current_label == label)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in disappeared {
+            let active = self.active.remove(&key).unwrap();
+            if self.send_resolved {
+                alerts.push(Alert {
+                    kind: AlertKind::Resolved,
+                    host_name: key.0,
+                    monitor_id: key.1,
+                    label: key.2,
+                    criticality: Criticality::Normal,
+                    previous_criticality: active.criticality,
+                    value: String::new(),
+                    description: String::from("No longer reported"),
+                    time: now,
+                });
+            }
+        }
+
+        for (label, data_point) in current {
+            if let Some(alert) = self.process_value(host_name, monitor_id, label, data_point, now) {
+                alerts.push(alert);
+            }
+        }
+
+        alerts
+    }
+
+    fn process_value(&mut self, host_name: &str, monitor_id: &str, label: String, data_point: &DataPoint, now: DateTime<Utc>) -> Option<Alert> {
+        let criticality = data_point.criticality;
+
+        // These don't tell anything about the actual state.
+        if matches!(criticality, Criticality::Ignore | Criticality::NoData | Criticality::NotAvailable) {
+            return None;
+        }
+
+        let key = (host_name.to_string(), monitor_id.to_string(), label);
+        let is_alerting = criticality != Criticality::Normal && criticality >= self.min_criticality;
+
+        let kind = match self.active.get_mut(&key) {
+            Some(active) if is_alerting && active.criticality == criticality => {
+                let since_last = now.signed_duration_since(active.last_sent).num_seconds();
+                if self.repeat_interval == 0 || since_last < self.repeat_interval as i64 {
+                    return None;
+                }
+
+                active.last_sent = now;
+                AlertKind::Repeated
+            },
+            _ if is_alerting => AlertKind::Firing,
+            Some(_) => AlertKind::Resolved,
+            None => return None,
+        };
+
+        let previous_criticality = match kind {
+            AlertKind::Repeated => criticality,
+            AlertKind::Firing => {
+                let previous = self.active.insert(key.clone(), ActiveAlert { criticality, last_sent: now });
+                previous.map(|active| active.criticality).unwrap_or(Criticality::Normal)
+            },
+            AlertKind::Resolved => {
+                let previous = self.active.remove(&key).unwrap();
+                if !self.send_resolved {
+                    return None;
+                }
+                previous.criticality
+            },
+        };
+
+        Some(Alert {
+            kind,
+            host_name: key.0,
+            monitor_id: key.1,
+            label: key.2,
+            criticality,
+            previous_criticality,
+            value: data_point.value.clone(),
+            description: data_point.description.clone(),
+            time: now,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::configuration::AlertSinkConfig;
+
+use super::{sinks, Alert};
+
+/// Maximum number of alerts waiting to be sent to a sink. Further alerts are dropped until there's room.
+const QUEUE_SIZE: usize = 100;
+/// Sending is abandoned after this, so a hanging sink doesn't hold up later alerts.
+const SEND_TIMEOUT: Duration = Duration::from_secs(60);
+
+
+/// Sends alerts to one sink in a separate thread, so slow sinks don't delay alert processing or other sinks.
+pub struct SinkWorker {
+    name: &'static str,
+    sender: mpsc::SyncSender<Alert>,
+}
+
+impl SinkWorker {
+    /// The thread stops after the worker is dropped and queued alerts have been sent.
+    pub fn new(sink: AlertSinkConfig) -> Self {
+        let name = sinks::sink_name(&sink);
+        let (sender, receiver) = mpsc::sync_channel::<Alert>(QUEUE_SIZE);
+
+        thread::spawn(move || {
+            // Send that timed out but is still running.
+            let mut abandoned: Option<thread::JoinHandle<()>> = None;
+
+            for alert in receiver {
+                if abandoned.as_ref().is_some_and(|thread| !thread.is_finished()) {
+                    log::error!("Dropping alert to {} sink, previous alert is still being sent: {}", name, alert.subject());
+                    continue;
+                }
+
+                let (result_sender, result_receiver) = mpsc::channel();
+                let sink = sink.clone();
+                let thread = thread::spawn(move || {
+                    let _ = result_sender.send(sinks::send(&sink, &alert));
+                });
+
+                match result_receiver.recv_timeout(SEND_TIMEOUT) {
+                    Ok(Ok(())) => abandoned = None,
+                    Ok(Err(error)) => {
+                        log::error!("Failed to send alert to {} sink: {}", name, error);
+                        abandoned = None;
+                    },
+                    Err(_) => {
+                        log::error!("Sending alert to {} sink timed out", name);
+                        abandoned = Some(thread);
+                    },
+                }
+            }
+        });
+
+        SinkWorker {
+            name,
+            sender,
+        }
+    }
+
+    /// Doesn't block. The alert is dropped if the queue is full.
+    pub fn send(&self, alert: &Alert) {
+        match self.sender.try_send(alert.clone()) {
+            Ok(()) => {},
+            Err(mpsc::TrySendError::Full(_)) => log::error!("Alert queue of {} sink is full, dropping alert", self.name),
+            Err(mpsc::TrySendError::Disconnected(_)) => log::error!("Alert worker of {} sink has stopped", self.name),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod command;
+pub mod dbus;
+pub mod smtp;
+pub mod webhook;
+
+use crate::configuration::AlertSinkConfig;
+use crate::error::LkError;
+
+use super::Alert;
+
+/// Sends the alert to a single sink.
+pub fn send(sink: &AlertSinkConfig, alert: &Alert) -> Result<(), LkError> {
+    match sink {
+        AlertSinkConfig::Command { command, args } => command::send(command, args, alert),
+        AlertSinkConfig::Smtp { server, port, security, username, password, from, to } => {
+            let settings = smtp::SmtpSettings {
+                server: server.clone(),
+                port: port.unwrap_or(smtp::default_port(*security)),
+                security: *security,
+                username: username.clone(),
+                password: password.clone(),
+            };
+            smtp::send(&settings, from, to, alert)
+        },
+        AlertSinkConfig::Webhook { url, headers } => webhook::send(url, headers, alert),
+        AlertSinkConfig::Dbus => dbus::send(alert),
+    }
+}
+
+/// For logging.
+pub fn sink_name(sink: &AlertSinkConfig) -> &'static str {
+    match sink {
+        AlertSinkConfig::Command { .. } => "command",
+        AlertSinkConfig::Smtp { .. } => "smtp",
+        AlertSinkConfig::Webhook { .. } => "webhook",
+        AlertSinkConfig::Dbus => "dbus",
+    }
+}
@@ -0,0 +1,45 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::error::LkError;
+use crate::alerting::Alert;
+
+/// Runs a local command with alert details in environment variables.
+/// Doesn't wait for the command to finish, but failures are logged.
+pub fn send(command: &str, args: &[String], alert: &Alert) -> Result<(), LkError> {
+    let child = Command::new(command)
+        .args(args)
+        .env("LIGHTKEEPER_ALERT_KIND", alert.kind.to_string())
+        .env("LIGHTKEEPER_ALERT_HOST", &alert.host_name)
+        .env("LIGHTKEEPER_ALERT_MONITOR", &alert.monitor_id)
+        .env("LIGHTKEEPER_ALERT_LABEL", &alert.label)
+        .env("LIGHTKEEPER_ALERT_CRITICALITY", alert.criticality.to_string())
+        .env("LIGHTKEEPER_ALERT_PREVIOUS_CRITICALITY", alert.previous_criticality.to_string())
+        .env("LIGHTKEEPER_ALERT_VALUE", &alert.value)
+        .env("LIGHTKEEPER_ALERT_DESCRIPTION", &alert.description)
+        .env("LIGHTKEEPER_ALERT_SUBJECT", alert.subject())
+        .env("LIGHTKEEPER_ALERT_TIME", alert.time.to_rfc3339())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| LkError::other_p("Failed to run alert command", error))?;
+
+    let command = command.to_string();
+    thread::spawn(move || {
+        match child.wait_with_output() {
+            Ok(output) if !output.status.success() => {
+                log::error!("Alert command {} failed ({}): {}", command, output.status, String::from_utf8_lossy(&output.stderr).trim());
+            },
+            Ok(_) => {},
+            Err(error) => log::error!("Failed to wait for alert command {}: {}", command, error),
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::time::Duration;
+
+use dbus::arg::PropMap;
+
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::alerting::{Alert, AlertKind};
+
+const CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends a desktop notification through org.freedesktop.Notifications.
+pub fn send(alert: &Alert) -> Result<(), LkError> {
+    let connection = dbus::blocking::Connection::new_session()
+        .map_err(|error| LkError::other_p("Failed to connect to session bus", error))?;
+
+    let proxy = connection.with_proxy("org.freedesktop.Notifications", "/org/freedesktop/Notifications", CALL_TIMEOUT);
+
+    // Urgency levels: 0 = low, 1 = normal, 2 = critical.
+    let urgency: u8 = match (alert.kind, alert.criticality) {
+        (AlertKind::Resolved, _) => 0,
+        (_, Criticality::Critical) => 2,
+        _ => 1,
+    };
+
+    let mut hints = PropMap::new();
+    hints.insert("urgency".to_string(), dbus::arg::Variant(Box::new(urgency)));
+
+    let (_notification_id,): (u32,) = proxy.method_call(
+        "org.freedesktop.Notifications",
+        "Notify",
+        ("Lightkeeper", 0_u32, "", alert.subject(), alert.body(), Vec::<String>::new(), hints, -1_i32),
+    ).map_err(|error| LkError::other_p("Failed to send desktop notification", error))?;
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use rustls::pki_types::ServerName;
+
+use crate::configuration::SmtpSecurity;
+use crate::error::LkError;
+use crate::secrets_manager;
+use crate::alerting::Alert;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+/// Limit for a single response line. Protects against misbehaving servers.
+const MAX_LINE_LENGTH: usize = 4096;
+
+pub struct SmtpSettings {
+    pub server: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: String,
+    /// Plaintext password or a keyring placeholder.
+    pub password: String,
+}
+
+pub fn default_port(security: SmtpSecurity) -> u16 {
+    match security {
+        SmtpSecurity::None => 25,
+        SmtpSecurity::StartTls => 587,
+        SmtpSecurity::Tls => 465,
+    }
+}
+
+/// Minimal SMTP client. Supports STARTTLS, implicit TLS and AUTH PLAIN.
+pub fn send(settings: &SmtpSettings, from: &str, to: &[String], alert: &Alert) -> Result<(), LkError> {
+    if to.is_empty() {
+        return Err(LkError::other("No recipients configured"));
+    }
+
+    let address = (settings.server.as_str(), settings.port).to_socket_addrs()?
+        .next()
+        .ok_or_else(|| LkError::other_p("Failed to resolve address", &settings.server))?;
+
+    let tcp_stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    tcp_stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    tcp_stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut stream = match settings.security {
+        SmtpSecurity::Tls => SmtpStream::Plain(tcp_stream).upgrade(&settings.server)?,
+        SmtpSecurity::None | SmtpSecurity::StartTls => SmtpStream::Plain(tcp_stream),
+    };
+
+    expect_reply(&mut stream, 220)?;
+    command(&mut stream, "EHLO lightkeeper", 250)?;
+
+    if settings.security == SmtpSecurity::StartTls {
+        command(&mut stream, "STARTTLS", 220)?;
+        stream = stream.upgrade(&settings.server)?;
+        command(&mut stream, "EHLO lightkeeper", 250)?;
+    }
+
+    if !settings.username.is_empty() {
+        let password = resolve_password(&settings.password)?;
+        let credentials = general_purpose::STANDARD.encode(format!("\0{}\0{}", settings.username, password));
+        command(&mut stream, &format!("AUTH PLAIN {}", credentials), 235)?;
+    }
+
+    command(&mut stream, &format!("MAIL FROM:<{}>", from), 250)?;
+    for recipient in to.iter() {
+        command(&mut stream, &format!("RCPT TO:<{}>", recipient), 250)?;
+    }
+    command(&mut stream, "DATA", 354)?;
+
+    stream.write_all(format_message(from, to, alert).as_bytes())?;
+    command(&mut stream, ".", 250)?;
+    command(&mut stream, "QUIT", 221)?;
+
+    Ok(())
+}
+
+fn format_message(from: &str, to: &[String], alert: &Alert) -> String {
+    let headers = [
+        format!("From: {}", from),
+        format!("To: {}", to.join(", ")),
+        format!("Subject: {}", alert.subject()),
+        format!("Date: {}", alert.time.to_rfc2822()),
+        String::from("MIME-Version: 1.0"),
+        String::from("Content-Type: text/plain; charset=utf-8"),
+        String::from("Content-Transfer-Encoding: 8bit"),
+    ];
+
+    // Lines starting with a dot have to be escaped (RFC 5321, section 4.5.2).
+    let body = alert.body().lines()
+        .map(|line| if line.starts_with('.') { format!(".{}", line) } else { line.to_string() })
+        .collect::<Vec<_>>();
+
+    format!("{}\r\n\r\n{}\r\n", headers.join("\r\n"), body.join("\r\n"))
+}
+
+fn resolve_password(password: &str) -> Result<String, LkError> {
+    if let Some(lookup_key) = password.strip_prefix(secrets_manager::KEYRING_PREFIX) {
+        secrets_manager::get(lookup_key)?
+            .ok_or_else(|| LkError::other_p("Keyring secret not found", lookup_key))
+    }
+    else {
+        Ok(password.to_string())
+    }
+}
+
+enum SmtpStream {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl SmtpStream {
+    /// Starts TLS on top of a plain connection.
+    fn upgrade(self, server: &str) -> Result<Self, LkError> {
+        let SmtpStream::Plain(tcp_stream) = self else {
+            return Ok(self);
+        };
+
+        let mut store = rustls::RootCertStore::empty();
+        let cert_result = rustls_native_certs::load_native_certs();
+        for error in cert_result.errors {
+            log::error!("Failed to load native CA certificate: {}", error);
+        }
+        for cert in cert_result.certs {
+            if store.add(cert).is_err() {
+                log::error!("Failed to add certificate to CA certificate store.");
+            }
+        }
+
+        let client_config = rustls::ClientConfig::builder().with_root_certificates(store).with_no_client_auth();
+        let server_name: ServerName = server.to_string().try_into()
+            .map_err(|error| LkError::other_p("Invalid server name", error))?;
+        let connection = rustls::ClientConnection::new(Arc::new(client_config), server_name)
+            .map_err(|error| LkError::other_p("TLS error", error))?;
+
+        Ok(SmtpStream::Tls(Box::new(rustls::StreamOwned::new(connection, tcp_stream))))
+    }
+}
+
+impl Read for SmtpStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            SmtpStream::Plain(stream) => stream.read(buf),
+            SmtpStream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for SmtpStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            SmtpStream::Plain(stream) => stream.write(buf),
+            SmtpStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            SmtpStream::Plain(stream) => stream.flush(),
+            SmtpStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+fn command(stream: &mut SmtpStream, line: &str, expected_code: u16) -> Result<String, LkError> {
+    stream.write_all(format!("{}\r\n", line).as_bytes())?;
+    stream.flush()?;
+    expect_reply(stream, expected_code)
+}
+
+/// Reads a possibly multi-line reply and verifies the status code.
+fn expect_reply(stream: &mut SmtpStream, expected_code: u16) -> Result<String, LkError> {
+    let mut reply = Vec::new();
+
+    loop {
+        let line = read_line(stream)?;
+        if line.len() < 3 {
+            return Err(LkError::other_p("Invalid SMTP reply", line));
+        }
+
+        let is_last = line.as_bytes().get(3) != Some(&b'-');
+        reply.push(line);
+
+        if is_last {
+            break;
+        }
+    }
+
+    let last_line = reply.last().unwrap();
+    let code = last_line.get(0..3).and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| LkError::other_p("Invalid SMTP reply", last_line))?;
+    if code != expected_code {
+        return Err(LkError::other_p("Unexpected SMTP reply", reply.join(" ")));
+    }
+
+    Ok(reply.join("\n"))
+}
+
+fn read_line(stream: &mut SmtpStream) -> Result<String, LkError> {
+    let mut line = Vec::new();
+    let mut byte = [0_u8; 1];
+
+    // Reading one byte at a time so nothing gets buffered over a STARTTLS upgrade.
+    while !line.ends_with(b"\r\n") {
+        if stream.read(&mut byte)? == 0 {
+            return Err(LkError::other("SMTP connection closed unexpectedly"));
+        }
+        line.push(byte[0]);
+
+        if line.len() > MAX_LINE_LENGTH {
+            return Err(LkError::other("SMTP reply line too long"));
+        }
+    }
+
+    line.truncate(line.len() - 2);
+    Ok(String::from_utf8_lossy(&line).to_string())
+}
@@ -0,0 +1,32 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::error::LkError;
+use crate::alerting::Alert;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// POSTs the alert as JSON. Subject is included as an extra field for convenience.
+pub fn send(url: &str, headers: &BTreeMap<String, String>, alert: &Alert) -> Result<(), LkError> {
+    let mut payload = serde_json::to_value(alert)
+        .map_err(|error| LkError::other_p("Failed to serialize alert", error))?;
+    payload["subject"] = serde_json::Value::String(alert.subject());
+
+    let agent = ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .build()
+        .new_agent();
+
+    let mut request = agent.post(url).header("Content-Type", "application/json");
+    for (name, value) in headers.iter() {
+        request = request.header(name, value);
+    }
+
+    request.send(payload.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration;
+use crate::error::LkError;
+
+//
+// Audit trail of requests sent to hosts. Records are appended as JSON lines to `audit.jsonl`.
+// When the file grows too large, it's rotated to `audit.1.jsonl`, `audit.2.jsonl` and so on.
+//
+
+const FILE_PREFIX: &str = "audit";
+const FILE_EXTENSION: &str = "jsonl";
+
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the request was started.
+    pub time: DateTime<Utc>,
+    /// Local user running Lightkeeper.
+    pub user: String,
+    pub host: String,
+    pub connector: String,
+    /// ID of the monitor or command module that created the request.
+    pub source_id: String,
+    pub invocation_id: u64,
+    /// Monitors whose commands were combined into the request. Empty for other requests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batched_monitors: Vec<String>,
+    pub request_type: AuditRequestType,
+    /// Command as sent to the connector or the remote file path.
+    pub target: String,
+    /// Not set if the request failed before a response was received.
+    pub return_code: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditRequestType {
+    Monitor,
+    Command,
+    FollowOutput,
+    Download,
+    Upload,
+}
+
+/// Empty fields match all records.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditQuery {
+    pub host: Option<String>,
+    pub user: Option<String>,
+    pub source_id: Option<String>,
+    pub request_type: Option<AuditRequestType>,
+    /// Case-insensitive substring of the command or file path.
+    pub target_contains: Option<String>,
+    /// Unix timestamp in seconds.
+    pub start_time: Option<i64>,
+    /// Unix timestamp in seconds.
+    pub end_time: Option<i64>,
+    /// Maximum number of records to return, newest first. 0 means no limit.
+    pub limit: usize,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        let timestamp = record.time.timestamp();
+
+        self.host.as_ref().is_none_or(|host| &record.host == host) &&
+        self.user.as_ref().is_none_or(|user| &record.user == user) &&
+        self.source_id.as_ref().is_none_or(|source_id| &record.source_id == source_id || record.batched_monitors.contains(source_id)) &&
+        self.request_type.is_none_or(|request_type| record.request_type == request_type) &&
+        self.target_contains.as_ref().is_none_or(|text| record.target.to_lowercase().contains(&text.to_lowercase())) &&
+        self.start_time.is_none_or(|start_time| timestamp >= start_time) &&
+        self.end_time.is_none_or(|end_time| timestamp <= end_time)
+    }
+}
+
+
+pub struct AuditLogger {
+    log_dir: PathBuf,
+    /// In bytes.
+    max_file_size: u64,
+    /// Includes the current file.
+    max_files: u32,
+    user: String,
+    /// Serializes writes and rotation.
+    file_lock: Mutex<()>,
+}
+
+impl AuditLogger {
+    pub fn new(log_dir: PathBuf, config: &configuration::AuditLog) -> Result<Self, LkError> {
+        fs::create_dir_all(&log_dir)?;
+
+        Ok(AuditLogger {
+            log_dir,
+            max_file_size: config.max_file_size.saturating_mul(1024 * 1024),
+            max_files: config.max_files.max(1),
+            user: local_user(),
+            file_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub fn append(&self, record: &AuditRecord) -> Result<(), LkError> {
+        let mut line = serde_json::to_string(record).map_err(|error| LkError::other(error.to_string()))?;
+        line.push('\n');
+
+        let _lock = self.file_lock.lock().unwrap();
+        let current_path = self.file_path(0);
+        let current_size = fs::metadata(&current_path).map(|metadata| metadata.len()).unwrap_or(0);
+
+        if current_size > 0 && current_size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&current_path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns matching records, newest first. Unreadable lines are skipped.
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, LkError> {
+        let _lock = self.file_lock.lock().unwrap();
+        let mut result = Vec::new();
+
+        for index in 0..self.max_files {
+            let file = match fs::File::open(self.file_path(index)) {
+                Ok(file) => file,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+
+            let mut records = BufReader::new(file).lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<AuditRecord>(&line).ok())
+                .filter(|record| query.matches(record))
+                .collect::<Vec<_>>();
+
+            records.reverse();
+            result.extend(records);
+
+            if query.limit > 0 && result.len() >= query.limit {
+                result.truncate(query.limit);
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Oldest file is removed and the rest are renamed to have a larger number.
+    fn rotate(&self) -> Result<(), LkError> {
+        let oldest_path = self.file_path(self.max_files - 1);
+        if oldest_path.exists() {
+            fs::remove_file(&oldest_path)?;
+        }
+
+        for index in (0..self.max_files - 1).rev() {
+            let path = self.file_path(index);
+            if path.exists() {
+                fs::rename(&path, self.file_path(index + 1))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Index 0 is the current file.
+    fn file_path(&self, index: u32) -> PathBuf {
+        match index {
+            0 => self.log_dir.join(format!("{}.{}", FILE_PREFIX, FILE_EXTENSION)),
+            index => self.log_dir.join(format!("{}.{}.{}", FILE_PREFIX, index, FILE_EXTENSION)),
+        }
+    }
+}
+
+fn local_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default()
+}
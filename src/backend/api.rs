@@ -6,12 +6,14 @@
 use std::collections::HashMap;
 use std::sync::mpsc;
 
-use crate::command_handler::CommandButtonData;
+use crate::audit_log::{AuditQuery, AuditRecord};
+use crate::command_handler::{BulkExecution, BulkRequest, CommandButtonData};
 use crate::configuration;
 use crate::connection_manager::ConnectorRequest;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host_manager::StateUpdateMessage;
+use crate::metrics::lmserver::Metric;
 
 //
 // Traits for command backend API and local backend API.
@@ -34,6 +36,10 @@ pub trait CommandBackend {
     fn custom_commands_for_host(&self, host_id: &str) -> Result<HashMap<String, configuration::CustomCommandConfig>, LkError>;
     fn all_host_categories(&self, host_id: &str) -> Result<Vec<String>, LkError>;
     fn execute_command(&mut self, host_id: &str, command_id: &str, parameters: &[String]) -> Result<u64, LkError>;
+    /// Returns the bulk execution ID.
+    fn execute_bulk(&mut self, request: BulkRequest) -> Result<u64, LkError>;
+    fn bulk_execution(&self, bulk_id: u64) -> Result<Option<BulkExecution>, LkError>;
+    fn cancel_bulk_execution(&self, bulk_id: u64) -> Result<bool, LkError>;
     fn interrupt_invocation(&self, invocation_id: u64);
     fn verify_host_key(&self, host_id: &str, connector_id: &str, key_id: &str);
     fn initialize_host(&mut self, host_id: &str);
@@ -48,6 +54,11 @@ pub trait CommandBackend {
     fn write_cached_file(&mut self, host_id: &str, remote_file_path: &str, new_contents: Vec<u8>) -> Result<(), LkError>;
     fn remove_cached_file(&mut self, host_id: &str, remote_file_path: &str) -> Result<(), LkError>;
     fn has_cached_file_changed(&self, host_id: &str, remote_file_path: &str, new_contents: &[u8]) -> Result<bool, LkError>;
+    /// Records of requests sent to hosts, newest first.
+    fn query_audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, LkError>;
+    /// Chart data stored by the backend, grouped by label. Start and end times are unix timestamps in seconds.
+    /// Returns `ErrorKind::NotImplemented` if the frontend stores metrics itself.
+    fn query_metrics(&self, host_id: &str, monitor_id: &str, start_time: i64, end_time: i64) -> Result<HashMap<String, Vec<Metric>>, LkError>;
 
     fn local_backend(&self) -> Option<&dyn LocalBackendApi> {
         None
@@ -4,27 +4,35 @@
  */
 
 use std::collections::HashMap;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 
 use super::api::{CommandBackend, LocalBackendApi};
-use crate::command_handler::{CommandButtonData, CommandHandler};
+use crate::audit_log::{AuditLogger, AuditQuery, AuditRecord};
+use crate::command_handler::{BulkExecution, BulkRequest, CommandButtonData, CommandHandler};
 use crate::configuration;
 use crate::connection_manager::ConnectorRequest;
 use crate::error::LkError;
 use crate::file_handler;
 use crate::frontend;
 use crate::host_manager::StateUpdateMessage;
+use crate::metrics::lmserver::Metric;
 use crate::monitor_manager::MonitorManager;
 
 #[derive(Default)]
 pub struct LocalCommandBackend {
     command_handler: CommandHandler,
     monitor_manager: MonitorManager,
+    audit_log: Option<Arc<AuditLogger>>,
 }
 
 impl LocalCommandBackend {
     pub fn new(command_handler: CommandHandler, monitor_manager: MonitorManager) -> Self {
-        LocalCommandBackend { command_handler, monitor_manager }
+        LocalCommandBackend { command_handler, monitor_manager, audit_log: None }
+    }
+
+    pub fn with_audit_log(mut self, audit_log: Option<Arc<AuditLogger>>) -> Self {
+        self.audit_log = audit_log;
+        self
     }
 
     fn connector_message(&self, host_id: &str, command_id: &str) -> Option<String> {
@@ -50,6 +58,7 @@ impl CommandBackend for LocalCommandBackend {
 
     fn start_processing_responses(&mut self) {
         self.monitor_manager.start_processing_responses();
+        self.monitor_manager.start_scheduler();
         self.command_handler.start_processing_responses();
     }
 
@@ -86,6 +95,18 @@ impl CommandBackend for LocalCommandBackend {
         self.command_handler.execute(host_id, command_id, parameters)
     }
 
+    fn execute_bulk(&mut self, request: BulkRequest) -> Result<u64, LkError> {
+        self.command_handler.execute_bulk(request)
+    }
+
+    fn bulk_execution(&self, bulk_id: u64) -> Result<Option<BulkExecution>, LkError> {
+        Ok(self.command_handler.get_bulk_execution(bulk_id))
+    }
+
+    fn cancel_bulk_execution(&self, bulk_id: u64) -> Result<bool, LkError> {
+        Ok(self.command_handler.cancel_bulk_execution(bulk_id))
+    }
+
     fn interrupt_invocation(&self, invocation_id: u64) {
         self.command_handler.interrupt_invocation(invocation_id);
     }
@@ -171,6 +192,18 @@ impl CommandBackend for LocalCommandBackend {
         self.command_handler.has_file_changed(host_id, remote_file_path, &content_hash)
     }
 
+    fn query_audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, LkError> {
+        match &self.audit_log {
+            Some(audit_log) => audit_log.query(query),
+            None => Err(LkError::other("Audit log is not enabled")),
+        }
+    }
+
+    /// Desktop records metrics from host updates in its own store.
+    fn query_metrics(&self, _host_id: &str, _monitor_id: &str, _start_time: i64, _end_time: i64) -> Result<HashMap<String, Vec<Metric>>, LkError> {
+        Err(LkError::not_implemented())
+    }
+
     fn local_backend(&self) -> Option<&dyn LocalBackendApi> {
         Some(self)
     }
@@ -14,13 +14,15 @@ use std::time::Duration;
 
 use super::api::{CommandBackend, ConfigBackend};
 use super::remote_config::RemoteConfigBackend;
-use crate::command_handler::CommandButtonData;
+use crate::audit_log::{AuditQuery, AuditRecord};
+use crate::command_handler::{BulkExecution, BulkRequest, CommandButtonData};
 use crate::configuration;
 use crate::connection_manager::ConnectorRequest;
 use crate::error::{ErrorKind, LkError};
 use crate::frontend;
 use crate::host_manager::StateUpdateMessage;
-use crate::remote_core::protocol::{read_message, write_message, ClientMessage, ServerMessage, PROTOCOL_VERSION};
+use crate::metrics::lmserver::Metric;
+use crate::remote_core::protocol::{read_message, write_message, ClientMessage, ServerMessage, FILE_CHUNK_SIZE, PROTOCOL_VERSION};
 use crate::utils::sha256;
 
 //
@@ -33,6 +35,9 @@ const REMOTE_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum PendingRpcKind {
     ExecuteCommand,
+    ExecuteBulk,
+    BulkExecution,
+    CancelBulkExecution,
     CommandsForHost,
     CommandForHost,
     CustomCommandsForHost,
@@ -47,11 +52,18 @@ enum PendingRpcKind {
     UploadFromCache,
     Config,
     UpdateConfig,
+    CreateSilence,
+    CancelSilence,
+    QueryMetrics,
+    QueryAuditLog,
 }
 
 #[allow(clippy::large_enum_variant)]
 enum PendingRpcReply {
     ExecuteCommand(u64),
+    BulkExecutionStarted(u64),
+    BulkExecution(Option<BulkExecution>),
+    BulkExecutionCancelled(bool),
     CommandsForHost(HashMap<String, CommandButtonData>),
     CommandForHost(Option<CommandButtonData>),
     CustomCommandsForHost(HashMap<String, configuration::CustomCommandConfig>),
@@ -69,6 +81,10 @@ enum PendingRpcReply {
         groups_yml: String,
     },
     UpdateConfigOk,
+    SilenceCreated(u64),
+    SilenceCancelled(bool),
+    Metrics(HashMap<String, Vec<Metric>>),
+    AuditLog(Vec<AuditRecord>),
     Error(String),
 }
 
@@ -80,6 +96,9 @@ struct PendingRpc {
 fn default_reply(kind: PendingRpcKind) -> PendingRpcReply {
     match kind {
         PendingRpcKind::ExecuteCommand => PendingRpcReply::ExecuteCommand(0),
+        PendingRpcKind::ExecuteBulk => PendingRpcReply::BulkExecutionStarted(0),
+        PendingRpcKind::BulkExecution => PendingRpcReply::BulkExecution(None),
+        PendingRpcKind::CancelBulkExecution => PendingRpcReply::BulkExecutionCancelled(false),
         PendingRpcKind::CommandsForHost => PendingRpcReply::CommandsForHost(HashMap::new()),
         PendingRpcKind::CommandForHost => PendingRpcReply::CommandForHost(None),
         PendingRpcKind::CustomCommandsForHost => PendingRpcReply::CustomCommandsForHost(HashMap::new()),
@@ -98,6 +117,10 @@ fn default_reply(kind: PendingRpcKind) -> PendingRpcReply {
             groups_yml: String::new(),
         },
         PendingRpcKind::UpdateConfig => PendingRpcReply::UpdateConfigOk,
+        PendingRpcKind::CreateSilence => PendingRpcReply::SilenceCreated(0),
+        PendingRpcKind::CancelSilence => PendingRpcReply::SilenceCancelled(false),
+        PendingRpcKind::QueryMetrics => PendingRpcReply::Metrics(HashMap::new()),
+        PendingRpcKind::QueryAuditLog => PendingRpcReply::AuditLog(Vec::new()),
     }
 }
 
@@ -109,6 +132,9 @@ fn reply_matches(kind: &PendingRpcKind, reply: &PendingRpcReply) -> bool {
     matches!(
         (kind, reply),
         (PendingRpcKind::ExecuteCommand, PendingRpcReply::ExecuteCommand(_)) |
+            (PendingRpcKind::ExecuteBulk, PendingRpcReply::BulkExecutionStarted(_)) |
+            (PendingRpcKind::BulkExecution, PendingRpcReply::BulkExecution(_)) |
+            (PendingRpcKind::CancelBulkExecution, PendingRpcReply::BulkExecutionCancelled(_)) |
             (PendingRpcKind::CommandsForHost, PendingRpcReply::CommandsForHost(_)) |
             (PendingRpcKind::CommandForHost, PendingRpcReply::CommandForHost(_)) |
             (PendingRpcKind::CustomCommandsForHost, PendingRpcReply::CustomCommandsForHost(_)) |
@@ -122,7 +148,11 @@ fn reply_matches(kind: &PendingRpcKind, reply: &PendingRpcReply) -> bool {
             (PendingRpcKind::HasCachedFileChanged, PendingRpcReply::FileChanged(_)) |
             (PendingRpcKind::UploadFromCache, PendingRpcReply::UploadFromCache(_)) |
             (PendingRpcKind::Config, PendingRpcReply::Config { .. }) |
-            (PendingRpcKind::UpdateConfig, PendingRpcReply::UpdateConfigOk)
+            (PendingRpcKind::UpdateConfig, PendingRpcReply::UpdateConfigOk) |
+            (PendingRpcKind::CreateSilence, PendingRpcReply::SilenceCreated(_)) |
+            (PendingRpcKind::CancelSilence, PendingRpcReply::SilenceCancelled(_)) |
+            (PendingRpcKind::QueryMetrics, PendingRpcReply::Metrics(_)) |
+            (PendingRpcKind::QueryAuditLog, PendingRpcReply::AuditLog(_))
     )
 }
 
@@ -376,6 +406,41 @@ impl RemoteCoreClient {
                         PendingRpcReply::UpdateConfigOk
                     });
                 }
+                ServerMessage::BulkExecutionStarted { request_id, bulk_id } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::ExecuteBulk, || {
+                        PendingRpcReply::BulkExecutionStarted(bulk_id)
+                    });
+                }
+                ServerMessage::BulkExecution { request_id, execution } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::BulkExecution, || {
+                        PendingRpcReply::BulkExecution(execution)
+                    });
+                }
+                ServerMessage::BulkExecutionCancelled { request_id, found } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::CancelBulkExecution, || {
+                        PendingRpcReply::BulkExecutionCancelled(found)
+                    });
+                }
+                ServerMessage::SilenceCreated { request_id, silence_id } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::CreateSilence, || {
+                        PendingRpcReply::SilenceCreated(silence_id)
+                    });
+                }
+                ServerMessage::SilenceCancelled { request_id, found } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::CancelSilence, || {
+                        PendingRpcReply::SilenceCancelled(found)
+                    });
+                }
+                ServerMessage::Metrics { request_id, metrics } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::QueryMetrics, || {
+                        PendingRpcReply::Metrics(metrics)
+                    });
+                }
+                ServerMessage::AuditLog { request_id, records } => {
+                    deliver_response(&pending_rpc, request_id, PendingRpcKind::QueryAuditLog, || {
+                        PendingRpcReply::AuditLog(records)
+                    });
+                }
                 ServerMessage::Error { request_id, message } => {
                     ::log::error!("Core server error: {}", message);
                     if let Some(request_id) = request_id {
@@ -511,6 +576,43 @@ impl RemoteCommandBackend {
     fn stop_connection(&mut self) {
         self.client.stop_connection();
     }
+
+    /// Suppresses alerts for a host, monitor or label on the core. Returns the silence ID.
+    pub fn create_silence(
+        &self,
+        host_id: &str,
+        monitor_id: Option<String>,
+        label: Option<String>,
+        duration_secs: u64,
+        comment: String,
+    ) -> Result<u64, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::CreateSilence, |request_id| ClientMessage::CreateSilence {
+                request_id,
+                host_id: host_id.to_string(),
+                monitor_id,
+                label,
+                duration_secs,
+                comment,
+            })? {
+            PendingRpcReply::SilenceCreated(silence_id) => Ok(silence_id),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
+    /// Returns false if the silence was not found.
+    pub fn cancel_silence(&self, silence_id: u64) -> Result<bool, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::CancelSilence, |request_id| ClientMessage::CancelSilence {
+                request_id,
+                silence_id,
+            })? {
+            PendingRpcReply::SilenceCancelled(found) => Ok(found),
+            _ => Err(LkError::unexpected()),
+        }
+    }
 }
 
 impl CommandBackend for RemoteCommandBackend {
@@ -605,6 +707,39 @@ impl CommandBackend for RemoteCommandBackend {
         }
     }
 
+    fn execute_bulk(&mut self, request: BulkRequest) -> Result<u64, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::ExecuteBulk, |request_id| ClientMessage::ExecuteBulk { request_id, request })? {
+            PendingRpcReply::BulkExecutionStarted(bulk_id) => Ok(bulk_id),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
+    fn bulk_execution(&self, bulk_id: u64) -> Result<Option<BulkExecution>, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::BulkExecution, |request_id| ClientMessage::GetBulkExecution {
+                request_id,
+                bulk_id,
+            })? {
+            PendingRpcReply::BulkExecution(execution) => Ok(execution),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
+    fn cancel_bulk_execution(&self, bulk_id: u64) -> Result<bool, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::CancelBulkExecution, |request_id| ClientMessage::CancelBulkExecution {
+                request_id,
+                bulk_id,
+            })? {
+            PendingRpcReply::BulkExecutionCancelled(found) => Ok(found),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
     fn interrupt_invocation(&self, invocation_id: u64) {
         if !self.client.is_connected() {
             return;
@@ -733,17 +868,28 @@ impl CommandBackend for RemoteCommandBackend {
         self.upload_file(host_id, command_id, remote_file_path)
     }
 
+    /// Sent in chunks so that large files don't have to fit in one message.
     fn write_cached_file(&mut self, host_id: &str, remote_file_path: &str, new_contents: Vec<u8>) -> Result<(), LkError> {
-        let host_id = host_id.to_string();
-        let remote_file_path = remote_file_path.to_string();
-        self.client
-            .send_message_result(PendingRpcKind::WriteCachedFile, move |request_id| ClientMessage::WriteCachedFile {
-                request_id,
-                host_id,
-                remote_file_path,
-                contents: new_contents,
-            })?;
-        Ok(())
+        let mut offset = 0;
+        loop {
+            let chunk_end = (offset + FILE_CHUNK_SIZE).min(new_contents.len());
+            let is_final = chunk_end == new_contents.len();
+
+            self.client
+                .send_message_result(PendingRpcKind::WriteCachedFile, |request_id| ClientMessage::WriteCachedFileChunk {
+                    request_id,
+                    host_id: host_id.to_string(),
+                    remote_file_path: remote_file_path.to_string(),
+                    offset: offset as u64,
+                    chunk: new_contents[offset..chunk_end].to_vec(),
+                    is_final,
+                })?;
+
+            if is_final {
+                return Ok(());
+            }
+            offset = chunk_end;
+        }
     }
 
     fn remove_cached_file(&mut self, host_id: &str, remote_file_path: &str) -> Result<(), LkError> {
@@ -772,6 +918,34 @@ impl CommandBackend for RemoteCommandBackend {
             _ => Err(LkError::unexpected()),
         }
     }
+
+    fn query_audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::QueryAuditLog, |request_id| ClientMessage::QueryAuditLog {
+                request_id,
+                query: query.clone(),
+            })? {
+            PendingRpcReply::AuditLog(records) => Ok(records),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
+    /// Chart data recorded by the core.
+    fn query_metrics(&self, host_id: &str, monitor_id: &str, start_time: i64, end_time: i64) -> Result<HashMap<String, Vec<Metric>>, LkError> {
+        match self
+            .client
+            .send_message_result(PendingRpcKind::QueryMetrics, |request_id| ClientMessage::QueryMetrics {
+                request_id,
+                host_id: host_id.to_string(),
+                monitor_id: monitor_id.to_string(),
+                start_time,
+                end_time,
+            })? {
+            PendingRpcReply::Metrics(metrics) => Ok(metrics),
+            _ => Err(LkError::unexpected()),
+        }
+    }
 }
 
 impl ConfigBackend for RemoteConfigBackend {
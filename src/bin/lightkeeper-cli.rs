@@ -0,0 +1,342 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+#![forbid(unsafe_code)]
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use lightkeeper::command_handler::{bulk, BulkRequest, RolloutStrategy};
+use lightkeeper::configuration;
+use lightkeeper::frontend::cli;
+use lightkeeper::module::command::UIAction;
+use lightkeeper::{Configuration, CoreComponents, ModuleFactory};
+
+#[derive(Parser, Clone)]
+pub struct Args {
+    #[clap(short, long, default_value = "")]
+    pub config_dir: String,
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    /// Refreshes hosts once and prints monitor states. Exit code is derived from the worst criticality.
+    Status {
+        /// Hosts to refresh. All hosts by default.
+        hosts: Vec<String>,
+        /// Also refresh hosts in these groups.
+        #[clap(short, long)]
+        group: Vec<String>,
+        #[clap(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+        /// Maximum time to wait for the refresh to complete. In seconds.
+        #[clap(short, long, default_value_t = 60)]
+        timeout: u64,
+    },
+    /// Executes a command module, e.g. `lightkeeper-cli exec -g web systemd-service-restart -- nginx.service`.
+    Exec {
+        command_id: String,
+        /// Command parameters.
+        #[clap(last = true)]
+        parameters: Vec<String>,
+        /// Hosts to run the command on.
+        #[clap(long = "host")]
+        hosts: Vec<String>,
+        /// Also run on hosts in these groups.
+        #[clap(short, long)]
+        group: Vec<String>,
+        /// Don't ask for confirmation.
+        #[clap(short, long)]
+        yes: bool,
+        /// Maximum time to wait for the command to complete. Hosts that haven't started by then are skipped. In seconds.
+        #[clap(short, long, default_value_t = 600)]
+        timeout: u64,
+        /// Order in which hosts are run: parallel, serial, canary:<hosts> or percent:<percent>.
+        #[clap(long, default_value = "parallel")]
+        rollout: RolloutStrategy,
+        /// Maximum number of hosts running at the same time. 0 means no limit.
+        #[clap(long, default_value_t = 10)]
+        max_concurrency: usize,
+        /// Don't start any more hosts after the first failure.
+        #[clap(long)]
+        stop_on_failure: bool,
+    },
+    /// Runs a runbook defined in runbooks.yml. Hosts are run in parallel.
+    Runbook {
+        runbook_id: String,
+        /// Hosts to run the runbook on.
+        #[clap(long = "host")]
+        hosts: Vec<String>,
+        /// Also run on hosts in these groups.
+        #[clap(short, long)]
+        group: Vec<String>,
+        /// Don't ask for confirmation.
+        #[clap(short, long)]
+        yes: bool,
+        /// Maximum time to wait for the runbook to complete. Unfinished runs are cancelled. In seconds.
+        #[clap(short, long, default_value_t = 3600)]
+        timeout: u64,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+}
+
+fn main() -> ExitCode {
+    std::env::set_var("LANGUAGE", "en_US");
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let args = Args::parse();
+
+    let (main_config, hosts_config, _group_config) = match Configuration::read(&args.config_dir) {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            log::error!("Error while reading configuration files: {}", error);
+            return ExitCode::from(cli::EXIT_UNKNOWN);
+        }
+    };
+
+    let result = match args.command {
+        Command::Status { hosts, group, format, timeout } => {
+            status(&main_config, hosts_config, &hosts, &group, format, Duration::from_secs(timeout))
+        }
+        Command::Exec { command_id, parameters, hosts, group, yes, timeout, rollout, max_concurrency, stop_on_failure } => {
+            if hosts.is_empty() && group.is_empty() {
+                Err(String::from("Select hosts with --host or --group"))
+            }
+            else {
+                let request = BulkRequest {
+                    command_id,
+                    parameters,
+                    host_ids: hosts,
+                    groups: group,
+                    strategy: rollout,
+                    max_concurrency,
+                    stop_on_failure,
+                };
+                exec(&main_config, hosts_config, request, yes, Duration::from_secs(timeout))
+            }
+        }
+        Command::Runbook { runbook_id, hosts, group, yes, timeout } => {
+            if hosts.is_empty() && group.is_empty() {
+                Err(String::from("Select hosts with --host or --group"))
+            }
+            else {
+                runbook(&args.config_dir, &main_config, hosts_config, &hosts, &group, &runbook_id, yes, Duration::from_secs(timeout))
+            }
+        }
+    };
+
+    match result {
+        Ok(exit_code) => ExitCode::from(exit_code),
+        Err(error) => {
+            log::error!("{}", error);
+            ExitCode::from(cli::EXIT_UNKNOWN)
+        }
+    }
+}
+
+/// Unselected hosts are left out completely so that no connections are made to them.
+/// All hosts are selected if no hosts or groups are given.
+fn initialize(
+    main_config: &Configuration,
+    mut hosts_config: configuration::Hosts,
+    hosts: &[String],
+    groups: &[String],
+) -> Result<(CoreComponents, Vec<String>), String> {
+    let host_ids = if hosts.is_empty() && groups.is_empty() {
+        hosts_config.hosts.keys().cloned().collect()
+    }
+    else {
+        bulk::resolve_hosts(&hosts_config, hosts, groups)?
+    };
+    hosts_config.hosts.retain(|host_id, _| host_ids.contains(host_id));
+
+    let core = lightkeeper::initialize_core(main_config, &hosts_config, Arc::new(ModuleFactory::new()))
+        .map_err(|error| format!("Failed to initialize: {}", error))?;
+
+    Ok((core, host_ids))
+}
+
+fn stop(core: &mut CoreComponents) {
+    core.command_handler.stop();
+    core.monitor_manager.stop();
+    core.alert_manager.stop();
+    core.host_manager.borrow_mut().stop();
+    core.connection_manager.stop();
+}
+
+fn status(
+    main_config: &Configuration,
+    hosts_config: configuration::Hosts,
+    hosts: &[String],
+    groups: &[String],
+    format: OutputFormat,
+    timeout: Duration,
+) -> Result<u8, String> {
+    let (mut core, host_ids) = initialize(main_config, hosts_config, hosts, groups)?;
+    let result = cli::refresh_hosts(&mut core, &host_ids, timeout);
+    stop(&mut core);
+    let report = result?;
+
+    let output = match format {
+        OutputFormat::Table => cli::render_table(&report),
+        OutputFormat::Json => serde_json::to_string_pretty(&report).map_err(|error| error.to_string())? + "\n",
+        OutputFormat::Yaml => serde_yaml::to_string(&report).map_err(|error| error.to_string())?,
+    };
+
+    print!("{}", output);
+    Ok(report.exit_code())
+}
+
+fn exec(
+    main_config: &Configuration,
+    hosts_config: configuration::Hosts,
+    mut request: BulkRequest,
+    yes: bool,
+    timeout: Duration,
+) -> Result<u8, String> {
+    let (mut core, host_ids) = initialize(main_config, hosts_config, &request.host_ids, &request.groups)?;
+    request.host_ids = host_ids.clone();
+    request.groups = Vec::new();
+
+    let display_options = match cli::resolve_command(&core, &host_ids, &request.command_id) {
+        Ok(display_options) => display_options,
+        Err(error) => {
+            stop(&mut core);
+            return Err(error.to_string());
+        }
+    };
+
+    if !display_options.confirmation_text.is_empty() && !yes {
+        let text = format!("{} (hosts: {})", display_options.confirmation_text, host_ids.join(", "));
+        let confirmed = confirm(&text);
+        if confirmed != Ok(true) {
+            stop(&mut core);
+            return Err(confirmed.err().unwrap_or_else(|| String::from("Cancelled")));
+        }
+    }
+
+    let follow_output = display_options.action == UIAction::FollowOutput;
+    let show_host = host_ids.len() > 1;
+    let mut follower = cli::OutputFollower::new();
+
+    let result = cli::execute_command(&mut core, &request, timeout, |host_id, result| {
+        if follow_output {
+            print_lines(host_id, &follower.new_output(host_id, result), show_host);
+        }
+    });
+    stop(&mut core);
+    let outcomes = result?;
+
+    for outcome in outcomes.iter() {
+        if let Some(result) = &outcome.result {
+            if !follow_output {
+                print_lines(&outcome.host_id, &result.message, show_host);
+            }
+            if !result.error.is_empty() {
+                eprintln!("{}: {}", outcome.host_id, result.error);
+            }
+        }
+        else if outcome.errors.is_empty() {
+            eprintln!("{}: Timed out", outcome.host_id);
+        }
+
+        for error in outcome.errors.iter() {
+            eprintln!("{}: {}", outcome.host_id, error);
+        }
+    }
+
+    Ok(cli::outcomes_exit_code(&outcomes))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn runbook(
+    config_dir: &str,
+    main_config: &Configuration,
+    hosts_config: configuration::Hosts,
+    hosts: &[String],
+    groups: &[String],
+    runbook_id: &str,
+    yes: bool,
+    timeout: Duration,
+) -> Result<u8, String> {
+    let runbooks = Configuration::read_runbooks(config_dir)
+        .map_err(|error| format!("Error while reading runbooks: {}", error))?;
+    let runbook = runbooks.runbooks.get(runbook_id)
+        .ok_or_else(|| format!("Runbook \"{}\" not found", runbook_id))?;
+
+    let (mut core, host_ids) = initialize(main_config, hosts_config, hosts, groups)?;
+
+    if !yes {
+        let text = format!("Run runbook \"{}\" with {} step(s)? (hosts: {})", runbook_id, runbook.steps.len(), host_ids.join(", "));
+        let confirmed = confirm(&text);
+        if confirmed != Ok(true) {
+            stop(&mut core);
+            return Err(confirmed.err().unwrap_or_else(|| String::from("Cancelled")));
+        }
+    }
+
+    let show_host = host_ids.len() > 1;
+    let mut follower = cli::RunbookFollower::new();
+
+    let result = cli::run_runbook(&mut core, &host_ids, runbook_id, runbook, timeout, |run| {
+        print_lines(&run.host_id, &follower.new_lines(run).join("\n"), show_host);
+    });
+    stop(&mut core);
+    let outcomes = result?;
+
+    for outcome in outcomes.iter() {
+        match &outcome.run {
+            Some(run) => eprintln!("{}: {:?}", outcome.host_id, run.state),
+            None if outcome.errors.is_empty() => eprintln!("{}: Timed out", outcome.host_id),
+            None => {},
+        }
+
+        for error in outcome.errors.iter() {
+            eprintln!("{}: {}", outcome.host_id, error);
+        }
+    }
+
+    Ok(cli::runbook_outcomes_exit_code(&outcomes))
+}
+
+fn print_lines(host_id: &str, text: &str, show_host: bool) {
+    if text.is_empty() {
+        return;
+    }
+
+    let mut stdout = io::stdout().lock();
+    for line in text.lines() {
+        let _ = match show_host {
+            true => writeln!(stdout, "[{}] {}", host_id, line),
+            false => writeln!(stdout, "{}", line),
+        };
+    }
+    let _ = stdout.flush();
+}
+
+/// Asks on the terminal. Without a terminal, `--yes` is required.
+fn confirm(text: &str) -> Result<bool, String> {
+    if !io::stdin().is_terminal() {
+        return Err(format!("Confirmation required: {}. Use --yes to confirm.", text));
+    }
+
+    eprint!("{} [y/N] ", text);
+    let _ = io::stderr().flush();
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).map_err(|error| error.to_string())?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
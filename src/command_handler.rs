@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+pub mod bulk;
+pub mod runbook;
 
 use core::panic;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -11,21 +13,24 @@ use std::sync::mpsc;
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::thread;
+use std::path::Path;
 use serde::{Serialize, Deserialize};
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::configuration::CustomCommandConfig;
+use crate::configuration::{CustomCommandConfig, Runbook};
 use crate::module::command::CommandModule;
 use crate::utils::sha256;
 use crate::configuration::Hosts;
 use crate::error::*;
 use crate::file_handler;
 use crate::file_handler::write_file_metadata;
-use crate::host_manager::HostManager;
+use crate::host_manager::{HostManager, HostStateCollection};
 use crate::module::command::UIAction;
 use crate::module::connection::request_response::RequestResponse;
+use crate::module::connection::ssh;
 use crate::module::module_factory::ModuleFactory;
+use crate::monitor_manager::MonitorManager;
 use crate::utils::*;
 use crate::{
     configuration::Preferences,
@@ -40,6 +45,11 @@ use crate::module::{
     command::CommandResult,
 };
 
+pub use bulk::{BulkExecution, BulkRequest, HostExecution, HostExecutionState, RolloutStrategy};
+use bulk::BulkExecutor;
+pub use runbook::{RunState, RunbookRun, StepProgress, StepState};
+use runbook::RunbookExecutor;
+
 // Default needs to be implemented because of Qt QObject requirements.
 #[derive(Default)]
 pub struct CommandHandler {
@@ -67,6 +77,10 @@ pub struct CommandHandler {
     response_sender_prototype: Option<mpsc::Sender<RequestResponse>>,
     response_receiver: Option<mpsc::Receiver<RequestResponse>>,
     response_receiver_thread: Option<thread::JoinHandle<()>>,
+    /// Runs bulk executions in the background.
+    bulk_executor: BulkExecutor,
+    /// Runs runbooks in the background.
+    runbook_executor: RunbookExecutor,
 }
 
 impl CommandHandler {
@@ -162,6 +176,9 @@ impl CommandHandler {
     }
 
     pub fn stop(&mut self) {
+        self.bulk_executor.stop();
+        self.runbook_executor.stop();
+
         if let Some(thread) = self.response_receiver_thread.take() {
             if let Err(_) = self.new_response_sender().send(RequestResponse::stop()) {
                 log::warn!("Couldn't stop thread, it may have already stopped.");
@@ -191,74 +208,72 @@ impl CommandHandler {
     }
 
     pub fn execute(&mut self, host_id: &str, command_id: &str, parameters: &[String]) -> Result<u64, LkError> {
-        let commands = self.commands.lock().map_err(|error| {
-            self.send_state_update(StateUpdateMessage::fatal_error());
-            LkError::from(error)
-        })?;
+        self.executor().execute(host_id, command_id, parameters)
+    }
 
-        let host = self.host_manager.borrow().get_host(host_id);
+    /// Runs the command on multiple hosts according to the rollout strategy. Returns the bulk execution ID.
+    /// Progress can be followed with `get_bulk_execution`.
+    pub fn execute_bulk(&mut self, request: BulkRequest) -> Result<u64, LkError> {
+        let host_ids = bulk::resolve_hosts(&self.hosts_config, &request.host_ids, &request.groups)?;
 
-        if !host.platform.is_set() {
-            log::warn!("[{}] Executing command \"{}\" despite missing platform info", host_id, command_id);
+        for host_id in host_ids.iter() {
+            if self.get_command_for_host(host_id, &request.command_id).is_none() {
+                return Err(LkError::other(format!("Command \"{}\" is not available on host {}", request.command_id, host_id)));
+            }
         }
 
-        let command = &commands[host_id][command_id];
-
+        if !self.bulk_executor.is_running() {
+            let update_sender = self.bulk_executor.start(self.executor());
+            self.host_manager.borrow_mut().add_observer(update_sender);
+        }
 
-        let messages = match get_command_connector_messages(&host, command, parameters) {
-            Ok(messages) => messages,
-            Err(error) => {
-                log::error!("Command failed: {}", error);
-                self.send_state_update(StateUpdateMessage {
-                    host_name: host.name,
-                    display_options: command.get_display_options(),
-                    module_spec: command.get_module_spec(),
-                    command_result: Some(CommandResult::new_error(error.clone())),
-                    ..Default::default()
-                });
+        self.bulk_executor.add(request, host_ids)
+    }
 
-                return Err(error);
-            }
-        };
+    pub fn get_bulk_execution(&self, bulk_id: u64) -> Option<BulkExecution> {
+        self.bulk_executor.get(bulk_id)
+    }
 
-        if !host.settings.contains(&crate::host::HostSetting::UseSudo) && messages.iter().any(|msg| {
-                // checking command instead of relying to module metadata is more accurate
-                let trimmed = msg.trim_start();
-                trimmed.starts_with("sudo ") || trimmed.starts_with("\"sudo\"")
-            }) {
+    /// Hosts that haven't started yet are skipped. Returns false if the bulk execution was not found.
+    pub fn cancel_bulk_execution(&self, bulk_id: u64) -> bool {
+        self.bulk_executor.cancel(bulk_id)
+    }
 
-            log::warn!("[{}][{}] Skipping, sudo required", host_id, command_id);
-            return Err(LkError::other("Sudo is required for this command but is disabled for this host"));
+    /// Runs the runbook steps on the host. Returns the run ID. Progress can be followed with `get_runbook_run`.
+    pub fn run_runbook(
+        &mut self,
+        runbook_id: &str,
+        runbook: &Runbook,
+        host_id: &str,
+        monitor_manager: &MonitorManager,
+    ) -> Result<u64, LkError> {
+        if !self.runbook_executor.is_running() {
+            let update_sender = self.runbook_executor.start();
+            self.host_manager.borrow_mut().add_observer(update_sender);
         }
 
-        let invocation_id = self.next_invocation_id();
+        self.runbook_executor.add(runbook_id, runbook, host_id, self.executor(), monitor_manager.refresher())
+    }
 
-        // Notify host state manager about new command, so it can keep track of pending invocations.
-        self.send_state_update(StateUpdateMessage {
-            host_name: host.name.clone(),
-            display_options: command.get_display_options(),
-            module_spec: command.get_module_spec(),
-            command_result: Some(CommandResult::pending()),
-            invocation_id,
-            ..Default::default()
-        });
+    pub fn get_runbook_run(&self, run_id: u64) -> Option<RunbookRun> {
+        self.runbook_executor.get(run_id)
+    }
 
-        let request_type = match command.get_display_options().action == UIAction::FollowOutput {
-            true => RequestType::CommandFollowOutput { commands: messages },
-            false => RequestType::Command { commands: messages }
-        };
+    /// No more steps are started. Returns false if the run was not found.
+    pub fn cancel_runbook_run(&self, run_id: u64) -> bool {
+        self.runbook_executor.cancel(run_id)
+    }
 
-        // Send request to ConnectionManager.
-        self.send_connector_request(ConnectorRequest {
-            connector_spec: command.get_connector_spec(),
-            source_id: command.get_module_spec().id,
-            host: host.clone(),
-            invocation_id,
-            request_type: request_type,
+    /// Handle for executing commands from other threads.
+    pub(crate) fn executor(&self) -> CommandExecutor {
+        CommandExecutor {
+            commands: self.commands.clone(),
+            host_states: self.host_manager.borrow().get_host_states(),
+            request_sender: self.request_sender.clone().unwrap(),
+            state_update_sender: self.state_update_sender.clone().unwrap(),
             response_sender: self.new_response_sender(),
-        });
-
-        Ok(invocation_id)
+            invocation_id_counter: self.invocation_id_counter.clone(),
+        }
     }
 
     //
@@ -328,9 +343,12 @@ impl CommandHandler {
 
         let invocation_id = self.next_invocation_id();
 
-        match file_handler::read_file(local_file_path) {
-            Ok((mut metadata, contents)) => {
-                let local_file_hash = sha256::hash(&contents);
+        // File can be large, so it's hashed without reading it to memory. Connection manager streams the upload.
+        let file_details = file_handler::read_file_metadata(local_file_path)
+            .and_then(|metadata| Ok((metadata, sha256::hash_file(Path::new(local_file_path))?)));
+
+        match file_details {
+            Ok((mut metadata, local_file_hash)) => {
 
                 if local_file_hash == metadata.remote_file_hash {
                     self.send_state_update(StateUpdateMessage {
@@ -442,7 +460,17 @@ impl CommandHandler {
         let mut command = self.remote_ssh_command(&host);
 
         if self.preferences.sudo_remote_editor {
-            command.argument("sudo");
+            match host.privilege_escalation().unwrap_or_default().interactive_prefix() {
+                Some(prefix) => {
+                    command.argument(prefix);
+                },
+                None => {
+                    // Arguments are joined by ssh, so the command has to be quoted for the remote shell too.
+                    let editor_command = format!("{} {}", self.preferences.remote_text_editor, sh_single_quoted(remote_file_path));
+                    command.arguments(vec![String::from("su"), String::from("root"), String::from("-c"), sh_single_quoted(&editor_command)]);
+                    return command;
+                },
+            }
         }
 
         command.argument(self.preferences.remote_text_editor.clone());
@@ -554,6 +582,11 @@ impl CommandHandler {
                     UIAction::FileBrowser =>
                         Self::process_command_response(command, new_state_update_sender, response),
                     UIAction::TextEditor => {
+                        if response.responses.iter().any(|result| result.as_ref().is_ok_and(|message| message.is_partial)) {
+                            Self::process_transfer_progress(command, new_state_update_sender, response);
+                            continue;
+                        }
+
                         match response.request_type.clone() {
                             RequestType::Download { .. } => {
                                 if preferences.text_editor == crate::configuration::INTERNAL 
@@ -663,6 +696,21 @@ impl CommandHandler {
     }
 
 
+    fn process_transfer_progress(command: &Command, state_update_sender: mpsc::Sender<StateUpdateMessage>, response: RequestResponse) {
+        let Some(Ok(response_message)) = response.responses.first() else {
+            return;
+        };
+
+        state_update_sender.send(StateUpdateMessage {
+            host_name: response.host.name,
+            display_options: command.get_display_options(),
+            module_spec: command.get_module_spec(),
+            command_result: Some(CommandResult::new_partial(&response_message.message, response_message.progress)),
+            invocation_id: response.invocation_id,
+            ..Default::default()
+        }).unwrap();
+    }
+
     fn process_download_for_internal_editor(command: &Command, state_update_sender: mpsc::Sender<StateUpdateMessage>, response: RequestResponse) {
         let message_result = &response.responses[0];
 
@@ -818,6 +866,12 @@ impl CommandHandler {
         file_handler::write_file(local_file_path, new_contents).unwrap();
     }
 
+    /// For contents received in chunks, e.g. from a remote frontend.
+    pub fn write_file_chunk(&mut self, local_file_path: &str, offset: u64, chunk: &[u8], is_final: bool) -> Result<(), LkError> {
+        file_handler::write_file_chunk(local_file_path, offset, chunk, is_final)
+            .map_err(|error| LkError::other(format!("Failed to write file {}: {}", local_file_path, error)))
+    }
+
     pub fn remove_file(&mut self, local_file_path: &String) {
         if file_handler::remove_file(local_file_path).is_ok() {
             log::debug!("Removed file {}", local_file_path);
@@ -845,7 +899,10 @@ impl CommandHandler {
     fn remote_ssh_command(&self, host: &Host) -> ShellCommand {
         let ssh_settings = self.hosts_config.hosts[&host.name].effective.connectors["ssh"].settings.clone();
 
-        let remote_address = if !host.fqdn.is_empty() {
+        let remote_address = if let Some(host_name) = ssh_settings.get("host_name").filter(|host_name| !host_name.is_empty()) {
+            host_name.clone()
+        }
+        else if !host.fqdn.is_empty() {
             host.fqdn.clone()
         }
         else {
@@ -867,10 +924,40 @@ impl CommandHandler {
             command.arguments(vec![String::from("-i"), private_key_path.clone()]);
         }
 
+        // Jump hosts are defined in a separate configuration file so that every hop uses its own keys and
+        // the same known_hosts as the connector. User's own configuration still applies to the target.
+        match ssh::parse_jump_hosts(&ssh_settings) {
+            Ok(jump_hosts) if !jump_hosts.is_empty() => {
+                let config = ssh::jump_hosts_to_ssh_config(&jump_hosts, &ssh_settings);
+                match Self::write_jump_host_config(host, &config) {
+                    Ok(config_path) => {
+                        let proxy_command = format!(
+                            "ProxyCommand=ssh -F {} -W '[%h]:%p' {}",
+                            sh_single_quoted(&config_path), ssh::jump_host_alias(jump_hosts.len())
+                        );
+                        command.arguments(vec![String::from("-o"), proxy_command]);
+                    },
+                    Err(error) => log::error!("[{}] Failed to write jump host configuration: {}", host.name, error),
+                }
+            },
+            Ok(_) => {},
+            Err(error) => log::error!("[{}] {}", host.name, error),
+        }
+
         command.argument(remote_address);
         command
     }
 
+    /// Overwritten every time, so there's always at most one file per host.
+    fn write_jump_host_config(host: &Host, config: &str) -> std::io::Result<String> {
+        let config_dir = file_handler::get_cache_dir().join("ssh");
+        std::fs::create_dir_all(&config_dir)?;
+
+        let config_path = config_dir.join(format!("{}.config", host.name));
+        std::fs::write(&config_path, config)?;
+        Ok(config_path.to_string_lossy().to_string())
+    }
+
     pub fn interrupt_invocation(&self, invocation_id: u64) {
         self.send_connector_request(ConnectorRequest {
             connector_spec: None,
@@ -898,6 +985,128 @@ impl CommandHandler {
     }
 }
 
+/// Thread-safe handle for executing commands. Host data is read directly from host states.
+#[derive(Clone)]
+pub(crate) struct CommandExecutor {
+    commands: Arc<Mutex<HashMap<String, HashMap<String, Command>>>>,
+    host_states: Arc<Mutex<HostStateCollection>>,
+    request_sender: mpsc::Sender<ConnectorRequest>,
+    state_update_sender: mpsc::Sender<StateUpdateMessage>,
+    response_sender: mpsc::Sender<RequestResponse>,
+    invocation_id_counter: Arc<AtomicU64>,
+}
+
+impl CommandExecutor {
+    pub fn has_command(&self, host_id: &str, command_id: &str) -> bool {
+        self.commands.lock().is_ok_and(|commands| {
+            commands.get(host_id).is_some_and(|host_commands| host_commands.contains_key(command_id))
+        })
+    }
+
+    pub fn execute(&self, host_id: &str, command_id: &str, parameters: &[String]) -> Result<u64, LkError> {
+        let commands = self.commands.lock().map_err(|error| {
+            self.send_state_update(StateUpdateMessage::fatal_error());
+            LkError::from(error)
+        })?;
+
+        let host = self.host_states.lock().map_err(LkError::from)?.hosts.get(host_id)
+            .map(|host_state| host_state.host.clone())
+            .ok_or_else(|| LkError::other_p("Host not found", host_id))?;
+
+        if !host.platform.is_set() {
+            log::warn!("[{}] Executing command \"{}\" despite missing platform info", host_id, command_id);
+        }
+
+        let command = commands.get(host_id).and_then(|host_commands| host_commands.get(command_id))
+            .ok_or_else(|| LkError::other_p("Command not found", command_id))?;
+
+
+        let request_type = match get_command_request_type(&host, command, parameters) {
+            Ok(request_type) => request_type,
+            Err(error) => {
+                log::error!("Command failed: {}", error);
+                self.send_state_update(StateUpdateMessage {
+                    host_name: host.name,
+                    display_options: command.get_display_options(),
+                    module_spec: command.get_module_spec(),
+                    command_result: Some(CommandResult::new_error(error.clone())),
+                    ..Default::default()
+                });
+
+                return Err(error);
+            }
+        };
+
+        if host.privilege_escalation().is_none() && request_type.uses_sudo() {
+            log::warn!("[{}][{}] Skipping, sudo required", host_id, command_id);
+            return Err(LkError::other("Sudo is required for this command but is disabled for this host"));
+        }
+
+        let invocation_id = self.next_invocation_id();
+
+        // Notify host state manager about new command, so it can keep track of pending invocations.
+        self.send_state_update(StateUpdateMessage {
+            host_name: host.name.clone(),
+            display_options: command.get_display_options(),
+            module_spec: command.get_module_spec(),
+            command_result: Some(CommandResult::pending()),
+            invocation_id,
+            ..Default::default()
+        });
+
+        // Send request to ConnectionManager.
+        self.send_connector_request(ConnectorRequest {
+            connector_spec: command.get_connector_spec(),
+            source_id: command.get_module_spec().id,
+            host: host.clone(),
+            invocation_id,
+            request_type,
+            response_sender: self.response_sender.clone(),
+        });
+
+        Ok(invocation_id)
+    }
+
+    fn next_invocation_id(&self) -> u64 {
+        self.invocation_id_counter.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn send_connector_request(&self, request: ConnectorRequest) {
+        if let Err(error) = self.request_sender.send(request) {
+            log::error!("Failed to send connector request: {}", error);
+            self.send_state_update(StateUpdateMessage::fatal_error());
+        }
+    }
+
+    fn send_state_update(&self, message: StateUpdateMessage) {
+        if let Err(error) = self.state_update_sender.send(message) {
+            log::error!("Failed to send state update message: {}", error);
+        }
+    }
+}
+
+/// Socket request of the command if it has one, otherwise its connector messages.
+fn get_command_request_type(host: &Host, command: &Command, parameters: &[String]) -> Result<RequestType, LkError> {
+    let follow_output = command.get_display_options().action == UIAction::FollowOutput;
+
+    match command.get_socket_request(host.clone(), parameters.to_owned()) {
+        Ok(request) => {
+            return match follow_output {
+                true => Ok(RequestType::SocketRequestFollowOutput { request }),
+                false => Ok(RequestType::SocketRequest { request }),
+            };
+        },
+        Err(error) if error.kind != ErrorKind::NotImplemented => return Err(error.set_source(command.get_module_spec().id)),
+        Err(_) => {},
+    }
+
+    let commands = get_command_connector_messages(host, command, parameters)?;
+    match follow_output {
+        true => Ok(RequestType::CommandFollowOutput { commands }),
+        false => Ok(RequestType::Command { commands }),
+    }
+}
+
 /// NOTE: Panics are not handled gracefully since this runs in main UI thread.
 /// get_connector_message and get_connector_messages should never panic.
 fn get_command_connector_messages(host: &Host, command: &Command, parameters: &[String]) -> Result<Vec<String>, LkError> {
@@ -0,0 +1,425 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use super::CommandExecutor;
+use crate::configuration::Hosts;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::frontend::{HostDisplayData, UIUpdate};
+use crate::module::command::CommandResult;
+
+//
+// Bulk execution runs the same command on multiple hosts in batches.
+// Progress is followed from host state updates in a separate thread, which also starts the next hosts.
+//
+
+/// Finished bulk executions are kept around for viewing results. Oldest ones are removed after this.
+const MAX_FINISHED_EXECUTIONS: usize = 20;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum RolloutStrategy {
+    /// All hosts in a single batch. Only limited by `max_concurrency`.
+    #[default]
+    Parallel,
+    /// One host at a time.
+    Serial,
+    /// The given number of hosts is run first. The rest are run only if none of them failed.
+    Canary { hosts: usize },
+    /// Batches of the given percentage of all hosts.
+    Percentage { percent: u8 },
+}
+
+/// Parses `parallel`, `serial`, `canary:<hosts>` or `percent:<percent>`.
+impl FromStr for RolloutStrategy {
+    type Err = LkError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match value.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (value, None),
+        };
+
+        let argument = argument.map(|argument| argument.parse::<usize>().ok());
+
+        match (name, argument) {
+            ("parallel", None) => Ok(RolloutStrategy::Parallel),
+            ("serial", None) => Ok(RolloutStrategy::Serial),
+            ("canary", Some(Some(hosts))) if hosts > 0 => Ok(RolloutStrategy::Canary { hosts }),
+            ("percent", Some(Some(percent @ 1..=100))) => Ok(RolloutStrategy::Percentage { percent: percent as u8 }),
+            _ => Err(LkError::other_p("Invalid rollout strategy", value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BulkRequest {
+    pub command_id: String,
+    pub parameters: Vec<String>,
+    pub host_ids: Vec<String>,
+    /// Hosts in these groups are also included.
+    pub groups: Vec<String>,
+    pub strategy: RolloutStrategy,
+    /// Maximum number of hosts running at the same time. 0 means no limit.
+    pub max_concurrency: usize,
+    /// Don't start any more hosts after the first failure.
+    pub stop_on_failure: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostExecutionState {
+    Waiting,
+    Running,
+    Succeeded,
+    Failed,
+    /// Not run because the bulk execution was stopped.
+    Skipped,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HostExecution {
+    pub host_id: String,
+    pub batch: usize,
+    pub state: HostExecutionState,
+    /// 0 until started.
+    pub invocation_id: u64,
+    pub result: Option<CommandResult>,
+    pub error: String,
+    /// Invocation has been seen in progress. Needed for noticing invocations that end without a result.
+    #[serde(skip)]
+    seen_running: bool,
+}
+
+impl HostExecution {
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, HostExecutionState::Succeeded | HostExecutionState::Failed | HostExecutionState::Skipped)
+    }
+}
+
+/// Aggregated state of a bulk execution.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulkExecution {
+    pub bulk_id: u64,
+    pub request: BulkRequest,
+    /// In execution order.
+    pub hosts: Vec<HostExecution>,
+    /// No more hosts will be started. Set after cancelling or after a failure that stops the rollout.
+    pub stopped: bool,
+}
+
+impl BulkExecution {
+    pub fn new(bulk_id: u64, request: BulkRequest, host_ids: Vec<String>) -> Self {
+        let host_count = host_ids.len();
+        let hosts = host_ids.into_iter().enumerate()
+            .map(|(index, host_id)| HostExecution {
+                host_id,
+                batch: Self::batch_of(&request.strategy, index, host_count),
+                state: HostExecutionState::Waiting,
+                invocation_id: 0,
+                result: None,
+                error: String::new(),
+                seen_running: false,
+            })
+            .collect();
+
+        BulkExecution {
+            bulk_id,
+            request,
+            hosts,
+            stopped: false,
+        }
+    }
+
+    fn batch_of(strategy: &RolloutStrategy, index: usize, host_count: usize) -> usize {
+        match strategy {
+            RolloutStrategy::Parallel => 0,
+            RolloutStrategy::Serial => index,
+            RolloutStrategy::Canary { hosts } => if index < (*hosts).max(1) { 0 } else { 1 },
+            RolloutStrategy::Percentage { percent } => {
+                let batch_size = (host_count * (*percent).clamp(1, 100) as usize).div_ceil(100).max(1);
+                index / batch_size
+            },
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.hosts.iter().all(|host| host.is_done())
+    }
+
+    pub fn count(&self, state: HostExecutionState) -> usize {
+        self.hosts.iter().filter(|host| host.state == state).count()
+    }
+
+    /// Batch that is currently running or next in line.
+    pub fn current_batch(&self) -> Option<usize> {
+        self.hosts.iter().filter(|host| !host.is_done()).map(|host| host.batch).min()
+    }
+
+    /// Skips hosts that haven't been started yet.
+    pub fn stop(&mut self) {
+        self.stopped = true;
+        for host in self.hosts.iter_mut().filter(|host| host.state == HostExecutionState::Waiting) {
+            host.state = HostExecutionState::Skipped;
+        }
+    }
+
+    /// Starts hosts of the current batch as far as concurrency allows.
+    fn start_ready(&mut self, executor: &CommandExecutor) {
+        loop {
+            if self.stopped {
+                self.stop();
+                return;
+            }
+
+            let Some(batch) = self.current_batch() else {
+                return;
+            };
+
+            let mut running = self.count(HostExecutionState::Running);
+            let mut started_any = false;
+
+            for index in 0..self.hosts.len() {
+                if self.hosts[index].batch != batch || self.hosts[index].state != HostExecutionState::Waiting {
+                    continue;
+                }
+
+                if self.request.max_concurrency > 0 && running >= self.request.max_concurrency {
+                    break;
+                }
+
+                started_any = true;
+                let host = &mut self.hosts[index];
+                match executor.execute(&host.host_id, &self.request.command_id, &self.request.parameters) {
+                    Ok(invocation_id) => {
+                        host.state = HostExecutionState::Running;
+                        host.invocation_id = invocation_id;
+                        running += 1;
+                    },
+                    Err(error) => {
+                        log::error!("[{}] Bulk execution {} failed to start: {}", host.host_id, self.bulk_id, error);
+                        host.error = error.to_string();
+                        self.finish(index, HostExecutionState::Failed);
+                    }
+                }
+            }
+
+            // If nothing is left running, the batch failed to start completely and the next one can be tried.
+            if running > 0 || !started_any {
+                return;
+            }
+        }
+    }
+
+    /// Returns true if state changed.
+    fn process_update(&mut self, display_data: &HostDisplayData) -> bool {
+        let host_state = &display_data.host_state;
+        let mut changed = false;
+
+        for index in 0..self.hosts.len() {
+            let host = &mut self.hosts[index];
+            if host.state != HostExecutionState::Running || host.host_id != host_state.host.name {
+                continue;
+            }
+
+            match &display_data.new_command_result {
+                Some((invocation_id, result)) if *invocation_id == host.invocation_id && result.progress >= 100 => {
+                    let failed = result.criticality >= Criticality::Error && result.criticality != Criticality::NotAvailable;
+                    host.error = result.error.clone();
+                    host.result = Some(result.clone());
+                    self.finish(index, if failed { HostExecutionState::Failed } else { HostExecutionState::Succeeded });
+                    changed = true;
+                },
+                _ => {
+                    if host_state.command_invocations.contains_key(&host.invocation_id) {
+                        host.seen_running = true;
+                    }
+                    else if host.seen_running {
+                        host.error = display_data.new_errors.iter()
+                            .map(|error| error.message.clone())
+                            .collect::<Vec<_>>()
+                            .join(". ");
+                        if host.error.is_empty() {
+                            host.error = String::from("Command ended without a result");
+                        }
+                        self.finish(index, HostExecutionState::Failed);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        changed
+    }
+
+    fn finish(&mut self, index: usize, state: HostExecutionState) {
+        let host = &mut self.hosts[index];
+        host.state = state;
+        log::debug!("[{}] Bulk execution {}: {:?}", host.host_id, self.bulk_id, state);
+
+        if state == HostExecutionState::Failed {
+            let is_canary = matches!(self.request.strategy, RolloutStrategy::Canary { .. }) && host.batch == 0;
+            if self.request.stop_on_failure || is_canary {
+                log::warn!("Bulk execution {} stopped after failure on host {}", self.bulk_id, host.host_id);
+                self.stop();
+            }
+        }
+    }
+}
+
+/// Hosts given by name come first in the given order, then hosts of the groups.
+pub fn resolve_hosts(hosts_config: &Hosts, host_ids: &[String], groups: &[String]) -> Result<Vec<String>, LkError> {
+    let mut resolved = Vec::<String>::new();
+
+    for host_id in host_ids {
+        if !hosts_config.hosts.contains_key(host_id) {
+            return Err(LkError::other_p("Unknown host", host_id));
+        }
+        if !resolved.contains(host_id) {
+            resolved.push(host_id.clone());
+        }
+    }
+
+    for group in groups {
+        let members = hosts_config.hosts.iter()
+            .filter(|(_, host_settings)| host_settings.groups.contains(group))
+            .map(|(host_id, _)| host_id)
+            .collect::<Vec<_>>();
+
+        if members.is_empty() {
+            return Err(LkError::other_p("No hosts in group", group));
+        }
+
+        for host_id in members {
+            if !resolved.contains(host_id) {
+                resolved.push(host_id.clone());
+            }
+        }
+    }
+
+    if resolved.is_empty() {
+        return Err(LkError::other("No hosts selected"));
+    }
+
+    Ok(resolved)
+}
+
+/// Keeps track of bulk executions and advances them as host states change.
+#[derive(Default)]
+pub(crate) struct BulkExecutor {
+    executions: Arc<Mutex<BTreeMap<u64, BulkExecution>>>,
+    executor: Option<CommandExecutor>,
+    /// Valid IDs begin from 1.
+    bulk_id_counter: u64,
+    update_sender: Option<mpsc::Sender<UIUpdate>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl BulkExecutor {
+    pub fn is_running(&self) -> bool {
+        self.thread.is_some()
+    }
+
+    /// Returns a sender that should be registered as an observer in HostManager.
+    pub fn start(&mut self, executor: CommandExecutor) -> mpsc::Sender<UIUpdate> {
+        let (update_sender, update_receiver) = mpsc::channel();
+        let executions = self.executions.clone();
+        let thread_executor = executor.clone();
+
+        self.executor = Some(executor);
+        self.update_sender = Some(update_sender.clone());
+        self.thread = Some(thread::spawn(move || {
+            Self::process_updates(executions, thread_executor, update_receiver);
+        }));
+
+        update_sender
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(update_sender) = self.update_sender.take() {
+            if update_sender.send(UIUpdate::Stop()).is_err() {
+                log::warn!("Bulk execution thread has already stopped");
+            }
+        }
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Error while waiting for bulk execution thread");
+            }
+        }
+
+        self.executor = None;
+    }
+
+    /// Starts the first hosts immediately. Returns the bulk execution ID.
+    pub fn add(&mut self, request: BulkRequest, host_ids: Vec<String>) -> Result<u64, LkError> {
+        let executor = self.executor.as_ref().ok_or_else(|| LkError::other("Bulk execution is not available"))?;
+        let mut executions = self.executions.lock()?;
+
+        let finished = executions.values().filter(|execution| execution.is_finished()).map(|execution| execution.bulk_id).collect::<Vec<_>>();
+        for bulk_id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED_EXECUTIONS - 1)) {
+            executions.remove(bulk_id);
+        }
+
+        self.bulk_id_counter += 1;
+        let bulk_id = self.bulk_id_counter;
+        log::info!("Starting bulk execution {} of \"{}\" on {} host(s)", bulk_id, request.command_id, host_ids.len());
+
+        let mut execution = BulkExecution::new(bulk_id, request, host_ids);
+        execution.start_ready(executor);
+        executions.insert(bulk_id, execution);
+        Ok(bulk_id)
+    }
+
+    pub fn get(&self, bulk_id: u64) -> Option<BulkExecution> {
+        self.executions.lock().ok()?.get(&bulk_id).cloned()
+    }
+
+    pub fn cancel(&self, bulk_id: u64) -> bool {
+        let Ok(mut executions) = self.executions.lock() else {
+            return false;
+        };
+
+        match executions.get_mut(&bulk_id) {
+            Some(execution) => {
+                execution.stop();
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn process_updates(
+        executions: Arc<Mutex<BTreeMap<u64, BulkExecution>>>,
+        executor: CommandExecutor,
+        receiver: mpsc::Receiver<UIUpdate>,
+    ) {
+        loop {
+            let display_data = match receiver.recv() {
+                Ok(UIUpdate::Host(display_data)) => display_data,
+                Ok(UIUpdate::Stop()) => break,
+                Ok(_) => continue,
+                Err(error) => {
+                    log::error!("Stopped bulk execution thread: {}", error);
+                    break;
+                }
+            };
+
+            let mut executions = executions.lock().unwrap();
+            for execution in executions.values_mut().filter(|execution| !execution.is_finished()) {
+                if execution.process_update(&display_data) {
+                    execution.start_ready(&executor);
+                }
+            }
+        }
+
+        log::debug!("Stopped bulk execution thread");
+    }
+}
@@ -0,0 +1,584 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::CommandExecutor;
+use crate::configuration::{Runbook, RunbookAction, RunbookCondition};
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::frontend::{HostDisplayData, UIUpdate};
+use crate::module::command::CommandResult;
+use crate::monitor_manager::MonitorRefresher;
+
+//
+// Runbook runs execute the steps of a runbook on a single host.
+// Progress is followed from host state updates in a separate thread, which also handles timeouts and conditions.
+//
+
+/// Finished runs are kept around for viewing results. Oldest ones are removed after this.
+const MAX_FINISHED_RUNS: usize = 20;
+/// How often timeouts and conditions are checked when there are no updates.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunState {
+    Running,
+    Succeeded,
+    /// A step failed and there was nothing to roll back.
+    Failed,
+    RollingBack,
+    RolledBack,
+    RollbackFailed,
+    Cancelled,
+}
+
+impl RunState {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, RunState::Running | RunState::RollingBack)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepState {
+    Waiting,
+    Running,
+    WaitingForCondition,
+    Succeeded,
+    Failed,
+    TimedOut,
+    /// Not run because an earlier step failed or the run was cancelled.
+    Skipped,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StepProgress {
+    pub name: String,
+    /// Empty for steps that only wait for a condition.
+    pub command_id: String,
+    pub state: StepState,
+    /// 0 if no command has been started.
+    pub invocation_id: u64,
+    pub result: Option<CommandResult>,
+    /// Error or condition status.
+    pub message: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl StepProgress {
+    fn new(name: &str, command_id: &str) -> Self {
+        StepProgress {
+            name: name.to_string(),
+            command_id: command_id.to_string(),
+            state: StepState::Waiting,
+            invocation_id: 0,
+            result: None,
+            message: String::new(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunbookRun {
+    pub run_id: u64,
+    pub runbook_id: String,
+    pub host_id: String,
+    pub state: RunState,
+    pub steps: Vec<StepProgress>,
+    /// Filled when rollback begins.
+    pub rollback: Vec<StepProgress>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl RunbookRun {
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+}
+
+/// Monitor states collected while waiting for a condition.
+struct ConditionCheck {
+    condition: RunbookCondition,
+    next_refresh: Instant,
+    /// Latest criticality of each monitor. Only values from our own refreshes are accepted.
+    criticalities: HashMap<String, Criticality>,
+    /// Refresh invocations and their monitors.
+    invocations: HashMap<u64, String>,
+}
+
+impl ConditionCheck {
+    fn is_fulfilled(&self) -> bool {
+        self.condition.monitors.iter().all(|monitor_id| {
+            self.criticalities.get(monitor_id).is_some_and(|criticality| *criticality <= self.condition.criticality)
+        })
+    }
+
+    fn status(&self) -> String {
+        self.condition.monitors.iter()
+            .map(|monitor_id| match self.criticalities.get(monitor_id) {
+                Some(criticality) => format!("{}: {}", monitor_id, criticality),
+                None => format!("{}: waiting", monitor_id),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The step or rollback action that is currently running.
+struct ActiveItem {
+    deadline: Instant,
+    /// Invocation has been seen in progress. Needed for noticing invocations that end without a result.
+    seen_running: bool,
+    /// Set after the command phase has finished.
+    condition: Option<ConditionCheck>,
+}
+
+struct ActiveRun {
+    run: RunbookRun,
+    runbook: Runbook,
+    executor: CommandExecutor,
+    refresher: MonitorRefresher,
+    rollback_actions: Vec<RunbookAction>,
+    /// Index to `steps` or `rollback` depending on the run state.
+    current: usize,
+    item: Option<ActiveItem>,
+}
+
+impl ActiveRun {
+    /// Starts items until one is left running or the run finishes.
+    fn advance(&mut self) {
+        while self.item.is_none() && !self.run.is_finished() {
+            match self.run.state {
+                RunState::Running => self.start_step(),
+                RunState::RollingBack => self.start_rollback_action(),
+                _ => return,
+            }
+        }
+    }
+
+    fn start_step(&mut self) {
+        let Some(step) = self.runbook.steps.get(self.current).cloned() else {
+            self.finish_run(RunState::Succeeded);
+            return;
+        };
+
+        log::info!("[{}] Runbook \"{}\": starting step \"{}\"", self.run.host_id, self.run.runbook_id, step.name);
+        self.run.steps[self.current].started_at = Some(Utc::now());
+        self.run.steps[self.current].state = StepState::Running;
+        self.item = Some(ActiveItem {
+            deadline: Instant::now() + Duration::from_secs(step.timeout),
+            seen_running: false,
+            condition: None,
+        });
+
+        if step.command.is_empty() {
+            self.command_finished(None);
+        }
+        else {
+            match self.executor.execute(&self.run.host_id, &step.command, &step.parameters) {
+                Ok(invocation_id) => self.run.steps[self.current].invocation_id = invocation_id,
+                Err(error) => self.finish_item(StepState::Failed, error.to_string()),
+            }
+        }
+    }
+
+    fn start_rollback_action(&mut self) {
+        let Some(action) = self.rollback_actions.get(self.current).cloned() else {
+            self.finish_run(RunState::RolledBack);
+            return;
+        };
+
+        log::info!("[{}] Runbook \"{}\": rolling back with \"{}\"", self.run.host_id, self.run.runbook_id, action.command);
+        self.run.rollback[self.current].started_at = Some(Utc::now());
+        self.run.rollback[self.current].state = StepState::Running;
+        self.item = Some(ActiveItem {
+            deadline: Instant::now() + Duration::from_secs(action.timeout),
+            seen_running: false,
+            condition: None,
+        });
+
+        match self.executor.execute(&self.run.host_id, &action.command, &action.parameters) {
+            Ok(invocation_id) => self.run.rollback[self.current].invocation_id = invocation_id,
+            Err(error) => self.finish_item(StepState::Failed, error.to_string()),
+        }
+    }
+
+    fn current_progress(&mut self) -> &mut StepProgress {
+        match self.run.state {
+            RunState::RollingBack => &mut self.run.rollback[self.current],
+            _ => &mut self.run.steps[self.current],
+        }
+    }
+
+    /// Moves on to the condition if there is one.
+    fn command_finished(&mut self, result: Option<CommandResult>) {
+        let condition = match self.run.state {
+            RunState::Running => self.runbook.steps[self.current].wait_for.clone(),
+            _ => None,
+        };
+
+        let progress = self.current_progress();
+        progress.result = result;
+
+        match condition {
+            Some(condition) => {
+                progress.state = StepState::WaitingForCondition;
+                if let Some(item) = self.item.as_mut() {
+                    item.condition = Some(ConditionCheck {
+                        next_refresh: Instant::now() + Duration::from_secs(condition.delay),
+                        condition,
+                        criticalities: HashMap::new(),
+                        invocations: HashMap::new(),
+                    });
+                }
+            },
+            None => self.finish_item(StepState::Succeeded, String::new()),
+        }
+    }
+
+    fn finish_item(&mut self, state: StepState, message: String) {
+        self.item = None;
+        let progress = self.current_progress();
+        progress.state = state;
+        progress.finished_at = Some(Utc::now());
+        if !message.is_empty() {
+            progress.message = message;
+        }
+        let name = progress.name.clone();
+        log::debug!("[{}] Runbook \"{}\": \"{}\" {:?}", self.run.host_id, self.run.runbook_id, name, state);
+
+        if state == StepState::Succeeded {
+            self.current += 1;
+        }
+        else if self.run.state == RunState::Running {
+            self.skip_remaining();
+            self.begin_rollback();
+        }
+        else {
+            log::error!("[{}] Runbook \"{}\": rollback failed", self.run.host_id, self.run.runbook_id);
+            self.finish_run(RunState::RollbackFailed);
+        }
+    }
+
+    /// Rollback actions of the failed step and all earlier steps are run in reverse order.
+    fn begin_rollback(&mut self) {
+        let steps = &self.runbook.steps[..=self.current];
+        self.rollback_actions = steps.iter().rev().flat_map(|step| step.rollback.clone()).collect();
+        self.run.rollback = steps.iter().rev()
+            .flat_map(|step| step.rollback.iter().map(|action| StepProgress::new(&step.name, &action.command)))
+            .collect();
+
+        if self.rollback_actions.is_empty() {
+            self.finish_run(RunState::Failed);
+        }
+        else {
+            log::warn!("[{}] Runbook \"{}\" failed, rolling back", self.run.host_id, self.run.runbook_id);
+            self.run.state = RunState::RollingBack;
+            self.current = 0;
+        }
+    }
+
+    fn skip_remaining(&mut self) {
+        for progress in self.run.steps.iter_mut().chain(self.run.rollback.iter_mut()) {
+            if progress.state == StepState::Waiting {
+                progress.state = StepState::Skipped;
+            }
+        }
+    }
+
+    fn finish_run(&mut self, state: RunState) {
+        self.skip_remaining();
+        self.item = None;
+        self.run.state = state;
+        self.run.finished_at = Some(Utc::now());
+        log::info!("[{}] Runbook \"{}\" finished: {:?}", self.run.host_id, self.run.runbook_id, state);
+    }
+
+    fn cancel(&mut self) {
+        if self.run.is_finished() {
+            return;
+        }
+
+        if self.item.is_some() {
+            self.item = None;
+            let progress = self.current_progress();
+            progress.state = StepState::Skipped;
+            progress.message = String::from("Cancelled");
+            progress.finished_at = Some(Utc::now());
+        }
+
+        self.finish_run(RunState::Cancelled);
+    }
+
+    fn process_update(&mut self, display_data: &HostDisplayData) {
+        let host_state = &display_data.host_state;
+        if host_state.host.name != self.run.host_id {
+            return;
+        }
+
+        let invocation_id = self.current_progress().invocation_id;
+        let Some(item) = self.item.as_mut() else {
+            return;
+        };
+
+        if let Some(check) = item.condition.as_mut() {
+            let Some((update_invocation_id, _)) = &display_data.new_monitoring_data else {
+                return;
+            };
+
+            if let Some(monitor_id) = check.invocations.remove(update_invocation_id) {
+                let criticality = host_state.monitor_data.get(&monitor_id)
+                    .and_then(|monitoring_data| monitoring_data.values.back())
+                    .map(|data_point| data_point.criticality)
+                    .unwrap_or(Criticality::NoData);
+                check.criticalities.insert(monitor_id, criticality);
+
+                let (fulfilled, status) = (check.is_fulfilled(), check.status());
+                if fulfilled {
+                    self.finish_item(StepState::Succeeded, status);
+                }
+                else {
+                    self.current_progress().message = status;
+                }
+            }
+            return;
+        }
+
+        match &display_data.new_command_result {
+            Some((update_invocation_id, result)) if *update_invocation_id == invocation_id && result.progress >= 100 => {
+                if result.criticality >= Criticality::Error && result.criticality != Criticality::NotAvailable {
+                    let message = if result.error.is_empty() { String::from("Command failed") } else { result.error.clone() };
+                    self.current_progress().result = Some(result.clone());
+                    self.finish_item(StepState::Failed, message);
+                }
+                else {
+                    self.command_finished(Some(result.clone()));
+                }
+            },
+            _ => {
+                if host_state.command_invocations.contains_key(&invocation_id) {
+                    item.seen_running = true;
+                }
+                else if item.seen_running {
+                    let mut message = display_data.new_errors.iter()
+                        .map(|error| error.message.clone())
+                        .collect::<Vec<_>>()
+                        .join(". ");
+                    if message.is_empty() {
+                        message = String::from("Command ended without a result");
+                    }
+                    self.finish_item(StepState::Failed, message);
+                }
+            }
+        }
+    }
+
+    /// Handles timeouts and refreshes monitors of conditions.
+    fn tick(&mut self, now: Instant) {
+        let Some(item) = self.item.as_mut() else {
+            return;
+        };
+
+        if now >= item.deadline {
+            let message = match item.condition.as_ref() {
+                Some(check) => format!("Timed out waiting for monitors ({})", check.status()),
+                None => String::from("Timed out"),
+            };
+            log::warn!("[{}] Runbook \"{}\": {}", self.run.host_id, self.run.runbook_id, message);
+            self.finish_item(StepState::TimedOut, message);
+            return;
+        }
+
+        let Some(check) = item.condition.as_mut() else {
+            return;
+        };
+
+        if now >= check.next_refresh {
+            check.next_refresh = now + Duration::from_secs(check.condition.refresh_interval.max(1));
+            for monitor_id in check.condition.monitors.iter() {
+                for invocation_id in self.refresher.refresh(&self.run.host_id, monitor_id) {
+                    check.invocations.insert(invocation_id, monitor_id.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Keeps track of runbook runs and advances them as host states change.
+#[derive(Default)]
+pub(crate) struct RunbookExecutor {
+    runs: Arc<Mutex<BTreeMap<u64, ActiveRun>>>,
+    /// Valid IDs begin from 1.
+    run_id_counter: u64,
+    update_sender: Option<mpsc::Sender<UIUpdate>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl RunbookExecutor {
+    pub fn is_running(&self) -> bool {
+        self.thread.is_some()
+    }
+
+    /// Returns a sender that should be registered as an observer in HostManager.
+    pub fn start(&mut self) -> mpsc::Sender<UIUpdate> {
+        let (update_sender, update_receiver) = mpsc::channel();
+        let runs = self.runs.clone();
+
+        self.update_sender = Some(update_sender.clone());
+        self.thread = Some(thread::spawn(move || {
+            Self::process_updates(runs, update_receiver);
+        }));
+
+        update_sender
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(update_sender) = self.update_sender.take() {
+            if update_sender.send(UIUpdate::Stop()).is_err() {
+                log::warn!("Runbook thread has already stopped");
+            }
+        }
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Error while waiting for runbook thread");
+            }
+        }
+    }
+
+    /// Checks that all commands and monitors are available and starts the first step. Returns the run ID.
+    pub fn add(
+        &mut self,
+        runbook_id: &str,
+        runbook: &Runbook,
+        host_id: &str,
+        executor: CommandExecutor,
+        refresher: MonitorRefresher,
+    ) -> Result<u64, LkError> {
+        if !self.is_running() {
+            return Err(LkError::other("Runbooks are not available"));
+        }
+
+        validate(runbook, host_id, &executor, &refresher)?;
+
+        let mut runs = self.runs.lock()?;
+        let finished = runs.values().filter(|active| active.run.is_finished()).map(|active| active.run.run_id).collect::<Vec<_>>();
+        for run_id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED_RUNS - 1)) {
+            runs.remove(run_id);
+        }
+
+        self.run_id_counter += 1;
+        let run_id = self.run_id_counter;
+        log::info!("[{}] Starting runbook \"{}\" (run {})", host_id, runbook_id, run_id);
+
+        let mut active = ActiveRun {
+            run: RunbookRun {
+                run_id,
+                runbook_id: runbook_id.to_string(),
+                host_id: host_id.to_string(),
+                state: RunState::Running,
+                steps: runbook.steps.iter().map(|step| StepProgress::new(&step.name, &step.command)).collect(),
+                rollback: Vec::new(),
+                started_at: Utc::now(),
+                finished_at: None,
+            },
+            runbook: runbook.clone(),
+            executor,
+            refresher,
+            rollback_actions: Vec::new(),
+            current: 0,
+            item: None,
+        };
+
+        active.advance();
+        runs.insert(run_id, active);
+        Ok(run_id)
+    }
+
+    pub fn get(&self, run_id: u64) -> Option<RunbookRun> {
+        self.runs.lock().ok()?.get(&run_id).map(|active| active.run.clone())
+    }
+
+    /// Running command is left to finish, but no more steps or rollback actions are started.
+    pub fn cancel(&self, run_id: u64) -> bool {
+        let Ok(mut runs) = self.runs.lock() else {
+            return false;
+        };
+
+        match runs.get_mut(&run_id) {
+            Some(active) => {
+                active.cancel();
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn process_updates(runs: Arc<Mutex<BTreeMap<u64, ActiveRun>>>, receiver: mpsc::Receiver<UIUpdate>) {
+        loop {
+            let display_data = match receiver.recv_timeout(TICK_INTERVAL) {
+                Ok(UIUpdate::Host(display_data)) => Some(display_data),
+                Ok(UIUpdate::Stop()) => break,
+                Ok(_) => continue,
+                Err(mpsc::RecvTimeoutError::Timeout) => None,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log::error!("Stopped runbook thread: host updates stopped");
+                    break;
+                }
+            };
+
+            let now = Instant::now();
+            let mut runs = runs.lock().unwrap();
+            for active in runs.values_mut().filter(|active| !active.run.is_finished()) {
+                if let Some(display_data) = display_data.as_ref() {
+                    active.process_update(display_data);
+                }
+                active.tick(now);
+                active.advance();
+            }
+        }
+
+        log::debug!("Stopped runbook thread");
+    }
+}
+
+fn validate(runbook: &Runbook, host_id: &str, executor: &CommandExecutor, refresher: &MonitorRefresher) -> Result<(), LkError> {
+    if runbook.steps.is_empty() {
+        return Err(LkError::other("Runbook has no steps"));
+    }
+
+    for step in runbook.steps.iter() {
+        if step.command.is_empty() && step.wait_for.is_none() {
+            return Err(LkError::other_p("Step has no command or condition", &step.name));
+        }
+
+        let commands = std::iter::once(&step.command).filter(|command| !command.is_empty())
+            .chain(step.rollback.iter().map(|action| &action.command));
+        for command_id in commands {
+            if !executor.has_command(host_id, command_id) {
+                return Err(LkError::other(format!("Command \"{}\" is not available on host {}", command_id, host_id)));
+            }
+        }
+
+        let monitors = step.wait_for.iter().flat_map(|condition| condition.monitors.iter());
+        for monitor_id in monitors {
+            if !refresher.has_monitor(host_id, monitor_id) {
+                return Err(LkError::other(format!("Monitor \"{}\" is not available on host {}", monitor_id, host_id)));
+            }
+        }
+    }
+
+    Ok(())
+}
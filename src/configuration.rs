@@ -5,6 +5,7 @@
 
 use std::collections::BTreeMap;
 use std::io::Write;
+use std::net::Ipv4Addr;
 use std::os::unix::prelude::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::{collections::HashMap, fs, io};
@@ -12,18 +13,21 @@ use std::{collections::HashMap, fs, io};
 use serde::{Deserialize, Serialize};
 use serde_yaml;
 
-use crate::enums::EditMode;
+use crate::enums::{Criticality, EditMode};
 use crate::file_handler;
 use crate::host::HostSetting;
 use crate::module::PlatformInfo;
+use crate::module::connection::ssh::ssh_config;
 use crate::secrets_manager;
 
 const MAIN_CONFIG_FILE: &str = "config.yml";
 const HOSTS_FILE: &str = "hosts.yml";
 const GROUPS_FILE: &str = "groups.yml";
+const RUNBOOKS_FILE: &str = "runbooks.yml";
 pub const DEFAULT_GROUPS_CONFIG: &str = include_str!("../groups.example.yml");
 pub const DEFAULT_MAIN_CONFIG: &str = include_str!("../config.example.yml");
 pub const DEFAULT_HOSTS_CONFIG: &str = include_str!("../hosts.example.yml");
+pub const EXAMPLE_RUNBOOKS_CONFIG: &str = include_str!("../runbooks.example.yml");
 pub const INTERNAL: &str = "internal";
 pub const INTERNAL_SIMPLE: &str = "internal-simple";
 pub const MIGRATION_VERSION: u16 = 5;
@@ -35,6 +39,14 @@ pub struct Configuration {
     pub preferences: Preferences,
     #[serde(default)]
     pub display_options: DisplayOptions,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub alerting: Alerting,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub exporter: Exporter,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub audit_log: AuditLog,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub connections: Connections,
     // Obsolete field:
     #[serde(default, skip_serializing_if = "Configuration::always")]
     pub cache_settings: Option<serde_yaml::Value>,
@@ -191,6 +203,188 @@ pub struct ChartCategory {
     pub monitors: Vec<String>,
 }
 
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Alerting {
+    /// Lowest criticality that triggers an alert.
+    #[serde(default = "Alerting::default_min_criticality")]
+    pub min_criticality: Criticality,
+    /// Resend unresolved alerts after this many seconds. 0 disables repeating.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub repeat_interval: u64,
+    /// Send a notification when an alert returns back to normal.
+    #[serde(default = "DisplayOptions::default_to_true")]
+    pub send_resolved: bool,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub sinks: Vec<AlertSinkConfig>,
+}
+
+impl Alerting {
+    fn default_min_criticality() -> Criticality {
+        Criticality::Warning
+    }
+}
+
+impl Default for Alerting {
+    fn default() -> Self {
+        Alerting {
+            min_criticality: Alerting::default_min_criticality(),
+            repeat_interval: 0,
+            send_resolved: true,
+            sinks: Vec::new(),
+        }
+    }
+}
+
+/// Prometheus exporter. Only used by lightkeeper-core.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Exporter {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address and port for the HTTP listener that serves `/metrics`.
+    #[serde(default = "Exporter::default_listen_address")]
+    pub listen_address: String,
+}
+
+/// Record of every request sent to hosts. Stored as JSON lines in the data directory.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AuditLog {
+    #[serde(default = "DisplayOptions::default_to_true")]
+    pub enabled: bool,
+    /// Log file is rotated when it grows larger than this. In megabytes.
+    #[serde(default = "AuditLog::default_max_file_size")]
+    pub max_file_size: u64,
+    /// Number of log files to keep, including the current one.
+    #[serde(default = "AuditLog::default_max_files")]
+    pub max_files: u32,
+}
+
+impl AuditLog {
+    fn default_max_file_size() -> u64 {
+        10
+    }
+
+    fn default_max_files() -> u32 {
+        10
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        AuditLog {
+            enabled: true,
+            max_file_size: AuditLog::default_max_file_size(),
+            max_files: AuditLog::default_max_files(),
+        }
+    }
+}
+
+/// Limits for requests sent to hosts.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Connections {
+    /// Number of requests processed at the same time, i.e. number of worker threads. Has to be at least 1.
+    #[serde(default = "Connections::default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Number of requests processed at the same time for a single host. The rest are queued. 0 means no limit.
+    #[serde(default = "Connections::default_max_concurrent_requests_per_host")]
+    pub max_concurrent_requests_per_host: usize,
+    /// Timeout for monitor and command requests in seconds. 0 disables the timeout.
+    /// Modules can have their own default and it can be overridden per module with the `request_timeout` setting.
+    #[serde(default = "Connections::default_timeout")]
+    pub default_timeout: u64,
+}
+
+impl Connections {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent_requests == 0 {
+            return Err(String::from("connections.max_concurrent_requests has to be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn default_max_concurrent_requests() -> usize {
+        6
+    }
+
+    fn default_max_concurrent_requests_per_host() -> usize {
+        3
+    }
+
+    fn default_timeout() -> u64 {
+        300
+    }
+}
+
+impl Default for Connections {
+    fn default() -> Self {
+        Connections {
+            max_concurrent_requests: Connections::default_max_concurrent_requests(),
+            max_concurrent_requests_per_host: Connections::default_max_concurrent_requests_per_host(),
+            default_timeout: Connections::default_timeout(),
+        }
+    }
+}
+
+impl Exporter {
+    fn default_listen_address() -> String {
+        String::from("127.0.0.1:9860")
+    }
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Exporter {
+            enabled: false,
+            listen_address: Exporter::default_listen_address(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum AlertSinkConfig {
+    /// Runs a local command. Alert details are passed in environment variables.
+    Command {
+        command: String,
+        #[serde(default, skip_serializing_if = "Configuration::is_default")]
+        args: Vec<String>,
+    },
+    Smtp {
+        server: String,
+        #[serde(default, skip_serializing_if = "Configuration::is_default")]
+        port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        #[serde(default, skip_serializing_if = "Configuration::is_default")]
+        username: String,
+        /// Can also be a keyring placeholder.
+        #[serde(default, skip_serializing_if = "Configuration::is_default")]
+        password: String,
+        from: String,
+        to: Vec<String>,
+    },
+    /// Sends the alert as JSON in a POST request.
+    Webhook {
+        url: String,
+        #[serde(default, skip_serializing_if = "Configuration::is_default")]
+        headers: BTreeMap<String, String>,
+    },
+    /// Desktop notification through the session bus.
+    Dbus,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    None,
+    #[default]
+    StartTls,
+    Tls,
+}
+
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct HostSettings {
@@ -237,9 +431,37 @@ pub struct ConfigGroup {
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub host_settings: Vec<HostSetting>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub config_helper: ConfigHelperData,
 }
 
+/// Time period when alerts are suppressed. Either one-off (`start` and `end`)
+/// or recurring (`schedule` and `duration`).
+#[derive(Serialize, Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindow {
+    /// RFC 3339 timestamp, e.g. "2026-01-31T22:00:00+02:00".
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub start: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub end: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Cron-like schedule in local time: minute, hour, day of month, month, day of week.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub schedule: String,
+    /// Length of a recurring window in minutes.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub duration: u32,
+    /// Affected monitors. Empty means all monitors.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub monitors: Vec<String>,
+    /// Affected multivalue labels. Empty means all labels.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub comment: String,
+}
+
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct ConfigHelperData {
     pub ignored_commands: Vec<String>,
@@ -255,6 +477,24 @@ impl HostSettings {
     pub fn is_default_address(address: &String) -> bool {
         address.is_empty() || address == "0.0.0.0"
     }
+
+    /// IP address and FQDN of the host. Hosts without an address of their own, e.g. ones defined only
+    /// by their ssh_config alias, use the host name of the ssh connector.
+    pub fn effective_address(&self) -> (String, String) {
+        if !Self::is_default_address(&self.address) || !self.fqdn.is_empty() {
+            return (self.address.clone(), self.fqdn.clone());
+        }
+
+        let ssh_host_name = self.effective.connectors.get("ssh")
+            .and_then(|ssh_config| ssh_config.settings.get("host_name"))
+            .filter(|host_name| !host_name.is_empty());
+
+        match ssh_host_name {
+            Some(host_name) if host_name.parse::<Ipv4Addr>().is_ok() => (host_name.clone(), String::new()),
+            Some(host_name) => (self.address.clone(), host_name.clone()),
+            None => (self.address.clone(), self.fqdn.clone()),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -269,6 +509,9 @@ pub struct MonitorConfig {
     pub enabled: Option<bool>,
     #[serde(skip_serializing_if = "Configuration::is_default")]
     pub is_critical: Option<bool>,
+    /// Refresh interval in seconds. If not set, monitor is only refreshed on demand.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub interval: Option<u64>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub settings: HashMap<String, String>,
 }
@@ -293,6 +536,7 @@ impl Default for MonitorConfig {
             version: MonitorConfig::default_version(),
             enabled: MonitorConfig::default_enabled(),
             is_critical: None,
+            interval: None,
             settings: HashMap::new(),
         }
     }
@@ -340,6 +584,84 @@ pub struct ConnectorConfig {
     pub settings: HashMap<String, String>,
 }
 
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Runbooks {
+    #[serde(default)]
+    pub runbooks: BTreeMap<String, Runbook>,
+}
+
+/// Steps are run in order on a single host. After a failure, rollback actions of the failed and earlier steps
+/// are run in reverse order.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Runbook {
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub description: String,
+    pub steps: Vec<RunbookStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RunbookStep {
+    pub name: String,
+    /// Command module ID. Can be left out if the step only waits for a condition.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub parameters: Vec<String>,
+    /// Checked after the command has succeeded.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub wait_for: Option<RunbookCondition>,
+    /// Time limit for the whole step, including the condition. In seconds.
+    #[serde(default = "RunbookStep::default_timeout")]
+    pub timeout: u64,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub rollback: Vec<RunbookAction>,
+}
+
+impl RunbookStep {
+    fn default_timeout() -> u64 {
+        300
+    }
+}
+
+/// Fulfilled when all monitors have been refreshed and none of them is worse than the given criticality.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RunbookCondition {
+    pub monitors: Vec<String>,
+    #[serde(default = "RunbookCondition::default_criticality")]
+    pub criticality: Criticality,
+    /// Time to wait before the first check, e.g. to let a reboot begin. In seconds.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub delay: u64,
+    /// How often the monitors are refreshed while waiting. In seconds.
+    #[serde(default = "RunbookCondition::default_refresh_interval")]
+    pub refresh_interval: u64,
+}
+
+impl RunbookCondition {
+    fn default_criticality() -> Criticality {
+        Criticality::Normal
+    }
+
+    fn default_refresh_interval() -> u64 {
+        10
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RunbookAction {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub parameters: Vec<String>,
+    /// In seconds.
+    #[serde(default = "RunbookStep::default_timeout")]
+    pub timeout: u64,
+}
+
 impl Configuration {
     #![cfg_attr(any(), rustfmt::skip)]
     pub fn read(config_dir: &str) -> io::Result<(Configuration, Hosts, Groups)> {
@@ -383,6 +705,9 @@ impl Configuration {
         let mut main_config = serde_yaml::from_str::<Configuration>(config_contents.as_str())
             .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))?;
 
+        main_config.connections.validate()
+            .map_err(io::Error::other)?;
+
         // Display options are currently defined in the app's defaults and not really user-configurable.
         let mut actual_display_options = get_default_main_config().display_options;
 
@@ -414,8 +739,9 @@ impl Configuration {
         let mut secrets = secrets_manager::SecretsManager::new();
 
         // Merge config groups to form the final, effective config.
-        for (_, host_config) in hosts.hosts.iter_mut() {
+        for (host_id, host_config) in hosts.hosts.iter_mut() {
             host_config.effective = Self::get_effective_group_config(host_config, &all_groups.groups);
+            Self::apply_ssh_config(host_id, host_config);
 
             // Old, deprecated host overrides.
             let old_overrides = ConfigGroup {
@@ -424,6 +750,7 @@ impl Configuration {
                 connectors: host_config.connectors.clone(),
                 custom_commands: Vec::new(),
                 host_settings: host_config.settings.clone(),
+                maintenance_windows: Vec::new(),
                 config_helper: Default::default(),
             };
 
@@ -455,6 +782,26 @@ impl Configuration {
         Ok((main_config, hosts, all_groups))
     }
 
+    /// Runbooks are optional, so a missing file is not an error.
+    pub fn read_runbooks(config_dir: &str) -> io::Result<Runbooks> {
+        let config_dir = if config_dir.is_empty() {
+            file_handler::get_config_dir()
+        }
+        else {
+            Path::new(config_dir).to_path_buf()
+        };
+
+        let runbooks_file_path = config_dir.join(RUNBOOKS_FILE);
+        if fs::metadata(&runbooks_file_path).is_err() {
+            return Ok(Runbooks::default());
+        }
+
+        log::info!("Reading runbooks from {}", runbooks_file_path.display());
+        let runbooks_contents = fs::read_to_string(runbooks_file_path)?;
+        serde_yaml::from_str::<Runbooks>(runbooks_contents.as_str())
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))
+    }
+
     fn resolve_secrets_in_effective_config(
         effective: &mut ConfigGroup,
         secrets: &mut secrets_manager::SecretsManager,
@@ -517,6 +864,7 @@ impl Configuration {
             connectors: host_config.connectors.clone(),
             custom_commands: Vec::new(),
             host_settings: host_config.settings.clone(),
+            maintenance_windows: Vec::new(),
             config_helper: Default::default(),
         };
 
@@ -526,6 +874,23 @@ impl Configuration {
         effective_config
     }
 
+    /// Fills missing ssh connector settings in the effective config from the OpenSSH client configuration, if enabled.
+    /// Also, tcp-connect checks the SSH port if it doesn't have a port set.
+    pub fn apply_ssh_config(host_id: &str, host_config: &mut HostSettings) {
+        let has_address = !HostSettings::is_default_address(&host_config.address) || !host_config.fqdn.is_empty();
+        let Some(ssh_config) = host_config.effective.connectors.get_mut("ssh") else {
+            return;
+        };
+
+        ssh_config::apply(host_id, has_address, &mut ssh_config.settings);
+
+        if let Some(port) = ssh_config.settings.get("port").cloned() {
+            if let Some(tcp_connect) = host_config.effective.monitors.get_mut("tcp-connect") {
+                tcp_connect.settings.entry(String::from("port")).or_insert(port);
+            }
+        }
+    }
+
     /// Merges configuration groups, second parameter will overwrite conflicting contents from first.
     pub fn merge_group_config(first_config: &ConfigGroup, second_config: &ConfigGroup) -> ConfigGroup {
         let mut result = first_config.clone();
@@ -535,6 +900,9 @@ impl Configuration {
             merged_config.settings.extend(new_config.settings.clone());
             merged_config.enabled = new_config.enabled.clone();
             merged_config.is_critical = new_config.is_critical;
+            if new_config.interval.is_some() {
+                merged_config.interval = new_config.interval;
+            }
             result.monitors.insert(monitor_id.clone(), merged_config);
         });
 
@@ -555,6 +923,10 @@ impl Configuration {
             result.custom_commands.push(new_config.clone());
         });
 
+        second_config.maintenance_windows.iter().for_each(|new_config| {
+            result.maintenance_windows.push(new_config.clone());
+        });
+
         if second_config.host_settings.len() > 0 {
             result.host_settings = second_config.host_settings.clone();
         }
@@ -782,6 +1154,10 @@ impl Configuration {
                     preferences: config.preferences.clone(),
                     cache_settings: config.cache_settings.clone(),
                     display_options: actual_display_options,
+                    alerting: config.alerting.clone(),
+                    exporter: config.exporter.clone(),
+                    audit_log: config.audit_log.clone(),
+                    connections: config.connections.clone(),
                     schema_version: config.schema_version.clone(),
                     config_errors: Vec::new(),
                 };
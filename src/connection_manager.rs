@@ -13,25 +13,81 @@ use std::{
     thread,
 };
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::error::LkError;
+use crate::audit_log::{AuditLogger, AuditRecord, AuditRequestType};
+use crate::error::{ErrorKind, LkError};
 use crate::module::monitoring::DataPoint;
 use crate::monitor_manager::CERT_MONITOR_HOST_ID;
+use crate::monitor_manager::command_batch::BatchedMonitor;
 use crate::Host;
-use crate::configuration::{ConfigGroup, HostSettings, Hosts};
+use crate::host_manager::StateUpdateMessage;
+use crate::configuration::{self, ConfigGroup, HostSettings, Hosts};
 use crate::file_handler::{self, FileMetadata};
 use crate::module::{ModuleFactory, ModuleSpecification, ModuleType};
 use crate::module::connection::*;
-use crate::utils::{normalize_line, ShellCommand};
+use crate::utils::{format_bytes, normalize_line, PrivilegeEscalation, ShellCommand};
 
 use self::request_response::RequestResponse;
 
+pub mod connection_state;
+pub use connection_state::{ConnectionState, ConnectionTracker, RequestStart};
+pub mod request_limits;
+use request_limits::{HostQueue, Job, RequestLimits, TimeoutGuard, Watchdog};
+
 
 type ConnectorStates = HashMap<ModuleSpecification, Connector>;
 
+/// Shared by the request processing jobs.
+struct WorkerContext {
+    worker_pool: rayon::ThreadPool,
+    host_queue: HostQueue,
+    stateful_connectors: Arc<Mutex<HashMap<String, ConnectorStates>>>,
+    module_factory: Arc<ModuleFactory>,
+    interrupt_pending: Arc<Mutex<Vec<u64>>>,
+    audit_log: Option<Arc<AuditLogger>>,
+    connection_tracker: Arc<ConnectionTracker>,
+    watchdog: Arc<Watchdog>,
+    limits: RequestLimits,
+    /// Interruptible requests in progress. Key is any invocation ID of the request and value is the
+    /// connector and the invocation ID it knows the request by.
+    running_requests: Mutex<HashMap<u64, (Connector, u64)>>,
+}
+
+impl WorkerContext {
+    /// Interrupts for any invocation of the request are passed to the connector until `unregister_running` is called.
+    fn register_running(&self, request: &ConnectorRequest, connector: &Connector) {
+        let mut running_requests = self.running_requests.lock().unwrap();
+        for invocation_id in request.invocation_ids() {
+            running_requests.insert(invocation_id, (connector.box_clone(), request.invocation_id));
+        }
+    }
 
-const MAX_WORKER_THREADS: usize = 6;
+    fn unregister_running(&self, request: &ConnectorRequest) {
+        let mut running_requests = self.running_requests.lock().unwrap();
+        for invocation_id in request.invocation_ids() {
+            running_requests.remove(&invocation_id);
+        }
+    }
+
+    /// Returns false if the invocation is not a running interruptible request.
+    fn interrupt_running(&self, invocation_id: u64) -> bool {
+        let Some((connector, request_invocation_id)) = self.running_requests.lock().unwrap()
+            .get(&invocation_id)
+            .map(|(connector, request_invocation_id)| (connector.box_clone(), *request_invocation_id)) else {
+            return false;
+        };
+
+        // Connectors may block while interrupting.
+        thread::spawn(move || {
+            if let Err(error) = connector.interrupt(request_invocation_id) {
+                log::error!("Failed to interrupt invocation {}: {}", invocation_id, error);
+            }
+        });
+        true
+    }
+}
 
 
 // Default needs to be implemented because of Qt QObject requirements.
@@ -47,6 +103,9 @@ pub struct ConnectionManager {
     request_sender_prototype: Option<mpsc::Sender<ConnectorRequest>>,
     receiver_thread: Option<thread::JoinHandle<()>>,
     interrupt_pending: Option<Arc<Mutex<Vec<u64>>>>,
+    audit_log: Option<Arc<AuditLogger>>,
+    connection_tracker: Arc<ConnectionTracker>,
+    limits: RequestLimits,
 }
 
 impl ConnectionManager {
@@ -104,6 +163,9 @@ impl ConnectionManager {
                 .collect::<BTreeMap<String, HostSettings>>()
         };
 
+        // Connectors are re-created for new and changed hosts.
+        self.connection_tracker.retain(|host_id| stateful_connectors.contains_key(host_id) && !new_host_configs.contains_key(host_id));
+
         // For regular host monitoring.
         for (host_id, host_config) in new_host_configs {
             let host_connectors = stateful_connectors.entry(host_id.clone()).or_insert(HashMap::new());
@@ -159,6 +221,9 @@ impl ConnectionManager {
             }
         }
 
+        self.limits.set_module_timeouts(hosts_config);
+        self.limits.set_module_default_timeouts(&self.module_factory);
+
         self.current_config = hosts_config.hosts.iter()
             .map(|(host_id, config)| (host_id.clone(), config.effective.clone()))
             .collect();
@@ -168,6 +233,25 @@ impl ConnectionManager {
         self.request_sender_prototype = Some(sender);
     }
 
+    /// Requests are recorded in the audit log if set. Takes effect when processing is started.
+    pub fn set_audit_log(&mut self, audit_log: Option<Arc<AuditLogger>>) {
+        self.audit_log = audit_log;
+    }
+
+    /// Concurrency limits and default timeout. Takes effect when processing is started.
+    pub fn set_limits(&mut self, config: &configuration::Connections) {
+        self.limits.set_config(config);
+    }
+
+    /// Connection state changes are sent to the host manager. Has to be set again after the host manager is re-configured.
+    pub fn set_state_update_sender(&self, sender: mpsc::Sender<StateUpdateMessage>) {
+        self.connection_tracker.set_state_update_sender(sender);
+    }
+
+    pub fn get_connection_state(&self, host_name: &str) -> ConnectionState {
+        self.connection_tracker.get(host_name)
+    }
+
     pub fn new_request_sender(&mut self) -> mpsc::Sender<ConnectorRequest> {
         self.request_sender_prototype.as_ref().unwrap().clone()
     }
@@ -179,6 +263,9 @@ impl ConnectionManager {
             self.request_receiver.take().unwrap(),
             self.module_factory.clone(),
             interrupt_pending,
+            self.audit_log.clone(),
+            self.connection_tracker.clone(),
+            self.limits.clone(),
         );
         self.receiver_thread = Some(thread);
     }
@@ -209,34 +296,55 @@ impl ConnectionManager {
         receiver: mpsc::Receiver<ConnectorRequest>,
         module_factory: Arc<ModuleFactory>,
         interrupt_pending: Arc<Mutex<Vec<u64>>>,
+        audit_log: Option<Arc<AuditLogger>>,
+        connection_tracker: Arc<ConnectionTracker>,
+        limits: RequestLimits,
     ) -> thread::JoinHandle<()> {
 
         thread::spawn(move || {
-            let worker_pool = rayon::ThreadPoolBuilder::new().num_threads(MAX_WORKER_THREADS).build()
+            let worker_pool = rayon::ThreadPoolBuilder::new().num_threads(limits.max_concurrent_requests).build()
                 .expect("Failed to create connection worker pool");
 
-            log::debug!("Created worker pool with {} threads", MAX_WORKER_THREADS);
+            log::debug!("Created worker pool with {} threads", worker_pool.current_num_threads());
+
+            let watchdog = Arc::new(Watchdog::default());
+            let watchdog_thread = watchdog.start();
+            let context = Arc::new(WorkerContext {
+                worker_pool,
+                host_queue: HostQueue::new(limits.max_concurrent_requests_per_host),
+                stateful_connectors,
+                module_factory,
+                interrupt_pending,
+                audit_log,
+                connection_tracker,
+                watchdog,
+                limits,
+                running_requests: Mutex::new(HashMap::new()),
+            });
 
             loop {
-                let stateful_connectors = stateful_connectors.clone();
-                let module_factory = module_factory.clone();
-                let interrupt_pending = interrupt_pending.clone();
-
                 let request = match receiver.recv() {
                     Ok(data) => data,
                     Err(error) => {
                         log::error!("Stopped receiver thread: {}", error);
+                        context.watchdog.stop();
                         return;
                     }
                 };
 
                 if let RequestType::Exit = request.request_type {
                     log::debug!("Gracefully stopping request processing");
+                    context.watchdog.stop();
+                    if let Err(error) = watchdog_thread.join() {
+                        log::error!("Error in thread: {:?}", error);
+                    }
                     return;
                 }
 
                 if let RequestType::Interrupt { invocation_id } = request.request_type {
-                    interrupt_pending.lock().unwrap().push(invocation_id);
+                    if !context.interrupt_running(invocation_id) {
+                        context.interrupt_pending.lock().unwrap().push(invocation_id);
+                    }
                     if let Err(error) = request.response_sender.send(RequestResponse::new_empty(&request)) {
                         log::error!("Failed to send response: {}", error);
                     }
@@ -255,107 +363,271 @@ impl ConnectionManager {
                     }
                 };
 
-                worker_pool.spawn(move || {
-                    log::debug!("[{}][{}] Worker {} processing a request",
-                        request.host.name, request.source_id, rayon::current_thread_index().unwrap_or_default());
+                let host_name = request.host.name.clone();
+                let job = Self::new_job(context.clone(), connector_spec, request);
+                Self::submit_job(context.clone(), host_name, job);
+            }
+        })
+    }
 
-                    let connector_metadata = module_factory.get_connector_module_metadata(&connector_spec);
+    fn new_job(context: Arc<WorkerContext>, connector_spec: ModuleSpecification, request: ConnectorRequest) -> Job {
+        Box::new(move || {
+            log::debug!("[{}][{}] Worker {} processing a request",
+                request.host.name, request.source_id, rayon::current_thread_index().unwrap_or_default());
 
-                    let connector = {
-                        let stateful_connectors = stateful_connectors.lock().unwrap();
+            let connector_metadata = context.module_factory.get_connector_module_metadata(&connector_spec);
 
-                        // Stateless connectors.
-                        if connector_metadata.is_stateless {
-                            match module_factory.new_connector(&connector_spec, &HashMap::new()) {
-                                Some(connector) => connector,
-                                None => return,
-                            }
-                        }
-                        // Stateful connectors.
-                        else {
-                            match stateful_connectors.get(&request.host.name)
-                                .and_then(|host_connectors| host_connectors.get(&connector_spec))
-                            {
-                                Some(connector) => connector.box_clone(),
-                                None => {
-                                    log::error!("[{}][{}] host connection is not configured", request.host.name, request.source_id);
-                                    return;
-                                }
-                            }
-                        }
-                    };
+            let connector = {
+                let stateful_connectors = context.stateful_connectors.lock().unwrap();
 
-                    connector.set_target(&request.host.get_address());
+                // Stateless connectors.
+                if connector_metadata.is_stateless {
+                    match context.module_factory.new_connector(&connector_spec, &HashMap::new()) {
+                        Some(connector) => connector,
+                        None => return,
+                    }
+                }
+                // Stateful connectors.
+                else {
+                    match stateful_connectors.get(&request.host.name)
+                        .and_then(|host_connectors| host_connectors.get(&connector_spec))
+                    {
+                        Some(connector) => connector.box_clone(),
+                        None => {
+                            log::error!("[{}][{}] host connection is not configured", request.host.name, request.source_id);
+                            return;
+                        }
+                    }
+                }
+            };
 
-                    // Key verifications have to be done before anything else.
-                    match request.request_type {
-                        RequestType::KeyVerification { ref key_id } => {
-                            log::debug!("[{}] Verifying host key", request.host.name);
+            connector.set_target(&request.host.get_address());
 
-                            if let Err(error) = connector.verify_host_key(&request.host.get_address(), &key_id) {
-                                let response = RequestResponse::new(
-                                    &request,
-                                    vec![Err(error.set_source(connector.get_module_spec().id))],
-                                );
+            // Key verifications have to be done before anything else.
+            match request.request_type {
+                RequestType::KeyVerification { ref key_id } => {
+                    log::debug!("[{}] Verifying host key", request.host.name);
 
-                                if let Err(error) = request.response_sender.send(response) {
-                                    log::error!("Failed to send response: {}", error);
-                                    return;
-                                }
-                            }
+                    if let Err(error) = connector.verify_host_key(&request.host.get_address(), &key_id) {
+                        let response = RequestResponse::new(
+                            &request,
+                            vec![Err(error.set_source(connector.get_module_spec().id))],
+                        );
 
+                        if let Err(error) = request.response_sender.send(response) {
+                            log::error!("Failed to send response: {}", error);
                             return;
-                        },
-                        _ => {}
+                        }
                     }
 
-                    let responses = match &request.request_type {
-                        RequestType::MonitorCommand { extension_monitors: _, parent_datapoint: _, commands } => {
-                            Self::process_commands(&request, &connector, &commands)
-                        },
-                        RequestType::Command { commands } => {
-                            Self::process_commands(&request, &connector, &commands)
-                        },
-                        RequestType::CommandFollowOutput { commands } => {
-                            if let [command] = &commands[..] {
-                                let pending = interrupt_pending.clone();
-                                vec![Self::process_command_follow_output(
-                                    &request,
-                                    &connector,
-                                    command,
-                                    request.response_sender.clone(),
-                                    &pending,
-                                )]
-                            }
-                            else {
-                                vec![Err(LkError::other("Follow output is only supported for a single command"))]
-                            }
-                        },
-                        RequestType::Download { remote_file_path: file_path } =>
-                            vec![Self::process_download(&request.host, &connector, &file_path)],
-                        RequestType::Upload { metadata: _, local_file_path } =>
-                            vec![Self::process_upload(&request.host, &connector, &local_file_path)],
-                        _ => {
-                            log::error!("[{}][{}] Unsupported request type", request.host.name, request.source_id);
-                            vec![Err(LkError::other("Unsupported request type"))]
+                    return;
+                },
+                _ => {}
+            }
+
+            // Stateless connectors don't keep connections open.
+            let is_tracked = if connector_metadata.is_stateless {
+                false
+            }
+            else {
+                let connect_result = match context.connection_tracker.begin_request(&request.host.name) {
+                    Ok(RequestStart::Connected) => Ok(()),
+                    Ok(RequestStart::Probe) => {
+                        let result = connector.connect();
+                        let waiting = context.connection_tracker.end_request(&request.host.name, true, result.as_ref().err());
+                        for job in waiting {
+                            Self::submit_job(context.clone(), request.host.name.clone(), job);
                         }
-                    };
+                        result
+                    },
+                    Ok(RequestStart::ProbeInProgress) => {
+                        // Doesn't hold a worker while waiting. The request is started again when the probe finishes.
+                        let host_name = request.host.name.clone();
+                        let job = Self::new_job(context.clone(), connector_spec, request);
+                        if let Some(job) = context.connection_tracker.wait_for_probe(&host_name, job) {
+                            Self::submit_job(context.clone(), host_name, job);
+                        }
+                        return;
+                    },
+                    Err(error) => Err(error),
+                };
 
-                    let response = RequestResponse::new(&request, responses);
-                    if let Err(error) = request.response_sender.send(response) {
-                        log::error!("Failed to send response: {}", error);
+                match connect_result {
+                    Ok(()) => true,
+                    Err(error) => {
+                        let response = RequestResponse::new(
+                            &request,
+                            vec![Err(error.set_source(connector.get_module_spec().id))],
+                        );
+
+                        if let Err(error) = request.response_sender.send(response) {
+                            log::error!("Failed to send response: {}", error);
+                        }
+                        return;
                     }
-                });
+                }
+            };
+
+            let audit_log = context.audit_log.as_deref();
+            let timeout = Self::watch_timeout(&request, &connector, &context.limits, &context.watchdog);
+            let timeout = timeout.as_ref();
+
+            let responses = match &request.request_type {
+                RequestType::MonitorCommand { extension_monitors: _, parent_datapoint: _, commands } |
+                RequestType::MonitorCommandBatch { monitors: _, delimiter: _, commands } => {
+                    context.register_running(&request, &connector);
+                    let responses = Self::process_commands(&request, &connector, &commands, AuditRequestType::Monitor, audit_log, timeout);
+                    context.unregister_running(&request);
+                    responses
+                },
+                RequestType::MonitorSocketRequest { request: socket_request, .. } => {
+                    vec![Self::process_socket_request(&request, &connector, socket_request, AuditRequestType::Monitor, audit_log, timeout)]
+                },
+                RequestType::Command { commands } => {
+                    context.register_running(&request, &connector);
+                    let responses = Self::process_commands(&request, &connector, &commands, AuditRequestType::Command, audit_log, timeout);
+                    context.unregister_running(&request);
+                    responses
+                },
+                RequestType::SocketRequest { request: socket_request } => {
+                    vec![Self::process_socket_request(&request, &connector, socket_request, AuditRequestType::Command, audit_log, timeout)]
+                },
+                RequestType::CommandFollowOutput { commands } => {
+                    if let [command] = &commands[..] {
+                        log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, command);
+                        let command = Self::escalate(&request.host, command);
+                        let started = Utc::now();
+                        let response = connector.send_message_partial(&command, request.invocation_id);
+                        let result = Self::process_follow_output(
+                            &request,
+                            &connector,
+                            response,
+                            request.response_sender.clone(),
+                            &context.interrupt_pending,
+                        );
+
+                        Self::audit(audit_log, &request, &connector, AuditRequestType::FollowOutput, &command, started, &result);
+                        vec![result]
+                    }
+                    else {
+                        vec![Err(LkError::other("Follow output is only supported for a single command"))]
+                    }
+                },
+                RequestType::SocketRequestFollowOutput { request: socket_request } => {
+                    log::debug!("[{}][{}] Socket request: {}", request.host.name, request.source_id, socket_request);
+                    let socket_request = Self::escalate_socket_request(&request.host, socket_request);
+                    let started = Utc::now();
+                    let response = connector.send_socket_request_partial(&socket_request, request.invocation_id);
+                    let result = Self::process_follow_output(
+                        &request,
+                        &connector,
+                        response,
+                        request.response_sender.clone(),
+                        &context.interrupt_pending,
+                    );
+
+                    Self::audit(audit_log, &request, &connector, AuditRequestType::FollowOutput, &socket_request.to_string(), started, &result);
+                    vec![result]
+                },
+                RequestType::Download { remote_file_path: file_path } => {
+                    let started = Utc::now();
+                    let result = Self::process_download(&request, &connector, &file_path, &context.interrupt_pending);
+                    Self::audit(audit_log, &request, &connector, AuditRequestType::Download, file_path, started, &result);
+                    vec![result]
+                },
+                RequestType::Upload { metadata, local_file_path } => {
+                    let started = Utc::now();
+                    let result = Self::process_upload(&request, &connector, &local_file_path, metadata, &context.interrupt_pending);
+                    Self::audit(audit_log, &request, &connector, AuditRequestType::Upload, &metadata.remote_path, started, &result);
+                    vec![result]
+                },
+                _ => {
+                    log::error!("[{}][{}] Unsupported request type", request.host.name, request.source_id);
+                    vec![Err(LkError::other("Unsupported request type"))]
+                }
+            };
+
+            if is_tracked {
+                let error = responses.iter().find_map(|response| response.as_ref().err());
+                context.connection_tracker.end_request(&request.host.name, false, error);
+            }
+
+            let mut response = RequestResponse::new(&request, responses);
+            if let RequestType::MonitorCommand { .. } | RequestType::MonitorSocketRequest { .. } = request.request_type {
+                response.connection_details = connector.get_connection_details();
+            }
+
+            if let Err(error) = request.response_sender.send(response) {
+                log::error!("Failed to send response: {}", error);
             }
         })
     }
 
+    /// Runs the job right away or after other jobs for the host have finished.
+    fn submit_job(context: Arc<WorkerContext>, host_name: String, job: Job) {
+        if let Some(job) = context.host_queue.submit(&host_name, job) {
+            Self::run_job(context, host_name, job);
+        }
+    }
+
+    /// Runs the job in the worker pool and then the next job queued for the host, if any.
+    fn run_job(context: Arc<WorkerContext>, host_name: String, job: Job) {
+        context.clone().worker_pool.spawn(move || {
+            job();
+
+            if let Some(next_job) = context.host_queue.finish(&host_name) {
+                Self::run_job(context, host_name, next_job);
+            }
+        });
+    }
+
+    /// Interrupts monitor and command requests that don't finish in time.
+    /// Follow-output commands and file transfers are expected to take long, so they don't have a timeout.
+    fn watch_timeout(
+        request: &ConnectorRequest,
+        connector: &Connector,
+        limits: &RequestLimits,
+        watchdog: &Arc<Watchdog>,
+    ) -> Option<TimeoutGuard> {
+
+        let timeout = match &request.request_type {
+            RequestType::MonitorCommand { .. } | RequestType::MonitorSocketRequest { .. } |
+            RequestType::Command { .. } | RequestType::SocketRequest { .. } => {
+                limits.timeout(&request.host.name, &[&request.source_id])
+            },
+            RequestType::MonitorCommandBatch { monitors, .. } => {
+                let monitor_ids = monitors.iter().map(|monitor| monitor.monitor_id.as_str()).collect::<Vec<_>>();
+                limits.timeout(&request.host.name, &monitor_ids)
+            },
+            _ => None,
+        }?;
+
+        let connector = connector.box_clone();
+        let invocation_id = request.invocation_id;
+        let log_prefix = request.log_prefix();
+
+        Some(watchdog.watch(timeout, move || {
+            log::warn!("{} Interrupting request after {} seconds", log_prefix, timeout.as_secs());
+            if let Err(error) = connector.interrupt(invocation_id) {
+                log::error!("{} Failed to interrupt request: {}", log_prefix, error);
+            }
+        }))
+    }
+
     fn process_commands(request: &ConnectorRequest,
                         connector: &Connector,
-                        request_messages: &Vec<String>) -> Vec<Result<ResponseMessage, LkError>> {
+                        request_messages: &Vec<String>,
+                        request_type: AuditRequestType,
+                        audit_log: Option<&AuditLogger>,
+                        timeout: Option<&TimeoutGuard>) -> Vec<Result<ResponseMessage, LkError>> {
 
         let mut results = Vec::new();
         for request_message in request_messages {
+            if let Some(timeout) = timeout.filter(|timeout| timeout.is_expired()) {
+                results.push(Err(timeout.error().set_source(connector.get_module_spec().id)));
+                break;
+            }
+
             // Some commands are supposed to not actually execute.
             if request_message.is_empty() {
                 log::debug!("[{}][{}] Ignoring empty command", request.host.name, request.source_id);
@@ -365,7 +637,18 @@ impl ConnectionManager {
                 log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, request_message);
             }
 
-            let response_result = connector.send_message(request_message);
+            let request_message = Self::escalate(&request.host, request_message);
+            let started = Utc::now();
+            let response_result = connector.send_message_interruptible(&request_message, request.invocation_id)
+                .map_err(|error| match timeout.filter(|timeout| timeout.is_expired()) {
+                    // Error of the interrupted command is replaced with a clearer one.
+                    Some(timeout) => timeout.error(),
+                    None => error,
+                });
+
+            if !request_message.is_empty() {
+                Self::audit(audit_log, request, connector, request_type, &request_message, started, &response_result);
+            }
 
             if let Ok(response) = response_result {
                 if response.return_code != 0 {
@@ -386,16 +669,96 @@ impl ConnectionManager {
         results
     }
 
-    fn process_command_follow_output(
+    fn process_socket_request(request: &ConnectorRequest,
+                              connector: &Connector,
+                              socket_request: &UnixSocketRequest,
+                              request_type: AuditRequestType,
+                              audit_log: Option<&AuditLogger>,
+                              timeout: Option<&TimeoutGuard>) -> Result<ResponseMessage, LkError> {
+
+        log::debug!("[{}][{}] Socket request: {}", request.host.name, request.source_id, socket_request);
+        let socket_request = Self::escalate_socket_request(&request.host, socket_request);
+        let started = Utc::now();
+        let response_result = connector.send_socket_request(&socket_request)
+            .map_err(|error| match timeout.filter(|timeout| timeout.is_expired()) {
+                Some(timeout) => timeout.error(),
+                None => error,
+            });
+
+        Self::audit(audit_log, request, connector, request_type, &socket_request.to_string(), started, &response_result);
+
+        match response_result {
+            Ok(response) => {
+                if response.return_code != 0 {
+                    log::warn!("[{}][{}] Socket request returned non-zero exit code: {}",
+                        request.host.name, request.source_id, response.return_code);
+                }
+                Ok(response)
+            },
+            Err(error) => Err(error.set_source(connector.get_module_spec().id)),
+        }
+    }
+
+    /// Failures are only logged so that the audit log never blocks requests.
+    fn audit(
+        audit_log: Option<&AuditLogger>,
+        request: &ConnectorRequest,
+        connector: &Connector,
+        request_type: AuditRequestType,
+        target: &str,
+        started: DateTime<Utc>,
+        result: &Result<ResponseMessage, LkError>,
+    ) {
+        let Some(audit_log) = audit_log else {
+            return;
+        };
+
+        let record = AuditRecord {
+            time: started,
+            user: audit_log.user().to_string(),
+            host: request.host.name.clone(),
+            connector: connector.get_module_spec().id,
+            source_id: request.source_id.clone(),
+            invocation_id: request.invocation_id,
+            batched_monitors: request.batched_monitors(),
+            request_type,
+            target: target.to_string(),
+            return_code: result.as_ref().ok().map(|response| response.return_code),
+            error: result.as_ref().err().map(|error| error.to_string()),
+            duration_ms: (Utc::now() - started).num_milliseconds().max(0) as u64,
+        };
+
+        if let Err(error) = audit_log.append(&record) {
+            log::error!("[{}][{}] Failed to write audit log: {}", request.host.name, request.source_id, error);
+        }
+    }
+
+    /// Modules render privileged commands with sudo. Switches to the method configured for the host.
+    fn escalate(host: &Host, command: &str) -> String {
+        match host.privilege_escalation() {
+            Some(escalation) => escalation.rewrite(command),
+            None => command.to_string(),
+        }
+    }
+
+    /// Escalation is only used if the connector falls back to a command.
+    fn escalate_socket_request(host: &Host, socket_request: &UnixSocketRequest) -> UnixSocketRequest {
+        match host.privilege_escalation() {
+            Some(escalation) => socket_request.clone().escalate_with(escalation),
+            None => socket_request.clone(),
+        }
+    }
+
+    /// Sends partial responses until `first_response` and the following ones are complete.
+    fn process_follow_output(
         request: &ConnectorRequest,
         connector: &Connector,
-        request_message: &String,
+        first_response: Result<ResponseMessage, LkError>,
         response_sender: mpsc::Sender<RequestResponse>,
         interrupt_pending: &Arc<Mutex<Vec<u64>>>,
     ) -> Result<ResponseMessage, LkError> {
 
-        log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, request_message);
-        let mut response_message_result = connector.send_message_partial(request_message, request.invocation_id);
+        let mut response_message_result = first_response;
 
         // Full message without last incomplete line.
         // Is sent in response to command handler so it can be processed as a whole.
@@ -478,46 +841,128 @@ impl ConnectionManager {
         }
     }
 
-    fn process_download(host: &Host, connector: &Connector, file_path: &str) -> Result<ResponseMessage, LkError> {
+    fn process_download(
+        request: &ConnectorRequest,
+        connector: &Connector,
+        file_path: &str,
+        interrupt_pending: &Arc<Mutex<Vec<u64>>>,
+    ) -> Result<ResponseMessage, LkError> {
+
+        let host = &request.host;
         log::debug!("[{}] Downloading file: {}", host.name, file_path);
-        if host.settings.contains(&crate::host::HostSetting::UseSudo) {
-            Self::download_file_with_sudo(host, connector, file_path)
-        } else {
-            match connector.download_file(file_path) {
-                Ok((metadata, contents)) => {
-                    match file_handler::create_file(host, file_path, metadata, contents) {
-                        Ok(file_path) => Ok(ResponseMessage::new_success(file_path)),
-                        Err(error) => Err(error.into()),
-                    }
-                },
-                Err(error) => Err(error),
-            }
+
+        if let Some(escalation) = host.privilege_escalation() {
+            return Self::download_file_with_sudo(host, connector, file_path, escalation);
         }
+
+        let stat = match connector.stat_file(file_path) {
+            Ok(stat) => stat,
+            Err(error) if error.kind == ErrorKind::NotImplemented => {
+                // Connector doesn't support streaming.
+                let (metadata, contents) = connector.download_file(file_path)?;
+                let local_file_path = file_handler::create_file(host, file_path, metadata, contents)?;
+                return Ok(ResponseMessage::new_success(local_file_path));
+            },
+            Err(error) => return Err(error),
+        };
+
+        let (mut partial_file, offset) = file_handler::open_partial_file(host, file_path, &stat)?;
+        if offset > 0 {
+            log::info!("[{}] Resuming download of {} at {}", host.name, file_path, format_bytes(offset));
+        }
+
+        let mut progress = Self::transfer_progress(request, connector, "Downloaded", interrupt_pending);
+        connector.download_file_stream(file_path, offset, &mut partial_file, request.invocation_id, &mut progress)?;
+
+        let metadata = FileMetadata {
+            download_time: chrono::Utc::now(),
+            local_path: None,
+            remote_path: file_path.to_string(),
+            // Calculated from the completed file.
+            remote_file_hash: String::new(),
+            owner_uid: stat.owner_uid,
+            owner_gid: stat.owner_gid,
+            permissions: stat.permissions,
+            temporary: true,
+        };
+
+        let local_file_path = file_handler::complete_partial_file(host, file_path, metadata)?;
+        Ok(ResponseMessage::new_success(local_file_path))
     }
 
-    fn process_upload(host: &Host, connector: &Connector, local_file_path: &str) -> Result<ResponseMessage, LkError> {
+    fn process_upload(
+        request: &ConnectorRequest,
+        connector: &Connector,
+        local_file_path: &str,
+        metadata: &FileMetadata,
+        interrupt_pending: &Arc<Mutex<Vec<u64>>>,
+    ) -> Result<ResponseMessage, LkError> {
+
+        let host = &request.host;
         log::debug!("[{}] Uploading file: {}", host.name, local_file_path);
-        match file_handler::read_file(local_file_path) {
-            Ok((metadata, contents)) => {
-                if host.settings.contains(&crate::host::HostSetting::UseSudo) {
-                    Self::upload_file_with_sudo(connector, &metadata.remote_path, contents, local_file_path)
-                } else {
-                    match connector.upload_file(&metadata, contents) {
-                        Ok(()) => Ok(ResponseMessage::empty()),
-                        Err(error) => Err(error),
-                    }
-                }
+
+        if let Some(escalation) = host.privilege_escalation() {
+            let (metadata, contents) = file_handler::read_file(local_file_path)?;
+            return Self::upload_file_with_sudo(connector, &metadata.remote_path, contents, local_file_path, escalation);
+        }
+
+        let mut file = std::fs::File::open(local_file_path)?;
+        let size = file.metadata()?.len();
+
+        let mut progress = Self::transfer_progress(request, connector, "Uploaded", interrupt_pending);
+        match connector.upload_file_stream(metadata, &mut file, size, request.invocation_id, &mut progress) {
+            Ok(()) => Ok(ResponseMessage::empty()),
+            Err(error) if error.kind == ErrorKind::NotImplemented => {
+                // Connector doesn't support streaming.
+                let (metadata, contents) = file_handler::read_file(local_file_path)?;
+                connector.upload_file(&metadata, contents)?;
+                Ok(ResponseMessage::empty())
             },
-            Err(error) => Err(error.into()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Sends transfer progress as partial responses and passes pending interrupts to the connector.
+    fn transfer_progress<'a>(
+        request: &'a ConnectorRequest,
+        connector: &'a Connector,
+        action: &'a str,
+        interrupt_pending: &'a Arc<Mutex<Vec<u64>>>,
+    ) -> impl FnMut(u64, u64) + 'a {
+
+        let mut last_progress = None;
+
+        move |transferred, total| {
+            {
+                let mut pending = interrupt_pending.lock().unwrap();
+                if let Some(pos) = pending.iter().position(|&id| id == request.invocation_id) {
+                    pending.remove(pos);
+                    let _ = connector.interrupt(request.invocation_id);
+                }
+            }
+
+            let progress = transferred.saturating_mul(100).checked_div(total).unwrap_or(100).min(100) as u8;
+
+            // Completion is reported by the final response. Otherwise at most one update per percent.
+            if progress >= 100 || last_progress == Some(progress) {
+                return;
+            }
+            last_progress = Some(progress);
+
+            let message = format!("{} {} of {}", action, format_bytes(transferred), format_bytes(total));
+            let response = RequestResponse::new(request, vec![Ok(ResponseMessage::new_progress(message, progress))]);
+            if let Err(error) = request.response_sender.send(response) {
+                log::error!("Failed to send response: {}", error);
+            }
         }
     }
 
-    fn download_file_with_sudo(host: &Host, connector: &Connector, file_path: &str) -> Result<ResponseMessage, LkError> {
+    fn download_file_with_sudo(host: &Host, connector: &Connector, file_path: &str, escalation: PrivilegeEscalation) -> Result<ResponseMessage, LkError> {
         use crate::file_handler::FileMetadata;
         use chrono::Utc;
         use crate::utils::sha256;
 
-        let cat_cmd = ShellCommand::new_from(vec!["cat", file_path]).use_sudo();
+        let cat_cmd = ShellCommand::new_from(vec!["cat", file_path]).use_sudo().escalate_with(escalation);
         let response = connector.send_message_binary(&cat_cmd.to_string(), &[])?;
 
         if response.return_code != 0 {
@@ -541,13 +986,19 @@ impl ConnectionManager {
         }
     }
 
-    fn upload_file_with_sudo(connector: &Connector, remote_path: &str, contents: Vec<u8>, local_file_path: &str) -> Result<ResponseMessage, LkError> {
+    fn upload_file_with_sudo(
+        connector: &Connector,
+        remote_path: &str,
+        contents: Vec<u8>,
+        local_file_path: &str,
+        escalation: PrivilegeEscalation,
+    ) -> Result<ResponseMessage, LkError> {
         use crate::utils::sha256;
         
         let expected_hash = sha256::hash(&contents);
         
         // Write directly to target file. Doesn't alter owner or permissions.
-        let tee_cmd = ShellCommand::new_from(vec!["tee", remote_path]).use_sudo();
+        let tee_cmd = ShellCommand::new_from(vec!["tee", remote_path]).use_sudo().escalate_with(escalation);
         let response = connector.send_message_binary(&tee_cmd.to_string(), &contents)?;
 
         if response.return_code != 0 {
@@ -558,7 +1009,7 @@ impl ConnectionManager {
         }
 
         // Verify file was written correctly, try sha256sum first.
-        let sha256_cmd = ShellCommand::new_from(vec!["sha256sum", remote_path]).use_sudo();
+        let sha256_cmd = ShellCommand::new_from(vec!["sha256sum", remote_path]).use_sudo().escalate_with(escalation);
         let sha256_response = connector.send_message(&sha256_cmd.to_string())?;
         
         if sha256_response.return_code == 0 {
@@ -571,7 +1022,7 @@ impl ConnectionManager {
         }
         
         // Fall back to cat and compare contents.
-        let cat_cmd = ShellCommand::new_from(vec!["cat", remote_path]).use_sudo();
+        let cat_cmd = ShellCommand::new_from(vec!["cat", remote_path]).use_sudo().escalate_with(escalation);
         let response = connector.send_message_binary(&cat_cmd.to_string(), &contents)?;
         
         if response.return_code != 0 {
@@ -617,6 +1068,33 @@ impl ConnectorRequest {
             response_sender: dummy_sender,
         }
     }
+
+    /// Batched requests cover the invocations of all member monitors.
+    pub fn invocation_ids(&self) -> Vec<u64> {
+        match &self.request_type {
+            RequestType::MonitorCommandBatch { monitors, .. } => monitors.iter().map(|monitor| monitor.invocation_id).collect(),
+            _ => vec![self.invocation_id],
+        }
+    }
+
+    /// Monitors of a batched request. Empty for other requests.
+    pub fn batched_monitors(&self) -> Vec<String> {
+        match &self.request_type {
+            RequestType::MonitorCommandBatch { monitors, .. } => monitors.iter().map(|monitor| monitor.monitor_id.clone()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// For log messages. Includes the members of batched requests.
+    fn log_prefix(&self) -> String {
+        let batched_monitors = self.batched_monitors();
+        if batched_monitors.is_empty() {
+            format!("[{}][{}]", self.host.name, self.source_id)
+        }
+        else {
+            format!("[{}][{}: {}]", self.host.name, self.source_id, batched_monitors.join(", "))
+        }
+    }
 }
 
 impl Debug for ConnectorRequest {
@@ -632,12 +1110,31 @@ pub enum RequestType {
         parent_datapoint: Option<DataPoint>,
         commands: Vec<String>,
     },
+    /// Commands of several base monitors combined into one. Split into `MonitorCommand` responses by the monitor manager.
+    MonitorCommandBatch {
+        monitors: Vec<BatchedMonitor>,
+        delimiter: String,
+        commands: Vec<String>,
+    },
+    /// Monitor that uses a Unix socket request instead of commands. Otherwise like `MonitorCommand`.
+    MonitorSocketRequest {
+        extension_monitors: Vec<String>,
+        parent_datapoint: Option<DataPoint>,
+        request: UnixSocketRequest,
+    },
     Command {
         commands: Vec<String>,
     },
     CommandFollowOutput {
         commands: Vec<String>,
     },
+    /// HTTP request to a Unix socket on the host, e.g. the Docker API.
+    SocketRequest {
+        request: UnixSocketRequest,
+    },
+    SocketRequestFollowOutput {
+        request: UnixSocketRequest,
+    },
     Interrupt {
         invocation_id: u64,
     },
@@ -654,4 +1151,24 @@ pub enum RequestType {
     /// Causes the receiver thread to exit.
     #[default]
     Exit,
+}
+
+impl RequestType {
+    /// Whether any of the commands is run with sudo. Socket requests use it only if the connector falls back to
+    /// a command, so they don't count.
+    pub fn uses_sudo(&self) -> bool {
+        let commands = match self {
+            RequestType::MonitorCommand { commands, .. } |
+            RequestType::MonitorCommandBatch { commands, .. } |
+            RequestType::Command { commands } |
+            RequestType::CommandFollowOutput { commands } => commands,
+            _ => return false,
+        };
+
+        // Checking commands instead of relying on module metadata is more accurate.
+        commands.iter().any(|command| {
+            let trimmed = command.trim_start();
+            trimmed.starts_with("sudo ") || trimmed.starts_with("\"sudo\"")
+        })
+    }
 }
\ No newline at end of file
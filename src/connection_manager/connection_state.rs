@@ -0,0 +1,246 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ErrorKind, LkError};
+use crate::host_manager::StateUpdateMessage;
+
+use super::request_limits::Job;
+
+//
+// Per-host circuit breaker for connections. When a host is unreachable, only one request at a time
+// tries to connect. Other requests are parked until it finishes and then fail immediately until the backoff expires.
+//
+
+pub const DEFAULT_BACKOFF_BASE: Duration = Duration::from_secs(5);
+pub const DEFAULT_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ConnectionState {
+    /// No requests sent yet.
+    #[default]
+    NotConnected,
+    Connecting,
+    Connected,
+    /// Host was unreachable. Requests fail immediately until `retry_at`.
+    Backoff { retry_at: DateTime<Utc>, error: String },
+    /// Requests fail immediately until `retry_at`.
+    AuthFailed { retry_at: DateTime<Utc>, error: String },
+    /// Host key is unknown or has changed and has to be verified by the user.
+    HostKeyMismatch,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionState::NotConnected => write!(f, "Not connected"),
+            ConnectionState::Connecting => write!(f, "Connecting"),
+            ConnectionState::Connected => write!(f, "Connected"),
+            ConnectionState::Backoff { error, .. } => write!(f, "Unreachable: {}", error),
+            ConnectionState::AuthFailed { error, .. } => write!(f, "Authentication failed: {}", error),
+            ConnectionState::HostKeyMismatch => write!(f, "Host key not verified"),
+        }
+    }
+}
+
+
+/// Result of `begin_request`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RequestStart {
+    Connected,
+    /// This request should connect. The result has to be then reported with `end_request`.
+    Probe,
+    /// Another request is connecting. The request should be parked with `wait_for_probe`.
+    ProbeInProgress,
+}
+
+
+#[derive(Default)]
+struct HostConnection {
+    state: ConnectionState,
+    /// Consecutive failures. Determines the backoff.
+    failures: u32,
+    /// A request is currently trying to connect.
+    probing: bool,
+    /// Requests parked until the probe finishes.
+    waiting: Vec<Job>,
+}
+
+pub struct ConnectionTracker {
+    hosts: Mutex<HashMap<String, HostConnection>>,
+    backoff_base: Duration,
+    backoff_max: Duration,
+    state_update_sender: Mutex<Option<mpsc::Sender<StateUpdateMessage>>>,
+}
+
+impl ConnectionTracker {
+    pub fn new(backoff_base: Duration, backoff_max: Duration) -> Self {
+        ConnectionTracker {
+            hosts: Mutex::new(HashMap::new()),
+            backoff_base,
+            backoff_max,
+            state_update_sender: Mutex::new(None),
+        }
+    }
+
+    /// State changes are sent to the host manager.
+    pub fn set_state_update_sender(&self, sender: mpsc::Sender<StateUpdateMessage>) {
+        *self.state_update_sender.lock().unwrap() = Some(sender);
+    }
+
+    pub fn get(&self, host_name: &str) -> ConnectionState {
+        self.hosts.lock().unwrap().get(host_name)
+            .map(|host| host.state.clone())
+            .unwrap_or_default()
+    }
+
+    /// Forgets the state of hosts that are not retained, e.g. after re-configuration.
+    pub fn retain(&self, keep: impl Fn(&str) -> bool) {
+        self.hosts.lock().unwrap().retain(|host_name, host| host.probing || keep(host_name));
+    }
+
+    /// Call before sending a request. Doesn't block.
+    pub fn begin_request(&self, host_name: &str) -> Result<RequestStart, LkError> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = hosts.entry(host_name.to_string()).or_default();
+
+        if host.probing {
+            return Ok(RequestStart::ProbeInProgress);
+        }
+
+        match &host.state {
+            ConnectionState::Connected => Ok(RequestStart::Connected),
+            ConnectionState::Backoff { retry_at, error } if *retry_at > Utc::now() => {
+                let message = format!("Host is unreachable ({}). Retrying in {} seconds.", error, Self::seconds_until(retry_at));
+                Err(LkError::new(ErrorKind::ConnectionFailed, message))
+            },
+            ConnectionState::AuthFailed { retry_at, error } if *retry_at > Utc::now() => {
+                let message = format!("Authentication failed ({}). Retrying in {} seconds.", error, Self::seconds_until(retry_at));
+                Err(LkError::new(ErrorKind::AuthenticationFailed, message))
+            },
+            _ => {
+                host.probing = true;
+                host.state = ConnectionState::Connecting;
+                drop(hosts);
+
+                self.send_state_update(host_name, ConnectionState::Connecting);
+                Ok(RequestStart::Probe)
+            }
+        }
+    }
+
+    /// Parks the job until the probe for the host finishes. It's then returned by `end_request`.
+    /// If the probe has already finished, the job is returned right away and should be started again.
+    pub fn wait_for_probe(&self, host_name: &str, job: Job) -> Option<Job> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = hosts.entry(host_name.to_string()).or_default();
+
+        if host.probing {
+            host.waiting.push(job);
+            None
+        }
+        else {
+            Some(job)
+        }
+    }
+
+    /// Updates the host state according to the first error of the request, if any.
+    /// `is_probe` is set when reporting the connection attempt from `begin_request`.
+    /// Returns the jobs that were waiting for the probe. They should be started again.
+    pub fn end_request(&self, host_name: &str, is_probe: bool, error: Option<&LkError>) -> Vec<Job> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = hosts.entry(host_name.to_string()).or_default();
+        let previous_state = host.state.clone();
+
+        match error.map(|error| (&error.kind, error)) {
+            Some((ErrorKind::ConnectionFailed, error)) => {
+                host.failures += 1;
+                host.state = ConnectionState::Backoff {
+                    retry_at: Utc::now() + self.backoff(host.failures),
+                    error: error.message.clone(),
+                };
+            },
+            Some((ErrorKind::AuthenticationFailed, error)) => {
+                host.failures += 1;
+                host.state = ConnectionState::AuthFailed {
+                    retry_at: Utc::now() + self.backoff(host.failures),
+                    error: error.message.clone(),
+                };
+            },
+            Some((ErrorKind::HostKeyNotVerified, _)) => {
+                host.failures = 0;
+                host.state = ConnectionState::HostKeyMismatch;
+            },
+            // Other errors come from the commands themselves, so the connection works.
+            _ => {
+                host.failures = 0;
+                host.state = ConnectionState::Connected;
+            },
+        }
+
+        let waiting = if is_probe {
+            host.probing = false;
+            std::mem::take(&mut host.waiting)
+        }
+        else {
+            Vec::new()
+        };
+
+        let new_state = host.state.clone();
+        drop(hosts);
+
+        if new_state != previous_state {
+            match &new_state {
+                ConnectionState::Backoff { retry_at, .. } | ConnectionState::AuthFailed { retry_at, .. } => {
+                    log::warn!("[{}] {}. Retrying in {} seconds.", host_name, new_state, Self::seconds_until(retry_at));
+                },
+                _ => log::debug!("[{}] Connection state: {}", host_name, new_state),
+            }
+
+            self.send_state_update(host_name, new_state);
+        }
+
+        waiting
+    }
+
+    /// Exponential backoff, doubled on every consecutive failure.
+    fn backoff(&self, failures: u32) -> chrono::Duration {
+        let exponent = failures.saturating_sub(1).min(16);
+        let delay = self.backoff_base.saturating_mul(1 << exponent).min(self.backoff_max);
+        chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX)
+    }
+
+    fn seconds_until(time: &DateTime<Utc>) -> i64 {
+        (*time - Utc::now()).num_seconds().max(0)
+    }
+
+    fn send_state_update(&self, host_name: &str, state: ConnectionState) {
+        if let Some(sender) = self.state_update_sender.lock().unwrap().as_ref() {
+            let message = StateUpdateMessage {
+                host_name: host_name.to_string(),
+                connection_state: Some(state),
+                ..Default::default()
+            };
+
+            if let Err(error) = sender.send(message) {
+                log::error!("Couldn't send state update: {}", error);
+            }
+        }
+    }
+}
+
+impl Default for ConnectionTracker {
+    fn default() -> Self {
+        ConnectionTracker::new(DEFAULT_BACKOFF_BASE, DEFAULT_BACKOFF_MAX)
+    }
+}
@@ -0,0 +1,272 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::configuration::{self, Hosts};
+use crate::error::{ErrorKind, LkError};
+use crate::module::ModuleFactory;
+
+//
+// Limits for request processing. `HostQueue` limits how many requests run concurrently for a host and
+// `Watchdog` interrupts requests that take longer than their timeout.
+//
+
+/// Monitor and command setting that overrides the default timeout. In seconds, 0 disables the timeout.
+pub const REQUEST_TIMEOUT_SETTING: &str = "request_timeout";
+
+pub type Job = Box<dyn FnOnce() + Send>;
+type TimeoutAction = Box<dyn FnOnce() + Send>;
+
+
+#[derive(Clone, Debug)]
+pub struct RequestLimits {
+    pub max_concurrent_requests: usize,
+    pub max_concurrent_requests_per_host: usize,
+    /// In seconds. 0 disables the timeout.
+    pub default_timeout: u64,
+    /// Timeouts set in module settings. Key is host ID and then module ID.
+    pub module_timeouts: HashMap<String, HashMap<String, u64>>,
+    /// Defaults from module metadata, used when the module setting is not set. Key is module ID.
+    pub module_default_timeouts: HashMap<String, u64>,
+}
+
+impl RequestLimits {
+    pub fn set_config(&mut self, config: &configuration::Connections) {
+        self.max_concurrent_requests = config.max_concurrent_requests;
+        self.max_concurrent_requests_per_host = config.max_concurrent_requests_per_host;
+        self.default_timeout = config.default_timeout;
+    }
+
+    pub fn set_module_timeouts(&mut self, hosts_config: &Hosts) {
+        self.module_timeouts = hosts_config.hosts.iter().map(|(host_id, host_config)| {
+            let monitor_settings = host_config.effective.monitors.iter()
+                .map(|(monitor_id, config)| (monitor_id, &config.settings));
+            let command_settings = host_config.effective.commands.iter()
+                .map(|(command_id, config)| (command_id, &config.settings));
+
+            let timeouts = monitor_settings.chain(command_settings)
+                .filter_map(|(module_id, settings)| {
+                    let timeout = settings.get(REQUEST_TIMEOUT_SETTING)?;
+                    match timeout.parse::<u64>() {
+                        Ok(timeout) => Some((module_id.clone(), timeout)),
+                        Err(_) => {
+                            log::error!("[{}][{}] Invalid {}: {}", host_id, module_id, REQUEST_TIMEOUT_SETTING, timeout);
+                            None
+                        }
+                    }
+                })
+                .collect();
+
+            (host_id.clone(), timeouts)
+        }).collect();
+    }
+
+    pub fn set_module_default_timeouts(&mut self, module_factory: &ModuleFactory) {
+        self.module_default_timeouts = module_factory.get_module_metadatas().into_iter()
+            .filter_map(|metadata| Some((metadata.module_spec.id, metadata.default_timeout?)))
+            .collect();
+    }
+
+    /// Timeout for a request made on behalf of the modules. With several modules, the longest timeout is used.
+    /// Module setting is preferred over the module's own default, which is preferred over the global default.
+    pub fn timeout(&self, host_id: &str, module_ids: &[&str]) -> Option<Duration> {
+        let host_timeouts = self.module_timeouts.get(host_id);
+        let timeouts = module_ids.iter()
+            .map(|module_id| {
+                host_timeouts.and_then(|timeouts| timeouts.get(*module_id))
+                    .or_else(|| self.module_default_timeouts.get(*module_id))
+                    .copied()
+                    .unwrap_or(self.default_timeout)
+            })
+            .collect::<Vec<_>>();
+
+        if timeouts.contains(&0) {
+            None
+        }
+        else {
+            timeouts.into_iter().max().map(Duration::from_secs)
+        }
+    }
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        let mut limits = RequestLimits {
+            max_concurrent_requests: 0,
+            max_concurrent_requests_per_host: 0,
+            default_timeout: 0,
+            module_timeouts: HashMap::new(),
+            module_default_timeouts: HashMap::new(),
+        };
+        limits.set_config(&configuration::Connections::default());
+        limits
+    }
+}
+
+
+#[derive(Default)]
+struct HostJobs {
+    running: usize,
+    queued: VecDeque<Job>,
+}
+
+/// Queued jobs don't occupy worker threads, so a slow host doesn't hold up requests to other hosts.
+pub struct HostQueue {
+    /// 0 means no limit.
+    max_per_host: usize,
+    hosts: Mutex<HashMap<String, HostJobs>>,
+}
+
+impl HostQueue {
+    pub fn new(max_per_host: usize) -> Self {
+        HostQueue {
+            max_per_host,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the job if it can be run right away. Otherwise, it's queued and returned later by `finish`.
+    pub fn submit(&self, host_name: &str, job: Job) -> Option<Job> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = hosts.entry(host_name.to_string()).or_default();
+
+        if self.max_per_host == 0 || host.running < self.max_per_host {
+            host.running += 1;
+            Some(job)
+        }
+        else {
+            host.queued.push_back(job);
+            None
+        }
+    }
+
+    /// Call after a job has finished. Returns the next queued job for the host, which should be run right away.
+    pub fn finish(&self, host_name: &str) -> Option<Job> {
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = hosts.get_mut(host_name)?;
+
+        match host.queued.pop_front() {
+            Some(job) => Some(job),
+            None => {
+                host.running = host.running.saturating_sub(1);
+                if host.running == 0 {
+                    hosts.remove(host_name);
+                }
+                None
+            }
+        }
+    }
+
+    pub fn running(&self, host_name: &str) -> usize {
+        self.hosts.lock().unwrap().get(host_name).map(|host| host.running).unwrap_or_default()
+    }
+
+    pub fn queued(&self, host_name: &str) -> usize {
+        self.hosts.lock().unwrap().get(host_name).map(|host| host.queued.len()).unwrap_or_default()
+    }
+}
+
+
+#[derive(Default)]
+struct WatchdogState {
+    next_id: u64,
+    deadlines: HashMap<u64, (Instant, TimeoutAction)>,
+    expired: HashSet<u64>,
+    stopped: bool,
+}
+
+/// Runs the timeout action of requests that don't finish in time.
+#[derive(Default)]
+pub struct Watchdog {
+    state: Mutex<WatchdogState>,
+    changed: Condvar,
+}
+
+impl Watchdog {
+    pub fn start(self: &Arc<Self>) -> thread::JoinHandle<()> {
+        let watchdog = self.clone();
+        thread::spawn(move || watchdog.run())
+    }
+
+    pub fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.changed.notify_all();
+    }
+
+    /// `on_timeout` is run in a separate thread so that a blocking interrupt doesn't delay other timeouts.
+    /// The returned guard stops watching when dropped.
+    pub fn watch(self: &Arc<Self>, timeout: Duration, on_timeout: impl FnOnce() + Send + 'static) -> TimeoutGuard {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.deadlines.insert(id, (Instant::now() + timeout, Box::new(on_timeout)));
+        drop(state);
+
+        self.changed.notify_all();
+
+        TimeoutGuard {
+            watchdog: self.clone(),
+            id,
+            timeout,
+        }
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if state.stopped {
+                return;
+            }
+
+            let now = Instant::now();
+            let expired_ids = state.deadlines.iter()
+                .filter(|(_, (deadline, _))| *deadline <= now)
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+
+            for id in expired_ids {
+                if let Some((_, action)) = state.deadlines.remove(&id) {
+                    state.expired.insert(id);
+                    thread::spawn(action);
+                }
+            }
+
+            let next_deadline = state.deadlines.values().map(|(deadline, _)| *deadline).min();
+            state = match next_deadline {
+                Some(deadline) => self.changed.wait_timeout(state, deadline.saturating_duration_since(now)).unwrap().0,
+                None => self.changed.wait(state).unwrap(),
+            };
+        }
+    }
+}
+
+pub struct TimeoutGuard {
+    watchdog: Arc<Watchdog>,
+    id: u64,
+    timeout: Duration,
+}
+
+impl TimeoutGuard {
+    pub fn is_expired(&self) -> bool {
+        self.watchdog.state.lock().unwrap().expired.contains(&self.id)
+    }
+
+    pub fn error(&self) -> LkError {
+        LkError::new(ErrorKind::TimedOut, format!("Request timed out after {} seconds", self.timeout.as_secs()))
+    }
+}
+
+impl Drop for TimeoutGuard {
+    fn drop(&mut self) {
+        let mut state = self.watchdog.state.lock().unwrap();
+        state.deadlines.remove(&self.id);
+        state.expired.remove(&self.id);
+    }
+}
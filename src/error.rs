@@ -139,6 +139,10 @@ pub enum ErrorKind {
     UnsupportedPlatform,
     /// Connection timed out, was refused or disconnected.
     ConnectionFailed,
+    /// Server rejected the credentials.
+    AuthenticationFailed,
+    /// Request didn't complete within the configured timeout.
+    TimedOut,
     /// Encountered an unknown host key.
     HostKeyNotVerified,
     /// Error in configuration files.
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::io::Write;
 use std::{env, fs, io, path::Path, path::PathBuf};
 
 use chrono::{DateTime, Utc};
@@ -15,6 +16,11 @@ use crate::Host;
 const MAX_PATH_COMPONENTS: u8 = 2;
 const APP_DIR_NAME: &str = "lightkeeper";
 const METADATA_SUFFIX: &str = ".metadata.yml";
+const PARTIAL_SUFFIX: &str = ".part";
+/// Stores the remote file details of a partial download so it's only resumed if the remote file is unchanged.
+const PARTIAL_STATE_SUFFIX: &str = ".part.yml";
+/// New contents received in chunks. Replaces the file after the last chunk.
+const NEW_CONTENTS_SUFFIX: &str = ".new.part";
 
 pub fn get_config_dir() -> PathBuf {
     let mut config_dir = if let Some(path) = env::var_os("XDG_CONFIG_HOME") {
@@ -97,6 +103,49 @@ pub fn create_file(host: &Host, remote_file_path: &str, mut metadata: FileMetada
     Ok(file_path)
 }
 
+/// Opens the partial file of an interrupted download for appending, or starts a new one.
+/// Returns the file and the number of bytes already downloaded.
+pub fn open_partial_file(host: &Host, remote_file_path: &str, stat: &RemoteFileStat) -> io::Result<(fs::File, u64)> {
+    let (dir_path, file_path) = convert_to_local_paths(host, remote_file_path);
+    fs::create_dir_all(&dir_path)?;
+
+    let partial_path = format!("{}{}", file_path, PARTIAL_SUFFIX);
+    let state_path = format!("{}{}", file_path, PARTIAL_STATE_SUFFIX);
+
+    let previous_stat = fs::read_to_string(&state_path).ok()
+        .and_then(|contents| serde_yaml::from_str::<RemoteFileStat>(&contents).ok());
+    let partial_size = fs::metadata(&partial_path).map(|metadata| metadata.len()).unwrap_or(0);
+
+    if previous_stat.as_ref() == Some(stat) && partial_size <= stat.size {
+        let file = fs::OpenOptions::new().append(true).open(&partial_path)?;
+        Ok((file, partial_size))
+    }
+    else {
+        let state = serde_yaml::to_string(stat).map_err(|error| io::Error::other(error.to_string()))?;
+        fs::write(&state_path, state)?;
+
+        let file = fs::OpenOptions::new().write(true).create(true).truncate(true).open(&partial_path)?;
+        Ok((file, 0))
+    }
+}
+
+/// Moves a completed partial download in place and writes the metadata file. Returns the local file path.
+pub fn complete_partial_file(host: &Host, remote_file_path: &str, mut metadata: FileMetadata) -> io::Result<String> {
+    let (_, file_path) = convert_to_local_paths(host, remote_file_path);
+    let partial_path = format!("{}{}", file_path, PARTIAL_SUFFIX);
+
+    metadata.remote_file_hash = sha256::hash_file(Path::new(&partial_path))?;
+    metadata.local_path = Some(file_path.clone());
+
+    fs::rename(&partial_path, &file_path)?;
+    if let Err(error) = fs::remove_file(format!("{}{}", file_path, PARTIAL_STATE_SUFFIX)) {
+        log::warn!("Failed to remove partial download state: {}", error);
+    }
+
+    write_file_metadata(metadata)?;
+    Ok(file_path)
+}
+
 /// Updates existing local file. File has to exist and have accompanying metadata file.
 pub fn write_file(local_file_path: &String, contents: Vec<u8>) -> io::Result<()> {
     // Verify, just in case, that path belongs to cache directory.
@@ -113,6 +162,41 @@ pub fn write_file(local_file_path: &String, contents: Vec<u8>) -> io::Result<()>
     }
 }
 
+/// Like `write_file`, but new contents are received in chunks. Offset 0 starts a new write.
+/// The file is replaced only after the final chunk, so an interrupted write leaves the old contents in place.
+pub fn write_file_chunk(local_file_path: &str, offset: u64, chunk: &[u8], is_final: bool) -> io::Result<()> {
+    // Verify, just in case, that path belongs to cache directory.
+    let cache_dir = get_cache_dir();
+    if Path::new(local_file_path).ancestors().all(|ancestor| ancestor != cache_dir.as_path()) {
+        return Err(io::Error::other("Path does not belong to cache directory"));
+    }
+    if !Path::new(local_file_path).is_file() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "File has to be downloaded first"));
+    }
+
+    let new_path = format!("{}{}", local_file_path, NEW_CONTENTS_SUFFIX);
+    let mut file = if offset == 0 {
+        fs::OpenOptions::new().write(true).create(true).truncate(true).open(&new_path)?
+    }
+    else {
+        fs::OpenOptions::new().append(true).open(&new_path)?
+    };
+
+    // Chunks have to arrive in order.
+    let written = file.metadata()?.len();
+    if written != offset {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Expected chunk at offset {}, got {}", written, offset)));
+    }
+
+    file.write_all(chunk)?;
+
+    if is_final {
+        file.sync_all()?;
+        fs::rename(&new_path, local_file_path)?;
+    }
+    Ok(())
+}
+
 pub fn write_file_metadata(metadata: FileMetadata) -> io::Result<()> {
     let local_file_path = metadata
         .local_path
@@ -232,6 +316,17 @@ pub struct FileMetadata {
     pub temporary: bool,
 }
 
+/// Details of a remote file needed for streaming transfers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemoteFileStat {
+    pub size: u64,
+    /// Unix timestamp.
+    pub modified: u64,
+    pub owner_uid: u32,
+    pub owner_gid: u32,
+    pub permissions: u32,
+}
+
 impl FileMetadata {
     pub fn update_hash(&mut self, new_hash: String) {
         self.remote_file_hash = new_hash;
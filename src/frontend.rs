@@ -13,6 +13,8 @@ pub use display_options::DisplayOptions;
 pub use display_options::DisplayStyle;
 pub use display_options::UserInputField;
 
+pub mod cli;
+
 #[cfg(feature = "gui")]
 pub mod qt;
 
@@ -0,0 +1,640 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::command_handler::{BulkExecution, BulkRequest, HostExecutionState, RunState, RunbookRun, StepProgress, StepState};
+use crate::configuration::Runbook;
+use crate::enums::{Criticality, HostStatus};
+use crate::error::LkError;
+use crate::frontend::{DisplayData, DisplayOptions, HostDisplayData, UIUpdate};
+use crate::module::command::{CommandResult, UIAction};
+use crate::module::monitoring::{DataPoint, MonitoringData};
+use crate::CoreComponents;
+
+//
+// Non-interactive frontend. Refreshes hosts or executes commands once and reports the results.
+// Exit codes follow the Nagios plugin convention.
+//
+
+pub const EXIT_OK: u8 = 0;
+pub const EXIT_WARNING: u8 = 1;
+pub const EXIT_CRITICAL: u8 = 2;
+pub const EXIT_UNKNOWN: u8 = 3;
+
+/// How often runbook progress is checked.
+const RUNBOOK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How often bulk execution progress is checked in addition to host updates.
+const BULK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub hosts: Vec<HostReport>,
+    /// Worst criticality of all unsilenced monitors.
+    pub criticality: Criticality,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HostReport {
+    pub name: String,
+    pub status: HostStatus,
+    /// False if the refresh didn't finish in time.
+    pub completed: bool,
+    pub errors: Vec<String>,
+    pub monitors: Vec<MonitorReport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MonitorReport {
+    pub id: String,
+    pub criticality: Criticality,
+    pub value: String,
+    pub unit: String,
+    pub silenced: bool,
+    /// Values of multivalue monitors.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<ValueReport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValueReport {
+    pub label: String,
+    pub value: String,
+    pub criticality: Criticality,
+}
+
+impl Report {
+    pub fn exit_code(&self) -> u8 {
+        exit_code(self.criticality)
+    }
+}
+
+/// Missing data is reported as unknown.
+pub fn exit_code(criticality: Criticality) -> u8 {
+    match criticality {
+        Criticality::Ignore | Criticality::Normal | Criticality::Info | Criticality::NotAvailable => EXIT_OK,
+        Criticality::NoData => EXIT_UNKNOWN,
+        Criticality::Warning => EXIT_WARNING,
+        Criticality::Error | Criticality::Critical => EXIT_CRITICAL,
+    }
+}
+
+/// Order of importance, which differs from the order of `Criticality`.
+fn severity(criticality: Criticality) -> u8 {
+    match exit_code(criticality) {
+        EXIT_OK => 0,
+        EXIT_UNKNOWN => 1,
+        EXIT_WARNING => 2,
+        _ => 3,
+    }
+}
+
+/// Outcome of a command on a single host.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommandOutcome {
+    pub host_id: String,
+    /// Final result. Missing if the command failed to start or didn't finish in time.
+    pub result: Option<CommandResult>,
+    pub errors: Vec<String>,
+}
+
+impl CommandOutcome {
+    pub fn criticality(&self) -> Criticality {
+        match &self.result {
+            Some(result) => result.criticality,
+            None if !self.errors.is_empty() => Criticality::Error,
+            None => Criticality::NoData,
+        }
+    }
+}
+
+/// Exit code for the worst outcome.
+pub fn outcomes_exit_code(outcomes: &[CommandOutcome]) -> u8 {
+    outcomes.iter()
+        .map(|outcome| outcome.criticality())
+        .max_by_key(|criticality| severity(*criticality))
+        .map(exit_code)
+        .unwrap_or(EXIT_OK)
+}
+
+/// Outcome of a runbook on a single host.
+#[derive(Clone, Debug, Serialize)]
+pub struct RunbookOutcome {
+    pub host_id: String,
+    /// Missing if the runbook failed to start.
+    pub run: Option<RunbookRun>,
+    pub errors: Vec<String>,
+}
+
+impl RunbookOutcome {
+    pub fn exit_code(&self) -> u8 {
+        match self.run.as_ref().map(|run| run.state) {
+            Some(RunState::Succeeded) => EXIT_OK,
+            Some(RunState::Failed) | Some(RunState::RolledBack) | Some(RunState::RollbackFailed) => EXIT_CRITICAL,
+            _ => EXIT_UNKNOWN,
+        }
+    }
+}
+
+/// Exit code for the worst outcome.
+pub fn runbook_outcomes_exit_code(outcomes: &[RunbookOutcome]) -> u8 {
+    outcomes.iter()
+        .map(|outcome| outcome.exit_code())
+        .max_by_key(|exit_code| match *exit_code {
+            EXIT_OK => 0,
+            EXIT_UNKNOWN => 1,
+            _ => 2,
+        })
+        .unwrap_or(EXIT_OK)
+}
+
+/// Tracks progress of a single host.
+#[derive(Default)]
+struct HostProgress {
+    /// Invocations that haven't finished yet.
+    pending: HashSet<u64>,
+    /// Invocations that have been seen in progress. Invocation is finished when it's no longer in progress.
+    started: HashSet<u64>,
+    /// Platform info is available and the actual work has been started.
+    is_ready: bool,
+    errors: Vec<String>,
+}
+
+impl HostProgress {
+    fn start(&mut self, core: &mut CoreComponents, host_id: &str, start: &mut impl FnMut(&mut CoreComponents, &str) -> Result<Vec<u64>, LkError>) {
+        self.is_ready = true;
+        match start(core, host_id) {
+            Ok(invocation_ids) => self.pending = invocation_ids.into_iter().collect(),
+            Err(error) => {
+                self.pending.clear();
+                self.errors.push(error.to_string());
+            }
+        }
+    }
+}
+
+/// Requests platform info for hosts that don't have it yet and then calls `start` for each host.
+/// Waits until all invocations returned by `start` have finished or until the timeout.
+/// `on_update` receives all updates of the selected hosts.
+fn run_on_hosts(
+    core: &mut CoreComponents,
+    host_ids: &[String],
+    timeout: Duration,
+    mut start: impl FnMut(&mut CoreComponents, &str) -> Result<Vec<u64>, LkError>,
+    mut on_update: impl FnMut(&HostDisplayData, &HostProgress),
+) -> Result<HashMap<String, HostProgress>, LkError> {
+    let (sender, receiver) = mpsc::channel();
+    core.host_manager.borrow_mut().add_observer(sender);
+
+    let mut progress = HashMap::<String, HostProgress>::new();
+    for host_id in host_ids {
+        let mut host_progress = HostProgress::default();
+
+        if !core.host_manager.borrow().get_host(host_id).platform.is_set() {
+            host_progress.pending.extend(core.monitor_manager.refresh_platform_info(host_id));
+        }
+
+        if host_progress.pending.is_empty() {
+            host_progress.start(core, host_id, &mut start);
+        }
+
+        progress.insert(host_id.clone(), host_progress);
+    }
+
+    let deadline = Instant::now() + timeout;
+    while progress.values().any(|host_progress| !host_progress.pending.is_empty()) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let display_data = match receiver.recv_timeout(remaining) {
+            Ok(UIUpdate::Host(display_data)) => display_data,
+            Ok(UIUpdate::FatalError()) => return Err(LkError::other("Fatal error while waiting for hosts")),
+            Ok(_) => continue,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::warn!("Timed out while waiting for hosts");
+                break;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Err(LkError::other("Host state updates stopped unexpectedly")),
+        };
+
+        let host_id = display_data.host_state.host.name.clone();
+        let Some(host_progress) = progress.get_mut(&host_id) else {
+            continue;
+        };
+
+        on_update(&display_data, host_progress);
+        host_progress.errors.extend(display_data.new_errors.iter().map(|error| error.message.clone()));
+
+        let host_state = &display_data.host_state;
+        let was_pending = !host_progress.pending.is_empty();
+        let in_progress = |id: &u64| host_state.monitor_invocations.contains_key(id) || host_state.command_invocations.contains_key(id);
+        host_progress.started.extend(host_progress.pending.iter().filter(|id| in_progress(id)));
+        let started = &host_progress.started;
+        host_progress.pending.retain(|id| !started.contains(id) || in_progress(id));
+
+        if host_state.just_initialized && !host_progress.is_ready {
+            log::debug!("[{}] Host initialized", host_id);
+            host_progress.start(core, &host_id, &mut start);
+        }
+        else if was_pending && host_progress.pending.is_empty() && !host_progress.is_ready {
+            log::warn!("[{}] Failed to initialize host", host_id);
+            host_progress.errors.push(String::from("Failed to initialize host"));
+        }
+    }
+
+    Ok(progress)
+}
+
+/// Refreshes platform info (if needed) and all monitors of the given hosts.
+/// Waits until all invocations have finished or until the timeout.
+pub fn refresh_hosts(core: &mut CoreComponents, host_ids: &[String], timeout: Duration) -> Result<Report, LkError> {
+    let progress = run_on_hosts(core, host_ids, timeout, |core, host_id| Ok(refresh_monitors(core, host_id)), |_, _| {})?;
+
+    let display_data = core.host_manager.borrow().get_display_data();
+    Ok(build_report(&display_data, host_ids, |host_id| {
+        progress.get(host_id)
+            .map(|host_progress| (host_progress.pending.is_empty(), host_progress.errors.clone()))
+            .unwrap_or_default()
+    }))
+}
+
+fn refresh_monitors(core: &mut CoreComponents, host_id: &str) -> Vec<u64> {
+    core.monitor_manager.get_all_host_categories(host_id).iter()
+        .flat_map(|category| core.monitor_manager.refresh_monitors_of_category(host_id, category))
+        .collect()
+}
+
+/// Returns the display options of the command. Fails if the command isn't available on all of the hosts
+/// or if it requires an interactive frontend.
+pub fn resolve_command(core: &CoreComponents, host_ids: &[String], command_id: &str) -> Result<DisplayOptions, LkError> {
+    let mut display_options = None;
+    for host_id in host_ids {
+        let command = core.command_handler.get_command_for_host(host_id, &command_id.to_string())
+            .ok_or_else(|| LkError::other(format!("Command \"{}\" is not available on host {}", command_id, host_id)))?;
+        display_options.get_or_insert(command.display_options);
+    }
+
+    let display_options = display_options.ok_or_else(|| LkError::other("No hosts selected"))?;
+    match display_options.action {
+        UIAction::Terminal | UIAction::TextEditor | UIAction::FileBrowser =>
+            Err(LkError::other(format!("Command \"{}\" can only be used in the desktop application", command_id))),
+        _ => Ok(display_options),
+    }
+}
+
+/// Executes the command on the hosts of the request as a bulk execution and waits until it has finished or until
+/// the timeout. Hosts that haven't started by then are skipped. `on_result` receives partial and final results.
+pub fn execute_command(
+    core: &mut CoreComponents,
+    request: &BulkRequest,
+    timeout: Duration,
+    mut on_result: impl FnMut(&str, &CommandResult),
+) -> Result<Vec<CommandOutcome>, LkError> {
+    resolve_command(core, &request.host_ids, &request.command_id)?;
+
+    // Commands are available only after platform info has been received.
+    let deadline = Instant::now() + timeout;
+    let progress = run_on_hosts(core, &request.host_ids, timeout, |_, _| Ok(Vec::new()), |_, _| {})?;
+    let ready_host_ids = request.host_ids.iter()
+        .filter(|host_id| progress.get(*host_id).is_some_and(|host_progress| host_progress.is_ready))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let execution = if ready_host_ids.is_empty() {
+        None
+    }
+    else {
+        let (sender, receiver) = mpsc::channel();
+        core.host_manager.borrow_mut().add_observer(sender);
+
+        let bulk_request = BulkRequest {
+            host_ids: ready_host_ids,
+            groups: Vec::new(),
+            ..request.clone()
+        };
+        let bulk_id = core.command_handler.execute_bulk(bulk_request)?;
+        Some(wait_for_bulk_execution(core, bulk_id, &receiver, deadline, &mut on_result)?)
+    };
+
+    Ok(request.host_ids.iter()
+        .map(|host_id| {
+            let mut errors = progress.get(host_id).map(|host_progress| host_progress.errors.clone()).unwrap_or_default();
+            let host_execution = execution.as_ref()
+                .and_then(|execution| execution.hosts.iter().find(|host| &host.host_id == host_id));
+
+            let result = host_execution.and_then(|host| host.result.clone());
+            if let Some(host) = host_execution.filter(|_| result.is_none()) {
+                if host.state == HostExecutionState::Skipped {
+                    errors.push(String::from("Skipped"));
+                }
+                else if !host.error.is_empty() {
+                    errors.push(host.error.clone());
+                }
+            }
+
+            CommandOutcome {
+                host_id: host_id.clone(),
+                result,
+                errors,
+            }
+        })
+        .collect())
+}
+
+/// Hosts that haven't started before the deadline are skipped.
+fn wait_for_bulk_execution(
+    core: &CoreComponents,
+    bulk_id: u64,
+    receiver: &mpsc::Receiver<UIUpdate>,
+    deadline: Instant,
+    on_result: &mut impl FnMut(&str, &CommandResult),
+) -> Result<BulkExecution, LkError> {
+    let get_execution = || core.command_handler.get_bulk_execution(bulk_id)
+        .ok_or_else(|| LkError::other("Bulk execution was not found"));
+    let mut reported = HashSet::<String>::new();
+
+    loop {
+        let execution = get_execution()?;
+        for host in execution.hosts.iter() {
+            if let Some(result) = &host.result {
+                if reported.insert(host.host_id.clone()) {
+                    on_result(&host.host_id, result);
+                }
+            }
+        }
+
+        if execution.is_finished() {
+            return Ok(execution);
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            log::warn!("Timed out while waiting for command");
+            core.command_handler.cancel_bulk_execution(bulk_id);
+            return get_execution();
+        }
+
+        // Host updates only wake up the loop. Execution state is updated in another thread, so it's also checked periodically.
+        match receiver.recv_timeout(remaining.min(BULK_POLL_INTERVAL)) {
+            Ok(UIUpdate::FatalError()) => return Err(LkError::other("Fatal error while waiting for hosts")),
+            Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Err(LkError::other("Host state updates stopped unexpectedly")),
+        }
+    }
+}
+
+/// Starts the runbook on all given hosts and waits until the runs have finished or until the timeout.
+/// Unfinished runs are cancelled. `on_progress` receives the current state of the runs periodically.
+pub fn run_runbook(
+    core: &mut CoreComponents,
+    host_ids: &[String],
+    runbook_id: &str,
+    runbook: &Runbook,
+    timeout: Duration,
+    mut on_progress: impl FnMut(&RunbookRun),
+) -> Result<Vec<RunbookOutcome>, LkError> {
+    let deadline = Instant::now() + timeout;
+    let mut run_ids = HashMap::<String, u64>::new();
+    let progress = run_on_hosts(
+        core,
+        host_ids,
+        timeout,
+        |core, host_id| {
+            let run_id = core.command_handler.run_runbook(runbook_id, runbook, host_id, &core.monitor_manager)?;
+            run_ids.insert(host_id.to_string(), run_id);
+            Ok(Vec::new())
+        },
+        |_, _| {},
+    )?;
+
+    loop {
+        let runs = run_ids.values().filter_map(|run_id| core.command_handler.get_runbook_run(*run_id)).collect::<Vec<_>>();
+        runs.iter().for_each(&mut on_progress);
+
+        if runs.iter().all(|run| run.is_finished()) {
+            break;
+        }
+        else if Instant::now() >= deadline {
+            log::warn!("Timed out while waiting for runbook");
+            for run in runs.iter().filter(|run| !run.is_finished()) {
+                core.command_handler.cancel_runbook_run(run.run_id);
+                if let Some(run) = core.command_handler.get_runbook_run(run.run_id) {
+                    on_progress(&run);
+                }
+            }
+            break;
+        }
+
+        thread::sleep(RUNBOOK_POLL_INTERVAL);
+    }
+
+    Ok(host_ids.iter()
+        .map(|host_id| RunbookOutcome {
+            host_id: host_id.clone(),
+            run: run_ids.get(host_id).and_then(|run_id| core.command_handler.get_runbook_run(*run_id)),
+            errors: progress.get(host_id).map(|host_progress| host_progress.errors.clone()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Turns cumulative output of follow-output commands into new complete lines.
+#[derive(Default)]
+pub struct OutputFollower {
+    /// Length of the already returned output per host.
+    returned: HashMap<String, usize>,
+}
+
+impl OutputFollower {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Incomplete last line is returned only when the result is final.
+    pub fn new_output(&mut self, host_id: &str, result: &CommandResult) -> String {
+        let message = strip_rich_text(&result.message);
+        let end = if result.progress >= 100 {
+            message.len()
+        }
+        else {
+            message.rfind('\n').map(|index| index + 1).unwrap_or(0)
+        };
+
+        let returned = self.returned.entry(host_id.to_string()).or_default();
+        let start = (*returned).min(end);
+        *returned = end.max(*returned);
+        message.get(start..end).unwrap_or_default().to_string()
+    }
+}
+
+/// Turns runbook progress into lines about changed steps.
+#[derive(Default)]
+pub struct RunbookFollower {
+    /// Already reported step states per run.
+    reported: HashMap<u64, Vec<StepState>>,
+}
+
+impl RunbookFollower {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn new_lines(&mut self, run: &RunbookRun) -> Vec<String> {
+        let reported = self.reported.entry(run.run_id).or_default();
+        let all_steps = run.steps.iter().map(|step| ("Step", step))
+            .chain(run.rollback.iter().map(|step| ("Rollback", step)))
+            .collect::<Vec<_>>();
+        reported.resize(all_steps.len(), StepState::Waiting);
+
+        let mut lines = Vec::new();
+        for (index, (kind, step)) in all_steps.into_iter().enumerate() {
+            if reported[index] != step.state {
+                reported[index] = step.state;
+                lines.push(format!("{} \"{}\": {}", kind, step.name, describe_step(step)));
+            }
+        }
+        lines
+    }
+}
+
+fn describe_step(step: &StepProgress) -> String {
+    let state = match step.state {
+        StepState::Waiting => "waiting",
+        StepState::Running => "running",
+        StepState::WaitingForCondition => "waiting for monitors",
+        StepState::Succeeded => "succeeded",
+        StepState::Failed => "failed",
+        StepState::TimedOut => "timed out",
+        StepState::Skipped => "skipped",
+    };
+
+    match step.message.is_empty() || step.state == StepState::WaitingForCondition {
+        true => state.to_string(),
+        false => format!("{} ({})", state, step.message),
+    }
+}
+
+/// Removes the formatting that follow-output commands get for the desktop application.
+fn strip_rich_text(text: &str) -> String {
+    let span_tag = Regex::new(r"</?span[^>]*>").unwrap();
+    span_tag.replace_all(text, "").into_owned()
+}
+
+/// `host_status` returns whether the refresh completed and the errors for a host.
+pub fn build_report(display_data: &DisplayData, host_ids: &[String], host_status: impl Fn(&str) -> (bool, Vec<String>)) -> Report {
+    let mut hosts = Vec::new();
+    let mut worst = Criticality::Normal;
+
+    for host_id in host_ids {
+        let Some(host_data) = display_data.hosts.get(host_id) else {
+            continue;
+        };
+
+        let host_state = &host_data.host_state;
+        let mut monitor_ids = host_state.monitor_data.keys().collect::<Vec<_>>();
+        monitor_ids.sort();
+
+        let monitors = monitor_ids.into_iter()
+            .filter_map(|monitor_id| monitor_report(&host_state.monitor_data[monitor_id], host_state.is_silenced(monitor_id, "")))
+            .collect::<Vec<_>>();
+
+        for monitor in monitors.iter().filter(|monitor| !monitor.silenced) {
+            if severity(monitor.criticality) > severity(worst) {
+                worst = monitor.criticality;
+            }
+        }
+
+        let (completed, errors) = host_status(host_id);
+        hosts.push(HostReport {
+            name: host_id.clone(),
+            status: host_state.status,
+            completed,
+            errors,
+            monitors,
+        });
+    }
+
+    Report {
+        hosts,
+        criticality: worst,
+    }
+}
+
+fn monitor_report(monitoring_data: &MonitoringData, silenced: bool) -> Option<MonitorReport> {
+    let data_point = monitoring_data.values.back()?;
+
+    Some(MonitorReport {
+        id: monitoring_data.monitor_id.clone(),
+        criticality: data_point.criticality,
+        value: data_point.value.clone(),
+        unit: monitoring_data.display_options.unit.clone(),
+        silenced,
+        values: data_point.multivalue.iter()
+            .map(|child: &DataPoint| ValueReport {
+                label: child.label.clone(),
+                value: child.value.clone(),
+                criticality: child.criticality,
+            })
+            .collect(),
+    })
+}
+
+/// Plain text table with one row per monitor.
+pub fn render_table(report: &Report) -> String {
+    let mut rows = vec![[
+        String::from("HOST"),
+        String::from("STATUS"),
+        String::from("MONITOR"),
+        String::from("CRITICALITY"),
+        String::from("VALUE"),
+    ]];
+
+    for host in report.hosts.iter() {
+        let status = if host.completed { host.status.to_string() } else { String::from("Timeout") };
+
+        for monitor in host.monitors.iter() {
+            let value = if monitor.values.is_empty() {
+                format!("{} {}", monitor.value, monitor.unit).trim().to_string()
+            }
+            else {
+                monitor.values.iter()
+                    .map(|value| format!("{}: {}", value.label, value.value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            let criticality = if monitor.silenced { format!("{} (silenced)", monitor.criticality) } else { monitor.criticality.to_string() };
+            rows.push([host.name.clone(), status.clone(), monitor.id.clone(), criticality, value]);
+        }
+    }
+
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (width, column) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in rows.iter() {
+        let line = row.iter().zip(widths.iter())
+            .map(|(column, width)| format!("{:width$}", column, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    for host in report.hosts.iter() {
+        for error in host.errors.iter() {
+            output.push_str(&format!("{}: {}\n", host.name, error));
+        }
+    }
+
+    output
+}
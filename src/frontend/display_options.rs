@@ -174,6 +174,17 @@ impl UserInputField {
         }
     }
 
+    pub fn option(label: &str, options: &[&str], option_descriptions: &[&str]) -> Self {
+        UserInputField {
+            field_type: UserInputFieldType::Option,
+            label: label.to_string(),
+            validator_regexp: format!("^({})$", options.join("|")),
+            options: options.iter().map(ToString::to_string).collect(),
+            option_descriptions: option_descriptions.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        }
+    }
+
     pub fn decimal_number_with_units(label: &str, default_value: &str, units: &[&str]) -> Self {
         let units = units.into_iter().map(|unit| unit.to_string()).collect::<Vec<_>>();
 
@@ -71,6 +71,8 @@ impl Default for HostDisplayData {
                 command_results: HashMap::new(),
                 monitor_invocations: HashMap::new(),
                 command_invocations: HashMap::new(),
+                silences: Vec::new(),
+                connection_state: Default::default(),
             },
             new_monitoring_data: None,
             new_command_result: None,
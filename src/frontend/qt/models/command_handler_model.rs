@@ -95,7 +95,7 @@ impl CommandHandlerModel {
         }
     }
 
-    fn backend(&self) -> &dyn CommandBackend {
+    pub fn backend(&self) -> &dyn CommandBackend {
         self.backend.as_deref().unwrap()
     }
 
@@ -688,7 +688,9 @@ impl ConfigManagerModel {
 
         let mut new_host_config = self.hosts_config.hosts.get(&host_id).cloned().unwrap_or_default();
         new_host_config.groups = grouplist;
-        let effective_config = Configuration::get_effective_group_config(&new_host_config, &self.groups_config.groups);
+        new_host_config.effective = Configuration::get_effective_group_config(&new_host_config, &self.groups_config.groups);
+        Configuration::apply_ssh_config(&host_id, &mut new_host_config);
+        let effective_config = new_host_config.effective;
 
         let modules_settings: HashMap<String, Vec<ModuleSetting>> = match Self::parse_module_type(&module_type) {
             ModuleType::Connector => effective_config.connectors.iter().map(|(module_id, module_config)| {
@@ -46,6 +46,7 @@ pub struct HostDataManagerModel {
     getCategoryMonitorIds: qt_method!(fn(&self, host_id: QString, category: QString) -> QStringList),
     refresh_hosts_on_start: qt_method!(fn(&self) -> bool),
     isHostInitialized: qt_method!(fn(&self, host_id: QString) -> bool),
+    getConnectionState: qt_method!(fn(&self, host_id: QString) -> QString),
     removeHost: qt_method!(fn(&self, host_id: QString)),
 
     getPendingMonitorCount: qt_method!(fn(&self, host_id: QString) -> u64),
@@ -121,7 +122,8 @@ impl HostDataManagerModel {
                 if let Some(old_monitor_data) = old_data.host_state.monitor_data.get(&new_monitor_data.monitor_id) {
                     let old_criticality = old_monitor_data.values.back().map(|p| p.criticality).unwrap_or(Criticality::NoData);
 
-                    if new_criticality != old_criticality {
+                    // Criticality is still shown for silenced monitors, but they won't trigger notifications.
+                    if new_criticality != old_criticality && !host_state.is_silenced(&new_monitor_data.monitor_id, "") {
                         self.monitorStateChanged(
                             QString::from(host_state.host.name.clone()),
                             QString::from(new_monitor_data.monitor_id.clone()),
@@ -232,6 +234,14 @@ impl HostDataManagerModel {
         }
     }
 
+    /// Describes why the host is unreachable, if it is.
+    fn getConnectionState(&self, host_id: QString) -> QString {
+        self.display_data.hosts.get(&host_id.to_string())
+            .map(|display_data| display_data.host_state.connection_state.to_string())
+            .unwrap_or_default()
+            .into()
+    }
+
     fn removeHost(&mut self, host_id: QString) {
         self.display_data.hosts.remove(&host_id.to_string());
     }
@@ -17,6 +17,7 @@ pub struct HostDataModel {
     pub name: qt_property!(QString),
     pub fqdn: qt_property!(QString),
     pub ip_address: qt_property!(QString),
+    pub connection_state: qt_property!(QString),
     pub monitor_data: qt_property!(MonitorDataModel),
 }
 
@@ -27,6 +28,7 @@ impl HostDataModel {
             name: host_display_data.host_state.host.name.clone().into(),
             fqdn: host_display_data.host_state.host.fqdn.clone().into(),
             ip_address: host_display_data.host_state.host.ip_address.to_string().into(),
+            connection_state: host_display_data.host_state.connection_state.to_string().into(),
             monitor_data: MonitorDataModel::new(&host_display_data),
         }
     }
@@ -42,6 +42,8 @@ pub struct LkBackend {
     receiveUpdates: qt_method!(fn(&self)),
     reload: qt_method!(fn(&mut self)),
     stop: qt_method!(fn(&mut self)),
+    // Charts are queried through the command backend, so this can't be in `metrics`.
+    refreshCharts: qt_method!(fn(&self, host_id: QString, monitor_id: QString, start_time_sec: i64, end_time_sec: i64) -> u64),
 
     //
     // Signals
@@ -173,6 +175,11 @@ impl LkBackend {
         self.update_receiver_thread = Some(thread);
     }
 
+    fn refreshCharts(&self, host_id: QString, monitor_id: QString, start_time_sec: i64, end_time_sec: i64) -> u64 {
+        let command = self.command.borrow();
+        self.metrics.borrow_mut().refresh_charts(command.backend(), host_id, monitor_id, start_time_sec, end_time_sec)
+    }
+
     pub fn new_update_sender(&self) -> mpsc::Sender<UIUpdate> {
         self.update_sender_prototype.clone().unwrap()
     }
@@ -181,7 +188,9 @@ impl LkBackend {
         match self.config.borrow_mut().reload_configuration() {
             Ok((main_config, hosts_config)) => {
                 self.connection_manager.configure(&hosts_config);
+                self.connection_manager.set_limits(&main_config.connections);
                 let reset_hosts = self.host_manager.borrow_mut().configure(&hosts_config);
+                self.connection_manager.set_state_update_sender(self.host_manager.borrow().new_state_update_sender());
                 self.command.borrow_mut().configure(
                     &main_config,
                     &hosts_config,
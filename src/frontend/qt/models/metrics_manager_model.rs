@@ -10,6 +10,7 @@ use std::time::SystemTime;
 use qmetaobject::*;
 
 use crate::{
+    backend::CommandBackend,
     configuration,
     metrics,
     module::monitoring::DataPoint
@@ -25,7 +26,6 @@ pub struct MetricsManagerModel {
     // Slots
     //
     startService: qt_method!(fn(&self) -> ()),
-    refreshCharts: qt_method!(fn(&self, host_id: QString, monitor_id: QString, start_time_sec: i64, end_time_sec: i64) -> u64),
     getCategories: qt_method!(fn(&self, host_id: QString) -> QStringList),
     getCategoryMonitorIds: qt_method!(fn(&self, host_id: QString, category_id: QString) -> QStringList),
 
@@ -62,7 +62,7 @@ impl MetricsManagerModel {
         if let Some(metrics_manager) = self.metrics_manager.as_mut() {
             // TODO: notify UI?
             if let Err(error) = metrics_manager.stop() {
-                ::log::error!("Error stopping metrics server: {}", error);
+                ::log::error!("Error stopping metrics store: {}", error);
             }
         }
     }
@@ -107,14 +107,16 @@ impl MetricsManagerModel {
             if let Err(error) = metrics_manager.start_service() {
                 // TODO: show in UI
                 ::log::error!("Error: {}", error);
-                ::log::error!("Failed to start metrics server. Charts will not be available.");
+                ::log::error!("Failed to start metrics store. Charts will not be available.");
             }
         }
     }
 
-    fn refreshCharts(&mut self, host_id: QString, monitor_id: QString, start_time_sec: i64, end_time_sec: i64) -> u64 {
+    /// Metrics stored by the backend (e.g. a remote core) are preferred over the local store.
+    pub fn refresh_charts(&mut self, backend: &dyn CommandBackend, host_id: QString, monitor_id: QString, start_time_sec: i64, end_time_sec: i64) -> u64 {
         if let Some(metrics_manager) = self.metrics_manager.as_mut() {
-            let invocation_result = metrics_manager.get_metrics(
+            let invocation_result = metrics_manager.get_backend_metrics(
+                backend,
                 &host_id.to_string(),
                 &monitor_id.to_string(),
                 start_time_sec,
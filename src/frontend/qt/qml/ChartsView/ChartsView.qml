@@ -253,7 +253,7 @@ Item {
                                     target: root
 
                                     function onRefreshRequested() {
-                                        chart.invocationId = LK.metrics.refreshCharts(
+                                        chart.invocationId = LK.refreshCharts(
                                             root.hostId, chart.monitoringData.monitor_id,
                                             root._chartStartTimeSec, root._chartEndTimeSec)
                                     }
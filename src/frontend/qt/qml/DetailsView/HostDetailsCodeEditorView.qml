@@ -92,6 +92,11 @@ Item {
         function onCommandResultReceived(commandResultJson, invocationId) {
             let commandResult = JSON.parse(commandResultJson)
 
+            // Progress of a file transfer.
+            if (commandResult.progress < 100) {
+                return
+            }
+
             if (root.pendingInvocation === invocationId) {
                 root.pendingInvocation = 0
                 root._saveOverlayActive = false
@@ -83,6 +83,7 @@ Item {
                 categoryName: "host"
                 hostId: root._hostDetails && root._hostDetails.host ? root._hostDetails.host.name : ""
                 status: root._hostDetails ? root._hostDetails.status : ""
+                connectionState: root._hostDetails ? LK.hosts.getConnectionState(root.hostId) : ""
                 fqdn: root._hostDetails && root._hostDetails.host ? root._hostDetails.host.fqdn : ""
                 ipAddress: root._hostDetails && root._hostDetails.host ? root._hostDetails.host.ip_address : ""
 
@@ -18,6 +18,7 @@ CategoryGroupBox {
 
     property string hostId: ""
     property string status: ""
+    property string connectionState: ""
     property string fqdn: ""
     property string ipAddress: ""
     property string _categoryName: "host"
@@ -142,6 +143,23 @@ CategoryGroupBox {
                         }
                     }
 
+                    Row {
+                        width: parent.width
+                        spacing: 0.075 * parent.width
+
+                        Label {
+                            width: 0.25 * parent.width
+                            lineHeight: 0.6
+                            text: "Connection"
+                        }
+
+                        SmallText {
+                            verticalAlignment: Text.AlignVCenter
+                            width: 0.35 * parent.width
+                            text: root.connectionState
+                        }
+                    }
+
                     Row {
                         width: parent.width
                         spacing: 0.075 * parent.width
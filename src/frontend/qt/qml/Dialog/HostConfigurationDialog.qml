@@ -70,11 +70,12 @@ LightkeeperDialog {
         }
 
         if (useSudoCheckbox.checked) {
-            newSettings.overrides.host_settings = ["use_sudo"]
+            newSettings.overrides.host_settings = [root.privilegeEscalationSetting() || "use_sudo"]
         }
         else {
             newSettings.overrides.host_settings = []
         }
+        newSettings.overrides.host_settings = newSettings.overrides.host_settings.concat(root.otherHostSettings())
 
         let sshSettings = {}
         for (let setting of root._sshModuleSettings) {
@@ -188,20 +189,16 @@ LightkeeperDialog {
                 Label {
                     Layout.fillWidth: true
                     horizontalAlignment: Text.AlignHCenter
-                    text: "Allow sudo (no pw)"
+                    text: "Allow sudo"
                 }
 
                 Switch {
                     id: useSudoCheckbox
                     Layout.alignment: Qt.AlignHCenter
                     checked: {
-                        if (root.hostSettings.overrides !== undefined &&
-                            root.hostSettings.overrides.host_settings !== undefined) {
-                            return root.hostSettings.overrides.host_settings.indexOf("use_sudo") !== -1
-                        }
-                        if (root.hostSettings.effective !== undefined &&
-                            root.hostSettings.effective.host_settings !== undefined) {
-                            return root.hostSettings.effective.host_settings.indexOf("use_sudo") !== -1
+                        if (root.hostSettings.overrides?.host_settings !== undefined ||
+                            root.hostSettings.effective?.host_settings !== undefined) {
+                            return root.privilegeEscalationSetting() !== ""
                         }
                         return true
                     }
@@ -575,6 +572,20 @@ LightkeeperDialog {
             ?.value ?? ""
     }
 
+    // Doas, run0 and su are configured in the configuration files and are kept as they are.
+    function privilegeEscalationSetting() {
+        let methods = ["use_sudo", "use_doas", "use_run0", "use_su"]
+        let hostSettings = root.hostSettings.overrides?.host_settings ?? root.hostSettings.effective?.host_settings ?? []
+        return hostSettings.find(setting => methods.indexOf(setting) !== -1) ?? ""
+    }
+
+    // Settings that can't be changed in this dialog, like batch_monitor_commands.
+    function otherHostSettings() {
+        let methods = ["use_sudo", "use_doas", "use_run0", "use_su"]
+        let hostSettings = root.hostSettings.overrides?.host_settings ?? root.hostSettings.effective?.host_settings ?? []
+        return hostSettings.filter(setting => methods.indexOf(setting) === -1)
+    }
+
     function updateOkButton() {
         let fieldsAreValid = hostIdField.acceptableInput && hostAddressField.acceptableInput
         root.standardButton(Dialog.Ok).enabled = fieldsAreValid
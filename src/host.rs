@@ -8,6 +8,7 @@ use std::{net::IpAddr, net::Ipv4Addr, net::ToSocketAddrs, str::FromStr};
 use serde::{Deserialize, Serialize};
 
 use crate::{error::*, module::PlatformInfo, utils};
+use crate::utils::PrivilegeEscalation;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Host {
@@ -86,6 +87,21 @@ impl Host {
             self.ip_address.to_string()
         }
     }
+
+    /// Configured privilege escalation method, if any.
+    pub fn privilege_escalation(&self) -> Option<PrivilegeEscalation> {
+        self.settings.iter().find_map(|setting| match setting {
+            HostSetting::UseSudo => Some(PrivilegeEscalation::Sudo),
+            HostSetting::UseDoas => Some(PrivilegeEscalation::Doas),
+            HostSetting::UseRun0 => Some(PrivilegeEscalation::Run0),
+            HostSetting::UseSu => Some(PrivilegeEscalation::Su),
+            HostSetting::BatchMonitorCommands | HostSetting::None => None,
+        })
+    }
+
+    pub fn batch_monitor_commands(&self) -> bool {
+        self.settings.contains(&HostSetting::BatchMonitorCommands)
+    }
 }
 
 impl Default for Host {
@@ -107,5 +123,15 @@ pub enum HostSetting {
     None,
     #[default]
     /// Use sudo for commands that require higher privileges.
+    /// If the ssh connector has `sudo_password` set, it's passed to sudo through stdin.
     UseSudo,
+    /// Use doas. Requires a `nopass` rule.
+    UseDoas,
+    /// Use run0. Requires polkit to allow it without authentication.
+    UseRun0,
+    /// Use su. Requires su to be allowed without a password.
+    UseSu,
+    /// Run the commands of monitors refreshed together in one SSH command execution.
+    /// Monitors requiring privilege escalation are still run separately.
+    BatchMonitorCommands,
 }
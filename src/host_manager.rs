@@ -9,12 +9,15 @@ use std::str::FromStr;
 use std::sync::mpsc;
 use std::thread;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
 use crate::configuration::HostSettings;
+use crate::connection_manager::ConnectionState;
 use crate::error::LkError;
 use crate::frontend::frontend::VerificationRequest;
+use crate::maintenance::{Silence, Silences};
 use crate::module::platform_info;
 use crate::module::{
     ModuleSpecification,
@@ -34,6 +37,10 @@ use crate::{
 };
 
 const DATA_POINT_BUFFER_SIZE: usize = 4;
+/// Ad-hoc silences longer than this are shortened.
+const MAX_SILENCE_DURATION: u64 = 366 * 24 * 60 * 60;
+/// How often to check if silences have started or ended without other state updates.
+const SILENCE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 
 /// Manages the host states.
@@ -41,6 +48,8 @@ pub struct HostManager {
     hosts: Arc<Mutex<HostStateCollection>>,
     /// Only meant for tracking config changes in re-configuration.
     current_config: BTreeMap<String, HostSettings>,
+    /// Maintenance windows and ad-hoc silences.
+    silences: Arc<Mutex<Silences>>,
 
     /// Provides sender handles for sending StateUpdateMessages to this instance.
     data_sender_prototype: Option<mpsc::Sender<StateUpdateMessage>>,
@@ -57,6 +66,7 @@ impl HostManager {
         HostManager {
             hosts: hosts,
             current_config: BTreeMap::new(),
+            silences: Arc::new(Mutex::new(Silences::new())),
             frontend_state_sender: frontend_state_sender,
             data_receiver: None,
             data_sender_prototype: None,
@@ -111,7 +121,8 @@ impl HostManager {
         for (host_id, new_host_config) in new_host_configs.iter() {
             log::debug!("Configuring host {}", host_id);
 
-            if let Ok(host) = Host::new(host_id, &new_host_config.address, &new_host_config.fqdn, &new_host_config.effective.host_settings) {
+            let (address, fqdn) = new_host_config.effective_address();
+            if let Ok(host) = Host::new(host_id, &address, &fqdn, &new_host_config.effective.host_settings) {
                 if host_states.hosts.contains_key(&host.name) {
                     log::error!("Host '{}' already exists", host.name);
                     continue;
@@ -136,6 +147,7 @@ impl HostManager {
         }
 
         self.current_config = hosts_config.hosts.clone();
+        self.silences.lock().unwrap().configure(hosts_config);
 
         let (sender, receiver) = mpsc::channel::<StateUpdateMessage>();
         self.data_sender_prototype = Some(sender);
@@ -164,6 +176,58 @@ impl HostManager {
                    .host.clone()
     }
 
+    /// Shared handle to host states. Only meant for reading the current state from other threads.
+    pub(crate) fn get_host_states(&self) -> Arc<Mutex<HostStateCollection>> {
+        self.hosts.clone()
+    }
+
+    /// Suppresses alerts for the host, or only for a monitor or label, starting now. Returns the silence ID.
+    pub fn create_silence(
+        &self,
+        host_name: &str,
+        monitor_id: Option<String>,
+        label: Option<String>,
+        duration_secs: u64,
+        comment: String,
+    ) -> Result<u64, LkError> {
+        if !self.hosts.lock().unwrap().hosts.contains_key(host_name) {
+            return Err(LkError::other_p("Host not found", host_name));
+        }
+
+        let duration = chrono::Duration::seconds(duration_secs.min(MAX_SILENCE_DURATION) as i64);
+        let silence_id = self.silences.lock().unwrap().add(host_name, monitor_id, label, duration, comment);
+        self.refresh_status(host_name);
+        Ok(silence_id)
+    }
+
+    /// Returns false if the silence was not found.
+    pub fn cancel_silence(&self, silence_id: u64) -> bool {
+        let mut silences = self.silences.lock().unwrap();
+        let Some(host_name) = silences.host_of(silence_id) else {
+            return false;
+        };
+
+        let cancelled = silences.cancel(silence_id);
+        drop(silences);
+
+        self.refresh_status(&host_name);
+        cancelled
+    }
+
+    /// Makes the receiver thread re-evaluate host status and notify observers.
+    fn refresh_status(&self, host_name: &str) {
+        if let Some(sender) = self.data_sender_prototype.as_ref() {
+            let message = StateUpdateMessage {
+                host_name: host_name.to_string(),
+                ..Default::default()
+            };
+
+            if let Err(error) = sender.send(message) {
+                log::error!("Couldn't send state update: {}", error);
+            }
+        }
+    }
+
     pub fn new_state_update_sender(&self) -> mpsc::Sender<StateUpdateMessage> {
         self.data_sender_prototype.as_ref().unwrap().clone()
     }
@@ -175,6 +239,7 @@ impl HostManager {
     pub fn start_receiving_updates(&mut self) {
         let thread = Self::_start_receiving_updates(
             self.hosts.clone(),
+            self.silences.clone(),
             self.data_receiver.take().unwrap(),
             self.frontend_state_sender.clone(),
         );
@@ -184,15 +249,24 @@ impl HostManager {
 
     fn _start_receiving_updates(
         hosts: Arc<Mutex<HostStateCollection>>,
+        silences: Arc<Mutex<Silences>>,
         receiver: mpsc::Receiver<StateUpdateMessage>,
         observers: Arc<Mutex<Vec<mpsc::Sender<frontend::UIUpdate>>>>) -> thread::JoinHandle<()> {
 
         thread::spawn(move || {
             log::debug!("Started receiving updates");
+            let mut next_silence_check = Instant::now() + SILENCE_CHECK_INTERVAL;
 
             loop {
-                let state_update = match receiver.recv() {
+                // Maintenance windows and silences can start and end between state updates.
+                if Instant::now() >= next_silence_check {
+                    Self::refresh_silences(&hosts, &silences, &observers);
+                    next_silence_check = Instant::now() + SILENCE_CHECK_INTERVAL;
+                }
+
+                let state_update = match receiver.recv_timeout(next_silence_check.saturating_duration_since(Instant::now())) {
                     Ok(data) => data,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
                     Err(error) => {
                         log::error!("Stopped receiver thread: {}", error);
                         return;
@@ -205,16 +279,7 @@ impl HostManager {
                 }
 
                 if state_update.fatal_error {
-                    let mut observers = observers.lock().unwrap();
-                    observers.retain(|observer| {
-                        match observer.send(frontend::UIUpdate::FatalError()) {
-                            Ok(()) => true,
-                            Err(error) => {
-                                log::debug!("Removing closed host observer: {}", error);
-                                false
-                            }
-                        }
-                    });
+                    Self::notify_observers(&observers, frontend::UIUpdate::FatalError());
                     continue;
                 }
 
@@ -229,8 +294,13 @@ impl HostManager {
                 };
 
                 host_state.just_initialized = false;
+                if let Some(connection_state) = state_update.connection_state {
+                    host_state.connection_state = connection_state;
+                }
+
                 let mut new_monitoring_data: Option<(u64, MonitoringData)> = None;
                 let mut new_command_results: Option<(u64, CommandResult)> = None;
+                let mut new_warnings: Vec<ErrorMessage> = Vec::new();
 
                 if let Some(message_data_point) = state_update.data_point {
                     // Specially structured data point for passing platform info here.
@@ -242,6 +312,13 @@ impl HostManager {
                             host_state.host.ip_address = ip_address;
                             log::debug!("[{}] Platform info updated", host_state.host.name);
 
+                            let certificate = message_data_point.multivalue.iter().find(|data| data.label == "ssh_certificate");
+                            if let Some(certificate) = certificate.filter(|data| data.criticality >= Criticality::Warning) {
+                                let message = format!("SSH certificate {}: {}", certificate.description, certificate.value);
+                                log::warn!("[{}] {}", host_state.host.name, message);
+                                new_warnings.push(ErrorMessage { message, criticality: certificate.criticality });
+                            }
+
                             // TODO: handle multiple platform info's.
                             host_state.just_initialized = true;
                             host_state.is_initialized = true;
@@ -255,6 +332,7 @@ impl HostManager {
                         if state_update.invocation_id == 0 {
                             if !host_state.monitor_data.contains_key(&state_update.module_spec.id) {
                                 let mut new_data = MonitoringData::new(state_update.module_spec.id.clone(), state_update.display_options);
+                                new_data.is_critical = state_update.is_critical;
                                 new_data.values.push_back(message_data_point.clone());
                                 host_state.monitor_data.insert(state_update.module_spec.id.clone(), new_data);
                             }
@@ -269,6 +347,7 @@ impl HostManager {
 
                             // Monitoring data for platform info providers / internal modules won't exist in `monitor_data`.
                             if let Some(monitoring_data) = host_state.monitor_data.get_mut(&state_update.module_spec.id) {
+                                monitoring_data.is_critical = state_update.is_critical;
                                 monitoring_data.values.push_back(message_data_point.clone());
 
                                 if monitoring_data.values.len() > DATA_POINT_BUFFER_SIZE {
@@ -319,6 +398,7 @@ impl HostManager {
                     }
                 }
 
+                host_state.silences = silences.lock().unwrap().active_for(&host_state.host.name, chrono::Utc::now());
                 host_state.update_status();
 
                 let (verification_requests, unhandled_errors): (Vec<_>, Vec<_>) = state_update.errors.into_iter()
@@ -337,51 +417,64 @@ impl HostManager {
                     host_state: host_state.clone(),
                     new_monitoring_data: new_monitoring_data.clone(),
                     new_command_result: new_command_results.clone(),
-                    new_errors: unhandled_errors.iter().cloned().map(ErrorMessage::from).collect(),
+                    new_errors: unhandled_errors.iter().cloned().map(ErrorMessage::from).chain(new_warnings).collect(),
                     verification_requests: verification_requests.clone(),
                     ..Default::default()
                 });
-                let mut observers = observers.lock().unwrap();
-                observers.retain(|observer| {
-                    match observer.send(update.clone()) {
-                        Ok(()) => true,
-                        Err(error) => {
-                            log::debug!("Removing closed host observer: {}", error);
-                            false
-                        }
-                    }
-                });
+                Self::notify_observers(&observers, update);
             }
         })
     }
 
-    pub fn get_display_data(&self) -> frontend::DisplayData {
-        let mut display_data = frontend::DisplayData::new();
+    /// Updates hosts whose active silences have changed and notifies observers about them.
+    fn refresh_silences(
+        hosts: &Mutex<HostStateCollection>,
+        silences: &Mutex<Silences>,
+        observers: &Mutex<Vec<mpsc::Sender<frontend::UIUpdate>>>) {
 
-        let hosts = self.hosts.lock().unwrap();
-        for (_, host_state) in hosts.hosts.iter() {
-            for (monitor_id, monitor_data) in host_state.monitor_data.iter() {
-                if !display_data.all_monitor_names.contains(monitor_id) {
-                    display_data.all_monitor_names.push(monitor_id.clone());
+        let now = chrono::Utc::now();
+        let mut host_states = hosts.lock().unwrap();
+        let mut silences = silences.lock().unwrap();
 
-                    let header = match monitor_data.display_options.unit.is_empty() {
-                        true => monitor_data.display_options.display_text.to_string(),
-                        false => format!("{} ({})", monitor_data.display_options.display_text, monitor_data.display_options.unit),
-                    };
-                    display_data.table_headers.push(header);
-                }
+        for host_state in host_states.hosts.values_mut() {
+            let active = silences.active_for(&host_state.host.name, now);
+            if active == host_state.silences {
+                continue;
             }
-        }
 
-        for (host_name, state) in hosts.hosts.iter() {
-            display_data.hosts.insert(host_name.clone(), frontend::HostDisplayData {
-                host_state: state.clone(),
+            log::debug!("[{}] Active silences changed", host_state.host.name);
+            host_state.silences = active;
+            host_state.update_status();
+
+            Self::notify_observers(observers, frontend::UIUpdate::Host(frontend::HostDisplayData {
+                host_state: host_state.clone(),
                 ..Default::default()
-            });
+            }));
         }
+    }
 
-        display_data.table_headers = vec![String::from("Status"), String::from("Name"), String::from("FQDN"), String::from("IP address")];
-        display_data
+    fn notify_observers(observers: &Mutex<Vec<mpsc::Sender<frontend::UIUpdate>>>, update: frontend::UIUpdate) {
+        let mut observers = observers.lock().unwrap();
+        observers.retain(|observer| {
+            match observer.send(update.clone()) {
+                Ok(()) => true,
+                Err(error) => {
+                    log::debug!("Removing closed host observer: {}", error);
+                    false
+                }
+            }
+        });
+    }
+
+    pub fn get_display_data(&self) -> frontend::DisplayData {
+        self.display_data_reader().get_display_data()
+    }
+
+    /// For reading the current state from other threads.
+    pub fn display_data_reader(&self) -> DisplayDataReader {
+        DisplayDataReader {
+            hosts: self.hosts.clone(),
+        }
     }
 
     fn read_platform_info(data_point: &DataPoint) -> Result<(platform_info::PlatformInfo, std::net::IpAddr), String> {
@@ -407,6 +500,8 @@ impl HostManager {
                 "ip_address" => {
                     ip_address = std::net::IpAddr::from_str(data.value.as_str()).map_err(|error| error.to_string())?
                 },
+                // Not part of the platform. Handled separately.
+                "ssh_certificate" => {},
                 _ => return Err(String::from("Invalid platform info data"))
             }
         }
@@ -433,6 +528,10 @@ pub struct StateUpdateMessage {
     pub data_point: Option<DataPoint>,
     /// Only used with commands.
     pub command_result: Option<CommandResult>,
+    /// Only used by the connection manager.
+    pub connection_state: Option<ConnectionState>,
+    /// Only used with monitors. Whether the monitor can bring the host down.
+    pub is_critical: bool,
     pub errors: Vec<LkError>,
     /// Signals fatal error has happened and app needs to be reloaded.
     pub fatal_error: bool,
@@ -457,8 +556,46 @@ impl StateUpdateMessage {
 }
 
 
-struct HostStateCollection {
-    hosts: HashMap<String, HostState>,
+/// Thread-safe handle for reading host states without access to HostManager.
+#[derive(Clone)]
+pub struct DisplayDataReader {
+    hosts: Arc<Mutex<HostStateCollection>>,
+}
+
+impl DisplayDataReader {
+    pub fn get_display_data(&self) -> frontend::DisplayData {
+        let mut display_data = frontend::DisplayData::new();
+
+        let hosts = self.hosts.lock().unwrap();
+        for (_, host_state) in hosts.hosts.iter() {
+            for (monitor_id, monitor_data) in host_state.monitor_data.iter() {
+                if !display_data.all_monitor_names.contains(monitor_id) {
+                    display_data.all_monitor_names.push(monitor_id.clone());
+
+                    let header = match monitor_data.display_options.unit.is_empty() {
+                        true => monitor_data.display_options.display_text.to_string(),
+                        false => format!("{} ({})", monitor_data.display_options.display_text, monitor_data.display_options.unit),
+                    };
+                    display_data.table_headers.push(header);
+                }
+            }
+        }
+
+        for (host_name, state) in hosts.hosts.iter() {
+            display_data.hosts.insert(host_name.clone(), frontend::HostDisplayData {
+                host_state: state.clone(),
+                ..Default::default()
+            });
+        }
+
+        display_data.table_headers = vec![String::from("Status"), String::from("Name"), String::from("FQDN"), String::from("IP address")];
+        display_data
+    }
+}
+
+
+pub(crate) struct HostStateCollection {
+    pub(crate) hosts: HashMap<String, HostState>,
 }
 
 impl HostStateCollection {
@@ -485,6 +622,10 @@ pub struct HostState {
     pub monitor_invocations: HashMap<u64, InvocationDetails>,
     /// Invocations in progress. Keeps track of command progress. Empty when all is done.
     pub command_invocations: HashMap<u64, InvocationDetails>,
+    /// Currently active maintenance windows and silences.
+    pub silences: Vec<Silence>,
+    /// Explains why the host is unreachable, if it is.
+    pub connection_state: ConnectionState,
 }
 
 impl HostState {
@@ -498,13 +639,25 @@ impl HostState {
             command_results: HashMap::new(),
             monitor_invocations: HashMap::new(),
             command_invocations: HashMap::new(),
+            silences: Vec::new(),
+            connection_state: ConnectionState::default(),
         }
     }
 
-    fn update_status(&mut self) {
+    /// Use empty label for the monitor-level value.
+    pub fn is_silenced(&self, monitor_id: &str, label: &str) -> bool {
+        self.silences.iter().any(|silence| silence.matches(monitor_id, label))
+    }
+
+    pub fn update_status(&mut self) {
         // There should always be some monitoring data available at this point.
+        // Silenced monitors still show their criticality but won't bring the host down.
         let critical_monitor = self.monitor_data.iter()
-            .find(|(_, data)| data.is_critical && data.values.back().unwrap().criticality == Criticality::Critical);
+            .find(|(name, data)| {
+                data.is_critical &&
+                data.values.back().unwrap().criticality == Criticality::Critical &&
+                !self.is_silenced(name, "")
+            });
 
         if let Some((name, _)) = critical_monitor {
             log::debug!("Host is now down since monitor \"{}\" is at critical level", name);
@@ -13,6 +13,9 @@ pub mod host_manager;
 pub mod connection_manager;
 pub mod command_handler;
 pub mod monitor_manager;
+pub mod alerting;
+pub mod maintenance;
+pub mod audit_log;
 pub mod secrets_manager;
 mod host;
 pub use host::HostSetting;
@@ -21,7 +24,7 @@ pub mod enums;
 // Made public for integration tests
 pub mod frontend;
 pub mod file_handler;
-mod metrics;
+pub mod metrics;
 pub mod remote_core;
 pub mod backend;
 
@@ -35,6 +38,8 @@ use host_manager::HostManager;
 use monitor_manager::MonitorManager;
 use connection_manager::ConnectionManager;
 use command_handler::CommandHandler;
+use alerting::AlertManager;
+use audit_log::AuditLogger;
 use host::Host;
 
 
@@ -51,6 +56,9 @@ pub struct CoreComponents {
     pub connection_manager: ConnectionManager,
     pub command_handler: CommandHandler,
     pub monitor_manager: MonitorManager,
+    pub alert_manager: AlertManager,
+    /// Not available if disabled or the log directory couldn't be created.
+    pub audit_log: Option<Arc<AuditLogger>>,
 }
 
 pub fn initialize_openssl() -> Result<(), error::LkError> {
@@ -73,8 +81,23 @@ pub fn initialize_core(
     let host_manager = Rc::new(RefCell::new(HostManager::new()));
     host_manager.borrow_mut().configure(hosts_config);
 
+    let audit_log = if main_config.audit_log.enabled {
+        file_handler::get_data_dir()
+            .map_err(error::LkError::from)
+            .and_then(|data_dir| AuditLogger::new(data_dir.join("audit"), &main_config.audit_log))
+            .map_err(|error| log::error!("Failed to open audit log: {}", error))
+            .ok()
+            .map(Arc::new)
+    }
+    else {
+        None
+    };
+
     let mut connection_manager = ConnectionManager::new(module_factory.clone());
     connection_manager.configure(hosts_config);
+    connection_manager.set_audit_log(audit_log.clone());
+    connection_manager.set_limits(&main_config.connections);
+    connection_manager.set_state_update_sender(host_manager.borrow().new_state_update_sender());
 
     let invocation_id_counter = Arc::new(AtomicU64::new(0));
 
@@ -101,10 +124,16 @@ pub fn initialize_core(
         host_manager.borrow().new_state_update_sender(),
     );
 
+    let mut alert_manager = AlertManager::new();
+    alert_manager.configure(&main_config.alerting);
+    host_manager.borrow_mut().add_observer(alert_manager.new_update_sender());
+
     host_manager.borrow_mut().start_receiving_updates();
     connection_manager.start_processing_requests();
     command_handler.start_processing_responses();
     monitor_manager.start_processing_responses();
+    monitor_manager.start_scheduler();
+    alert_manager.start_processing_updates();
 
     Ok(CoreComponents {
         module_factory,
@@ -112,6 +141,8 @@ pub fn initialize_core(
         connection_manager,
         command_handler,
         monitor_manager,
+        alert_manager,
+        audit_log,
     })
 }
 
@@ -130,11 +161,14 @@ pub fn run(
         connection_manager,
         command_handler,
         monitor_manager,
+        // Kept alive until the frontend exits.
+        alert_manager: _alert_manager,
+        audit_log,
     } = initialize_core(main_config, hosts_config, Arc::new(ModuleFactory::new())).map_err(String::from)?;
 
     let module_metadatas = module_factory.get_module_metadatas();
     let command_backend: Box<dyn backend::CommandBackend> =
-        Box::new(backend::LocalCommandBackend::new(command_handler, monitor_manager));
+        Box::new(backend::LocalCommandBackend::new(command_handler, monitor_manager).with_audit_log(audit_log));
     let config_backend: Box<dyn backend::ConfigBackend> = Box::new(backend::LocalConfigBackend::new(config_dir.clone()));
 
     let mut frontend = frontend::qt::QmlFrontend::new(
@@ -0,0 +1,205 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, DurationRound, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::{self, MaintenanceWindow};
+use crate::error::LkError;
+use crate::utils::CronSchedule;
+
+
+/// Active suppression of alerts for a host. Either from a configured maintenance window or an ad-hoc silence.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Silence {
+    /// ID of an ad-hoc silence. 0 for maintenance windows.
+    pub id: u64,
+    pub host_name: String,
+    /// Empty means all monitors.
+    pub monitors: Vec<String>,
+    /// Empty means all labels.
+    pub labels: Vec<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub comment: String,
+}
+
+impl Silence {
+    /// Use empty label for the monitor-level value.
+    /// Label-specific silences don't apply to the whole monitor.
+    pub fn matches(&self, monitor_id: &str, label: &str) -> bool {
+        let monitor_matches = self.monitors.is_empty() || self.monitors.iter().any(|monitor| monitor == monitor_id);
+        let label_matches = self.labels.is_empty() || self.labels.iter().any(|silenced_label| silenced_label == label);
+        monitor_matches && label_matches
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+}
+
+
+/// Maintenance window with a parsed schedule.
+#[derive(Clone, Debug)]
+pub struct ScheduledWindow {
+    window: MaintenanceWindow,
+    schedule: Option<CronSchedule>,
+}
+
+impl ScheduledWindow {
+    pub fn new(window: &MaintenanceWindow) -> Result<Self, LkError> {
+        let schedule = if window.schedule.is_empty() {
+            match (window.start, window.end) {
+                (Some(start), Some(end)) if start < end => None,
+                (Some(_), Some(_)) => return Err(LkError::other("Maintenance window ends before it starts")),
+                _ => return Err(LkError::other("Maintenance window needs either a schedule or both start and end")),
+            }
+        }
+        else {
+            if window.duration == 0 {
+                return Err(LkError::other("Recurring maintenance window needs a duration"));
+            }
+            Some(CronSchedule::parse(&window.schedule)?)
+        };
+
+        Ok(ScheduledWindow {
+            window: window.clone(),
+            schedule,
+        })
+    }
+
+    /// Returns the start and end of the currently active period. Schedules are matched in the time zone of `now`.
+    pub fn active_period<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let now_utc = now.with_timezone(&Utc);
+        // For recurring windows, start and end limit the time when the schedule is in effect.
+        let within_bounds = self.window.start.is_none_or(|start| start <= now_utc) &&
+                            self.window.end.is_none_or(|end| now_utc < end);
+
+        if !within_bounds {
+            return None;
+        }
+
+        let Some(schedule) = &self.schedule else {
+            return Some((self.window.start?.with_timezone(&Utc), self.window.end?.with_timezone(&Utc)));
+        };
+
+        // Find the latest scheduled start that is still within the duration.
+        let duration = Duration::minutes(self.window.duration as i64);
+        let current_minute = now.clone().duration_trunc(Duration::minutes(1)).ok()?;
+        let earliest_start = current_minute.clone() - duration + Duration::minutes(1);
+        schedule.latest_between(&earliest_start, &current_minute)
+            .map(|start| {
+                let start = start.with_timezone(&Utc);
+                (start, start + duration)
+            })
+    }
+
+    fn to_silence(&self, host_name: &str, (starts_at, ends_at): (DateTime<Utc>, DateTime<Utc>)) -> Silence {
+        Silence {
+            id: 0,
+            host_name: host_name.to_string(),
+            monitors: self.window.monitors.clone(),
+            labels: self.window.labels.clone(),
+            starts_at,
+            ends_at,
+            comment: self.window.comment.clone(),
+        }
+    }
+}
+
+
+/// Keeps track of configured maintenance windows and ad-hoc silences.
+pub struct Silences {
+    /// Host name as key.
+    windows: HashMap<String, Vec<ScheduledWindow>>,
+    adhoc: Vec<Silence>,
+    next_id: u64,
+}
+
+impl Silences {
+    pub fn new() -> Self {
+        Silences {
+            windows: HashMap::new(),
+            adhoc: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Invalid windows are logged and skipped. Ad-hoc silences are kept.
+    pub fn configure(&mut self, hosts_config: &configuration::Hosts) {
+        self.windows.clear();
+
+        for (host_id, host_config) in hosts_config.hosts.iter() {
+            let windows = host_config.effective.maintenance_windows.iter()
+                .filter_map(|window| match ScheduledWindow::new(window) {
+                    Ok(window) => Some(window),
+                    Err(error) => {
+                        log::error!("[{}] Invalid maintenance window: {}", host_id, error);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if !windows.is_empty() {
+                self.windows.insert(host_id.clone(), windows);
+            }
+        }
+
+        self.adhoc.retain(|silence| hosts_config.hosts.contains_key(&silence.host_name));
+    }
+
+    /// Adds an ad-hoc silence starting now. Returns the silence ID.
+    pub fn add(&mut self, host_name: &str, monitor_id: Option<String>, label: Option<String>, duration: Duration, comment: String) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let now = Utc::now();
+        self.adhoc.push(Silence {
+            id,
+            host_name: host_name.to_string(),
+            monitors: monitor_id.into_iter().collect(),
+            labels: label.into_iter().collect(),
+            starts_at: now,
+            ends_at: now + duration,
+            comment,
+        });
+
+        id
+    }
+
+    /// Returns false if the silence didn't exist or had already expired.
+    pub fn cancel(&mut self, silence_id: u64) -> bool {
+        let count_before = self.adhoc.len();
+        self.adhoc.retain(|silence| silence.id != silence_id);
+        self.adhoc.len() != count_before
+    }
+
+    pub fn host_of(&self, silence_id: u64) -> Option<String> {
+        self.adhoc.iter().find(|silence| silence.id == silence_id).map(|silence| silence.host_name.clone())
+    }
+
+    /// Returns silences currently in effect for the host. Expired ad-hoc silences are removed.
+    pub fn active_for(&mut self, host_name: &str, now: DateTime<Utc>) -> Vec<Silence> {
+        self.adhoc.retain(|silence| now < silence.ends_at);
+
+        let local_now = now.with_timezone(&chrono::Local);
+        let windows = self.windows.get(host_name).into_iter().flatten()
+            .filter_map(|window| window.active_period(&local_now).map(|period| window.to_silence(host_name, period)));
+
+        let adhoc = self.adhoc.iter()
+            .filter(|silence| silence.host_name == host_name && silence.is_active(now))
+            .cloned();
+
+        windows.chain(adhoc).collect()
+    }
+}
+
+impl Default for Silences {
+    fn default() -> Self {
+        Silences::new()
+    }
+}
@@ -5,12 +5,14 @@
 
 pub mod lmserver;
 
-#[cfg(feature = "gui")]
 pub mod metric;
-#[cfg(feature = "gui")]
 pub use metric::*;
 
-#[cfg(feature = "gui")]
+pub mod store;
+pub use store::{MetricsStore, RetentionPolicy};
+
 pub mod metrics_manager;
-#[cfg(feature = "gui")]
 pub use metrics_manager::MetricsManager;
+
+pub mod exporter;
+pub use exporter::MetricsExporter;
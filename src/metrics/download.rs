@@ -1,5 +0,0 @@
-/*
- * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
- * SPDX-License-Identifier: GPL-3.0-or-later
- */
-
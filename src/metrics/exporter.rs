@@ -0,0 +1,211 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::LkError;
+use crate::frontend::DisplayData;
+use crate::host_manager::DisplayDataReader;
+
+//
+// Serves current monitoring data over HTTP in the Prometheus text format.
+// Only the latest data point of each monitor is exported. Multivalue data points are exported as their children only.
+//
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct MetricsExporter {
+    local_address: SocketAddr,
+    stop_flag: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MetricsExporter {
+    /// Port 0 in the listen address binds to a random free port.
+    pub fn start(listen_address: &str, source: DisplayDataReader) -> Result<Self, LkError> {
+        let listener = TcpListener::bind(listen_address)
+            .map_err(|error| LkError::other_p("Failed to bind exporter listener", error))?;
+        let local_address = listener.local_addr()?;
+        log::info!("Serving metrics at http://{}/metrics", local_address);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread_stop_flag = stop_flag.clone();
+        let thread = thread::spawn(move || {
+            Self::process_requests(listener, source, thread_stop_flag);
+        });
+
+        Ok(MetricsExporter {
+            local_address,
+            stop_flag,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+
+    pub fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+
+        self.stop_flag.store(true, Ordering::SeqCst);
+        // Wakes up the listener so it notices the stop flag.
+        if let Err(error) = TcpStream::connect(self.local_address) {
+            log::warn!("Failed to wake up exporter thread: {}", error);
+        }
+
+        if thread.join().is_err() {
+            log::error!("Error while waiting for exporter thread");
+        }
+    }
+
+    fn process_requests(listener: TcpListener, source: DisplayDataReader, stop_flag: Arc<AtomicBool>) {
+        for stream in listener.incoming() {
+            if stop_flag.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let result = stream.map_err(LkError::from).and_then(|stream| {
+                let display_data = source.get_display_data();
+                Self::handle_request(stream, &display_data)
+            });
+
+            if let Err(error) = result {
+                log::warn!("Failed to handle exporter request: {}", error);
+            }
+        }
+
+        log::debug!("Stopped exporter");
+    }
+
+    fn handle_request(mut stream: TcpStream, display_data: &DisplayData) -> Result<(), LkError> {
+        // Requests are handled one at a time, so a stalled client shouldn't block others for long.
+        stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+        stream.set_write_timeout(Some(SOCKET_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Headers are not needed.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default();
+        let path = path.split('?').next().unwrap_or_default();
+
+        let (status, body) = match (method, path) {
+            ("GET", "/metrics") => ("200 OK", render(display_data)),
+            ("GET", _) => ("404 Not Found", String::from("Not found\n")),
+            _ => ("405 Method Not Allowed", String::from("Method not allowed\n")),
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, CONTENT_TYPE, body.len(), body
+        );
+        stream.write_all(response.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Renders monitoring data in the Prometheus text exposition format.
+pub fn render(display_data: &DisplayData) -> String {
+    let mut values = String::new();
+    let mut criticalities = String::new();
+    // Duplicate series would make the whole scrape fail.
+    let mut seen = HashSet::new();
+
+    let mut host_names = display_data.hosts.keys().collect::<Vec<_>>();
+    host_names.sort();
+
+    for host_name in host_names {
+        let host_state = &display_data.hosts[host_name].host_state;
+        let mut monitor_ids = host_state.monitor_data.keys().collect::<Vec<_>>();
+        monitor_ids.sort();
+
+        for monitor_id in monitor_ids {
+            let Some(data_point) = host_state.monitor_data[monitor_id].values.back() else {
+                continue;
+            };
+
+            let _ = writeln!(
+                criticalities,
+                "lightkeeper_monitor_criticality{{host=\"{}\",monitor=\"{}\"}} {}",
+                escape_label_value(host_name), escape_label_value(monitor_id), data_point.criticality as u8
+            );
+
+            // Value of a multivalue parent is only a summary of its children.
+            let data_points = if data_point.multivalue.is_empty() {
+                std::slice::from_ref(data_point)
+            }
+            else {
+                data_point.multivalue.as_slice()
+            };
+
+            for data_point in data_points.iter() {
+                if !seen.insert((host_name, monitor_id, &data_point.label)) {
+                    continue;
+                }
+
+                let _ = writeln!(
+                    values,
+                    "lightkeeper_monitor_value{{host=\"{}\",monitor=\"{}\",label=\"{}\",tags=\"{}\"}} {}",
+                    escape_label_value(host_name),
+                    escape_label_value(monitor_id),
+                    escape_label_value(&data_point.label),
+                    escape_label_value(&data_point.tags.join(",")),
+                    format_value(data_point.value_float),
+                );
+            }
+        }
+    }
+
+    format!(
+        "# HELP lightkeeper_monitor_value Numeric value of a monitor data point.\n\
+         # TYPE lightkeeper_monitor_value gauge\n\
+         {}\
+         # HELP lightkeeper_monitor_criticality Criticality of the latest monitor data point: \
+         0=Ignore, 1=Normal, 2=Info, 3=NoData, 4=Warning, 5=Error, 6=Critical, 7=NotAvailable.\n\
+         # TYPE lightkeeper_monitor_criticality gauge\n\
+         {}",
+        values, criticalities
+    )
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f32) -> String {
+    if value.is_nan() {
+        String::from("NaN")
+    }
+    else if value.is_infinite() {
+        if value > 0.0 { String::from("+Inf") } else { String::from("-Inf") }
+    }
+    else {
+        value.to_string()
+    }
+}
@@ -5,8 +5,3 @@
 
 pub mod lmsrequest;
 pub use lmsrequest::*;
-
-#[cfg(feature = "gui")]
-pub mod connection;
-#[cfg(feature = "gui")]
-pub use connection::*;
@@ -4,11 +4,10 @@
  */
 
 ///
-/// This module contains the client-server communication protocol of the locally run LMServer metrics server.
-/// Protocol version 1.0
+/// This module contains the request and response types of the metrics store.
+/// Originally the protocol of the separate LMServer metrics server, now handled by the embedded store.
 ///
 use std::collections::HashMap;
-#[cfg(feature = "gui")]
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
@@ -20,7 +19,6 @@ pub struct Metric {
     pub value: f32,
 }
 
-#[cfg(feature = "gui")]
 #[derive(Serialize, Deserialize)]
 pub struct LMSRequest {
     pub request_id: u64,
@@ -29,7 +27,6 @@ pub struct LMSRequest {
     pub request_type: RequestType,
 }
 
-#[cfg(feature = "gui")]
 #[derive(Serialize, Deserialize)]
 pub enum RequestType {
     Healthcheck,
@@ -49,7 +46,6 @@ pub enum RequestType {
     },
 }
 
-#[cfg(feature = "gui")]
 impl LMSRequest {
     pub fn exit() -> Self {
         LMSRequest {
@@ -60,7 +56,6 @@ impl LMSRequest {
     }
 }
 
-#[cfg(feature = "gui")]
 impl Debug for LMSRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ServiceRequest({})", self.request_id)
@@ -3,260 +3,111 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::io::{self, BufRead, Read, Write};
-use std::os::unix::fs::PermissionsExt;
-use std::os::unix::net::UnixStream;
-use std::path::Path;
-use std::process::Command;
-use std::sync::mpsc;
-use std::time::{Duration, SystemTime};
-use std::{process, thread};
-
-use crate::error::LkError;
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::backend::CommandBackend;
+use crate::error::{ErrorKind, LkError};
 use crate::file_handler;
 use crate::frontend::UIUpdate;
 use crate::metrics::lmserver::{self, LMSRequest, LMSResponse, RequestType};
+use crate::metrics::store::{MetricsStore, RetentionPolicy};
 
 //
-// NOTE: MetricsManager handles connections to metrics server that stores host metrics for charts.
-// Only LMServer (Light Metrics Server) is currently supported. It is a simple, lightweight, locally run,
-// metrics server developed by the Lightkeeper project. It is closed-source to help make open-core model possible.
-//
-// Using LMServer and metrics is optional. The server is not installed by default.
-// It's downloaded automatically if charts are enabled. Download is sourced from GitLab (where it's built and signed) and verified.
-// The communication protocol is open, see lmserver/lmsrequest.rs. The protocol can not be used to send malicious input to Lightkeeper.
-// The metrics server does not require network access (it uses unix sockets) and if using flatpak, this is enforced.
+// NOTE: MetricsManager stores host metrics for charts. Metrics are stored in an embedded time-series store
+// (see store.rs) in the data directory, so no network access or external processes are needed.
+// Requests are processed in a separate thread and responses are sent as UIUpdate::Chart.
+// Alternatively, data points can be recorded directly from host updates, which is what lightkeeper-core does.
 //
 
-/// In milliseconds.
-const SERVICE_EXIT_WAIT_TIME: u64 = 5000;
-const LMSERVER_VERSION: &str = "v0.2.1";
+/// How often old data is downsampled and expired.
+const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 pub struct MetricsManager {
-    process_handle: Option<process::Child>,
-    log_thread: Option<thread::JoinHandle<()>>,
+    store: Option<Arc<Mutex<MetricsStore>>>,
     request_thread: Option<thread::JoinHandle<()>>,
     request_sender: Option<mpsc::Sender<LMSRequest>>,
+    recorder_thread: Option<thread::JoinHandle<()>>,
+    recorder_sender: Option<mpsc::Sender<UIUpdate>>,
 
     /// Every request gets an invocation ID. Valid numbers begin from 1.
     invocation_id_counter: u64,
-    // TODO: support remote database backends in the future?
-    update_sender: mpsc::Sender<UIUpdate>,
+    /// Responses to requests are sent here. Not needed if only recording and synchronous queries are used.
+    update_sender: Option<mpsc::Sender<UIUpdate>>,
+    retention: RetentionPolicy,
 }
 
 impl MetricsManager {
     pub fn new(update_sender: mpsc::Sender<UIUpdate>) -> Self {
         MetricsManager {
-            process_handle: None,
-            log_thread: None,
+            update_sender: Some(update_sender),
+            ..Self::new_headless()
+        }
+    }
+
+    /// Without a frontend, there is no receiver for responses.
+    pub fn new_headless() -> Self {
+        MetricsManager {
+            store: None,
             request_thread: None,
             request_sender: None,
+            recorder_thread: None,
+            recorder_sender: None,
             invocation_id_counter: 1,
-            update_sender: update_sender,
+            update_sender: None,
+            retention: RetentionPolicy::default(),
         }
     }
 
-    /// Downloads (if needed) and verifies metrics server binary and then spawns a new process for it.
+    /// Opens the metrics store in the data directory and starts processing requests.
     pub fn start_service(&mut self) -> Result<(), LkError> {
+        let data_dir = file_handler::get_data_dir()?.join("metrics");
+        self.start_service_in(data_dir)
+    }
+
+    pub fn start_service_in(&mut self, data_dir: std::path::PathBuf) -> Result<(), LkError> {
         if self.is_running() {
             return Ok(());
         }
 
-        log::info!("Starting metrics server");
-        let data_dir = file_handler::get_data_dir()?;
-        let lmserver_path = data_dir.join("lmserver");
-        let signature_path = lmserver_path.with_extension("sig");
-        let socket_path = lmserver_path.with_extension("sock");
-        let version_file_path = lmserver_path.with_extension("version");
-
-        // If data directory is missing, this is probably the first run, so create data directory.
-        std::fs::create_dir_all(&data_dir)?;
-
-        // Sometimes there can be a one-time network problem.
-        for retry_count in 0..2 {
-            if let Err(error) = Self::download_lmserver(&lmserver_path, &signature_path) {
-                if retry_count > 0 {
-                    return Err(LkError::from(error));
-                }
-
-                continue;
-            }
+        log::info!("Starting metrics store in {}", data_dir.display());
+        let store = Arc::new(Mutex::new(MetricsStore::new(data_dir, self.retention.clone())?));
+        self.store = Some(store.clone());
 
-            // Verify signature only if not in debug mode.
-            if !cfg!(debug_assertions) {
-                let sign_cert = include_bytes!("../../certs/sign.crt");
-                if let Err(error) = verify_signature(&lmserver_path, &signature_path, sign_cert) {
-                    // Delete files to force re-download and to lower the risk of accidentally running possibly unverified binary.
-                    std::fs::remove_file(&lmserver_path)?;
-                    std::fs::remove_file(&signature_path)?;
-                    std::fs::remove_file(&version_file_path)?;
-
-                    if retry_count > 0 {
-                        return Err(LkError::from(error));
-                    }
-                }
-            }
+        let (request_sender, request_receiver) = mpsc::channel();
+        let update_sender = self.update_sender.clone();
+        self.request_sender = Some(request_sender);
+        self.request_thread = Some(thread::spawn(move || {
+            Self::process_requests(store, request_receiver, update_sender);
+        }));
 
-            break;
-        }
-
-        if socket_path.exists() {
-            log::debug!("Found existing socket file. Connecting to existing server.");
+        Ok(())
+    }
 
-            let (request_sender, request_receiver) = mpsc::channel();
-            let new_update_sender = self.update_sender.clone();
-            self.request_sender = Some(request_sender);
+    /// Records data points meant for charts from host updates.
+    /// Returns a sender that should be registered as an observer in HostManager.
+    pub fn start_recording(&mut self) -> Result<mpsc::Sender<UIUpdate>, LkError> {
+        let store = self.store.clone().ok_or_else(|| LkError::other("Metrics are not available."))?;
 
-            match lmserver::setup_connection(&socket_path) {
-                Err(_error) => {
-                    log::debug!("Failed to connect to metrics server, removing socket file");
-                    std::fs::remove_file(&socket_path)?;
-                }
-                Ok(tls_stream) => {
-                    self.request_thread = Some(thread::spawn(move || {
-                        Self::process_requests(tls_stream, request_receiver, new_update_sender);
-                    }));
-                }
-            }
+        if let Some(recorder_sender) = self.recorder_sender.as_ref() {
+            return Ok(recorder_sender.clone());
         }
 
-        if !socket_path.exists() {
-            // Start Light Metrics Server process. Failure is not critical, but charts will be unavailable.
-            // Logs are printed to stderr by default. With flatpak, access is further restricted.
-            let is_flatpak = std::env::var("FLATPAK_ID").is_ok();
-            let mut process_handle = if is_flatpak {
-                Command::new("flatpak-spawn")
-                    .arg("--no-network")
-                    .arg(lmserver_path)
-                    .arg("-d")
-                    .arg(data_dir)
-                    .stderr(process::Stdio::piped())
-                    .spawn()?
-            }
-            else {
-                Command::new(lmserver_path)
-                    .arg("-d")
-                    .arg(data_dir)
-                    .stderr(process::Stdio::piped())
-                    .spawn()?
-            };
-
-            let log_thread;
-            if let Some(stderr) = process_handle.stderr.take() {
-                let stderr_reader = std::io::BufReader::new(stderr);
-                log_thread = thread::spawn(move || {
-                    for line in stderr_reader.lines() {
-                        match line {
-                            Ok(line) => log::info!("[lmserver] {}", line),
-                            Err(error) => {
-                                log::error!("Error while reading process output: {}", error);
-                            }
-                        }
-                    }
-                });
-            }
-            else {
-                return Err(LkError::other("Couldn't capture process output."));
-            }
-
-            self.process_handle = Some(process_handle);
-            self.log_thread = Some(log_thread);
-
-            // Wait a little bit for server to start.
-            // TODO: replace with some kind of signalling.
-            thread::sleep(Duration::from_millis(100));
-
-            let (request_sender, request_receiver) = mpsc::channel();
-            let new_update_sender = self.update_sender.clone();
-            self.request_sender = Some(request_sender);
+        let (recorder_sender, recorder_receiver) = mpsc::channel();
+        self.recorder_sender = Some(recorder_sender.clone());
+        self.recorder_thread = Some(thread::spawn(move || {
+            Self::process_host_updates(store, recorder_receiver);
+        }));
 
-            self.request_thread = Some(thread::spawn(move || {
-                match lmserver::setup_connection(&socket_path) {
-                    Err(error) => {
-                        log::error!("Failed to connect to metrics server: {}", error);
-                        // TODO: send error to UI?
-                    }
-                    Ok(tls_stream) => {
-                        Self::process_requests(tls_stream, request_receiver, new_update_sender);
-                    }
-                }
-            }));
-        }
-
-        Ok(())
+        Ok(recorder_sender)
     }
 
-    fn download_lmserver(lmserver_path: &Path, signature_path: &Path) -> io::Result<()> {
-        use base64::{engine::general_purpose, Engine as _};
-
-        // Check and store version info for triggering updates.
-        let version_file_path = lmserver_path.with_extension("version");
-        let download_lmserver = match std::fs::metadata(&version_file_path) {
-            Err(_) => true,
-            Ok(_) => {
-                if std::fs::metadata(&lmserver_path).is_err() {
-                    true
-                }
-                else {
-                    let version = std::fs::read_to_string(&version_file_path)?;
-                    if version.trim() != "dev" && version.trim() != LMSERVER_VERSION {
-                        log::debug!("New version of Light Metrics Server available");
-                        true
-                    }
-                    else {
-                        false
-                    }
-                }
-            }
-        };
-
-        // let t = "".chars().zip("".bytes()).map(|(b, k)| (b as u8) ^ k).collect::<Vec<u8>>();
-        // let t_b64 = general_purpose::STANDARD.encode(t);
-
-        // The binary is not included by default so download it first.
-        if download_lmserver {
-            log::debug!("Downloading Light Metrics Server");
-
-            // NOTE: Unfortunately, the download token's lifetime is limited to 1 year, so it will get updated periodically.
-            // Simple read-only token is used to try to limit metrics server downloads to Lightkeeper and
-            // to keep the repo less public.
-            // Date suffix in filename, so old tokens can be kept available without overwriting.
-            let token_b64 =
-                download_string("https://github.com/kalaksi/lightkeeper/raw/refs/heads/develop/src/metrics/token-2602.txt")?;
-            let token = general_purpose::STANDARD
-                .decode(token_b64.as_str())
-                .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?
-                .iter()
-                .zip("LoremipsumdolorsitametconsecteturadipiscingelitCurabitura".bytes())
-                .map(|(b, k)| (b ^ k) as char)
-                .collect::<String>();
-
-            // Built and signed with GitLab CI.
-            download_file(
-                &format!(
-                    "https://gitlab.com/api/v4/projects/68049585/repository/files/lmserver.sig/raw?ref={}",
-                    LMSERVER_VERSION
-                ),
-                &token,
-                signature_path,
-            )?;
-            download_file(
-                &format!(
-                    "https://gitlab.com/api/v4/projects/68049585/repository/files/lmserver/raw?ref={}",
-                    LMSERVER_VERSION
-                ),
-                &token,
-                lmserver_path,
-            )?;
-
-            // Update version info.
-            std::fs::write(&version_file_path, LMSERVER_VERSION)?;
-
-            // Make sure lmserver is executable.
-            std::fs::set_permissions(&lmserver_path, std::fs::Permissions::from_mode(0o755))?;
-        }
-
-        Ok(())
+    /// Synchronous version of `get_metrics`. Start and end times are unix timestamps in seconds.
+    pub fn query_metrics(&self, host_id: &str, monitor_id: &str, start_time: i64, end_time: i64) -> Result<HashMap<String, Vec<lmserver::Metric>>, LkError> {
+        let store = self.store.as_ref().ok_or_else(|| LkError::other("Metrics are not available."))?;
+        store.lock().unwrap().query(host_id, monitor_id, start_time, end_time)
     }
 
     pub fn is_running(&self) -> bool {
@@ -264,52 +115,21 @@ impl MetricsManager {
     }
 
     pub fn stop(&mut self) -> Result<(), LkError> {
-        let mut stop_success = false;
-
-        if let Some(request_sender) = self.request_sender.take() {
-            let exit_request = LMSRequest::exit();
-            if request_sender.send(exit_request).is_ok() {
-                // May not really be completely successful, but we can try.
-                stop_success = true;
+        if let Some(recorder_sender) = self.recorder_sender.take() {
+            if recorder_sender.send(UIUpdate::Stop()).is_err() {
+                log::warn!("Metrics recorder thread has already stopped");
             }
         }
 
-        if let Some(mut process_handle) = self.process_handle.take() {
-            if !stop_success {
-                match process_handle.kill() {
-                    Ok(_) => stop_success = true,
-                    Err(error) => log::error!("Failed to kill process: {}", error),
-                }
-            }
-
-            let mut waited = 0;
-            loop {
-                if let Ok(_) = process_handle.try_wait() {
-                    stop_success = true;
-                    break;
-                }
-
-                if waited >= SERVICE_EXIT_WAIT_TIME {
-                    log::warn!("Forcing metrics server to stop.");
-                    match process_handle.kill() {
-                        Ok(_) => stop_success = true,
-                        Err(error) => log::error!("Failed to kill process: {}", error),
-                    }
-
-                    break;
-                }
-
-                thread::sleep(Duration::from_millis(100));
-                waited += 100;
+        if let Some(recorder_thread) = self.recorder_thread.take() {
+            if recorder_thread.join().is_err() {
+                log::error!("Error while waiting for recorder thread");
             }
         }
 
-        // Remove socket file.
-        if stop_success {
-            let data_dir = file_handler::get_data_dir()?;
-            let socket_path = data_dir.join("lmserver.sock");
-            if socket_path.exists() {
-                std::fs::remove_file(&socket_path)?;
+        if let Some(request_sender) = self.request_sender.take() {
+            if request_sender.send(LMSRequest::exit()).is_err() {
+                log::warn!("Metrics store thread has already stopped");
             }
         }
 
@@ -319,12 +139,7 @@ impl MetricsManager {
             }
         }
 
-        if let Some(log_thread) = self.log_thread.take() {
-            if log_thread.join().is_err() {
-                log::error!("Error while waiting for log thread");
-            }
-        }
-
+        self.store = None;
         Ok(())
     }
 
@@ -342,27 +157,70 @@ impl MetricsManager {
         let invocation_id = self.send_request(RequestType::MetricsQuery {
             host_id: host_id.to_string(),
             metric_id: monitor_id.to_string(),
-            start_time: start_time,
-            end_time: end_time,
+            start_time,
+            end_time,
         })?;
 
         Ok(invocation_id)
     }
 
+    /// Like `get_metrics`, but prefers metrics stored by the backend, e.g. a remote core.
+    /// Response is sent as UIUpdate::Chart in both cases.
+    pub fn get_backend_metrics(
+        &mut self,
+        backend: &dyn CommandBackend,
+        host_id: &str,
+        monitor_id: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<u64, LkError> {
+        let result = match backend.query_metrics(host_id, monitor_id, start_time, end_time) {
+            Err(error) if error.kind == ErrorKind::NotImplemented => {
+                return self.get_metrics(host_id, monitor_id, start_time, end_time);
+            },
+            result => result,
+        };
+
+        let update_sender = self.update_sender.clone().ok_or_else(|| LkError::other("Metrics are not available."))?;
+        let mut response = LMSResponse {
+            request_id: self.next_invocation_id(),
+            ..Default::default()
+        };
+
+        match result {
+            Ok(metrics) => response.metrics = metrics,
+            Err(error) => {
+                log::error!("Metrics error: {}", error);
+                response.errors.push(error.to_string());
+            }
+        }
+
+        let invocation_id = response.request_id;
+        update_sender
+            .send(UIUpdate::Chart(response))
+            .map_err(|error| LkError::other(format!("Failed to send update: {}", error)))?;
+        Ok(invocation_id)
+    }
+
+    fn next_invocation_id(&mut self) -> u64 {
+        let invocation_id = self.invocation_id_counter;
+        self.invocation_id_counter += 1;
+        invocation_id
+    }
+
     fn send_request(&mut self, request_type: RequestType) -> Result<u64, LkError> {
-        if let Some(request_sender) = self.request_sender.as_ref() {
-            let invocation_id = self.invocation_id_counter;
-            self.invocation_id_counter += 1;
+        if let Some(request_sender) = self.request_sender.clone() {
+            let invocation_id = self.next_invocation_id();
 
             let current_unix_ms = SystemTime::now()
                 .duration_since(SystemTime::UNIX_EPOCH)
-                .map_err(|_| LkError::other(format!("Time calculation error")))?
+                .map_err(|_| LkError::other("Time calculation error"))?
                 .as_millis() as u32;
 
             let service_request = LMSRequest {
                 request_id: invocation_id,
                 time: current_unix_ms,
-                request_type: request_type,
+                request_type,
             };
 
             request_sender
@@ -371,160 +229,111 @@ impl MetricsManager {
             Ok(invocation_id)
         }
         else {
-            return Err(LkError::other("Metrics are not available."));
+            Err(LkError::other("Metrics are not available."))
         }
     }
 
     fn process_requests(
-        mut tls_stream: rustls::StreamOwned<rustls::ClientConnection, UnixStream>,
+        store: Arc<Mutex<MetricsStore>>,
         request_receiver: mpsc::Receiver<LMSRequest>,
-        update_sender: mpsc::Sender<UIUpdate>,
+        update_sender: Option<mpsc::Sender<UIUpdate>>,
     ) {
+        let mut last_compaction: Option<Instant> = None;
+
         loop {
-            // These should never fail.
-            let service_request = request_receiver.recv().unwrap();
-            let serialized = bincode::serialize(&service_request).unwrap();
-
-            // TODO: send errors to UI?
-            if let Err(error) = tls_stream.write_all(&serialized) {
-                log::error!("Failed to send request: {}", error);
-
-                match service_request.request_type {
-                    RequestType::Exit => break,
-                    _ => continue,
-                };
+            if last_compaction.is_none_or(|last| last.elapsed() >= COMPACTION_INTERVAL) {
+                if let Err(error) = store.lock().unwrap().compact(chrono::Utc::now().timestamp()) {
+                    log::error!("Failed to compact metrics: {}", error);
+                }
+                last_compaction = Some(Instant::now());
             }
 
-            if let Err(error) = tls_stream.flush() {
-                log::error!("Failed to send request: {}", error);
-
-                match service_request.request_type {
-                    RequestType::Exit => break,
-                    _ => continue,
-                };
-            }
+            let service_request = match request_receiver.recv_timeout(COMPACTION_INTERVAL) {
+                Ok(request) => request,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
 
-            // 16 MB buffer.
-            let mut buffer = vec![0; 16777216];
+            let request_started = Instant::now();
+            let mut response = LMSResponse {
+                request_id: service_request.request_id,
+                ..Default::default()
+            };
 
-            let read_count = match service_request.request_type {
-                RequestType::Exit => match tls_stream.read_to_end(&mut buffer) {
-                    Ok(count) => count,
-                    Err(error) => {
-                        log::error!("Failed to read response when exiting: {}", error);
-                        break;
+            match service_request.request_type {
+                RequestType::Exit => break,
+                RequestType::Healthcheck => {},
+                RequestType::MetricsInsert { host_id, metric_id, metrics } => {
+                    if let Err(error) = store.lock().unwrap().insert(&host_id, &metric_id, &metrics) {
+                        response.errors.push(error.to_string());
                     }
                 },
-                _ => match tls_stream.read(&mut buffer) {
-                    Ok(count) => count,
-                    Err(error) => {
-                        // Too small buffer may also result in an error.
-                        log::error!("Failed to read response: {}", error);
-                        continue;
+                RequestType::MetricsQuery { host_id, metric_id, start_time, end_time } => {
+                    match store.lock().unwrap().query(&host_id, &metric_id, start_time, end_time) {
+                        Ok(metrics) => response.metrics = metrics,
+                        Err(error) => response.errors.push(error.to_string()),
                     }
                 },
-            };
-
-            if read_count == 0 {
-                log::error!("No data received.");
             }
 
-            let response = match bincode::deserialize::<LMSResponse>(&buffer[..read_count]) {
-                Ok(response) => response,
-                Err(error) => {
-                    log::error!("Failed to deserialize response: {}", error);
-                    continue;
-                }
-            };
-
-            if response.lag > 100 {
-                log::debug!("Request took {} ms", response.lag);
-            }
-            else if response.lag > 500 {
+            response.lag = request_started.elapsed().as_millis() as u32;
+            if response.lag > 500 {
                 log::warn!("Request took {} ms", response.lag);
             }
-
-            if response.errors.len() > 0 {
-                log::error!("Service error: {}", response.errors.join(". "));
+            else if response.lag > 100 {
+                log::debug!("Request took {} ms", response.lag);
             }
 
-            match service_request.request_type {
-                RequestType::Exit => break,
-                _ => (),
-            };
+            if !response.errors.is_empty() {
+                log::error!("Metrics error: {}", response.errors.join(". "));
+            }
 
-            if let Err(error) = update_sender.send(UIUpdate::Chart(response)) {
-                log::error!("Failed to send update: {}", error);
+            if let Some(update_sender) = update_sender.as_ref() {
+                if let Err(error) = update_sender.send(UIUpdate::Chart(response)) {
+                    log::error!("Failed to send update: {}", error);
+                }
             }
         }
-    }
-}
-/// Function to download a file using ureq.
-fn download_file(url: &str, access_token: &str, output_path: &Path) -> io::Result<()> {
-    let agent = ureq::Agent::config_builder()
-        // Keep timeout short so it doesn't block startup too long in case there's no access to internet.
-        .timeout_global(Some(Duration::from_secs(3)))
-        .build()
-        .new_agent();
-    let mut response = agent
-        .get(url)
-        .header("PRIVATE-TOKEN", access_token)
-        .call()
-        .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
-
-    if response.status().as_u16() == 200 {
-        let mut file = std::fs::File::create(output_path)?;
-        let mut reader = response.body_mut().as_reader();
-        io::copy(&mut reader, &mut file)?;
-
-        log::debug!("Downloaded file: {}", output_path.display());
-        Ok(())
-    }
-    else {
-        Err(io::Error::new(io::ErrorKind::Other, "Failed to download file."))
-    }
-}
 
-fn download_string(url: &str) -> io::Result<String> {
-    let agent = ureq::Agent::config_builder()
-        // Keep timeout short so it doesn't block startup too long in case there's no access to internet.
-        .timeout_global(Some(Duration::from_secs(3)))
-        .build()
-        .new_agent();
-    let mut response = agent.get(url).call().map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        log::debug!("Stopped metrics store");
+    }
 
-    if response.status().as_u16() == 200 {
-        let mut reader = response.body_mut().as_reader();
-        let mut content = String::new();
-        reader.read_to_string(&mut content)?;
+    fn process_host_updates(store: Arc<Mutex<MetricsStore>>, receiver: mpsc::Receiver<UIUpdate>) {
+        loop {
+            let display_data = match receiver.recv() {
+                Ok(UIUpdate::Host(display_data)) => display_data,
+                Ok(UIUpdate::Stop()) => break,
+                Ok(_) => continue,
+                Err(error) => {
+                    log::error!("Stopped metrics recorder thread: {}", error);
+                    break;
+                }
+            };
 
-        Ok(content)
-    }
-    else {
-        Err(io::Error::new(io::ErrorKind::Other, "Failed to download file."))
-    }
-}
+            let Some((_, monitoring_data)) = display_data.new_monitoring_data else {
+                continue;
+            };
 
-fn verify_signature(file_path: &std::path::Path, signature_path: &std::path::Path, sign_cert: &[u8]) -> io::Result<()> {
-    let file_bytes = std::fs::read(file_path)?;
-    let signature = std::fs::read(signature_path)?;
-    let sign_cert = openssl::x509::X509::from_pem(sign_cert)?.public_key()?;
+            if !monitoring_data.display_options.use_with_charts {
+                continue;
+            }
 
-    let mut verifier = openssl::sign::Verifier::new(openssl::hash::MessageDigest::sha256(), &sign_cert)?;
-    verifier.update(&file_bytes)?;
+            let time = chrono::Utc::now().timestamp_millis();
+            let metrics = monitoring_data.values.iter()
+                .flat_map(|data_point| std::iter::once(data_point).chain(data_point.multivalue.iter()))
+                .map(|data_point| lmserver::Metric {
+                    time,
+                    label: data_point.label.clone(),
+                    value: data_point.value_float,
+                })
+                .collect::<Vec<_>>();
+
+            let host_id = &display_data.host_state.host.name;
+            if let Err(error) = store.lock().unwrap().insert(host_id, &monitoring_data.monitor_id, &metrics) {
+                log::error!("[{}] Failed to record metrics: {}", host_id, error);
+            }
+        }
 
-    if !verifier.verify(&signature)? {
-        log::error!(
-            "{} integrity verification failed.",
-            file_path.file_name().unwrap_or_default().to_string_lossy()
-        );
-        Err(io::Error::new(io::ErrorKind::Other, "Integrity verification failed."))
-    }
-    else {
-        log::debug!(
-            "{} integrity verified.",
-            file_path.file_name().unwrap_or_default().to_string_lossy()
-        );
-        Ok(())
+        log::debug!("Stopped metrics recorder");
     }
 }
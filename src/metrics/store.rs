@@ -0,0 +1,325 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::LkError;
+use crate::metrics::lmserver::Metric;
+
+//
+// Embedded time-series store. Every host and monitor has their own append-only segment file.
+// Old raw data is periodically downsampled into a rollup segment that has averages over a longer interval.
+//
+// Segment file format: magic bytes followed by records of
+// time (i64, ms), value (f32), label length (u16) and label (UTF-8), all in little-endian.
+// A write interrupted by a crash can leave a partial record at the end. It's cut off before the next append.
+//
+
+const SEGMENT_MAGIC: &[u8; 4] = b"LKM1";
+const RECORD_HEADER_LENGTH: usize = 14;
+const RAW_EXTENSION: &str = "seg";
+const ROLLUP_EXTENSION: &str = "rollup.seg";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetentionPolicy {
+    /// How long to keep raw data points. In seconds.
+    pub raw_retention: i64,
+    /// Length of the downsampling interval. In seconds.
+    pub rollup_interval: i64,
+    /// How long to keep downsampled data. In seconds.
+    pub rollup_retention: i64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            raw_retention: 7 * 24 * 60 * 60,
+            rollup_interval: 60 * 60,
+            rollup_retention: 365 * 24 * 60 * 60,
+        }
+    }
+}
+
+pub struct MetricsStore {
+    data_dir: PathBuf,
+    retention: RetentionPolicy,
+    /// Segment lengths after the latest append. Segments are checked for partial records if their length differs.
+    valid_lengths: Mutex<HashMap<PathBuf, u64>>,
+}
+
+impl MetricsStore {
+    pub fn new(data_dir: PathBuf, retention: RetentionPolicy) -> Result<Self, LkError> {
+        fs::create_dir_all(&data_dir)?;
+        Ok(MetricsStore {
+            data_dir,
+            retention,
+            valid_lengths: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Appends metrics. Metric time is in milliseconds.
+    pub fn insert(&self, host_id: &str, metric_id: &str, metrics: &[Metric]) -> Result<(), LkError> {
+        let path = self.segment_path(host_id, metric_id, RAW_EXTENSION);
+        let mut valid_lengths = self.valid_lengths.lock()?;
+
+        // On failure, the segment will be checked again on the next append.
+        let known_length = valid_lengths.remove(&path);
+        let length = append_records(&path, metrics, known_length)?;
+        valid_lengths.insert(path, length);
+        Ok(())
+    }
+
+    /// Returns metrics grouped by label and sorted by time. Start and end times are unix timestamps in seconds.
+    /// Downsampled data is returned for periods where raw data is no longer available.
+    pub fn query(&self, host_id: &str, metric_id: &str, start_time: i64, end_time: i64) -> Result<HashMap<String, Vec<Metric>>, LkError> {
+        let start_ms = start_time.saturating_mul(1000);
+        let end_ms = end_time.saturating_mul(1000);
+
+        let raw = read_records(&self.segment_path(host_id, metric_id, RAW_EXTENSION))?;
+        let rollup = read_records(&self.segment_path(host_id, metric_id, ROLLUP_EXTENSION))?;
+        // Rollups overlapping with raw data would show up as duplicates.
+        let first_raw_time = raw.iter().map(|metric| metric.time).min().unwrap_or(i64::MAX);
+
+        let mut result = HashMap::<String, Vec<Metric>>::new();
+        let metrics = rollup.into_iter()
+            .filter(|metric| metric.time < first_raw_time)
+            .chain(raw)
+            .filter(|metric| metric.time >= start_ms && metric.time <= end_ms);
+
+        for metric in metrics {
+            result.entry(metric.label.clone()).or_default().push(metric);
+        }
+
+        for metrics in result.values_mut() {
+            metrics.sort_by_key(|metric| metric.time);
+        }
+
+        Ok(result)
+    }
+
+    /// Downsamples raw data older than the raw retention and removes expired rollups.
+    /// `now` is a unix timestamp in seconds.
+    pub fn compact(&self, now: i64) -> Result<(), LkError> {
+        let interval_ms = self.retention.rollup_interval.max(1).saturating_mul(1000);
+        // Aligned to the rollup interval so that each interval is downsampled only once and with all its data points.
+        let raw_cutoff = (now - self.retention.raw_retention).saturating_mul(1000);
+        let raw_cutoff = raw_cutoff - raw_cutoff.rem_euclid(interval_ms);
+        let rollup_cutoff = (now - self.retention.rollup_retention).saturating_mul(1000);
+
+        for host_dir in fs::read_dir(&self.data_dir)? {
+            let host_dir = host_dir?.path();
+            if !host_dir.is_dir() {
+                continue;
+            }
+
+            for entry in fs::read_dir(&host_dir)? {
+                let raw_path = entry?.path();
+                let file_name = raw_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                if !file_name.ends_with(&format!(".{}", RAW_EXTENSION)) || file_name.ends_with(&format!(".{}", ROLLUP_EXTENSION)) {
+                    continue;
+                }
+
+                let rollup_path = raw_path.with_extension(ROLLUP_EXTENSION);
+                let (old, recent): (Vec<_>, Vec<_>) = read_records(&raw_path)?.into_iter()
+                    .partition(|metric| metric.time < raw_cutoff);
+
+                let mut rollup = read_records(&rollup_path)?;
+                let rollup_length = rollup.len();
+                rollup.retain(|metric| metric.time >= rollup_cutoff);
+
+                if old.is_empty() {
+                    if rollup.len() != rollup_length {
+                        write_records(&rollup_path, &rollup)?;
+                    }
+                    continue;
+                }
+
+                log::debug!("Downsampled {} data points in {}", old.len(), raw_path.display());
+                rollup.extend(downsample(&old, interval_ms).into_iter().filter(|metric| metric.time >= rollup_cutoff));
+
+                write_records(&rollup_path, &rollup)?;
+                write_records(&raw_path, &recent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn segment_path(&self, host_id: &str, metric_id: &str, extension: &str) -> PathBuf {
+        self.data_dir
+            .join(escape_file_name(host_id))
+            .join(format!("{}.{}", escape_file_name(metric_id), extension))
+    }
+}
+
+/// Averages over fixed intervals, separately for each label.
+fn downsample(metrics: &[Metric], interval_ms: i64) -> Vec<Metric> {
+    let mut buckets = BTreeMap::<(String, i64), (f64, u32)>::new();
+    for metric in metrics {
+        let bucket_start = metric.time - metric.time.rem_euclid(interval_ms);
+        let (sum, count) = buckets.entry((metric.label.clone(), bucket_start)).or_default();
+        *sum += metric.value as f64;
+        *count += 1;
+    }
+
+    buckets.into_iter()
+        .map(|((label, time), (sum, count))| Metric {
+            time,
+            label,
+            value: (sum / count as f64) as f32,
+        })
+        .collect()
+}
+
+/// Keeps file names safe and unambiguous.
+fn escape_file_name(name: &str) -> String {
+    name.bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn encode_records(metrics: &[Metric], buffer: &mut Vec<u8>) {
+    for metric in metrics {
+        // Overly long labels are cut without splitting a character.
+        let mut label_length = metric.label.len().min(u16::MAX as usize);
+        while !metric.label.is_char_boundary(label_length) {
+            label_length -= 1;
+        }
+        let label = &metric.label.as_bytes()[..label_length];
+
+        buffer.extend_from_slice(&metric.time.to_le_bytes());
+        buffer.extend_from_slice(&metric.value.to_le_bytes());
+        buffer.extend_from_slice(&(label.len() as u16).to_le_bytes());
+        buffer.extend_from_slice(label);
+    }
+}
+
+/// Returns the new length of the segment. If the segment isn't `known_length` long, partial records are cut off first.
+fn append_records(path: &Path, metrics: &[Metric], known_length: Option<u64>) -> io::Result<u64> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::OpenOptions::new().create(true).read(true).append(true).open(path)?;
+
+    let mut length = file.metadata()?.len();
+    if known_length != Some(length) {
+        length = repair_segment(&mut file, path)?;
+    }
+
+    let mut buffer = Vec::new();
+    if length == 0 {
+        buffer.extend_from_slice(SEGMENT_MAGIC);
+    }
+    encode_records(metrics, &mut buffer);
+
+    // Single write so that records don't get interleaved.
+    file.write_all(&buffer)?;
+    Ok(length + buffer.len() as u64)
+}
+
+/// Truncates the segment to the last whole record. Returns the resulting length.
+fn repair_segment(file: &mut fs::File, path: &Path) -> io::Result<u64> {
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+
+    let valid_length = if is_partial_header(&contents) {
+        0
+    }
+    else if let Some(records) = contents.strip_prefix(SEGMENT_MAGIC.as_slice()) {
+        SEGMENT_MAGIC.len() + decode_records(records).1
+    }
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid segment file {}", path.display())));
+    };
+
+    if valid_length < contents.len() {
+        log::warn!("Removing truncated record from {}", path.display());
+        file.set_len(valid_length as u64)?;
+    }
+
+    Ok(valid_length as u64)
+}
+
+/// Header that was cut off, e.g. by a crash right after the file was created.
+fn is_partial_header(contents: &[u8]) -> bool {
+    contents.len() < SEGMENT_MAGIC.len() && SEGMENT_MAGIC.starts_with(contents)
+}
+
+/// Replaces the whole segment. Removes the file if there are no records left.
+fn write_records(path: &Path, metrics: &[Metric]) -> io::Result<()> {
+    if metrics.is_empty() {
+        return match fs::remove_file(path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        };
+    }
+
+    let mut buffer = SEGMENT_MAGIC.to_vec();
+    encode_records(metrics, &mut buffer);
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, &buffer)?;
+    fs::rename(&temp_path, path)
+}
+
+/// A truncated record at the end (e.g. after a crash) is ignored.
+fn read_records(path: &Path) -> io::Result<Vec<Metric>> {
+    let mut contents = Vec::new();
+    match fs::File::open(path) {
+        Ok(mut file) => file.read_to_end(&mut contents)?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    if is_partial_header(&contents) {
+        return Ok(Vec::new());
+    }
+
+    let Some(records) = contents.strip_prefix(SEGMENT_MAGIC.as_slice()) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid segment file {}", path.display())));
+    };
+
+    let (metrics, decoded_length) = decode_records(records);
+    if decoded_length < records.len() {
+        log::warn!("Ignoring truncated record in {}", path.display());
+    }
+
+    Ok(metrics)
+}
+
+/// Returns the whole records and their total length in bytes.
+fn decode_records(mut remaining: &[u8]) -> (Vec<Metric>, usize) {
+    let mut metrics = Vec::new();
+    let mut decoded_length = 0;
+
+    while remaining.len() >= RECORD_HEADER_LENGTH {
+        let time = i64::from_le_bytes(remaining[0..8].try_into().unwrap());
+        let value = f32::from_le_bytes(remaining[8..12].try_into().unwrap());
+        let label_length = u16::from_le_bytes(remaining[12..14].try_into().unwrap()) as usize;
+        let record_length = RECORD_HEADER_LENGTH + label_length;
+
+        let Some(label) = remaining.get(RECORD_HEADER_LENGTH..record_length) else {
+            break;
+        };
+
+        metrics.push(Metric {
+            time,
+            label: String::from_utf8_lossy(label).to_string(),
+            value,
+        });
+        remaining = &remaining[record_length..];
+        decoded_length += record_length;
+    }
+
+    (metrics, decoded_length)
+}
@@ -13,7 +13,7 @@ use crate::{
     error::LkError,
     frontend,
     host::Host,
-    module::{connection::ResponseMessage, MetadataSupport, Module, ModuleSpecification},
+    module::{connection::{ResponseMessage, UnixSocketRequest}, MetadataSupport, Module, ModuleSpecification},
 };
 
 pub type Command = Box<dyn CommandModule + Send + Sync>;
@@ -48,6 +48,11 @@ pub trait CommandModule: BoxCloneableCommand + MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
+    /// Used instead of connector messages if implemented. Should never panic.
+    fn get_socket_request(&self, _host: Host, _parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
+        Err(LkError::not_implemented())
+    }
+
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
         Ok(CommandResult::new_info(response.message.clone()))
     }
@@ -6,6 +6,12 @@
 pub mod inspect;
 pub use inspect::Inspect;
 
+pub mod events;
+pub use events::Events;
+
+pub mod logs;
+pub use logs::Logs;
+
 pub mod restart;
 pub use restart::Restart;
 
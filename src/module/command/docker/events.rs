@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use chrono::{TimeZone, Utc};
+use serde::Deserialize;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::EXIT_CODE_CONNECT_FAILED;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
+use crate::module::*;
+use crate::module::command::*;
+use lightkeeper_module::command_module;
+
+#[command_module(
+    name="docker-events",
+    version="0.0.1",
+    description="Follows events of the Docker daemon.",
+    uses_sudo=true,
+)]
+pub struct Events;
+
+impl Module for Events {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        Events { }
+    }
+}
+
+impl CommandModule for Events {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("docker-containers"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("view-document"),
+            display_text: String::from("Follow events"),
+            tab_title: String::from("Docker events"),
+            action: UIAction::FollowOutput,
+            ..Default::default()
+        }
+    }
+
+    fn get_socket_request(&self, host: Host, _parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, "/events").use_sudo())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_partial {
+            // Following doesn't end on its own, so there's no real progress.
+            Ok(CommandResult::new_partial(format_events(&response.message_increment), 10))
+        }
+        else if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            Ok(CommandResult::new_hidden(String::from("Couldn't connect to Docker daemon."))
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else if response.return_code != 0 {
+            Ok(CommandResult::new_hidden(response.message_increment.clone())
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else {
+            Ok(CommandResult::new_hidden(format_events(&response.message_increment)))
+        }
+    }
+}
+
+/// Events are streamed as JSON objects, one per line. Libpod API uses the same fields.
+/// Lines that can't be parsed (e.g. incomplete last line) are kept as they are.
+pub fn format_events(output: &str) -> String {
+    output.split('\n').map(|line| {
+        match serde_json::from_str::<Event>(line) {
+            Ok(event) => {
+                let time = Utc.timestamp_opt(event.time, 0).single()
+                              .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+                              .unwrap_or_default();
+                let name = event.actor.attributes.get("name").cloned()
+                                .unwrap_or_else(|| event.actor.id.chars().take(12).collect());
+                format!("{} {} {} {}", time, event.event_type, event.action, name)
+            },
+            Err(_) => line.to_string(),
+        }
+    }).collect::<Vec<_>>().join("\n")
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "Type")]
+    event_type: String,
+    #[serde(rename = "Action")]
+    action: String,
+    #[serde(rename = "Actor", default)]
+    actor: EventActor,
+    #[serde(default)]
+    time: i64,
+}
+
+#[derive(Deserialize, Default)]
+struct EventActor {
+    #[serde(rename = "ID", default)]
+    id: String,
+    #[serde(rename = "Attributes", default)]
+    attributes: HashMap<String, String>,
+}
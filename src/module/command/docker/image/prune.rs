@@ -9,10 +9,11 @@ use serde_json;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
 #[command_module(
@@ -45,13 +46,9 @@ impl CommandModule for Prune {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _parameters: Vec<String>) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
+    fn get_socket_request(&self, host: Host, _parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "POST", "http://localhost/images/prune"]);
-            Ok(command.to_string())
+            Ok(UnixSocketRequest::post(DOCKER_SOCKET_PATH, "/images/prune").use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -59,6 +56,13 @@ impl CommandModule for Prune {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Err(String::from("Couldn't connect to Docker daemon."));
+        }
+        else if response.return_code != 0 {
+            return Err(api_error_message(response));
+        }
+
         let result: PruneResult = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
         Ok(CommandResult::new_info(format!("Total reclaimed space: {} B", result.space_reclaimed)))
     }
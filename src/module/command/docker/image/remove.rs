@@ -11,10 +11,11 @@ use serde_json;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
@@ -50,19 +51,15 @@ impl CommandModule for Remove {
         }
     }
 
-    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         let target_id = parameters.first().unwrap();
 
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
         if !string_validation::is_alphanumeric_with(target_id, ":-.") {
             Err(LkError::invalid_parameter("Invalid image ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            let url = format!("http://localhost/images/{}", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "DELETE", &url]);
-            Ok(command.to_string())
+            let path = format!("/images/{}", target_id);
+            Ok(UnixSocketRequest::delete(DOCKER_SOCKET_PATH, &path).use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -70,6 +67,13 @@ impl CommandModule for Remove {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Ok(CommandResult::new_error(String::from("Couldn't connect to Docker daemon.")));
+        }
+        else if response.return_code != 0 {
+            return Ok(CommandResult::new_error(api_error_message(response)));
+        }
+
         if response.message.len() > 0 {
             if let Ok(deletion_details) = serde_json::from_str::<Vec<DeletionDetails>>(&response.message) {
                 // Alternatively, display verbosely.
@@ -90,9 +94,6 @@ impl CommandModule for Remove {
                 let response_message = format!("{} layers untagged, {} layers deleted", untagged_count, deleted_count);
                 return Ok(CommandResult::new_info(response_message));
             }
-            else if let Ok(docker_response) = serde_json::from_str::<ErrorMessage>(&response.message) {
-                return Ok(CommandResult::new_error(docker_response.message));
-            }
         }
         Ok(CommandResult::new_info(response.message.clone()))
     }
@@ -104,8 +105,3 @@ struct DeletionDetails {
     untagged: Option<String>,
     deleted: Option<String>,
 }
-
-#[derive(Deserialize)]
-struct ErrorMessage {
-    message: String,
-}
@@ -7,10 +7,11 @@ use std::collections::HashMap;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::api_error_message;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
@@ -45,19 +46,15 @@ impl CommandModule for Inspect {
         }
     }
 
-    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         let target_id = parameters.first().unwrap();
 
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
         if !string_validation::is_alphanumeric_with(target_id, &"-_") {
             Err(LkError::invalid_parameter("Invalid container ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            let url = format!("http://localhost/containers/{}/json?all=true", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", &url]);
-            Ok(command.to_string())
+            let path = format!("/containers/{}/json", target_id);
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, &path).use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -66,7 +63,7 @@ impl CommandModule for Inspect {
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
         if response.is_error() {
-            Ok(CommandResult::new_error(response.message.clone()))
+            Ok(CommandResult::new_error(api_error_message(response)))
         }
         else {
             Ok(CommandResult::new_hidden(response.message.clone()))
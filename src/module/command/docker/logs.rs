@@ -0,0 +1,92 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::EXIT_CODE_CONNECT_FAILED;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::string_validation;
+use lightkeeper_module::command_module;
+
+#[command_module(
+    name="docker-logs",
+    version="0.0.1",
+    description="Follows logs of a Docker container.",
+    uses_sudo=true,
+    settings={
+        tail => "Number of earlier log lines to show. Default: 1000.",
+    }
+)]
+pub struct Logs {
+    tail: u32,
+}
+
+impl Module for Logs {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Logs {
+            tail: settings.get("tail").and_then(|value| value.parse::<u32>().ok()).unwrap_or(1000),
+        }
+    }
+}
+
+impl CommandModule for Logs {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("docker-containers"),
+            parent_id: String::from("docker-containers"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("view-document"),
+            display_text: String::from("Follow logs"),
+            tab_title: String::from("Container logs"),
+            action: UIAction::FollowOutput,
+            ..Default::default()
+        }
+    }
+
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
+        let target_id = parameters.first().unwrap();
+
+        if !string_validation::is_alphanumeric_with(target_id, "-_.") {
+            Err(LkError::invalid_parameter("Invalid container ID", target_id))
+        }
+        else if host.platform.os == platform_info::OperatingSystem::Linux {
+            let path = format!(
+                "/containers/{}/logs?follow=true&stdout=true&stderr=true&timestamps=true&tail={}",
+                target_id, self.tail
+            );
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, &path).use_sudo().demultiplex())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_partial {
+            // Following doesn't end on its own, so there's no real progress.
+            Ok(CommandResult::new_partial(response.message_increment.clone(), 10))
+        }
+        else if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            Ok(CommandResult::new_hidden(String::from("Couldn't connect to Docker daemon."))
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else if response.return_code != 0 {
+            Ok(CommandResult::new_hidden(response.message_increment.clone())
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else {
+            Ok(CommandResult::new_hidden(response.message_increment.clone()))
+        }
+    }
+}
@@ -7,10 +7,11 @@ use std::collections::HashMap;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
@@ -45,18 +46,14 @@ impl CommandModule for Restart {
         }
     }
 
-    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         let target_id = parameters.first().unwrap();
         if !string_validation::is_alphanumeric(target_id) {
             Err(LkError::invalid_parameter("Invalid container ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            let url = format!("http://localhost/containers/{}/restart", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "POST", &url]);
-            Ok(command.to_string())
+            let path = format!("/containers/{}/restart", target_id);
+            Ok(UnixSocketRequest::post(DOCKER_SOCKET_PATH, &path).use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -64,6 +61,13 @@ impl CommandModule for Restart {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Err(String::from("Couldn't connect to Docker daemon."));
+        }
+        else if response.return_code != 0 {
+            return Err(api_error_message(response));
+        }
+
         Ok(CommandResult::new_info(response.message.clone()))
     }
 }
\ No newline at end of file
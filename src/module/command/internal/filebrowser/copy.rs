@@ -48,7 +48,7 @@ impl CommandModule for FileBrowserCopy {
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
         let mut command = ShellCommand::new();
-        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+        command.use_sudo = host.privilege_escalation().is_some();
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
             if parameters.len() < 2 {
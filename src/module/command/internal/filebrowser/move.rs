@@ -65,7 +65,6 @@ impl CommandModule for FileBrowserMove {
             }
         }
 
-        let sudo = if host.settings.contains(&HostSetting::UseSudo) { "sudo " } else { "" };
         let dest = match destination.ends_with('/') {
             true => destination.clone(),
             false => format!("{}/", destination),
@@ -88,32 +87,37 @@ impl CommandModule for FileBrowserMove {
         let script = format!(
             concat!(
                 "dst={dest} && ",
-                "dst_dev=$({sudo}stat -c %d -- \"$dst\") && ",
+                "dst_dev=$(stat -c %d -- \"$dst\") && ",
                 "need_rsync=0 && ",
                 "for src in {quoted_sources}; do ",
-                    "src_dev=$({sudo}stat -c %d -- \"$src\") || exit 1; ",
+                    "src_dev=$(stat -c %d -- \"$src\") || exit 1; ",
                     "if [ \"$src_dev\" != \"$dst_dev\" ]; then need_rsync=1; break; fi; ",
                 "done && ",
                 "if [ \"$need_rsync\" = 0 ]; then ",
-                    "{sudo}mv -n -- {quoted_sources} \"$dst\" || exit $?; ",
+                    "mv -n -- {quoted_sources} \"$dst\" || exit $?; ",
                 "else ",
                     // `--` stops option parsing so a path starting with '-' can never be
                     // treated as an rsync flag. Paths are absolute, so the ':' in any name
                     // always follows a '/' and is never mistaken for a host:path remote spec.
-                    "{sudo}env LANG=C LC_ALL=C rsync -av --info=progress2 --stats ",
+                    "env LANG=C LC_ALL=C rsync -av --info=progress2 --stats ",
                     "--ignore-existing --remove-source-files {out_format} -- {quoted_sources} \"$dst\" || exit $?; ",
                     // --remove-source-files only removes files, leaving empty source
                     // directories behind, so clean those up to complete the move.
-                    "{sudo}find -- {quoted_sources} -depth -type d -empty -delete 2>/dev/null || true; ",
+                    "find -- {quoted_sources} -depth -type d -empty -delete 2>/dev/null || true; ",
                 "fi"
             ),
             dest = sh_single_quoted(&dest),
-            sudo = sudo,
             quoted_sources = quoted_sources,
             out_format = out_format,
         );
 
-        Ok(script)
+        // The whole script is run with higher privileges so it works with any privilege escalation method.
+        if host.privilege_escalation().is_some() {
+            Ok(format!("sudo sh -c {}", sh_single_quoted(&script)))
+        }
+        else {
+            Ok(script)
+        }
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
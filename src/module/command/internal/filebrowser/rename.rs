@@ -66,7 +66,7 @@ impl CommandModule for FileBrowserRename {
         let new_path_str = new_path.to_string_lossy();
 
         let mut command = ShellCommand::new();
-        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+        command.use_sudo = host.privilege_escalation().is_some();
         command.arguments(vec!["mv", "-n", old_path, &new_path_str]);
 
         Ok(command.to_string())
@@ -57,7 +57,7 @@ impl CommandModule for FileBrowserRm {
         }
 
         let mut command = ShellCommand::new();
-        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+        command.use_sudo = host.privilege_escalation().is_some();
         let mut args = vec!["rm", "-r", "--"];
         args.extend(parameters.iter().map(String::as_str));
         command.arguments(args);
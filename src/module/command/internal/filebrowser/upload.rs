@@ -96,8 +96,9 @@ impl CommandModule for FileBrowserUpload {
             self.custom_known_hosts_path.as_deref(),
         )?);
 
-        if host.settings.contains(&HostSetting::UseSudo) {
-            command.argument("--rsync-path=sudo rsync");
+        if let Some(escalation) = host.privilege_escalation() {
+            let rsync_path = escalation.rsync_path().ok_or_else(|| LkError::other("Rsync can't be used with su"))?;
+            command.argument(format!("--rsync-path={}", rsync_path));
         }
         command.arguments(vec![local_path.clone(), remote_spec]);
 
@@ -16,6 +16,7 @@ use lightkeeper_module::command_module;
     name="linux-packages-install",
     version="0.0.1",
     description="Installs system packages.",
+    default_timeout=1800,
 )]
 pub struct Install;
 
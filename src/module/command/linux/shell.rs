@@ -59,12 +59,10 @@ impl CommandModule for Shell {
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
             if self.as_root {
-                if host.settings.contains(&crate::host::HostSetting::UseSudo) {
-                    command.arguments(vec!["sudo", "-i"]);
-                }
-                else {
-                    command.arguments(vec!["su", "-"]);
-                }
+                match host.privilege_escalation() {
+                    Some(escalation) => command.arguments(escalation.login_shell()),
+                    None => command.arguments(vec!["su", "-"]),
+                };
             }
         }
         else {
@@ -17,6 +17,7 @@ use lightkeeper_module::command_module;
     name="nixos-channel-update",
     version="0.0.1",
     description="Updates the nix channel.",
+    default_timeout=1800,
     uses_sudo=true,
 )]
 pub struct ChannelUpdate;
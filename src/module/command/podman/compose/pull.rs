@@ -17,6 +17,7 @@ use lightkeeper_module::command_module;
     name="podman-compose-pull",
     version="0.0.1",
     description="Pulls images for podman-compose projects or services.",
+    default_timeout=1800,
     uses_sudo=true,
     settings={
         as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::command::docker::events::format_events;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
+use crate::module::*;
+use crate::module::command::*;
+use lightkeeper_module::command_module;
+
+#[command_module(
+    name="podman-events",
+    version="0.0.1",
+    description="Follows Podman events.",
+    uses_sudo=true,
+    settings={
+        as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+        socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                        For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
+    }
+)]
+pub struct Events {
+    as_root: bool,
+    socket_path: String,
+}
+
+impl Module for Events {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Events {
+            as_root: settings.get("as_root").map(|value| value == "true").unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
+        }
+    }
+}
+
+impl CommandModule for Events {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("podman-containers"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("view-document"),
+            display_text: String::from("Follow events"),
+            tab_title: String::from("Podman events"),
+            action: UIAction::FollowOutput,
+            ..Default::default()
+        }
+    }
+
+    fn get_socket_request(&self, host: Host, _parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let path = format!("{}/events?stream=true", LIBPOD_API_PATH);
+            Ok(api_request(UnixSocketRequest::get(&self.socket_path, &path), self.as_root))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_partial {
+            // Following doesn't end on its own, so there's no real progress.
+            Ok(CommandResult::new_partial(format_events(&response.message_increment), 10))
+        }
+        else if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            Ok(CommandResult::new_hidden(String::from("Couldn't connect to Podman API."))
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else if response.return_code != 0 {
+            Ok(CommandResult::new_hidden(api_error_message(response))
+                            .with_criticality(crate::enums::Criticality::Error))
+        }
+        else {
+            Ok(CommandResult::new_hidden(format_events(&response.message_increment)))
+        }
+    }
+}
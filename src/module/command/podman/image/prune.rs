@@ -4,14 +4,18 @@
  */
 
 use std::collections::HashMap;
+use serde::Deserialize;
+use serde_json;
 
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
+use crate::utils::format_bytes;
 use lightkeeper_module::command_module;
 
 #[command_module(
@@ -20,17 +24,21 @@ use lightkeeper_module::command_module;
     description="Prunes all unused Podman images.",
     uses_sudo=true,
     settings={
-        as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
+        as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+        socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                        For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
     }
 )]
 pub struct Prune {
     as_root: bool,
+    socket_path: String,
 }
 
 impl Module for Prune {
     fn new(settings: &HashMap<String, String>) -> Self {
         Prune {
             as_root: settings.get("as_root").and_then(|value| Some(value == "true")).unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
         }
     }
 }
@@ -51,13 +59,10 @@ impl CommandModule for Prune {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _parameters: Vec<String>) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = self.as_root;
-
+    fn get_socket_request(&self, host: Host, _parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["podman", "image", "prune", "-f"]);
-            Ok(command.to_string())
+            let path = format!("{}/images/prune", LIBPOD_API_PATH);
+            Ok(api_request(UnixSocketRequest::post(&self.socket_path, &path), self.as_root))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -65,15 +70,30 @@ impl CommandModule for Prune {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
-        if response.return_code != 0 {
-            return Ok(CommandResult::new_error(response.message.trim()));
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Ok(CommandResult::new_error("Couldn't connect to Podman API."));
         }
-        let text = response.message.trim();
-        if text.is_empty() {
-            Ok(CommandResult::new_info("Prune completed."))
+        else if response.return_code != 0 {
+            return Ok(CommandResult::new_error(api_error_message(response)));
         }
-        else {
-            Ok(CommandResult::new_info(text))
+
+        let reports: Vec<PruneReport> = serde_json::from_str(&response.message).map_err(|e| e.to_string())?;
+        let errors = reports.iter().filter_map(|report| report.err.clone()).collect::<Vec<String>>();
+        if !errors.is_empty() {
+            return Ok(CommandResult::new_error(errors.join("\n")));
         }
+
+        let space_reclaimed = reports.iter().map(|report| report.size).sum::<u64>();
+        Ok(CommandResult::new_info(format!("{} images removed. Total reclaimed space: {}", reports.len(), format_bytes(space_reclaimed))))
     }
 }
+
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PruneReport {
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    err: Option<String>,
+}
@@ -5,13 +5,17 @@
 
 use std::collections::HashMap;
 
+use serde::Deserialize;
+use serde_json;
+
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
@@ -21,17 +25,21 @@ use lightkeeper_module::command_module;
     description="Removes a Podman image.",
     uses_sudo=true,
     settings={
-        as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
+        as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+        socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                        For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
     }
 )]
 pub struct Remove {
     as_root: bool,
+    socket_path: String,
 }
 
 impl Module for Remove {
     fn new(settings: &HashMap<String, String>) -> Self {
         Remove {
             as_root: settings.get("as_root").and_then(|value| Some(value == "true")).unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
         }
     }
 }
@@ -53,18 +61,15 @@ impl CommandModule for Remove {
         }
     }
 
-    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         let target_id = parameters.first().unwrap();
 
-        let mut command = ShellCommand::new();
-        command.use_sudo = self.as_root;
-
         if !string_validation::is_alphanumeric_with(target_id, ":-.") {
             Err(LkError::invalid_parameter("Invalid image ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["podman", "rmi", "-f", target_id]);
-            Ok(command.to_string())
+            let path = format!("{}/images/{}?force=true", LIBPOD_API_PATH, target_id);
+            Ok(api_request(UnixSocketRequest::delete(&self.socket_path, &path), self.as_root))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -72,15 +77,29 @@ impl CommandModule for Remove {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
-        if response.return_code != 0 {
-            return Ok(CommandResult::new_error(response.message.trim()));
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Ok(CommandResult::new_error("Couldn't connect to Podman API."));
         }
-        let text = response.message.trim();
-        if text.is_empty() {
-            Ok(CommandResult::new_info("Removed."))
+        else if response.return_code != 0 {
+            return Ok(CommandResult::new_error(api_error_message(response)));
         }
-        else {
-            Ok(CommandResult::new_info(text))
+
+        let report: RemoveReport = serde_json::from_str(&response.message).map_err(|e| e.to_string())?;
+        if !report.errors.is_empty() {
+            return Ok(CommandResult::new_error(report.errors.join("\n")));
         }
+
+        Ok(CommandResult::new_info(format!("{} images untagged, {} images deleted", report.untagged.len(), report.deleted.len())))
     }
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RemoveReport {
+    #[serde(default)]
+    untagged: Vec<String>,
+    #[serde(default)]
+    deleted: Vec<String>,
+    #[serde(default)]
+    errors: Vec<String>,
+}
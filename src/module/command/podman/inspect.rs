@@ -7,10 +7,11 @@ use std::collections::HashMap;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
 use crate::module::*;
 use crate::module::command::*;
-use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
@@ -20,17 +21,21 @@ use lightkeeper_module::command_module;
     description="Inspects a Podman container.",
     uses_sudo=true,
     settings={
-        as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
+        as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+        socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                        For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
     }
 )]
 pub struct Inspect {
     as_root: bool,
+    socket_path: String,
 }
 
 impl Module for Inspect {
     fn new(settings: &HashMap<String, String>) -> Self {
         Inspect {
             as_root: settings.get("as_root").and_then(|value| Some(value == "true")).unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
         }
     }
 }
@@ -52,18 +57,15 @@ impl CommandModule for Inspect {
         }
     }
 
-    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+    fn get_socket_request(&self, host: Host, parameters: Vec<String>) -> Result<UnixSocketRequest, LkError> {
         let target_id = parameters.first().unwrap();
 
-        let mut command = ShellCommand::new();
-        command.use_sudo = self.as_root;
-
         if !string_validation::is_alphanumeric_with(target_id, &"-_") {
             Err(LkError::invalid_parameter("Invalid container ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["podman", "inspect", target_id]);
-            Ok(command.to_string())
+            let path = format!("{}/containers/{}/json", LIBPOD_API_PATH, target_id);
+            Ok(api_request(UnixSocketRequest::get(&self.socket_path, &path), self.as_root))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -71,8 +73,11 @@ impl CommandModule for Inspect {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
-        if response.return_code != 0 {
-            return Ok(CommandResult::new_error(response.message.trim()));
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            return Ok(CommandResult::new_error("Couldn't connect to Podman API."));
+        }
+        else if response.return_code != 0 {
+            return Ok(CommandResult::new_error(api_error_message(response)));
         }
         Ok(CommandResult::new_hidden(response.message.trim()))
     }
@@ -4,6 +4,10 @@
  */
 
 pub mod lvm;
+pub mod smart;
+pub mod zfs;
+pub mod btrfs;
+pub mod mdadm;
 
 pub mod file_space_usage;
 pub mod filebrowser;
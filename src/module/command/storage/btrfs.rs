@@ -0,0 +1,10 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod scrub;
+pub use scrub::Scrub;
+
+pub mod scrub_status;
+pub use scrub_status::ScrubStatus;
\ No newline at end of file
@@ -0,0 +1,71 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+
+#[command_module(
+    name="storage-btrfs-scrub",
+    version="0.0.1",
+    description="Starts scrubbing a Btrfs filesystem.",
+    uses_sudo=true,
+)]
+pub struct Scrub;
+
+impl Module for Scrub {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        Scrub { }
+    }
+}
+
+impl CommandModule for Scrub {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("storage"),
+            parent_id: String::from("storage-btrfs"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("start"),
+            display_text: String::from("Start scrub"),
+            confirmation_text: String::from("Are you sure? Scrubbing reads all data in the filesystem and can take hours."),
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let mountpoint = parameters.first().unwrap();
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = true;
+
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            command.arguments(vec!["btrfs", "scrub", "start", mountpoint]);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.return_code == 0 {
+            Ok(CommandResult::new_info(String::from("Scrub started")))
+        }
+        else {
+            Ok(CommandResult::new_error(response.message.clone()))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::command::UIAction;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+
+#[command_module(
+    name="storage-btrfs-scrub-status",
+    version="0.0.1",
+    description="Shows scrub status of a Btrfs filesystem.",
+    uses_sudo=true,
+)]
+pub struct ScrubStatus;
+
+impl Module for ScrubStatus {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        ScrubStatus { }
+    }
+}
+
+impl CommandModule for ScrubStatus {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("storage"),
+            parent_id: String::from("storage-btrfs"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("view-document"),
+            display_text: String::from("Show scrub status"),
+            tab_title: String::from("Scrub status"),
+            action: UIAction::TextDialog,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let mountpoint = parameters.first().unwrap();
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = true;
+
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            command.arguments(vec!["btrfs", "scrub", "status", "-d", mountpoint]);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_error() {
+            return Err(response.message.clone());
+        }
+        let monospaced_markdown = format!("```\n{}\n```", response.message);
+        Ok(CommandResult::new_hidden(monospaced_markdown))
+    }
+}
\ No newline at end of file
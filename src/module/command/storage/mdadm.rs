@@ -0,0 +1,10 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod check;
+pub use check::Check;
+
+pub mod detail;
+pub use detail::Detail;
\ No newline at end of file
@@ -0,0 +1,10 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod selftest;
+pub use selftest::SelfTest;
+
+pub mod selftest_log;
+pub use selftest_log::SelfTestLog;
\ No newline at end of file
@@ -0,0 +1,97 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+
+#[command_module(
+    name="storage-smart-selftest",
+    version="0.0.1",
+    description="Starts a S.M.A.R.T. self-test on a disk.",
+    uses_sudo=true,
+)]
+pub struct SelfTest;
+
+impl Module for SelfTest {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        SelfTest { }
+    }
+}
+
+impl CommandModule for SelfTest {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("storage"),
+            parent_id: String::from("storage-smart"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("start"),
+            display_text: String::from("Start self-test"),
+            multivalue_level: 1,
+            user_parameters: vec![
+                frontend::UserInputField::option("Test type", &["short", "long"], &[
+                    "Takes a few minutes.",
+                    "Reads the whole disk. Can take hours.",
+                ]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let device = parameters.first().ok_or(LkError::invalid_parameter("Missing parameter", "device"))?;
+        let device_type = parameters.get(1).ok_or(LkError::invalid_parameter("Missing parameter", "device type"))?;
+        let test_type = parameters.get(2).ok_or(LkError::invalid_parameter("Missing parameter", "test type"))?;
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = true;
+
+        if !self.get_display_options().user_parameters[0].options.contains(test_type) {
+            Err(LkError::other_p("Invalid test type", test_type))
+        }
+        else if host.platform.os == platform_info::OperatingSystem::Linux {
+            command.arguments(vec!["smartctl"]);
+            // Let smartctl detect the type if it's unknown.
+            if !device_type.is_empty() {
+                command.arguments(vec!["-d", device_type]);
+            }
+            command.arguments(vec!["-t", test_type, device]);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        // Lowest bits of the exit code are for command line, device and command errors. The rest are about disk health.
+        if response.return_code & 0b111 != 0 {
+            return Ok(CommandResult::new_error(response.message.clone()));
+        }
+
+        // E.g. "Please wait 2 minutes for test to complete."
+        let summary = response.message.lines()
+            .filter(|line| line.starts_with("Please wait") || line.starts_with("Test will complete"))
+            .collect::<Vec<_>>();
+
+        if summary.is_empty() {
+            Ok(CommandResult::new_info(String::from("Self-test started")))
+        }
+        else {
+            Ok(CommandResult::new_info(summary.join("\n")))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::command::UIAction;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+
+#[command_module(
+    name="storage-smart-selftest-log",
+    version="0.0.1",
+    description="Shows the S.M.A.R.T. self-test log of a disk.",
+    uses_sudo=true,
+)]
+pub struct SelfTestLog;
+
+impl Module for SelfTestLog {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        SelfTestLog { }
+    }
+}
+
+impl CommandModule for SelfTestLog {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("storage"),
+            parent_id: String::from("storage-smart"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("view-document"),
+            display_text: String::from("Show self-test results"),
+            tab_title: String::from("Self-test results"),
+            action: UIAction::TextDialog,
+            multivalue_level: 1,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let device = parameters.first().ok_or(LkError::invalid_parameter("Missing parameter", "device"))?;
+        let device_type = parameters.get(1).ok_or(LkError::invalid_parameter("Missing parameter", "device type"))?;
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = true;
+
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            // Capabilities show the progress of a running test.
+            command.arguments(vec!["smartctl"]);
+            // Let smartctl detect the type if it's unknown.
+            if !device_type.is_empty() {
+                command.arguments(vec!["-d", device_type]);
+            }
+            command.arguments(vec!["-c", "-l", "selftest", device]);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        // Lowest bits of the exit code are for command line, device and command errors. The rest are about disk health.
+        if response.return_code & 0b111 != 0 {
+            return Err(response.message.clone());
+        }
+        let monospaced_markdown = format!("```\n{}\n```", response.message);
+        Ok(CommandResult::new_hidden(monospaced_markdown))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod scrub;
+pub use scrub::Scrub;
+
+pub mod scrub_status;
+pub use scrub_status::ScrubStatus;
+
+pub mod snapshot;
+pub use snapshot::Snapshot;
+
+pub mod snapshot_list;
+pub use snapshot_list::SnapshotList;
\ No newline at end of file
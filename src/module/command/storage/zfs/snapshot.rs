@@ -0,0 +1,90 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use chrono;
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+
+#[command_module(
+    name="storage-zfs-snapshot",
+    version="0.0.1",
+    description="Creates a snapshot of a ZFS pool.",
+    uses_sudo=true,
+    settings={
+      snapshot_name => "The name of the snapshot. Defaults to 'snapshot_$TIME'.",
+      recursive => "Also create snapshots of child datasets. Default: true."
+    }
+)]
+pub struct Snapshot {
+    pub snapshot_name: String,
+    pub recursive: bool,
+}
+
+impl Module for Snapshot {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Snapshot {
+            snapshot_name: settings.get("snapshot_name").unwrap_or(&String::from("snapshot_$TIME")).clone(),
+            recursive: settings.get("recursive").and_then(|value| value.parse().ok()).unwrap_or(true),
+        }
+    }
+}
+
+impl CommandModule for Snapshot {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("storage"),
+            parent_id: String::from("storage-zfs"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("copy"),
+            display_text: String::from("Create a snapshot"),
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let pool = parameters.first().unwrap();
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+        let snapshot_name = format!("{}@{}", pool, self.snapshot_name.replace("$TIME", &timestamp));
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = true;
+
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            if self.recursive {
+                command.arguments(vec!["zfs", "snapshot", "-r", &snapshot_name]);
+            }
+            else {
+                command.arguments(vec!["zfs", "snapshot", &snapshot_name]);
+            }
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.return_code == 0 {
+            Ok(CommandResult::new_info(String::new()))
+        }
+        else {
+            Ok(CommandResult::new_error(response.message.clone()))
+        }
+    }
+}
\ No newline at end of file
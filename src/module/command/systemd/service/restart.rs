@@ -38,11 +38,14 @@ impl CommandModule for Restart {
         frontend::DisplayOptions {
             category: String::from("systemd"),
             parent_id: String::from("systemd-service"),
+            // Also for services that are running with deleted libraries.
+            secondary_parent_id: String::from("linux-restart-required"),
             display_style: frontend::DisplayStyle::Icon,
             display_icon: String::from("refresh"),
             display_text: String::from("Restart"),
             // Only displayed if the service is running.
             depends_on_criticality: vec![Criticality::Normal, Criticality::Info, Criticality::Warning],
+            depends_on_no_tags: vec![String::from("Reboot"), String::from("Process")],
             confirmation_text: String::from("Really restart service?"),
             ..Default::default()
         }
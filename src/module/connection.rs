@@ -15,6 +15,9 @@ pub use request_response::RequestResponse;
 pub mod ssh;
 pub use ssh::Ssh2;
 
+pub mod unix_socket;
+pub use unix_socket::UnixSocketRequest;
+
 pub mod http;
 pub use http::Http;
 
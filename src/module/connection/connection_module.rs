@@ -4,10 +4,11 @@
  */
 
 use std::collections::HashMap;
+use std::io::{Read, Write};
 
 use crate::error::LkError;
-use crate::file_handler::FileMetadata;
-use crate::module::connection::ResponseMessage;
+use crate::file_handler::{FileMetadata, RemoteFileStat};
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
 use crate::module::module::Module;
 use crate::module::MetadataSupport;
 
@@ -17,9 +18,20 @@ pub trait ConnectionModule: BoxCloneableConnector + MetadataSupport + Module {
     /// Stores target address. Should be called before anything else since connects/reconnects can happen at any point.
     fn set_target(&self, _address: &str) {}
 
+    /// Opens the connection if the connector keeps one open. Otherwise, connecting happens when sending messages.
+    fn connect(&self) -> Result<(), LkError> {
+        Ok(())
+    }
+
     /// Sends a request / message and waits for response. Response can be complete or partial.
     fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError>;
 
+    /// Like `send_message`, but can be cancelled by calling `interrupt` with the same invocation ID.
+    /// Connectors that don't support it just send the message.
+    fn send_message_interruptible(&self, message: &str, _invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        self.send_message(message)
+    }
+
     fn send_message_partial(&self, _message: &str, _invocation_id: u64) -> Result<ResponseMessage, LkError> {
         Err(LkError::not_implemented())
     }
@@ -29,6 +41,16 @@ pub trait ConnectionModule: BoxCloneableConnector + MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
+    /// HTTP request to a Unix socket on the host, e.g. the Docker API.
+    fn send_socket_request(&self, _request: &UnixSocketRequest) -> Result<ResponseMessage, LkError> {
+        Err(LkError::not_implemented())
+    }
+
+    /// Like `send_socket_request`, but the response is read with `receive_partial_response`.
+    fn send_socket_request_partial(&self, _request: &UnixSocketRequest, _invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        Err(LkError::not_implemented())
+    }
+
     fn download_file(&self, _source: &str) -> Result<(FileMetadata, Vec<u8>), LkError> {
         Err(LkError::not_implemented())
     }
@@ -37,12 +59,50 @@ pub trait ConnectionModule: BoxCloneableConnector + MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
+    /// Needed for streaming transfers. Size is used for progress and modification time for resuming.
+    fn stat_file(&self, _path: &str) -> Result<RemoteFileStat, LkError> {
+        Err(LkError::not_implemented())
+    }
+
+    /// Streams a remote file to `destination` in chunks, starting from `offset` to resume an interrupted transfer.
+    /// `progress` is called after every chunk with transferred and total bytes.
+    /// Can be cancelled by calling `interrupt` with the same invocation ID.
+    fn download_file_stream(
+        &self,
+        _source: &str,
+        _offset: u64,
+        _destination: &mut dyn Write,
+        _invocation_id: u64,
+        _progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<(), LkError> {
+        Err(LkError::not_implemented())
+    }
+
+    /// Streams `source` (`size` bytes) to a remote file in chunks. Written first to a partial file next to the target
+    /// so that interrupted uploads of the same contents are resumed. Otherwise like `download_file_stream`.
+    fn upload_file_stream(
+        &self,
+        _metadata: &FileMetadata,
+        _source: &mut dyn Read,
+        _size: u64,
+        _invocation_id: u64,
+        _progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<(), LkError> {
+        Err(LkError::not_implemented())
+    }
+
     /// Sends a command and writes data to its stdin, then reads the response.
     /// Sends a command and reads binary output (for file downloads and uploads).
     fn send_message_binary(&self, _command: &str, _stdin_data: &[u8]) -> Result<ResponseMessage, LkError> {
         Err(LkError::not_implemented())
     }
 
+    /// Details about the established connection that can't be queried by sending messages,
+    /// e.g. the SSH certificate used for authentication. Empty if there's nothing to report.
+    fn get_connection_details(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
     fn verify_host_key(&self, _hostname: &str, _key_id: &str) -> Result<(), LkError> {
         Err(LkError::not_implemented())
     }
@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::collections::HashMap;
+
 use serde::{Serialize, Deserialize};
 
 use crate::error::LkError;
@@ -17,6 +19,8 @@ pub struct RequestResponse {
     pub invocation_id: u64,
     pub request_type: RequestType,
     pub responses: Vec<Result<ResponseMessage, LkError>>,
+    /// Filled for monitor requests. See `ConnectionModule::get_connection_details`.
+    pub connection_details: HashMap<String, String>,
     pub stop: bool,
 }
 
@@ -72,6 +76,9 @@ pub struct ResponseMessage {
     pub data: Vec<u8>,
     pub return_code: i32,
     pub is_partial: bool,
+    /// For partial responses of file transfers. Percentage of progress (0-100).
+    #[serde(default)]
+    pub progress: u8,
 }
 
 impl ResponseMessage {
@@ -109,6 +116,15 @@ impl ResponseMessage {
         }
     }
 
+    pub fn new_progress(message: String, progress: u8) -> ResponseMessage {
+        ResponseMessage {
+            message,
+            is_partial: true,
+            progress,
+            ..Default::default()
+        }
+    }
+
     pub fn new_success<Stringable: ToString>(message: Stringable) -> ResponseMessage {
         ResponseMessage {
             message: message.to_string(),
@@ -3,7 +3,10 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, MutexGuard};
 use std::time::Duration;
 use std::sync::Mutex;
@@ -12,23 +15,36 @@ use std::{
     net::ToSocketAddrs,
     collections::HashMap,
     path::Path,
-    io::{Read, Write},
+    io::{self as stdio, Read, Seek, SeekFrom, Write},
 };
 
 use base64::Engine;
 use hex::FromHex;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use ssh2;
 use crate::{error::*, file_handler};
-use crate::file_handler::FileMetadata;
+use crate::file_handler::{FileMetadata, RemoteFileStat};
 use crate::utils::{sha256, strip_newline};
 use lightkeeper_module::connection_module;
 use crate::module::*;
 use crate::module::connection::*;
+use crate::module::connection::unix_socket::{HttpResponseDecoder, EXIT_CODE_CONNECT_FAILED};
+
+mod tunnel;
+use tunnel::Tunnel;
+pub mod ssh_config;
+mod certificate;
+pub use certificate::Certificate;
+pub mod sudo;
+use sudo::SudoPassword;
 
 static MODULE_NAME: &str = "ssh";
 const SESSION_WAIT_SLEEP: u64 = 200;
 const PARTIAL_READ_TIMEOUT_MS: u32 = 2000;
+const TRANSFER_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Certificate used for authentication and where it was loaded from.
+type UsedCertificate = (Certificate, String);
 
 
 #[connection_module(
@@ -39,30 +55,59 @@ const PARTIAL_READ_TIMEOUT_MS: u32 = 2000;
       port => "Port of the SSH server. Default: 22.",
       username => "Username for the SSH connection. Default: root.",
       private_key_path => "Path to the private key file for the SSH connection. Default: empty.",
+      certificate_path => "Path to an OpenSSH user certificate for the private key. \
+                           Default: <private_key_path>-cert.pub if it exists.",
+      certificate_expiry_warning_hours => "Warn when the certificate used for authentication expires in less than this. \
+                                           Default: 24, or a tenth of the validity period if that is shorter.",
       connection_timeout => "Timeout (in seconds) for the SSH connection. Default: 15.",
+      keepalive_interval => "Interval (in seconds) for sending keepalive messages on idle sessions. 0 disables keepalives. \
+                             Default: 30.",
       agent_key_identifier => "Identifier for selecting key from ssh-agent. This is the comment part of the \
                                key (e.g. user@desktop). Default: empty (all keys are tried).",
       verify_host_key => "Whether to verify the host key using a known_hosts-file. Default: true.",
       custom_known_hosts_path => "Path to a custom known_hosts file. Default: (inside configuration directory).",
       parallel_sessions => "Number of parallel login sessions. Improves performance. Default: 2.",
+      jump_hosts => "Comma-separated list of jump hosts ([user@]host[:port]) in connection order, like ProxyJump in \
+                     OpenSSH. Hop-specific settings use the hop number, e.g. jump_host_1_private_key_path, \
+                     jump_host_1_password, jump_host_1_private_key_passphrase and jump_host_1_agent_key_identifier. \
+                     Username and key settings default to the ones of the target host. Default: empty.",
+      host_name => "Address used for SSH connections instead of the address of the host. Default: empty.",
+      identities_only => "Whether to use only the private key file and not fall back to ssh-agent if it fails. \
+                          Default: true.",
+      use_ssh_config => "Whether to read missing settings (HostName, User, Port, IdentityFile, IdentitiesOnly and \
+                         ProxyJump) from the OpenSSH client configuration. Explicit settings take precedence. Default: false.",
+      ssh_config_path => "Path to the OpenSSH client configuration. Default: ~/.ssh/config.",
+      ssh_config_host => "Name matched against Host patterns of the OpenSSH client configuration. Default: host ID.",
     },
     secrets={
       password => "Password for the SSH connection. Default: empty (not used).",
       private_key_passphrase => "Passphrase for the private key file. Default: empty.",
+      sudo_password => "Password for sudo. Written to stdin when sudo prompts for it. Default: empty (passwordless sudo).",
     }
 )]
 /// SSH connection module. Manages parallel SSH sessions internally.
 pub struct Ssh2 {
     address: Arc<Mutex<String>>,
+    /// Overrides the target address if set.
+    host_name: Option<String>,
     port: Arc<Mutex<u16>>,
-    username: String,
-    password: Option<String>,
-    private_key_path: Option<String>,
-    private_key_passphrase: Option<String>,
-    agent_key_identifier: Option<String>,
+    credentials: Credentials,
+    sudo_password: Option<SudoPassword>,
+    certificate_expiry_warning_hours: Option<u32>,
+    /// Certificate used in the latest authentication.
+    certificate_status: Arc<Mutex<Option<CertificateStatus>>>,
     connection_timeout: u16,
+    keepalive_interval: u32,
+    /// Keepalive thread is started on the first connection and shared by clones.
+    keepalive_started: Arc<AtomicBool>,
     verify_host_key: bool,
     custom_known_hosts_path: Option<PathBuf>,
+    /// Invalid jump host settings prevent connecting at all instead of falling back to a direct connection.
+    jump_hosts: Result<Vec<JumpHost>, String>,
+    /// Streaming transfers in progress by invocation ID. Value is set to true when interrupted.
+    transfers: Arc<Mutex<HashMap<u64, bool>>>,
+    /// Interruptible commands in progress by invocation ID. Value is set to true when interrupted.
+    running_commands: Arc<Mutex<HashMap<u64, bool>>>,
 
     available_sessions: Arc<Vec<Mutex<SessionData>>>,
 }
@@ -70,7 +115,11 @@ pub struct Ssh2 {
 pub struct SessionData {
     is_initialized: bool,
     session: ssh2::Session,
+    /// Forwarding through jump hosts. Dropped after the session.
+    tunnels: Vec<Tunnel>,
     open_channel: Option<ssh2::Channel>,
+    /// Set when the open channel is reading an HTTP response from a Unix socket.
+    http_decoder: Option<HttpResponseDecoder>,
     // For incomplete invocations, tag with the invocation ID.
     invocation_id: u64,
 }
@@ -87,22 +136,37 @@ impl Module for Ssh2 {
             available_sessions.push(Mutex::new(SessionData {
                 is_initialized: false,
                 session: ssh2::Session::new().expect("Unable to initialize SSH sessions."),
+                tunnels: Vec::new(),
                 open_channel: None,
+                http_decoder: None,
                 invocation_id: 0,
             }));
         }
 
         Ssh2 {
             address: Arc::new(Mutex::new(String::from("0.0.0.0"))),
+            host_name: settings.get("host_name").filter(|host_name| !host_name.is_empty()).cloned(),
             port: Arc::new(Mutex::new(settings.get("port").and_then(|value| value.parse::<u16>().ok()).unwrap_or(22))),
-            username: settings.get("username").unwrap_or(&String::from("root")).clone(),
-            password: settings.get("password").cloned(),
-            private_key_path: settings.get("private_key_path").cloned(),
-            private_key_passphrase: settings.get("private_key_passphrase").cloned(),
-            agent_key_identifier: settings.get("agent_key_identifier").cloned(),
+            credentials: Credentials {
+                username: settings.get("username").unwrap_or(&String::from("root")).clone(),
+                password: settings.get("password").cloned(),
+                private_key_path: settings.get("private_key_path").cloned(),
+                certificate_path: settings.get("certificate_path").cloned(),
+                private_key_passphrase: settings.get("private_key_passphrase").cloned(),
+                agent_key_identifier: settings.get("agent_key_identifier").cloned(),
+                identities_only: settings.get("identities_only").and_then(|value| value.parse::<bool>().ok()).unwrap_or(true),
+            },
+            sudo_password: settings.get("sudo_password").filter(|password| !password.is_empty()).map(|password| SudoPassword::new(password)),
+            certificate_expiry_warning_hours: settings.get("certificate_expiry_warning_hours").and_then(|value| value.parse::<u32>().ok()),
+            certificate_status: Arc::new(Mutex::new(None)),
             connection_timeout: settings.get("connection_timeout").and_then(|value| value.parse::<u16>().ok()).unwrap_or(15),
+            keepalive_interval: settings.get("keepalive_interval").and_then(|value| value.parse::<u32>().ok()).unwrap_or(30),
+            keepalive_started: Arc::new(AtomicBool::new(false)),
             verify_host_key: settings.get("verify_host_key").and_then(|value| value.parse::<bool>().ok()).unwrap_or(true),
             custom_known_hosts_path: settings.get("custom_known_hosts_path").map(|path| PathBuf::from(path)),
+            jump_hosts: parse_jump_hosts(settings).map_err(|error| error.to_string()),
+            transfers: Arc::new(Mutex::new(HashMap::new())),
+            running_commands: Arc::new(Mutex::new(HashMap::new())),
             available_sessions: Arc::new(available_sessions),
         }
     }
@@ -111,7 +175,11 @@ impl Module for Ssh2 {
 impl ConnectionModule for Ssh2 {
     fn set_target(&self, address: &str) {
         let mut mutex_address = self.address.lock().unwrap();
-         *mutex_address = address.to_string();
+         *mutex_address = self.host_name.clone().unwrap_or_else(|| address.to_string());
+    }
+
+    fn connect(&self) -> Result<(), LkError> {
+        self.wait_for_session(0, true).map(|_| ())
     }
 
     fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError> {
@@ -122,6 +190,18 @@ impl ConnectionModule for Ssh2 {
         Ok(response)
     }
 
+    fn send_message_interruptible(&self, message: &str, invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        self.running_commands.lock().unwrap().insert(invocation_id, false);
+        let result = self.execute_command(message, &[], Some(invocation_id));
+        self.running_commands.lock().unwrap().remove(&invocation_id);
+
+        let mut response = result?;
+        response.message = String::from_utf8_lossy(&response.data).to_string();
+        response.data = Vec::new();
+
+        Ok(response)
+    }
+
     fn send_message_partial(&self, message: &str, invocation_id: u64) -> Result<ResponseMessage, LkError> {
         let mut session_data = self.wait_for_session(0, true)?;
 
@@ -131,7 +211,7 @@ impl ConnectionModule for Ssh2 {
                 // Error is likely duo to disconnected or timeouted session. Try to reconnect once.
                 log::error!("Reconnecting channel due to error: {}", error);
                 self.reconnect(&mut session_data)
-                    .map_err(|error| format!("Error reconnecting: {}", error))?;
+                    .map_err(|error| LkError::new(error.kind.clone(), format!("Error reconnecting: {}", error)))?;
 
                 session_data.session.channel_session()
                     .map_err(|error| format!("Error opening channel: {}", error))?
@@ -142,12 +222,24 @@ impl ConnectionModule for Ssh2 {
         channel.handle_extended_data(ssh2::ExtendedData::Merge)
             .map_err(|error| LkError::other(error.to_string()))?;
 
+        let (message, sudo_password) = self.with_sudo_password(message);
+
         // Used to enable sending CTRL-C to the command.
-        channel.request_pty("dumb", None, None)?;
-        
-        channel.exec(message)
+        // Echo is disabled so that the sudo password doesn't end up in the output.
+        let mut pty_modes = ssh2::PtyModes::new();
+        if sudo_password.is_some() {
+            pty_modes.set_boolean(ssh2::PtyModeOpcode::ECHO, false);
+        }
+        channel.request_pty("dumb", Some(pty_modes), None)?;
+
+        channel.exec(&message)
             .map_err(|error| format!("Error executing command '{}': {}", message, error))?;
 
+        let initial_output = match sudo_password {
+            Some(sudo_password) => sudo_password.authenticate(&mut channel)?,
+            None => Vec::new(),
+        };
+
         let prev_timeout = session_data.session.timeout();
         session_data.session.set_timeout(PARTIAL_READ_TIMEOUT_MS);
 
@@ -157,10 +249,10 @@ impl ConnectionModule for Ssh2 {
         session_data.session.set_timeout(prev_timeout);
 
         let output = match read_result {
-            Ok(bytes_read) => String::from_utf8_lossy(&buffer[..bytes_read]).to_string(),
+            Ok(bytes_read) => String::from_utf8_lossy(&[initial_output.as_slice(), &buffer[..bytes_read]].concat()).to_string(),
             Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
                 session_data.open_channel = Some(channel);
-                return Ok(ResponseMessage::new_partial(String::new()));
+                return Ok(ResponseMessage::new_partial(String::from_utf8_lossy(&initial_output).to_string()));
             },
             Err(error) => {
                 session_data.invocation_id = 0;
@@ -183,52 +275,7 @@ impl ConnectionModule for Ssh2 {
     }
 
     fn send_message_binary(&self, command: &str, stdin_data: &[u8]) -> Result<ResponseMessage, LkError> {
-        if command.is_empty() {
-            return Ok(ResponseMessage::empty());
-        }
-
-        let mut session_data = self.wait_for_session(0, true)?;
-
-        let mut channel = match session_data.session.channel_session() {
-            Ok(channel) => channel,
-            Err(error) => {
-                log::error!("Reconnecting channel due to error: {}", error);
-                self.reconnect(&mut session_data)
-                    .map_err(|error| format!("Error reconnecting: {}", error))?;
-
-                session_data.session.channel_session()
-                    .map_err(|error| format!("Error opening channel: {}", error))?
-            }
-        };
-
-        channel.handle_extended_data(ssh2::ExtendedData::Merge)?;
-
-        channel.exec(command)
-               .map_err(|error| format!("Error executing command '{}': {}", command, error))?;
-
-        if stdin_data.len() > 0 {
-            channel.write_all(stdin_data)
-                .map_err(|error| format!("Error writing to stdin: {}", error))?;
-
-            channel.send_eof()
-                .map_err(|error| format!("Error while closing stdin: {}", error))?;
-        }
-
-        // Read binary data instead of string
-        let mut output = Vec::new();
-        channel.read_to_end(&mut output)
-               .map_err(|error| format!("Invalid output received: {}", error))?;
-
-        if !channel.eof() {
-            return Err(LkError::new(ErrorKind::Other, "Channel is not at EOF even though full response was requested"));
-        }
-
-        let exit_status = channel.exit_status().unwrap_or(-1);
-
-        channel.wait_close()
-               .map_err(|error| format!("Error while closing channel: {}", error))?;
-
-        Ok(ResponseMessage::new_binary(output, exit_status))
+        self.execute_command(command, stdin_data, None)
     }
 
     fn receive_partial_response(&self, invocation_id: u64) -> Result<ResponseMessage, LkError> {
@@ -238,6 +285,10 @@ impl ConnectionModule for Ssh2 {
             None => return Err(LkError::other("Can't do partial receive. No open channel available.")),
         };
 
+        if partial_session.http_decoder.is_some() {
+            return Self::receive_socket_response(&mut partial_session, channel);
+        }
+
         let prev_timeout = partial_session.session.timeout();
         partial_session.session.set_timeout(PARTIAL_READ_TIMEOUT_MS);
 
@@ -274,8 +325,27 @@ impl ConnectionModule for Ssh2 {
     }
 
     fn interrupt(&self, invocation_id: u64) -> Result<(), LkError> {
+        // Transfers check the flag between chunks.
+        if let Some(interrupted) = self.transfers.lock().unwrap().get_mut(&invocation_id) {
+            *interrupted = true;
+            return Ok(());
+        }
+
+        // Commands check the flag between reads.
+        if let Some(interrupted) = self.running_commands.lock().unwrap().get_mut(&invocation_id) {
+            *interrupted = true;
+            return Ok(());
+        }
+
         let mut session_data = self.wait_for_session(invocation_id, true)?;
+        let is_socket_request = session_data.http_decoder.is_some();
         if let Some(ref mut channel) = session_data.open_channel {
+            if is_socket_request {
+                // Streaming endpoints (e.g. logs) end when the connection is closed.
+                channel.close()?;
+                return Ok(());
+            }
+
             // Sends Ctrl-C.
             channel.write_all(&[3])?;
             channel.flush()?;
@@ -283,6 +353,85 @@ impl ConnectionModule for Ssh2 {
         Ok(())
     }
 
+    /// Sends the request through a direct-streamlocal channel and falls back to curl if the channel can't be opened.
+    fn send_socket_request(&self, request: &UnixSocketRequest) -> Result<ResponseMessage, LkError> {
+        {
+            let session_data = self.wait_for_session(0, true)?;
+
+            match session_data.session.channel_direct_streamlocal(&request.socket_path, None) {
+                Ok(mut channel) => {
+                    // EOF is not sent since some servers (e.g. Docker) cancel the request when the client half-closes.
+                    channel.write_all(&request.to_http())
+                        .map_err(|error| format!("Error writing to socket: {}", error))?;
+
+                    let mut output = Vec::new();
+                    channel.read_to_end(&mut output)
+                        .map_err(|error| format!("Invalid output received: {}", error))?;
+
+                    channel.wait_close()
+                        .map_err(|error| format!("Error while closing channel: {}", error))?;
+
+                    return HttpResponseDecoder::decode_response(&output, request.demultiplex);
+                },
+                Err(error) => {
+                    log::debug!("Couldn't open socket {} directly, using curl: {}", request.socket_path, error);
+                }
+            }
+        }
+
+        let response = self.send_message_binary(&request.to_command().to_string(), &[])?;
+        match response.return_code {
+            0 => HttpResponseDecoder::decode_response(&response.data, request.demultiplex),
+            127 => Ok(ResponseMessage::new(
+                format!("Couldn't open socket {} and curl is not available", request.socket_path),
+                EXIT_CODE_CONNECT_FAILED
+            )),
+            return_code => Ok(ResponseMessage::new(String::from_utf8_lossy(&response.data).to_string(), return_code)),
+        }
+    }
+
+    /// Response is read with `receive_partial_response`.
+    fn send_socket_request_partial(&self, request: &UnixSocketRequest, invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        let mut session_data = self.wait_for_session(0, true)?;
+        let mut initial_output = Vec::new();
+
+        let channel = match session_data.session.channel_direct_streamlocal(&request.socket_path, None) {
+            Ok(mut channel) => {
+                channel.write_all(&request.to_http())
+                    .map_err(|error| format!("Error writing to socket: {}", error))?;
+                channel
+            },
+            Err(error) => {
+                log::debug!("Couldn't open socket {} directly, using curl: {}", request.socket_path, error);
+
+                let mut channel = session_data.session.channel_session()
+                    .map_err(|error| format!("Error opening channel: {}", error))?;
+
+                // sudo prompts on stderr, so it's read until curl has been started.
+                let (command, sudo_password) = self.with_sudo_password(&request.to_command().to_string());
+                channel.handle_extended_data(ssh2::ExtendedData::Merge)?;
+                channel.exec(&command)
+                    .map_err(|error| format!("Error executing command '{}': {}", command, error))?;
+
+                initial_output = match sudo_password {
+                    Some(sudo_password) => sudo_password.authenticate(&mut channel)?,
+                    None => Vec::new(),
+                };
+
+                // Output has to contain only the HTTP response.
+                channel.handle_extended_data(ssh2::ExtendedData::Ignore)?;
+                channel
+            }
+        };
+
+        session_data.invocation_id = invocation_id;
+        session_data.open_channel = Some(channel);
+        let mut http_decoder = HttpResponseDecoder::new(request.demultiplex);
+        let output = http_decoder.decode(&initial_output)?;
+        session_data.http_decoder = Some(http_decoder);
+        Ok(ResponseMessage::new_partial(String::from_utf8_lossy(&output).to_string()))
+    }
+
     fn download_file(&self, source: &str) -> Result<(FileMetadata, Vec<u8>), LkError> {
         let session_data = self.wait_for_session(0, true)?;
         let sftp = session_data.session.sftp()?;
@@ -329,66 +478,355 @@ impl ConnectionModule for Ssh2 {
              .map(|_| Ok(()))?
     }
 
+    fn stat_file(&self, path: &str) -> Result<RemoteFileStat, LkError> {
+        let session_data = self.wait_for_session(0, true)?;
+        let stat = session_data.session.sftp()?.stat(Path::new(path))?;
+
+        match (stat.size, stat.uid, stat.gid, stat.perm) {
+            (Some(size), Some(uid), Some(gid), Some(perm)) => Ok(RemoteFileStat {
+                size,
+                modified: stat.mtime.unwrap_or_default(),
+                owner_uid: uid,
+                owner_gid: gid,
+                permissions: perm,
+            }),
+            _ => Err(LkError::unexpected()),
+        }
+    }
+
+    fn download_file_stream(
+        &self,
+        source: &str,
+        offset: u64,
+        destination: &mut dyn Write,
+        invocation_id: u64,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<(), LkError> {
+        let session_data = self.wait_for_session(0, true)?;
+        let sftp = session_data.session.sftp()?;
+
+        let mut file = sftp.open(Path::new(source))?;
+        let total = file.stat()?.size.unwrap_or_default();
+        if offset > total {
+            return Err(LkError::other("Partial download is larger than the remote file"));
+        }
+        file.seek(SeekFrom::Start(offset))?;
+
+        self.run_transfer(invocation_id, || {
+            let mut transferred = offset;
+            let mut buffer = vec![0_u8; TRANSFER_CHUNK_SIZE];
+
+            loop {
+                self.check_transfer_interrupt(invocation_id)?;
+
+                let bytes_read = file.read(&mut buffer)?;
+                if bytes_read == 0 {
+                    break;
+                }
+
+                destination.write_all(&buffer[..bytes_read])?;
+                transferred += bytes_read as u64;
+                progress(transferred, total);
+            }
+
+            destination.flush()?;
+            Ok(())
+        })
+    }
+
+    fn upload_file_stream(
+        &self,
+        metadata: &FileMetadata,
+        source: &mut dyn Read,
+        size: u64,
+        invocation_id: u64,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Result<(), LkError> {
+        let session_data = self.wait_for_session(0, true)?;
+        let sftp = session_data.session.sftp()?;
+
+        // Symlinks would otherwise get replaced by the renamed file.
+        let target = sftp.realpath(Path::new(&metadata.remote_path))
+            .unwrap_or_else(|_| PathBuf::from(&metadata.remote_path));
+
+        // Hash in the name ensures that only uploads of the same contents are resumed.
+        let hash = &metadata.remote_file_hash;
+        let partial_path = PathBuf::from(format!("{}.{}.part", target.to_string_lossy(), hash.get(..16).unwrap_or(hash)));
+
+        let offset = match sftp.stat(&partial_path) {
+            Ok(stat) if stat.size.unwrap_or_default() <= size => stat.size.unwrap_or_default(),
+            _ => 0,
+        };
+
+        let open_flags = if offset > 0 {
+            ssh2::OpenFlags::WRITE
+        }
+        else {
+            ssh2::OpenFlags::WRITE | ssh2::OpenFlags::CREATE | ssh2::OpenFlags::TRUNCATE
+        };
+
+        let mut file = sftp.open_mode(&partial_path, open_flags, (metadata.permissions & 0o7777) as i32, ssh2::OpenType::File)?;
+        file.seek(SeekFrom::Start(offset))?;
+        stdio::copy(&mut source.take(offset), &mut stdio::sink())?;
+
+        self.run_transfer(invocation_id, || {
+            let mut transferred = offset;
+            let mut buffer = vec![0_u8; TRANSFER_CHUNK_SIZE];
+
+            loop {
+                self.check_transfer_interrupt(invocation_id)?;
+
+                let bytes_read = source.read(&mut buffer)?;
+                if bytes_read == 0 {
+                    break;
+                }
+
+                file.write_all(&buffer[..bytes_read])?;
+                transferred += bytes_read as u64;
+                progress(transferred, size);
+            }
+
+            Ok(())
+        })?;
+
+        drop(file);
+
+        // Keep the permissions and ownership of the original file. Changing ownership needs privileges.
+        let stat = ssh2::FileStat {
+            size: None,
+            uid: Some(metadata.owner_uid),
+            gid: Some(metadata.owner_gid),
+            perm: Some(metadata.permissions),
+            atime: None,
+            mtime: None,
+        };
+        if let Err(error) = sftp.setstat(&partial_path, stat) {
+            log::debug!("Couldn't change ownership of uploaded file: {}", error);
+            sftp.setstat(&partial_path, ssh2::FileStat { size: None, uid: None, gid: None, perm: Some(metadata.permissions), atime: None, mtime: None })?;
+        }
+
+        // Servers using SFTP version 3 (e.g. OpenSSH) refuse to rename over existing files.
+        let rename_flags = ssh2::RenameFlags::OVERWRITE | ssh2::RenameFlags::ATOMIC | ssh2::RenameFlags::NATIVE;
+        if sftp.rename(&partial_path, &target, Some(rename_flags)).is_err() {
+            if sftp.stat(&target).is_ok() {
+                sftp.unlink(&target)?;
+            }
+            sftp.rename(&partial_path, &target, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_connection_details(&self) -> HashMap<String, String> {
+        self.certificate_status.lock().unwrap().as_ref()
+            .map(|status| status.to_details())
+            .unwrap_or_default()
+    }
+
     fn verify_host_key(&self, hostname: &str, key_id: &str) -> Result<(), LkError> {
-        let mut session_data = self.wait_for_session(0, false)?;
-        let self_address = self.address.lock().unwrap().to_string();
+        // Blocks other connection attempts while verifying.
+        let _session_data = self.wait_for_session(0, false)?;
         let self_port = *self.port.lock().unwrap();
+        let added_key = std::cell::Cell::new(false);
+
+        // Every host on the path is checked since the unverified key can belong to a jump host.
+        // Only one key is added at a time, so the user gets asked again about the next unverified host.
+        let result = self.open_session(hostname, self_port, |session, hop_hostname, hop_port| {
+            match self.check_known_hosts(session, hop_hostname, hop_port) {
+                Err(error) if error.kind == ErrorKind::HostKeyNotVerified && !added_key.get() => {
+                    self.add_known_host(session, hop_hostname, hop_port, key_id)?;
+                    added_key.set(true);
+                    Ok(())
+                },
+                result => result,
+            }
+        });
 
-        // One last check to avoid writing duplicates. Can otherwise happen with parallel SSH sessions.
-        if self.check_known_hosts(&session_data, hostname, self_port).is_ok() {
-            return Ok(());
+        match result {
+            Ok(_) => Ok(()),
+            // Authentication isn't needed for verification.
+            Err(_) if added_key.get() => Ok(()),
+            Err(error) => Err(error),
         }
+    }
+}
 
-        let known_hosts_path = self.get_known_hosts_path()?;
+impl Ssh2 {
+    /// Executes a command and reads the binary output. With an invocation ID, the command can be interrupted.
+    fn execute_command(&self, command: &str, stdin_data: &[u8], invocation_id: Option<u64>) -> Result<ResponseMessage, LkError> {
+        if command.is_empty() {
+            return Ok(ResponseMessage::empty());
+        }
 
-        let mut known_hosts = session_data.session.known_hosts()
-            .map_err(|error| LkError::other(format!("Failed to initialize known hosts file: {}", error)))?;
+        let mut session_data = self.wait_for_session(0, true)?;
 
-        known_hosts.read_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
-            .map_err(|error| LkError::other_p("Failed to read known hosts file", error))?;
+        let mut channel = match session_data.session.channel_session() {
+            Ok(channel) => channel,
+            Err(error) => {
+                log::error!("Reconnecting channel due to error: {}", error);
+                self.reconnect(&mut session_data)
+                    .map_err(|error| LkError::new(error.kind.clone(), format!("Error reconnecting: {}", error)))?;
 
-        // The session probably gets disconnected since receiving host key fails if not reconnecting.
-        let mut socket_addresses = format!("{}:{}", self_address, self_port).to_socket_addrs()?;
-        let socket_address = match socket_addresses.next() {
-            Some(address) => address,
-            None => return Err(LkError::other("Failed to resolve address")),
+                session_data.session.channel_session()
+                    .map_err(|error| format!("Error opening channel: {}", error))?
+            }
         };
 
-        let connection_timeout = std::time::Duration::from_secs(self.connection_timeout as u64);
-        let stream = TcpStream::connect_timeout(&socket_address, connection_timeout)?;
+        channel.handle_extended_data(ssh2::ExtendedData::Merge)?;
+
+        let (command, sudo_password) = self.with_sudo_password(command);
+        channel.exec(&command)
+               .map_err(|error| format!("Error executing command '{}': {}", command, error))?;
 
-        log::info!("Connected to {}:{}", self_address, self_port);
-        session_data.session = ssh2::Session::new().expect("Unable to initialize SSH sessions.");
-        session_data.session.set_tcp_stream(stream);
-        session_data.session.handshake()?;
+        // Stdin is written only after sudo has started the command, so the payload can't be taken as the password.
+        let mut output = match sudo_password {
+            Some(sudo_password) => sudo_password.authenticate(&mut channel)?,
+            None => Vec::new(),
+        };
 
-        if let Some((key, key_type)) = session_data.session.host_key() {
-            let key_string = Self::get_host_key_id(key_type, key)?;
-            let known_hosts_name = if self_port == 22 {
-                hostname.to_string()
+        if !stdin_data.is_empty() || sudo_password.is_some() {
+            channel.write_all(stdin_data)
+                .map_err(|error| format!("Error writing to stdin: {}", error))?;
+
+            channel.send_eof()
+                .map_err(|error| format!("Error while closing stdin: {}", error))?;
+        }
+
+        // Read binary data instead of string
+        match invocation_id {
+            Some(invocation_id) => self.read_to_end_interruptible(&session_data.session, &mut channel, &mut output, invocation_id)?,
+            None => {
+                channel.read_to_end(&mut output)
+                       .map_err(|error| format!("Invalid output received: {}", error))?;
             }
-            else {
-                format!("[{}]:{}", hostname, self_port)
-            };
+        }
 
-            if key_string == key_id {
-                known_hosts.add(&known_hosts_name, key, hostname, key_type.into())
-                           .map_err(|error| LkError::other_p("Failed to add host key to known hosts", error))?;
-                known_hosts.write_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
-                           .map_err(|error| LkError::other_p("Failed to write known hosts file", error))?;
-                Ok(())
+        if !channel.eof() {
+            return Err(LkError::new(ErrorKind::Other, "Channel is not at EOF even though full response was requested"));
+        }
+
+        let exit_status = channel.exit_status().unwrap_or(-1);
+
+        channel.wait_close()
+               .map_err(|error| format!("Error while closing channel: {}", error))?;
+
+        Ok(ResponseMessage::new_binary(output, exit_status))
+    }
+
+
+    /// Reads with a short timeout so that interrupts are noticed while waiting for output.
+    fn read_to_end_interruptible(
+        &self,
+        session: &ssh2::Session,
+        channel: &mut ssh2::Channel,
+        output: &mut Vec<u8>,
+        invocation_id: u64,
+    ) -> Result<(), LkError> {
+
+        let prev_timeout = session.timeout();
+        session.set_timeout(PARTIAL_READ_TIMEOUT_MS);
+
+        let mut buffer = [0u8; 8192];
+        let result = loop {
+            if self.running_commands.lock().unwrap().get(&invocation_id).copied().unwrap_or(false) {
+                // There's no pty for sending Ctrl-C, so the channel is closed instead.
+                let _ = channel.close();
+                break Err(LkError::other("Command was interrupted"));
             }
-            else {
-                Err(LkError::other("Host key changed again?!"))
+
+            match channel.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(bytes_read) => output.extend_from_slice(&buffer[..bytes_read]),
+                Err(error) if error.kind() == std::io::ErrorKind::TimedOut => continue,
+                Err(error) => break Err(LkError::other(format!("Invalid output received: {}", error))),
             }
+        };
+
+        session.set_timeout(prev_timeout);
+        result
+    }
+
+    /// Registers the transfer for interrupts for the duration of `transfer`.
+    fn run_transfer<T>(&self, invocation_id: u64, transfer: impl FnOnce() -> Result<T, LkError>) -> Result<T, LkError> {
+        self.transfers.lock().unwrap().insert(invocation_id, false);
+        let result = transfer();
+        self.transfers.lock().unwrap().remove(&invocation_id);
+        result
+    }
+
+    fn check_transfer_interrupt(&self, invocation_id: u64) -> Result<(), LkError> {
+        if self.transfers.lock().unwrap().get(&invocation_id).copied().unwrap_or(false) {
+            Err(LkError::other("Transfer was interrupted"))
         }
         else {
-            Err(LkError::other("Failed to get host key"))
+            Ok(())
+        }
+    }
+
+    fn receive_socket_response(session_data: &mut MutexGuard<'_, SessionData>, mut channel: ssh2::Channel) -> Result<ResponseMessage, LkError> {
+        let prev_timeout = session_data.session.timeout();
+        session_data.session.set_timeout(PARTIAL_READ_TIMEOUT_MS);
+
+        let mut buffer = vec![0u8; 8192];
+        let read_result = channel.read(&mut buffer);
+
+        session_data.session.set_timeout(prev_timeout);
+
+        let decode_result = match read_result {
+            Ok(bytes_read) => session_data.http_decoder.as_mut().unwrap().decode(&buffer[..bytes_read]),
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                session_data.open_channel = Some(channel);
+                return Ok(ResponseMessage::new_partial(String::new()));
+            },
+            Err(error) => Err(LkError::other(format!("Invalid output received: {}", error))),
+        };
+
+        let mut output = match decode_result {
+            Ok(output) => output,
+            Err(error) => {
+                session_data.invocation_id = 0;
+                session_data.http_decoder = None;
+                return Err(error);
+            }
+        };
+
+        let decoder = session_data.http_decoder.as_mut().unwrap();
+        if decoder.is_complete() || channel.eof() {
+            output.extend(decoder.finish());
+
+            let return_code = match decoder.status() {
+                Some(_) => decoder.return_code(),
+                // Curl failed or the connection was closed before a response.
+                None => channel.exit_status().ok().filter(|code| *code != 0).unwrap_or(-1),
+            };
+
+            session_data.invocation_id = 0;
+            session_data.http_decoder = None;
+
+            channel.close()
+                .and_then(|_| channel.wait_close())
+                .map_err(|error| format!("Error while closing channel: {}", error))?;
+
+            Ok(ResponseMessage::new_partial_complete(String::from_utf8_lossy(&output).to_string(), return_code))
+        }
+        else {
+            session_data.open_channel = Some(channel);
+            Ok(ResponseMessage::new_partial(String::from_utf8_lossy(&output).to_string()))
+        }
+    }
+
+    /// If a sudo password is set, makes sudo read it from stdin. Returns the command and the password handshake
+    /// that has to be done after executing it.
+    fn with_sudo_password(&self, command: &str) -> (String, Option<&SudoPassword>) {
+        let wrapped = self.sudo_password.as_ref()
+            .and_then(|sudo_password| Some((sudo_password.wrap_command(command)?, sudo_password)));
+
+        match wrapped {
+            Some((command, sudo_password)) => (command, Some(sudo_password)),
+            None => (command.to_string(), None),
         }
     }
-}
 
-impl Ssh2 {
     fn wait_for_session(&self, invocation_id: u64, connect_automatically: bool) -> Result<MutexGuard<'_, SessionData>, LkError> {
         let mut total_wait = Duration::from_secs(0);
 
@@ -405,7 +843,7 @@ impl Ssh2 {
                     if connect_automatically && !session_data.is_initialized {
                         let address = self.address.lock().unwrap().clone();
                         let port = *self.port.lock().unwrap();
-                        if let Err(error) = self.connect(&mut session_data, &address, port) {
+                        if let Err(error) = self.connect_session(&mut session_data, &address, port) {
                             log::error!("Error while connecting {}: {}", address, error);
                             return Err(error);
                         }
@@ -425,12 +863,103 @@ impl Ssh2 {
         }
     }
 
-    fn connect(&self, session_data: &mut MutexGuard<'_, SessionData>, address: &str, port: u16) -> Result<(), LkError> {
+    fn connect_session(&self, session_data: &mut MutexGuard<'_, SessionData>, address: &str, port: u16) -> Result<(), LkError> {
         if session_data.is_initialized {
             return Ok(())
         }
 
-        let mut socket_addresses = format!("{}:{}", address, port).to_socket_addrs()?;
+        let (session, tunnels, certificate) = self.open_session(address, port, |session, hostname, port| {
+            if self.verify_host_key {
+                self.check_known_hosts(session, hostname, port)
+            }
+            else {
+                Ok(())
+            }
+        }).map_err(|mut error| {
+            // Host key and authentication errors need user action. Anything else means the host couldn't be reached.
+            if !matches!(error.kind, ErrorKind::HostKeyNotVerified | ErrorKind::AuthenticationFailed) {
+                error.kind = ErrorKind::ConnectionFailed;
+            }
+            error
+        })?;
+
+        *self.certificate_status.lock().unwrap() = certificate.map(|(certificate, source)| {
+            let warning_threshold = match self.certificate_expiry_warning_hours {
+                Some(hours) => chrono::Duration::hours(hours as i64),
+                None => {
+                    let default = chrono::Duration::hours(24);
+                    certificate.lifetime().map(|lifetime| std::cmp::min(default, lifetime / 10)).unwrap_or(default)
+                }
+            };
+
+            CertificateStatus {
+                key_id: certificate.key_id,
+                source,
+                valid_before: certificate.valid_before,
+                warning_threshold,
+            }
+        });
+
+        if self.keepalive_interval > 0 {
+            session.set_keepalive(false, self.keepalive_interval);
+            self.start_keepalive();
+        }
+
+        session_data.session = session;
+        session_data.tunnels = tunnels;
+        session_data.is_initialized = true;
+        Ok(())
+    }
+
+    /// Sends keepalives on idle sessions so that firewalls don't drop them. Dead sessions are reconnected on next use.
+    /// The thread stops when the connector and its clones are dropped.
+    fn start_keepalive(&self) {
+        if self.keepalive_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let sessions = Arc::downgrade(&self.available_sessions);
+        let interval = Duration::from_secs(self.keepalive_interval as u64);
+
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+
+            let Some(sessions) = sessions.upgrade() else {
+                return;
+            };
+
+            for session in sessions.iter() {
+                // Sessions in use don't need keepalives.
+                let Ok(mut session_data) = session.try_lock() else {
+                    continue;
+                };
+
+                if session_data.is_initialized && session_data.open_channel.is_none() {
+                    if let Err(error) = session_data.session.keepalive_send() {
+                        log::warn!("Keepalive failed, reconnecting on next use: {}", error);
+                        session_data.is_initialized = false;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Opens an authenticated session to the target through the configured jump hosts.
+    /// Host keys of all hosts on the path are checked with `verify_host_key`.
+    fn open_session(
+        &self,
+        address: &str,
+        port: u16,
+        verify_host_key: impl Fn(&ssh2::Session, &str, u16) -> Result<(), LkError>,
+    ) -> Result<(ssh2::Session, Vec<Tunnel>, Option<UsedCertificate>), LkError> {
+        let jump_hosts = self.jump_hosts.as_ref().map_err(|error| LkError::other_p("Invalid jump host settings", error))?;
+        let path = jump_hosts.iter()
+            .map(|jump_host| (jump_host.address.as_str(), jump_host.port, &jump_host.credentials))
+            .chain(std::iter::once((address, port, &self.credentials)))
+            .collect::<Vec<_>>();
+
+        let (first_address, first_port, _) = path[0];
+        let mut socket_addresses = format!("{}:{}", first_address, first_port).to_socket_addrs()?;
         let socket_address = match socket_addresses.next() {
             Some(address) => address,
             None => return Err(LkError::other("Failed to resolve address")),
@@ -438,84 +967,157 @@ impl Ssh2 {
 
         let connection_timeout = std::time::Duration::from_secs(self.connection_timeout as u64);
         let stream = TcpStream::connect_timeout(&socket_address, connection_timeout)?;
-        log::info!("Connected to {}:{}", address, port);
+        log::info!("Connected to {}:{}", first_address, first_port);
+
+        let mut transport_fd = stream.as_raw_fd();
+        let mut session = ssh2::Session::new().expect("Unable to initialize SSH sessions.");
+        session.set_tcp_stream(stream);
+        let mut tunnels = Vec::new();
+        let mut certificate = None;
+
+        for (index, (hostname, port, credentials)) in path.into_iter().enumerate() {
+            if index > 0 {
+                let channel = session.channel_direct_tcpip(hostname, port, None)
+                    .map_err(|error| LkError::other(format!("Failed to open tunnel to {}:{}: {}", hostname, port, error)))?;
+
+                let (local_socket, tunnel_socket) = UnixStream::pair()?;
+                let next_transport_fd = local_socket.as_raw_fd();
+                tunnels.push(Tunnel::start(session, channel, tunnel_socket, transport_fd)?);
+                log::info!("Connected to {}:{} through a jump host", hostname, port);
+
+                transport_fd = next_transport_fd;
+                session = ssh2::Session::new().expect("Unable to initialize SSH sessions.");
+                session.set_tcp_stream(local_socket);
+            }
 
-        session_data.session = ssh2::Session::new().expect("Unable to initialize SSH sessions.");
-        session_data.session.set_tcp_stream(stream);
-        if let Err(error) = session_data.session.handshake() {
-            log::debug!("Supported Kex algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::Kex));
-            log::debug!("Supported MacCs algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::MacCs));
-            log::debug!("Supported HostKey algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::HostKey));
-            log::debug!("Supported CryptCs algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::CryptCs));
-            return Err(LkError::from(error))
-        }
+            // Tunneled connections can otherwise hang if a jump host stops responding.
+            session.set_timeout(self.connection_timeout as u32 * 1000);
+            if let Err(error) = session.handshake() {
+                log::debug!("Supported Kex algs: {:?}", session.supported_algs(ssh2::MethodType::Kex));
+                log::debug!("Supported MacCs algs: {:?}", session.supported_algs(ssh2::MethodType::MacCs));
+                log::debug!("Supported HostKey algs: {:?}", session.supported_algs(ssh2::MethodType::HostKey));
+                log::debug!("Supported CryptCs algs: {:?}", session.supported_algs(ssh2::MethodType::CryptCs));
+                return Err(LkError::from(error))
+            }
 
-        if self.verify_host_key {
-            self.check_known_hosts(&session_data, &address, port)?;
+            verify_host_key(&session, hostname, port)?;
+            // Only the certificate used for the target is of interest.
+            certificate = Self::authenticate(&session, credentials)
+                .map_err(|error| LkError::new(ErrorKind::AuthenticationFailed, error.message))?;
+            session.set_timeout(0);
         }
 
-        if let Some(password) = &self.password {
-            session_data.session.userauth_password(self.username.as_str(), password.as_str())
+        Ok((session, tunnels, certificate))
+    }
+
+    /// Returns the certificate used and where it was loaded from, if any.
+    fn authenticate(session: &ssh2::Session, credentials: &Credentials) -> Result<Option<UsedCertificate>, LkError> {
+        let username = credentials.username.as_str();
+
+        if let Some(password) = &credentials.password {
+            session.userauth_password(username, password.as_str())
                 .map_err(|error| LkError::other(format!("Failed to authenticate with password: {}", error)))?;
         }
-        else if let Some(private_key_path) = &self.private_key_path {
+        else if let Some(private_key_path) = &credentials.private_key_path {
             let path = Path::new(private_key_path);
-            let passphrase_option = self.private_key_passphrase.as_ref().map(|pass| pass.as_str());
+            let passphrase_option = credentials.private_key_passphrase.as_deref();
 
-            session_data.session.userauth_pubkey_file(self.username.as_str(), None, path, passphrase_option)
-                .map_err(|error| LkError::other(format!("Failed to authenticate with private key: {}", error)))?;
+            let certificate_path = match &credentials.certificate_path {
+                Some(certificate_path) => Some(PathBuf::from(certificate_path)),
+                None => Some(PathBuf::from(format!("{}-cert.pub", private_key_path))).filter(|path| path.is_file()),
+            };
+
+            // Like OpenSSH, the plain key is tried if the certificate is not accepted.
+            if let Some(certificate_path) = certificate_path {
+                match session.userauth_pubkey_file(username, Some(&certificate_path), path, passphrase_option) {
+                    Ok(()) => {
+                        let source = certificate_path.to_string_lossy().to_string();
+                        return match Certificate::from_file(&certificate_path) {
+                            Ok(certificate) => Ok(Some((certificate, source))),
+                            Err(error) => {
+                                log::warn!("Authenticated, but failed to read certificate: {}", error);
+                                Ok(None)
+                            }
+                        };
+                    },
+                    Err(error) => log::warn!("Failed to authenticate with certificate {}: {}", certificate_path.to_string_lossy(), error),
+                }
+            }
+
+            if let Err(error) = session.userauth_pubkey_file(username, None, path, passphrase_option) {
+                if credentials.identities_only {
+                    return Err(LkError::other(format!("Failed to authenticate with private key: {}", error)));
+                }
+
+                log::debug!("Failed to authenticate with private key ({}), trying SSH agent.", error);
+                return Self::authenticate_with_agent(session, credentials);
+            }
         }
         else {
             log::debug!("Password or key is not set, using SSH agent for authentication.");
-            let mut agent = session_data.session.agent()
-                .map_err(|error| LkError::other(format!("Failed to connect to SSH agent: {}", error)))?;
+            return Self::authenticate_with_agent(session, credentials);
+        }
 
-            agent.connect()
-                 .map_err(|error| LkError::other(format!("Failed to connect to SSH agent: {}", error)))?;
+        Ok(None)
+    }
 
-            agent.list_identities()?;
-            let mut valid_identities = agent.identities()?;
+    fn authenticate_with_agent(session: &ssh2::Session, credentials: &Credentials) -> Result<Option<UsedCertificate>, LkError> {
+        let mut agent = session.agent()
+            .map_err(|error| LkError::other(format!("Failed to connect to SSH agent: {}", error)))?;
 
-            if let Some(selected_id) = self.agent_key_identifier.as_ref() {
-                valid_identities.retain(|identity| identity.comment() == selected_id.as_str());
-            }
+        agent.connect()
+             .map_err(|error| LkError::other(format!("Failed to connect to SSH agent: {}", error)))?;
 
-            for identity in valid_identities.iter() {
-                log::debug!("Trying to authenticate with key \"{}\".", identity.comment());
-                if agent.userauth(self.username.as_str(), identity).is_ok() {
-                    break;
-                }
-            }
+        agent.list_identities()?;
+        let mut valid_identities = agent.identities()?;
 
-            if !session_data.session.authenticated() {
-                return Err(LkError::other("Failed to authenticate with SSH agent."));
+        if let Some(selected_id) = credentials.agent_key_identifier.as_ref() {
+            valid_identities.retain(|identity| identity.comment() == selected_id.as_str());
+        }
+
+        // Certificates are tried first as they are usually the preferred way if available.
+        let mut identities = valid_identities.into_iter()
+            .map(|identity| {
+                let certificate = Certificate::from_blob(identity.blob()).ok();
+                (identity, certificate)
+            })
+            .collect::<Vec<_>>();
+        identities.sort_by_key(|(_, certificate)| certificate.is_none());
+
+        for (identity, certificate) in identities.into_iter() {
+            log::debug!("Trying to authenticate with key \"{}\".", identity.comment());
+            if agent.userauth(&credentials.username, &identity).is_ok() {
+                let source = format!("ssh-agent ({})", identity.comment());
+                return Ok(certificate.map(|certificate| (certificate, source)));
             }
         }
 
-        session_data.is_initialized = true;
-        Ok(())
+        Err(LkError::other("Failed to authenticate with SSH agent."))
     }
 
     fn reconnect(&self, session_data: &mut MutexGuard<'_, SessionData>) -> Result<(), LkError> {
         let address = self.address.lock().unwrap().clone();
         let port = *self.port.lock().unwrap();
 
-        session_data.session.disconnect(None, "", None)?;
+        // The connection is likely already broken, so disconnecting can fail.
+        if let Err(error) = session_data.session.disconnect(None, "", None) {
+            log::debug!("Error while disconnecting: {}", error);
+        }
         session_data.is_initialized = false;
         log::debug!("Disconnected");
-        self.connect(session_data, &address, port)
+        self.connect_session(session_data, &address, port)
     }
 
-    fn check_known_hosts(&self, session_data: &MutexGuard<'_, SessionData>, hostname: &str, port: u16) -> Result<(), LkError> {
+    fn check_known_hosts(&self, session: &ssh2::Session, hostname: &str, port: u16) -> Result<(), LkError> {
         let known_hosts_path = self.get_known_hosts_path()?;
 
-        let mut known_hosts = session_data.session.known_hosts()
+        let mut known_hosts = session.known_hosts()
             .map_err(|error| LkError::other(format!("Failed to initialize known hosts file: {}", error)))?;
 
         known_hosts.read_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
             .map_err(|error| LkError::other(format!("Failed to read known hosts file: {}", error)))?;
 
-        if let Some((key, key_type)) = session_data.session.host_key() {
+        if let Some((key, key_type)) = session.host_key() {
             let key_string = Self::get_host_key_id(key_type, key)?;
 
             match known_hosts.check_port(hostname, port, key) {
@@ -539,6 +1141,38 @@ impl Ssh2 {
         }
     }
 
+    fn add_known_host(&self, session: &ssh2::Session, hostname: &str, port: u16, key_id: &str) -> Result<(), LkError> {
+        let known_hosts_path = self.get_known_hosts_path()?;
+
+        let mut known_hosts = session.known_hosts()
+            .map_err(|error| LkError::other(format!("Failed to initialize known hosts file: {}", error)))?;
+
+        known_hosts.read_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
+            .map_err(|error| LkError::other_p("Failed to read known hosts file", error))?;
+
+        let Some((key, key_type)) = session.host_key() else {
+            return Err(LkError::other("Failed to get host key"));
+        };
+
+        if Self::get_host_key_id(key_type, key)? != key_id {
+            return Err(LkError::other("Host key changed again?!"));
+        }
+
+        let known_hosts_name = if port == 22 {
+            hostname.to_string()
+        }
+        else {
+            format!("[{}]:{}", hostname, port)
+        };
+
+        known_hosts.add(&known_hosts_name, key, hostname, key_type.into())
+                   .map_err(|error| LkError::other_p("Failed to add host key to known hosts", error))?;
+        known_hosts.write_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
+                   .map_err(|error| LkError::other_p("Failed to write known hosts file", error))?;
+        log::info!("Added host key of {} to known hosts", known_hosts_name);
+        Ok(())
+    }
+
     fn get_known_hosts_path(&self) -> Result<PathBuf, LkError> {
         if let Some(custom_path) = &self.custom_known_hosts_path {
             if !custom_path.exists() {
@@ -549,8 +1183,7 @@ impl Ssh2 {
             }
         }
         else {
-            let config_dir = file_handler::get_config_dir();
-            let known_hosts_path = config_dir.join("known_hosts");
+            let known_hosts_path = default_known_hosts_path();
 
             // Create known_hosts if it's missing.
             if !known_hosts_path.exists() {
@@ -577,6 +1210,184 @@ impl Ssh2 {
 }
 
 
+/// Authentication settings of a single host on the connection path.
+#[derive(Clone, Default, PartialEq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: Option<String>,
+    pub private_key_path: Option<String>,
+    /// OpenSSH certificate for the private key.
+    pub certificate_path: Option<String>,
+    pub private_key_passphrase: Option<String>,
+    pub agent_key_identifier: Option<String>,
+    /// If false, ssh-agent is tried if authenticating with the private key file fails.
+    pub identities_only: bool,
+}
+
+/// Certificate that was used for authentication.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificateStatus {
+    pub key_id: String,
+    /// Certificate file path or ssh-agent.
+    pub source: String,
+    /// None if the certificate never expires.
+    pub valid_before: Option<DateTime<Utc>>,
+    /// Expiry is warned about when less than this is remaining.
+    pub warning_threshold: chrono::Duration,
+}
+
+impl CertificateStatus {
+    /// For `ConnectionModule::get_connection_details`.
+    pub fn to_details(&self) -> HashMap<String, String> {
+        let valid_before = self.valid_before
+            .map(|valid_before| valid_before.timestamp().to_string())
+            .unwrap_or_else(|| String::from("never"));
+
+        HashMap::from([
+            (String::from("key_id"), self.key_id.clone()),
+            (String::from("source"), self.source.clone()),
+            (String::from("valid_before"), valid_before),
+            (String::from("warning_threshold"), self.warning_threshold.num_seconds().to_string()),
+        ])
+    }
+
+    /// None if the details don't contain a certificate, e.g. when password or plain key authentication was used.
+    pub fn from_details(values: &HashMap<String, String>) -> Option<Self> {
+        let valid_before = match values.get("valid_before")?.as_str() {
+            "never" => None,
+            timestamp => Some(DateTime::from_timestamp(timestamp.parse::<i64>().ok()?, 0)?),
+        };
+
+        Some(CertificateStatus {
+            key_id: values.get("key_id")?.to_string(),
+            source: values.get("source")?.to_string(),
+            valid_before,
+            warning_threshold: chrono::Duration::seconds(values.get("warning_threshold")?.parse::<i64>().ok()?),
+        })
+    }
+}
+
+/// Intermediate host that the connection is tunneled through.
+#[derive(Clone, PartialEq)]
+pub struct JumpHost {
+    pub address: String,
+    pub port: u16,
+    pub credentials: Credentials,
+}
+
+impl JumpHost {
+    /// In the format used by the ProxyJump option of OpenSSH.
+    pub fn to_proxy_jump(&self) -> String {
+        let address = if self.address.contains(':') { format!("[{}]", self.address) } else { self.address.clone() };
+        format!("{}@{}:{}", self.credentials.username, address, self.port)
+    }
+}
+
+/// Parses `jump_hosts` and the hop-specific `jump_host_<number>_*` settings. Hops are numbered from 1.
+pub fn parse_jump_hosts(settings: &HashMap<String, String>) -> Result<Vec<JumpHost>, LkError> {
+    let Some(jump_hosts) = settings.get("jump_hosts") else {
+        return Ok(Vec::new());
+    };
+
+    jump_hosts.split(',').map(str::trim).filter(|spec| !spec.is_empty()).enumerate()
+        .map(|(index, spec)| {
+            let setting = |key: &str| {
+                settings.get(&format!("jump_host_{}_{}", index + 1, key))
+                    .or_else(|| settings.get(key))
+                    .cloned()
+            };
+
+            let (username, host_and_port) = match spec.rsplit_once('@') {
+                Some((username, host_and_port)) => (Some(username.to_string()), host_and_port),
+                None => (None, spec),
+            };
+
+            let (address, port) = if let Some(bracketed) = host_and_port.strip_prefix('[') {
+                // IPv6 address.
+                let (address, rest) = bracketed.split_once(']').ok_or_else(|| LkError::other_p("Invalid jump host", spec))?;
+                (address, rest.strip_prefix(':'))
+            }
+            else {
+                match host_and_port.split_once(':') {
+                    Some((address, port)) if !port.contains(':') => (address, Some(port)),
+                    _ => (host_and_port, None),
+                }
+            };
+
+            let port = match port {
+                Some(port) => port.parse::<u16>().map_err(|_| LkError::other_p("Invalid port for jump host", spec))?,
+                None => 22,
+            };
+
+            if address.is_empty() {
+                return Err(LkError::other_p("Invalid jump host", spec));
+            }
+
+            Ok(JumpHost {
+                address: address.to_string(),
+                port,
+                credentials: Credentials {
+                    username: username.or_else(|| setting("username")).unwrap_or_else(|| String::from("root")),
+                    // Password of the target host is never sent to jump hosts.
+                    password: settings.get(&format!("jump_host_{}_password", index + 1)).cloned(),
+                    private_key_path: setting("private_key_path"),
+                    certificate_path: settings.get(&format!("jump_host_{}_certificate_path", index + 1)).cloned(),
+                    private_key_passphrase: setting("private_key_passphrase"),
+                    agent_key_identifier: setting("agent_key_identifier"),
+                    identities_only: setting("identities_only").and_then(|value| value.parse::<bool>().ok()).unwrap_or(true),
+                },
+            })
+        })
+        .collect()
+}
+
+/// Used unless `custom_known_hosts_path` is set.
+pub fn default_known_hosts_path() -> PathBuf {
+    file_handler::get_config_dir().join("known_hosts")
+}
+
+/// Alias of a jump host in the configuration returned by `jump_hosts_to_ssh_config`. Hops are numbered from 1.
+pub fn jump_host_alias(hop_number: usize) -> String {
+    format!("lightkeeper-jump-{}", hop_number)
+}
+
+/// OpenSSH client configuration for the jump hosts, so that the ssh command uses the same keys and host key
+/// settings for every hop as the connector does. Hops are chained with ProxyJump, so the last hop is the one to connect through.
+/// `settings` are the connector settings that `jump_hosts` were parsed from.
+pub fn jump_hosts_to_ssh_config(jump_hosts: &[JumpHost], settings: &HashMap<String, String>) -> String {
+    let known_hosts_path = settings.get("custom_known_hosts_path").map(PathBuf::from).unwrap_or_else(default_known_hosts_path);
+    let verify_host_key = settings.get("verify_host_key").and_then(|value| value.parse::<bool>().ok()).unwrap_or(true);
+
+    let config = jump_hosts.iter().enumerate().map(|(index, jump_host)| {
+        let credentials = &jump_host.credentials;
+        let mut lines = vec![
+            format!("Host {}", jump_host_alias(index + 1)),
+            format!("    HostName {}", jump_host.address),
+            format!("    Port {}", jump_host.port),
+            format!("    User \"{}\"", credentials.username),
+            format!("    IdentitiesOnly {}", if credentials.identities_only { "yes" } else { "no" }),
+            format!("    UserKnownHostsFile \"{}\"", known_hosts_path.display()),
+        ];
+
+        if let Some(private_key_path) = &credentials.private_key_path {
+            lines.push(format!("    IdentityFile \"{}\"", private_key_path));
+        }
+        if let Some(certificate_path) = &credentials.certificate_path {
+            lines.push(format!("    CertificateFile \"{}\"", certificate_path));
+        }
+        if !verify_host_key {
+            lines.push(String::from("    StrictHostKeyChecking no"));
+        }
+        if index > 0 {
+            lines.push(format!("    ProxyJump {}", jump_host_alias(index)));
+        }
+
+        lines.join("\n")
+    }).collect::<Vec<_>>();
+
+    format!("{}\n", config.join("\n\n"))
+}
+
 /// Simplify conversion from SSH2 errors to internal error type.
 /// See: https://github.com/alexcrichton/ssh2-rs/blob/master/libssh2-sys/lib.rs
 impl From<ssh2::Error> for LkError {
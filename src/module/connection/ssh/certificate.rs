@@ -0,0 +1,141 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+
+use crate::error::LkError;
+
+//
+// Parser for OpenSSH certificates (PROTOCOL.certkeys in OpenSSH sources).
+// Only the fields needed for showing the validity are parsed. Signatures are not verified since the server does that.
+//
+
+const CERT_SUFFIX: &str = "-cert-v01@openssh.com";
+
+/// OpenSSH user or host certificate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Certificate {
+    pub key_type: String,
+    pub serial: u64,
+    pub key_id: String,
+    pub principals: Vec<String>,
+    pub valid_after: DateTime<Utc>,
+    /// None if the certificate never expires.
+    pub valid_before: Option<DateTime<Utc>>,
+}
+
+impl Certificate {
+    pub fn is_certificate_type(key_type: &str) -> bool {
+        key_type.ends_with(CERT_SUFFIX)
+    }
+
+    /// Parses a certificate file (e.g. id_ed25519-cert.pub).
+    pub fn from_file(path: &Path) -> Result<Self, LkError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| LkError::other(format!("Failed to read certificate {}: {}", path.to_string_lossy(), error)))?;
+        Self::from_public_key_line(&contents)
+    }
+
+    /// Parses the format used in public key files: type, base64-encoded blob and optional comment.
+    pub fn from_public_key_line(line: &str) -> Result<Self, LkError> {
+        let encoded = line.split_whitespace().nth(1)
+            .ok_or_else(|| LkError::other("Invalid certificate format"))?;
+
+        let blob = base64::engine::general_purpose::STANDARD.decode(encoded)
+            .map_err(|error| LkError::other_p("Invalid certificate encoding", error))?;
+
+        Self::from_blob(&blob)
+    }
+
+    /// Parses the binary format, e.g. a key blob from ssh-agent.
+    pub fn from_blob(blob: &[u8]) -> Result<Self, LkError> {
+        let mut reader = BlobReader { data: blob };
+        let key_type = reader.read_string()?;
+
+        // Number of length-prefixed public key fields after the nonce.
+        let key_fields = match key_type.strip_suffix(CERT_SUFFIX) {
+            Some("ssh-rsa") => 2,
+            Some("ssh-dss") => 4,
+            Some("ssh-ed25519") => 1,
+            Some("sk-ssh-ed25519") => 2,
+            Some(other) if other.starts_with("ecdsa-sha2-") => 2,
+            Some(other) if other.starts_with("sk-ecdsa-sha2-") => 3,
+            _ => return Err(LkError::other_p("Unsupported certificate type", &key_type)),
+        };
+
+        // Nonce.
+        reader.read_bytes()?;
+        for _ in 0..key_fields {
+            reader.read_bytes()?;
+        }
+
+        let serial = reader.read_u64()?;
+        let _certificate_type = reader.read_u32()?;
+        let key_id = reader.read_string()?;
+
+        let mut principals_reader = BlobReader { data: reader.read_bytes()? };
+        let mut principals = Vec::new();
+        while !principals_reader.data.is_empty() {
+            principals.push(principals_reader.read_string()?);
+        }
+
+        let valid_after = reader.read_u64()?;
+        let valid_before = reader.read_u64()?;
+
+        Ok(Certificate {
+            key_type,
+            serial,
+            key_id,
+            principals,
+            valid_after: Self::to_datetime(valid_after).unwrap_or_default(),
+            valid_before: if valid_before == u64::MAX { None } else { Self::to_datetime(valid_before) },
+        })
+    }
+
+    /// Validity period length, or None for certificates that never expire.
+    pub fn lifetime(&self) -> Option<chrono::Duration> {
+        self.valid_before.map(|valid_before| valid_before - self.valid_after)
+    }
+
+    fn to_datetime(timestamp: u64) -> Option<DateTime<Utc>> {
+        i64::try_from(timestamp).ok().and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+    }
+}
+
+struct BlobReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> BlobReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], LkError> {
+        if self.data.len() < length {
+            return Err(LkError::other("Truncated certificate"));
+        }
+        let (taken, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, LkError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, LkError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], LkError> {
+        let length = self.read_u32()? as usize;
+        self.take(length)
+    }
+
+    fn read_string(&mut self) -> Result<String, LkError> {
+        Ok(String::from_utf8_lossy(self.read_bytes()?).to_string())
+    }
+}
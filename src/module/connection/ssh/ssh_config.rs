@@ -0,0 +1,380 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::LkError;
+
+//
+// Minimal reader for the OpenSSH client configuration (ssh_config(5)).
+// Only the options that map to connector settings are read. Like OpenSSH, the first obtained value is used,
+// except for IdentityFile which accumulates. Match blocks are not supported and are skipped, except for `Match all`.
+//
+
+/// Protects against include loops.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Values for a host resolved from the OpenSSH client configuration.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ResolvedHost {
+    pub host_name: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// Paths with tilde and tokens expanded.
+    pub identity_files: Vec<String>,
+    pub identities_only: Option<bool>,
+    pub proxy_jump: Option<String>,
+}
+
+/// Default location of the user's configuration.
+pub fn default_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".ssh").join("config"))
+}
+
+/// Resolves options for `alias` from the configuration file at `path`.
+/// Relative Include paths are relative to the directory of `path`, which is ~/.ssh for the user's configuration.
+pub fn resolve(path: &Path, alias: &str) -> Result<ResolvedHost, LkError> {
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut resolved = ResolvedHost::default();
+    read_file(path, &base_dir, alias, true, 0, &mut resolved)?;
+
+    let host_name = resolved.host_name.take()
+        .map(|host_name| expand_tokens(&host_name, alias, alias, None))
+        .unwrap_or_else(|| alias.to_string());
+
+    resolved.identity_files = resolved.identity_files.iter()
+        .map(|path| expand_tokens(&expand_tilde(path), alias, &host_name, resolved.user.as_deref()))
+        .collect();
+    resolved.host_name = Some(host_name);
+    Ok(resolved)
+}
+
+fn read_file(path: &Path, base_dir: &Path, alias: &str, active: bool, depth: usize, resolved: &mut ResolvedHost) -> Result<(), LkError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(LkError::other_p("Too many nested includes in SSH configuration", path.to_string_lossy()));
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|error| LkError::other(format!("Failed to read SSH configuration {}: {}", path.to_string_lossy(), error)))?;
+
+    // Options before the first Host line apply to all hosts. Included files inherit the state of the including block.
+    let mut active = active;
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let Some((keyword, arguments)) = split_line(line) else {
+            continue;
+        };
+
+        match keyword.as_str() {
+            "host" => {
+                active = host_matches(&arguments, alias);
+            },
+            "match" => {
+                if !arguments.iter().all(|argument| argument.eq_ignore_ascii_case("all")) {
+                    log::debug!("{}:{}: Match blocks are not supported, skipping", path.to_string_lossy(), line_number + 1);
+                }
+                active = !arguments.is_empty() && arguments.iter().all(|argument| argument.eq_ignore_ascii_case("all"));
+            },
+            "include" if active => {
+                for pattern in arguments.iter() {
+                    for included_path in expand_include(pattern, base_dir) {
+                        read_file(&included_path, base_dir, alias, active, depth + 1, resolved)?;
+                    }
+                }
+            },
+            _ if active => {
+                let Some(value) = arguments.first().cloned() else {
+                    continue;
+                };
+
+                match keyword.as_str() {
+                    "hostname" => { resolved.host_name.get_or_insert(value); },
+                    "user" => { resolved.user.get_or_insert(value); },
+                    "port" if resolved.port.is_none() => {
+                        let port = value.parse::<u16>()
+                            .map_err(|_| LkError::other_p("Invalid port in SSH configuration", &value))?;
+                        resolved.port = Some(port);
+                    },
+                    "identityfile" => resolved.identity_files.push(value),
+                    "identitiesonly" => { resolved.identities_only.get_or_insert(value.eq_ignore_ascii_case("yes")); },
+                    "proxyjump" => { resolved.proxy_jump.get_or_insert(value); },
+                    _ => {},
+                }
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the lowercase keyword and its arguments. Supports both `Keyword value` and `Keyword=value`.
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let separator = line.find(|character: char| character.is_whitespace() || character == '=')?;
+    let keyword = line[..separator].to_lowercase();
+    let rest = line[separator..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_argument = false;
+
+    for character in rest.chars() {
+        match character {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_argument = true;
+            },
+            character if character.is_whitespace() && !in_quotes => {
+                if has_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    has_argument = false;
+                }
+            },
+            character => {
+                current.push(character);
+                has_argument = true;
+            },
+        }
+    }
+
+    if has_argument {
+        arguments.push(current);
+    }
+
+    Some((keyword, arguments))
+}
+
+/// A negated pattern that matches prevents the whole block from matching.
+fn host_matches(patterns: &[String], alias: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard_match(negated, alias) {
+                return false;
+            }
+        }
+        else if wildcard_match(pattern, alias) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Supports `*` and `?`. Host patterns are case-insensitive.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+
+    let (mut pattern_index, mut text_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while text_index < text.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                backtrack = Some((pattern_index, text_index));
+                pattern_index += 1;
+            },
+            Some(character) if *character == '?' || *character == text[text_index] => {
+                pattern_index += 1;
+                text_index += 1;
+            },
+            _ => match backtrack {
+                Some((star_index, star_text_index)) => {
+                    pattern_index = star_index + 1;
+                    text_index = star_text_index + 1;
+                    backtrack = Some((star_index, star_text_index + 1));
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_index..].iter().all(|character| *character == '*')
+}
+
+/// Wildcards are supported in the file name only.
+fn expand_include(pattern: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let expanded = PathBuf::from(expand_tilde(pattern));
+    let path = if expanded.is_absolute() { expanded } else { base_dir.join(expanded) };
+
+    let file_pattern = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    if !file_pattern.contains(['*', '?']) {
+        return if path.is_file() { vec![path] } else { Vec::new() };
+    }
+
+    let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let Ok(entries) = fs::read_dir(&directory) else {
+        return Vec::new();
+    };
+
+    let mut paths = entries.filter_map(Result::ok)
+        .filter(|entry| wildcard_match(&file_pattern, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+
+    // Same order as glob(3).
+    paths.sort();
+    paths
+}
+
+fn expand_tilde(path: &str) -> String {
+    match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+/// Supports %%, %d (local home directory), %h (host name), %n (original alias), %r (remote user) and %u (local user).
+fn expand_tokens(value: &str, alias: &str, host_name: &str, remote_user: Option<&str>) -> String {
+    let mut result = String::new();
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '%' {
+            result.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('%') => result.push('%'),
+            Some('d') => result.push_str(&env::var("HOME").unwrap_or_default()),
+            Some('h') => result.push_str(host_name),
+            Some('n') => result.push_str(alias),
+            Some('r') => result.push_str(remote_user.unwrap_or("root")),
+            Some('u') => result.push_str(&env::var("USER").unwrap_or_default()),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            },
+            None => result.push('%'),
+        }
+    }
+
+    result
+}
+
+/// Fills connector settings of the `ssh` connector that are not set explicitly, if `use_ssh_config` is enabled.
+/// Host patterns are matched against `ssh_config_host` or, if not set, the host ID.
+/// `host_name` is filled only if the host doesn't have an address of its own.
+pub fn apply(host_id: &str, has_address: bool, settings: &mut HashMap<String, String>) {
+    if settings.get("use_ssh_config").and_then(|value| value.parse::<bool>().ok()) != Some(true) {
+        return;
+    }
+
+    let Some(path) = settings.get("ssh_config_path").map(PathBuf::from).or_else(default_path) else {
+        log::warn!("Can't locate SSH configuration for host {}", host_id);
+        return;
+    };
+
+    let alias = settings.get("ssh_config_host").cloned().unwrap_or_else(|| host_id.to_string());
+    let resolve_alias = |alias: &str| match resolve(&path, alias) {
+        Ok(resolved) => Some(resolved),
+        Err(error) => {
+            log::error!("Host {}: {}", host_id, error);
+            None
+        },
+    };
+
+    let Some(resolved) = resolve_alias(&alias) else {
+        return;
+    };
+
+    let mut set_default = |key: &str, value: String| {
+        settings.entry(key.to_string()).or_insert(value);
+    };
+
+    if !has_address {
+        if let Some(host_name) = resolved.host_name.clone() {
+            set_default("host_name", host_name);
+        }
+    }
+
+    if let Some(user) = resolved.user.clone() {
+        set_default("username", user);
+    }
+
+    if let Some(port) = resolved.port {
+        set_default("port", port.to_string());
+    }
+
+    if let Some(identities_only) = resolved.identities_only {
+        set_default("identities_only", identities_only.to_string());
+    }
+
+    // Like OpenSSH, ssh-agent is also tried unless IdentitiesOnly is set.
+    if !settings.contains_key("private_key_path") {
+        if let Some(identity_file) = resolved.identity_files.iter().find(|path| Path::new(path).is_file()) {
+            settings.insert(String::from("private_key_path"), identity_file.clone());
+            settings.entry(String::from("identities_only")).or_insert_with(|| String::from("false"));
+        }
+    }
+
+    if let Some(proxy_jump) = resolved.proxy_jump.as_ref().filter(|proxy_jump| !proxy_jump.eq_ignore_ascii_case("none")) {
+        if !settings.contains_key("jump_hosts") {
+            let hops = proxy_jump.split(',').map(str::trim).filter(|hop| !hop.is_empty()).collect::<Vec<_>>();
+            let mut jump_hosts = Vec::new();
+
+            for (index, hop) in hops.iter().enumerate() {
+                let (jump_host, identity_file) = resolve_jump_host(hop, &resolve_alias);
+                jump_hosts.push(jump_host);
+
+                if let Some(identity_file) = identity_file {
+                    settings.entry(format!("jump_host_{}_private_key_path", index + 1)).or_insert(identity_file);
+                }
+            }
+
+            settings.insert(String::from("jump_hosts"), jump_hosts.join(","));
+        }
+    }
+}
+
+/// Jump hosts can be aliases too. Returns the hop in the format of the `jump_hosts` setting and its identity file.
+fn resolve_jump_host(hop: &str, resolve_alias: &impl Fn(&str) -> Option<ResolvedHost>) -> (String, Option<String>) {
+    let hop = hop.strip_prefix("ssh://").unwrap_or(hop);
+    let (user, host_and_port) = match hop.rsplit_once('@') {
+        Some((user, host_and_port)) => (Some(user.to_string()), host_and_port),
+        None => (None, hop),
+    };
+
+    // Bracketed IPv6 addresses are never aliases.
+    let (alias, port) = match host_and_port.split_once(':') {
+        _ if host_and_port.starts_with('[') => return (hop.to_string(), None),
+        None => (host_and_port, None),
+        Some((alias, port)) => match port.parse::<u16>() {
+            Ok(port) => (alias, Some(port)),
+            // Invalid values are reported when parsing the setting.
+            Err(_) => return (hop.to_string(), None),
+        },
+    };
+
+    let Some(resolved) = resolve_alias(alias) else {
+        return (hop.to_string(), None);
+    };
+
+    let host_name = resolved.host_name.unwrap_or_else(|| alias.to_string());
+    let host_name = if host_name.contains(':') { format!("[{}]", host_name) } else { host_name };
+    let mut jump_host = match user.or(resolved.user) {
+        Some(user) => format!("{}@{}", user, host_name),
+        None => host_name,
+    };
+
+    if let Some(port) = port.or(resolved.port) {
+        jump_host = format!("{}:{}", jump_host, port);
+    }
+
+    let identity_file = resolved.identity_files.into_iter().find(|path| Path::new(path).is_file());
+    (jump_host, identity_file)
+}
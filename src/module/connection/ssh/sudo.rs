@@ -0,0 +1,82 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::{Read, Write};
+
+use crate::error::LkError;
+
+
+/// Passes the sudo password only after sudo has prompted for it, so that it never ends up as input for the command
+/// (e.g. with NOPASSWD). Both the prompt and the start of the command are marked on stderr with random tokens.
+#[derive(Clone)]
+pub struct SudoPassword {
+    password: String,
+    prompt: String,
+    started: String,
+}
+
+impl SudoPassword {
+    pub fn new(password: &str) -> Self {
+        SudoPassword {
+            password: password.to_string(),
+            prompt: format!("lightkeeper-sudo-prompt-{:016x}", rand::random::<u64>()),
+            started: format!("lightkeeper-sudo-started-{:016x}", rand::random::<u64>()),
+        }
+    }
+
+    /// Returns None if the command isn't run with sudo.
+    pub fn wrap_command(&self, command: &str) -> Option<String> {
+        let trimmed = command.trim_start();
+        let rest = trimmed.strip_prefix("\"sudo\" ").or_else(|| trimmed.strip_prefix("sudo "))?;
+
+        // Cached credentials are ignored (-k) so that sudo either prompts or doesn't need a password at all.
+        Some(format!(
+            "\"sudo\" \"-S\" \"-k\" \"-p\" \"{}\" \"sh\" \"-c\" 'echo {} >&2; exec \"$@\"' \"sh\" {}",
+            self.prompt, self.started, rest
+        ))
+    }
+
+    /// Answers the prompt and reads until the command has started. Returns the output received after that.
+    pub fn authenticate<C: Read + Write>(&self, channel: &mut C) -> Result<Vec<u8>, LkError> {
+        let mut received = Vec::new();
+        let mut password_sent = false;
+        let mut buffer = [0u8; 1024];
+
+        loop {
+            if let Some(position) = find(&received, self.started.as_bytes()) {
+                let output = received.split_off(position + self.started.len());
+                let output = output.strip_prefix(b"\r").unwrap_or(&output);
+                return Ok(output.strip_prefix(b"\n").unwrap_or(output).to_vec());
+            }
+
+            if let Some(position) = find(&received, self.prompt.as_bytes()) {
+                if password_sent {
+                    return Err(LkError::other("Incorrect sudo password"));
+                }
+
+                channel.write_all(format!("{}\n", self.password).as_bytes())
+                    .and_then(|_| channel.flush())
+                    .map_err(|error| LkError::other(format!("Error writing to stdin: {}", error)))?;
+
+                password_sent = true;
+                received.drain(..position + self.prompt.len());
+                continue;
+            }
+
+            match channel.read(&mut buffer) {
+                Ok(0) => {
+                    let output = String::from_utf8_lossy(&received);
+                    return Err(LkError::other(format!("sudo failed: {}", output.trim())));
+                },
+                Ok(bytes_read) => received.extend_from_slice(&buffer[..bytes_read]),
+                Err(error) => return Err(LkError::other(format!("Invalid output received: {}", error))),
+            }
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
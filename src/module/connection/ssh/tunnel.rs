@@ -0,0 +1,137 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::error::LkError;
+
+//
+// Forwards traffic between a local socket and a direct-tcpip channel of a jump host session.
+// The other end of the socket is used as the transport of the next session, so sessions can be chained.
+// libssh2 sessions can't be used from multiple threads at the same time, so the jump host session
+// is used only by the forwarding thread after the channel has been opened.
+//
+
+/// Poll timeout when there's nothing to do. Only affects how fast the stop flag is noticed.
+const IDLE_TIMEOUT_MS: i32 = 200;
+/// Poll timeout when data is waiting for the SSH channel window to open.
+const BLOCKED_TIMEOUT_MS: i32 = 5;
+/// Reading is paused when this much data is waiting to be written.
+const MAX_PENDING: usize = 256 * 1024;
+
+pub struct Tunnel {
+    stop_flag: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Tunnel {
+    /// `transport_fd` is the socket used by the jump host session.
+    pub fn start(session: ssh2::Session, channel: ssh2::Channel, socket: UnixStream, transport_fd: RawFd) -> Result<Self, LkError> {
+        socket.set_nonblocking(true)?;
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread_stop_flag = stop_flag.clone();
+        let thread = thread::spawn(move || {
+            session.set_blocking(false);
+            if let Err(error) = Self::forward(channel, socket, transport_fd, &thread_stop_flag) {
+                log::debug!("SSH tunnel closed: {}", error);
+            }
+            // Session stays alive until here.
+            drop(session);
+        });
+
+        Ok(Tunnel {
+            stop_flag,
+            thread: Some(thread),
+        })
+    }
+
+    fn forward(mut channel: ssh2::Channel, mut socket: UnixStream, transport_fd: RawFd, stop_flag: &AtomicBool) -> io::Result<()> {
+        let mut buffer = vec![0u8; 32 * 1024];
+        let mut to_channel = Vec::<u8>::new();
+        let mut to_socket = Vec::<u8>::new();
+
+        while !stop_flag.load(Ordering::SeqCst) {
+            // Channel to socket.
+            while to_socket.len() < MAX_PENDING {
+                match channel.read(&mut buffer) {
+                    Ok(0) if channel.eof() => return Ok(()),
+                    Ok(0) => break,
+                    Ok(bytes_read) => to_socket.extend_from_slice(&buffer[..bytes_read]),
+                    Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(error) => return Err(error),
+                }
+            }
+            Self::flush(&mut socket, &mut to_socket)?;
+
+            // Socket to channel.
+            while to_channel.len() < MAX_PENDING {
+                match socket.read(&mut buffer) {
+                    // Session using the socket was closed.
+                    Ok(0) => {
+                        let _ = channel.close();
+                        return Ok(());
+                    },
+                    Ok(bytes_read) => to_channel.extend_from_slice(&buffer[..bytes_read]),
+                    Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(error) => return Err(error),
+                }
+            }
+            Self::flush(&mut channel, &mut to_channel)?;
+
+            let timeout = if to_channel.is_empty() && to_socket.is_empty() { IDLE_TIMEOUT_MS } else { BLOCKED_TIMEOUT_MS };
+            let socket_events = if to_socket.is_empty() { libc::POLLIN } else { libc::POLLIN | libc::POLLOUT };
+            Self::poll(&[(socket.as_raw_fd(), socket_events), (transport_fd, libc::POLLIN)], timeout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes as much as possible without blocking.
+    fn flush(target: &mut impl Write, pending: &mut Vec<u8>) -> io::Result<()> {
+        let mut written = 0;
+        while written < pending.len() {
+            match target.write(&pending[written..]) {
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+                Ok(bytes_written) => written += bytes_written,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error),
+            }
+        }
+        pending.drain(..written);
+        Ok(())
+    }
+
+    fn poll(fds: &[(RawFd, i16)], timeout_ms: i32) -> io::Result<()> {
+        let mut pollfds = fds.iter()
+            .map(|(fd, events)| libc::pollfd { fd: *fd, events: *events, revents: 0 })
+            .collect::<Vec<_>>();
+
+        let ret = unsafe { libc::poll(pollfds.as_mut_ptr(), pollfds.len() as libc::nfds_t, timeout_ms) };
+        if ret < 0 {
+            let error = io::Error::last_os_error();
+            if error.kind() != io::ErrorKind::Interrupted {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("SSH tunnel thread had paniced");
+            }
+        }
+    }
+}
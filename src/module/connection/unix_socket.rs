@@ -0,0 +1,369 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::{PrivilegeEscalation, ShellCommand};
+
+/// Return code when the socket couldn't be connected to. Same as the exit code of curl.
+pub const EXIT_CODE_CONNECT_FAILED: i32 = 7;
+
+
+/// HTTP/1.1 request to a Unix socket on the host. Sent with `ConnectionModule::send_socket_request`.
+/// The ssh connector uses a direct-streamlocal channel and falls back to curl if the socket can't be opened that way,
+/// e.g. because forwarding is disabled or the socket requires higher privileges.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnixSocketRequest {
+    pub method: String,
+    pub socket_path: String,
+    /// Path and query, e.g. /containers/json?all=true.
+    pub path: String,
+    /// JSON request body.
+    pub body: Option<String>,
+    /// Whether the curl fallback is run with higher privileges.
+    pub use_sudo: bool,
+    pub escalation: PrivilegeEscalation,
+    /// Response body multiplexes stdout and stderr in the Docker stream format (e.g. logs of containers without TTY).
+    pub demultiplex: bool,
+}
+
+impl UnixSocketRequest {
+    pub fn new(method: &str, socket_path: &str, path: &str) -> Self {
+        UnixSocketRequest {
+            method: method.to_string(),
+            socket_path: socket_path.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn get(socket_path: &str, path: &str) -> Self {
+        Self::new("GET", socket_path, path)
+    }
+
+    pub fn post(socket_path: &str, path: &str) -> Self {
+        Self::new("POST", socket_path, path)
+    }
+
+    pub fn delete(socket_path: &str, path: &str) -> Self {
+        Self::new("DELETE", socket_path, path)
+    }
+
+    pub fn body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn use_sudo(mut self) -> Self {
+        self.use_sudo = true;
+        self
+    }
+
+    pub fn escalate_with(mut self, escalation: PrivilegeEscalation) -> Self {
+        self.escalation = escalation;
+        self
+    }
+
+    pub fn demultiplex(mut self) -> Self {
+        self.demultiplex = true;
+        self
+    }
+
+    /// Connection is closed by the server after the response.
+    pub fn to_http(&self) -> Vec<u8> {
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nUser-Agent: lightkeeper\r\nConnection: close\r\n",
+            self.method, self.path
+        );
+
+        let body = self.body.clone().unwrap_or_default();
+        if self.body.is_some() {
+            request.push_str("Content-Type: application/json\r\n");
+        }
+        if self.body.is_some() || self.method != "GET" {
+            request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        }
+
+        request.push_str("\r\n");
+        request.push_str(&body);
+        request.into_bytes()
+    }
+
+    /// Fallback for when the socket can't be accessed directly. Outputs the raw HTTP response.
+    pub fn to_command(&self) -> ShellCommand {
+        let url = format!("http://localhost{}", self.path);
+        let mut command = ShellCommand::new_from(vec![
+            "curl", "-s", "-i", "--raw", "-N", "--unix-socket", &self.socket_path, "-X", &self.method,
+        ]);
+
+        if let Some(body) = &self.body {
+            command.arguments(vec!["-H", "Content-Type: application/json", "--data-binary", body]);
+        }
+
+        command.argument(url);
+        command.use_sudo = self.use_sudo;
+        command.escalate_with(self.escalation)
+    }
+}
+
+impl fmt::Display for UnixSocketRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.method, self.socket_path, self.path)
+    }
+}
+
+/// Error message from a Docker or Podman API error response, or the whole body if it's not in the expected format.
+pub fn api_error_message(response: &ResponseMessage) -> String {
+    #[derive(Deserialize)]
+    struct ApiError {
+        message: String,
+    }
+
+    match serde_json::from_str::<ApiError>(&response.message) {
+        Ok(error) => error.message,
+        Err(_) => response.message.trim().to_string(),
+    }
+}
+
+
+#[derive(Clone, Debug, Default, PartialEq)]
+enum DecoderState {
+    #[default]
+    Headers,
+    /// Remaining length if known. Otherwise the body ends when the connection is closed.
+    Body(Option<u64>),
+    ChunkSize,
+    ChunkData(u64),
+    ChunkEnd,
+    Done,
+}
+
+/// Decodes a raw HTTP/1.1 response incrementally. Handles chunked transfer encoding and the Docker stream format,
+/// so that streaming endpoints (e.g. logs and events) can be read as the data arrives.
+#[derive(Clone, Debug, Default)]
+pub struct HttpResponseDecoder {
+    demultiplex: bool,
+    state: DecoderState,
+    status: Option<u16>,
+    /// Received data that isn't decoded yet.
+    buffer: Vec<u8>,
+    /// Decided from the content type or the first frame header.
+    multiplexed: Option<bool>,
+    /// Body data of incomplete stream frames.
+    frame_buffer: Vec<u8>,
+}
+
+impl HttpResponseDecoder {
+    pub fn new(demultiplex: bool) -> Self {
+        HttpResponseDecoder {
+            demultiplex,
+            ..Default::default()
+        }
+    }
+
+    /// Decodes a complete response.
+    pub fn decode_response(data: &[u8], demultiplex: bool) -> Result<ResponseMessage, LkError> {
+        let mut decoder = Self::new(demultiplex);
+        let mut body = decoder.decode(data)?;
+        body.extend(decoder.finish());
+
+        if decoder.status.is_none() {
+            return Err(LkError::other("Invalid HTTP response"));
+        }
+
+        Ok(ResponseMessage::new(String::from_utf8_lossy(&body).to_string(), decoder.return_code()))
+    }
+
+    /// Returns the body data that could be decoded so far.
+    pub fn decode(&mut self, data: &[u8]) -> Result<Vec<u8>, LkError> {
+        self.buffer.extend_from_slice(data);
+        let mut body = Vec::new();
+
+        loop {
+            match self.state {
+                DecoderState::Headers => {
+                    let Some(end) = find(&self.buffer, b"\r\n\r\n") else {
+                        break;
+                    };
+
+                    let headers = String::from_utf8_lossy(&self.buffer[..end]).to_string();
+                    self.buffer.drain(..end + 4);
+                    self.parse_headers(&headers)?;
+                },
+                DecoderState::Body(remaining) => {
+                    if self.buffer.is_empty() {
+                        break;
+                    }
+
+                    let length = remaining.map(|remaining| remaining.min(self.buffer.len() as u64) as usize).unwrap_or(self.buffer.len());
+                    body.extend(self.buffer.drain(..length));
+
+                    if let Some(remaining) = remaining {
+                        self.state = match remaining - length as u64 {
+                            0 => DecoderState::Done,
+                            remaining => DecoderState::Body(Some(remaining)),
+                        };
+                    }
+                },
+                DecoderState::ChunkSize => {
+                    let Some(end) = find(&self.buffer, b"\r\n") else {
+                        break;
+                    };
+
+                    let line = String::from_utf8_lossy(&self.buffer[..end]).to_string();
+                    self.buffer.drain(..end + 2);
+
+                    // Chunk extensions are ignored.
+                    let size_hex = line.split(';').next().unwrap_or_default().trim();
+                    let size = u64::from_str_radix(size_hex, 16)
+                        .map_err(|_| LkError::other_p("Invalid chunk size in HTTP response", &line))?;
+
+                    // Trailers after the last chunk are ignored.
+                    self.state = if size == 0 { DecoderState::Done } else { DecoderState::ChunkData(size) };
+                },
+                DecoderState::ChunkData(remaining) => {
+                    if self.buffer.is_empty() {
+                        break;
+                    }
+
+                    let length = remaining.min(self.buffer.len() as u64) as usize;
+                    body.extend(self.buffer.drain(..length));
+
+                    self.state = match remaining - length as u64 {
+                        0 => DecoderState::ChunkEnd,
+                        remaining => DecoderState::ChunkData(remaining),
+                    };
+                },
+                DecoderState::ChunkEnd => {
+                    if self.buffer.len() < 2 {
+                        break;
+                    }
+                    self.buffer.drain(..2);
+                    self.state = DecoderState::ChunkSize;
+                },
+                DecoderState::Done => {
+                    self.buffer.clear();
+                    break;
+                },
+            }
+        }
+
+        Ok(self.demultiplex_body(body))
+    }
+
+    /// Returns data held back when the stream ended in the middle of a frame header.
+    pub fn finish(&mut self) -> Vec<u8> {
+        if self.multiplexed == Some(true) {
+            Vec::new()
+        }
+        else {
+            std::mem::take(&mut self.frame_buffer)
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.state == DecoderState::Done
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// 0 for successful responses, otherwise the HTTP status code. -1 if status wasn't received.
+    pub fn return_code(&self) -> i32 {
+        match self.status {
+            Some(200..=299) => 0,
+            Some(status) => status as i32,
+            None => -1,
+        }
+    }
+
+    fn parse_headers(&mut self, headers: &str) -> Result<(), LkError> {
+        let mut lines = headers.split("\r\n");
+        let status_line = lines.next().unwrap_or_default();
+        let status = status_line.split_whitespace().nth(1)
+            .and_then(|status| status.parse::<u16>().ok())
+            .ok_or_else(|| LkError::other_p("Invalid HTTP status line", status_line))?;
+
+        // Informational responses are followed by the actual response.
+        if (100..200).contains(&status) {
+            return Ok(());
+        }
+
+        self.status = Some(status);
+        self.state = DecoderState::Body(None);
+
+        for line in lines {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match name.trim().to_lowercase().as_str() {
+                "transfer-encoding" if value.to_lowercase().contains("chunked") => {
+                    self.state = DecoderState::ChunkSize;
+                },
+                "content-length" if self.state != DecoderState::ChunkSize => {
+                    let length = value.parse::<u64>().map_err(|_| LkError::other_p("Invalid content length", value))?;
+                    self.state = DecoderState::Body(Some(length));
+                },
+                "content-type" if value.starts_with("application/vnd.docker.multiplexed-stream") => {
+                    self.multiplexed = Some(true);
+                },
+                _ => {},
+            }
+        }
+
+        if self.state == DecoderState::Body(Some(0)) || status == 204 || status == 304 {
+            self.state = DecoderState::Done;
+        }
+
+        Ok(())
+    }
+
+    /// Stream frames have an 8-byte header: stream type (0-2), 3 zero bytes and the payload length.
+    fn demultiplex_body(&mut self, body: Vec<u8>) -> Vec<u8> {
+        if !self.demultiplex || self.multiplexed == Some(false) {
+            return body;
+        }
+
+        self.frame_buffer.extend(body);
+
+        if self.multiplexed.is_none() {
+            // Older API versions don't tell in the content type whether the stream is multiplexed.
+            if self.frame_buffer.len() < 8 {
+                return Vec::new();
+            }
+
+            let is_frame_header = self.frame_buffer[0] <= 2 && self.frame_buffer[1..4] == [0, 0, 0];
+            self.multiplexed = Some(is_frame_header);
+
+            if !is_frame_header {
+                return std::mem::take(&mut self.frame_buffer);
+            }
+        }
+
+        let mut output = Vec::new();
+        while self.frame_buffer.len() >= 8 {
+            let length = u32::from_be_bytes(self.frame_buffer[4..8].try_into().unwrap()) as usize;
+            if self.frame_buffer.len() < 8 + length {
+                break;
+            }
+
+            output.extend_from_slice(&self.frame_buffer[8..8 + length]);
+            self.frame_buffer.drain(..8 + length);
+        }
+
+        output
+    }
+}
+
+fn find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+    data.windows(pattern.len()).position(|window| window == pattern)
+}
@@ -22,4 +22,6 @@ pub struct Metadata {
     pub is_stateless: bool,
     /// Module uses sudo if available. True for modules that require sudo or can work with/without it.
     pub uses_sudo: bool,
+    /// Request timeout in seconds used when the `request_timeout` setting is not set. None means the global default.
+    pub default_timeout: Option<u64>,
 }
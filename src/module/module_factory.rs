@@ -335,10 +335,19 @@ impl ModuleFactory {
             (monitoring::linux::Uptime::get_metadata(), monitoring::linux::Uptime::new_monitoring_module),
             (monitoring::linux::Load::get_metadata(), monitoring::linux::Load::new_monitoring_module),
             (monitoring::linux::Ram::get_metadata(), monitoring::linux::Ram::new_monitoring_module),
+            (monitoring::linux::Cpu::get_metadata(), monitoring::linux::Cpu::new_monitoring_module),
+            (monitoring::linux::Sensors::get_metadata(), monitoring::linux::Sensors::new_monitoring_module),
+            (monitoring::linux::Fans::get_metadata(), monitoring::linux::Fans::new_monitoring_module),
+            (monitoring::linux::Voltages::get_metadata(), monitoring::linux::Voltages::new_monitoring_module),
+            (monitoring::linux::RestartRequired::get_metadata(), monitoring::linux::RestartRequired::new_monitoring_module),
             (monitoring::linux::Who::get_metadata(), monitoring::linux::Who::new_monitoring_module),
             (monitoring::nixos::RebuildGenerations::get_metadata(), monitoring::nixos::RebuildGenerations::new_monitoring_module),
             (monitoring::storage::Filesystem::get_metadata(), monitoring::storage::Filesystem::new_monitoring_module),
             (monitoring::storage::Cryptsetup::get_metadata(),  monitoring::storage::Cryptsetup::new_monitoring_module),
+            (monitoring::storage::Smart::get_metadata(), monitoring::storage::Smart::new_monitoring_module),
+            (monitoring::storage::Mdadm::get_metadata(), monitoring::storage::Mdadm::new_monitoring_module),
+            (monitoring::storage::Zfs::get_metadata(), monitoring::storage::Zfs::new_monitoring_module),
+            (monitoring::storage::Btrfs::get_metadata(), monitoring::storage::Btrfs::new_monitoring_module),
             (monitoring::storage::lvm::LogicalVolume::get_metadata(), monitoring::storage::lvm::LogicalVolume::new_monitoring_module),
             (monitoring::storage::lvm::VolumeGroup::get_metadata(), monitoring::storage::lvm::VolumeGroup::new_monitoring_module),
             (monitoring::storage::lvm::PhysicalVolume::get_metadata(), monitoring::storage::lvm::PhysicalVolume::new_monitoring_module),
@@ -391,8 +400,20 @@ impl ModuleFactory {
             (command::storage::lvm::LVResize::get_metadata(), command::storage::lvm::LVResize::new_command_module),
             (command::storage::lvm::LVRemove::get_metadata(), command::storage::lvm::LVRemove::new_command_module),
             (command::storage::lvm::LVRefresh::get_metadata(), command::storage::lvm::LVRefresh::new_command_module),
+            (command::storage::smart::SelfTest::get_metadata(), command::storage::smart::SelfTest::new_command_module),
+            (command::storage::smart::SelfTestLog::get_metadata(), command::storage::smart::SelfTestLog::new_command_module),
+            (command::storage::zfs::Scrub::get_metadata(), command::storage::zfs::Scrub::new_command_module),
+            (command::storage::zfs::ScrubStatus::get_metadata(), command::storage::zfs::ScrubStatus::new_command_module),
+            (command::storage::zfs::Snapshot::get_metadata(), command::storage::zfs::Snapshot::new_command_module),
+            (command::storage::zfs::SnapshotList::get_metadata(), command::storage::zfs::SnapshotList::new_command_module),
+            (command::storage::btrfs::Scrub::get_metadata(), command::storage::btrfs::Scrub::new_command_module),
+            (command::storage::btrfs::ScrubStatus::get_metadata(), command::storage::btrfs::ScrubStatus::new_command_module),
+            (command::storage::mdadm::Check::get_metadata(), command::storage::mdadm::Check::new_command_module),
+            (command::storage::mdadm::Detail::get_metadata(), command::storage::mdadm::Detail::new_command_module),
             (command::docker::Restart::get_metadata(), command::docker::Restart::new_command_module),
             (command::docker::Inspect::get_metadata(), command::docker::Inspect::new_command_module),
+            (command::docker::Logs::get_metadata(), command::docker::Logs::new_command_module),
+            (command::docker::Events::get_metadata(), command::docker::Events::new_command_module),
             (command::docker::Shell::get_metadata(), command::docker::Shell::new_command_module),
             (command::docker::image::Remove::get_metadata(), command::docker::image::Remove::new_command_module),
             (command::docker::image::Prune::get_metadata(), command::docker::image::Prune::new_command_module),
@@ -408,6 +429,8 @@ impl ModuleFactory {
             (command::docker::compose::Build::get_metadata(), command::docker::compose::build::Build::new_command_module),
             (command::podman::Restart::get_metadata(), command::podman::Restart::new_command_module),
             (command::podman::Inspect::get_metadata(), command::podman::Inspect::new_command_module),
+            (command::podman::Logs::get_metadata(), command::podman::Logs::new_command_module),
+            (command::podman::Events::get_metadata(), command::podman::Events::new_command_module),
             (command::podman::Shell::get_metadata(), command::podman::Shell::new_command_module),
             (command::podman::image::Remove::get_metadata(), command::podman::image::Remove::new_command_module),
             (command::podman::image::Prune::get_metadata(), command::podman::image::Prune::new_command_module),
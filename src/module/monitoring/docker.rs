@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+/// Docker Engine API is used through this socket.
+pub const DOCKER_SOCKET_PATH: &str = "/var/run/docker.sock";
 
 pub mod containers;
 pub use containers::Containers;
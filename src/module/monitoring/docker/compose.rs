@@ -10,13 +10,14 @@ use std::{
 
 use crate::enums::Criticality;
 use crate::error::LkError;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::api_error_message;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::{ Host, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::monitoring::docker::containers::ContainerDetails;
 use crate::module::*;
 use crate::module::monitoring::*;
-use crate::utils::ShellCommand;
 
 #[monitoring_module(
     name="docker-compose",
@@ -61,15 +62,11 @@ impl MonitoringModule for Compose {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
+    fn get_socket_request(&self, host: Host, _result: DataPoint) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
             // Docker API is much better suited for this than using the docker-compose CLI. More effective too.
             // TODO: find down-status compose-projects with find-command?
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/containers/json?all=true"]);
-            Ok(command.to_string())
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, "/containers/json?all=true").use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -82,6 +79,9 @@ impl MonitoringModule for Compose {
             let result = DataPoint::value_with_level(String::from("Couldn't connect to Docker daemon."), Criticality::Critical);
             return Ok(result);
         }
+        else if response.return_code != 0 {
+            return Err(api_error_message(&response));
+        }
 
         let mut containers: Vec<ContainerDetails> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
         containers.retain(|container| container.labels.contains_key("com.docker.compose.config-hash"));
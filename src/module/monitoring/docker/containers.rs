@@ -10,12 +10,13 @@ use serde::Deserialize;
 use serde_json;
 
 use crate::error::LkError;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::api_error_message;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::{ Host, enums::Criticality, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
-use crate::utils::ShellCommand;
 
 #[monitoring_module(
     name="docker-containers",
@@ -54,14 +55,9 @@ impl MonitoringModule for Containers {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
+    fn get_socket_request(&self, host: Host, _result: DataPoint) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            // TODO: somehow connect directly to the unix socket instead of using curl?
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/containers/json?all=true"]);
-            Ok(command.to_string())
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, "/containers/json?all=true").use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -74,6 +70,9 @@ impl MonitoringModule for Containers {
             let result = DataPoint::value_with_level(String::from("Couldn't connect to Docker daemon."), Criticality::Critical);
             return Ok(result);
         }
+        else if response.return_code != 0 {
+            return Err(api_error_message(&response));
+        }
 
         let mut containers: Vec<ContainerDetails> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
 
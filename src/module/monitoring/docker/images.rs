@@ -11,13 +11,14 @@ use serde_json;
 use chrono::Utc;
 
 use crate::error::LkError;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::api_error_message;
+use crate::module::monitoring::docker::DOCKER_SOCKET_PATH;
 use crate::enums::Criticality;
 use crate::{ Host, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
-use crate::utils::ShellCommand;
 
 
 #[monitoring_module(
@@ -65,13 +66,9 @@ impl MonitoringModule for Images {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = true;
-
+    fn get_socket_request(&self, host: Host, _result: DataPoint) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/images/json"]);
-            Ok(command.to_string())
+            Ok(UnixSocketRequest::get(DOCKER_SOCKET_PATH, "/images/json").use_sudo())
         }
         else {
             Err(LkError::unsupported_platform())
@@ -84,6 +81,9 @@ impl MonitoringModule for Images {
             let result = DataPoint::value_with_level(String::from("Couldn't connect to Docker daemon."), Criticality::Critical);
             return Ok(result);
         }
+        else if response.return_code != 0 {
+            return Err(api_error_message(&response));
+        }
 
         let images: Vec<ImageDetails> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
 
@@ -6,8 +6,12 @@
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
+
 use crate::error::LkError;
+use crate::enums::Criticality;
 use crate::module::connection::ResponseMessage;
+use crate::module::connection::ssh::CertificateStatus;
 use crate::Host;
 use crate::utils::{VersionNumber, string_manipulation};
 use lightkeeper_module::monitoring_module;
@@ -68,8 +72,42 @@ impl MonitoringModule for PlatformInfoSsh {
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("architecture"), platform.architecture.to_string()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("os_variant_id"), platform.os_variant_id.clone()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("ip_address"), host.ip_address.to_string()));
+
         Ok(datapoint)
     }
+
+    fn process_connection_details(&self, details: &HashMap<String, String>, mut data_point: DataPoint) -> DataPoint {
+        if let Some(status) = CertificateStatus::from_details(details) {
+            data_point.multivalue.push(certificate_data_point(&status, Utc::now()));
+        }
+        data_point
+    }
+}
+
+/// Criticality is Warning when close to expiring and Critical when expired.
+pub fn certificate_data_point(status: &CertificateStatus, now: DateTime<Utc>) -> DataPoint {
+    let Some(valid_before) = status.valid_before else {
+        return DataPoint::labeled_value(String::from("ssh_certificate"), String::from("Never expires"));
+    };
+
+    let remaining = valid_before - now;
+    let criticality = if remaining <= chrono::Duration::zero() {
+        Criticality::Critical
+    }
+    else if remaining < status.warning_threshold {
+        Criticality::Warning
+    }
+    else {
+        Criticality::Normal
+    };
+
+    let mut datapoint = DataPoint::labeled_value_with_level(
+        String::from("ssh_certificate"),
+        format!("Expires {}", valid_before.format("%Y-%m-%d %H:%M:%S UTC")),
+        criticality,
+    );
+    datapoint.description = format!("{} ({})", status.key_id, status.source);
+    datapoint
 }
 
 fn parse_os_release(message: &String) -> (platform_info::Flavor, VersionNumber, String) {
@@ -22,4 +22,20 @@ pub mod load;
 pub use load::Load;
 
 pub mod ram;
-pub use ram::Ram;
\ No newline at end of file
+pub use ram::Ram;
+
+pub mod cpu;
+pub use cpu::Cpu;
+
+
+pub mod sensors;
+pub use sensors::Sensors;
+
+pub mod fans;
+pub use fans::Fans;
+
+pub mod voltages;
+pub use voltages::Voltages;
+
+pub mod restart_required;
+pub use restart_required::RestartRequired;
\ No newline at end of file
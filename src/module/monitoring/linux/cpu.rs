@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::module::platform_info;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+#[monitoring_module(
+    name="cpu",
+    version="0.0.1",
+    description="Provides CPU utilization with user, system, iowait and steal breakdown (using /proc/stat).",
+    settings={
+        sample_interval => "Time between the two samples of /proc/stat in seconds. Default: 1",
+        show_cores => "Show per-core utilization. Default: true",
+        warning_threshold => "Warning threshold for total utilization in percent. Default: 80",
+        error_threshold => "Error threshold for total utilization in percent. Default: 90",
+        critical_threshold => "Critical threshold for total utilization in percent. Default: 95",
+    }
+)]
+pub struct Cpu {
+    sample_interval: f32,
+    show_cores: bool,
+    threshold_critical: f32,
+    threshold_error: f32,
+    threshold_warning: f32,
+}
+
+impl Module for Cpu {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Cpu {
+            sample_interval: settings.get("sample_interval").and_then(|value| value.parse().ok()).unwrap_or(1.0),
+            show_cores: settings.get("show_cores").and_then(|value| value.parse().ok()).unwrap_or(true),
+            threshold_critical: settings.get("critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(95.0),
+            threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+        }
+    }
+}
+
+impl MonitoringModule for Cpu {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::ProgressBar,
+            display_text: String::from("CPU usage"),
+            category: String::from("host"),
+            unit: String::from("%"),
+            use_multivalue: true,
+            use_with_charts: true,
+            charts_warning_level_setting: String::from("warning_threshold"),
+            charts_critical_level_setting: String::from("critical_threshold"),
+            charts_warning_value_default: Some(80.0),
+            charts_critical_value_default: Some(95.0),
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            // Counters are cumulative since boot, so utilization is calculated from the difference of two samples.
+            Ok(format!("grep '^cpu' /proc/stat; sleep {}; grep '^cpu' /proc/stat", self.sample_interval))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        if response.is_error() {
+            return Err(response.message);
+        }
+
+        let samples = response.message.lines()
+            .filter_map(CpuTimes::parse)
+            .collect::<Vec<_>>();
+
+        // Both samples start with the aggregate line.
+        let second_start = samples.iter().skip(1).position(|(name, _)| name == "cpu").map(|position| position + 1)
+            .ok_or_else(|| String::from("Invalid response: expected two samples"))?;
+        let (first, second) = samples.split_at(second_start);
+
+        let mut result = DataPoint::empty();
+
+        for (name, current) in second.iter() {
+            if name != "cpu" && !self.show_cores {
+                continue;
+            }
+
+            let Some((_, previous)) = first.iter().find(|(previous_name, _)| previous_name == name) else {
+                continue;
+            };
+
+            let usage = current.usage_since(previous);
+            let label = match name.strip_prefix("cpu") {
+                Some("") => String::from("Total"),
+                Some(core) => format!("Core {}", core),
+                None => name.clone(),
+            };
+
+            let mut data_point = DataPoint::labeled_value(label, format!("{:.0} %", usage.total));
+            data_point.value_float = usage.total;
+            data_point.description = format!(
+                "user {:.1} % | system {:.1} % | iowait {:.1} % | steal {:.1} %",
+                usage.user, usage.system, usage.iowait, usage.steal
+            );
+
+            // Single cores being busy is normal, so thresholds only apply to the total.
+            if name == "cpu" {
+                data_point.criticality = if usage.total >= self.threshold_critical {
+                    Criticality::Critical
+                }
+                else if usage.total >= self.threshold_error {
+                    Criticality::Error
+                }
+                else if usage.total >= self.threshold_warning {
+                    Criticality::Warning
+                }
+                else {
+                    Criticality::Normal
+                };
+            }
+
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+/// Cumulative time counters of a line in /proc/stat, in clock ticks.
+#[derive(Default, Clone, Copy)]
+struct CpuTimes {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+}
+
+/// Percentages of the elapsed time.
+struct CpuUsage {
+    total: f32,
+    user: f32,
+    system: f32,
+    iowait: f32,
+    steal: f32,
+}
+
+impl CpuTimes {
+    /// Parses lines like `cpu0 4705 356 584 3699 23 23 0 0 0 0`. Older kernels have fewer columns.
+    fn parse(line: &str) -> Option<(String, CpuTimes)> {
+        let mut parts = line.split_whitespace();
+        let name = parts.next().filter(|name| name.starts_with("cpu"))?;
+        let values = parts.map(|value| value.parse::<u64>().ok()).collect::<Option<Vec<_>>>()?;
+
+        if values.len() < 4 {
+            return None;
+        }
+
+        let value = |index: usize| values.get(index).copied().unwrap_or(0);
+        let times = CpuTimes {
+            user: value(0),
+            nice: value(1),
+            system: value(2),
+            idle: value(3),
+            iowait: value(4),
+            irq: value(5),
+            softirq: value(6),
+            steal: value(7),
+        };
+
+        Some((name.to_string(), times))
+    }
+
+    /// Guest time is already included in user time, so it's not counted separately.
+    fn sum(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    fn usage_since(&self, previous: &CpuTimes) -> CpuUsage {
+        let elapsed = self.sum().saturating_sub(previous.sum());
+        let percent = |current: u64, previous: u64| {
+            if elapsed == 0 {
+                0.0
+            }
+            else {
+                current.saturating_sub(previous) as f32 / elapsed as f32 * 100.0
+            }
+        };
+
+        let idle = percent(self.idle + self.iowait, previous.idle + previous.iowait);
+
+        CpuUsage {
+            total: if elapsed == 0 { 0.0 } else { 100.0 - idle },
+            user: percent(self.user + self.nice, previous.user + previous.nice),
+            system: percent(self.system + self.irq + self.softirq, previous.system + previous.irq + previous.softirq),
+            iowait: percent(self.iowait, previous.iowait),
+            steal: percent(self.steal, previous.steal),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use super::sensors::{self, SensorKind, SensorReading};
+
+#[monitoring_module(
+    name="linux-fans",
+    version="0.0.1",
+    description="Provides fan speeds from hardware sensors (using /sys/class/hwmon or lm-sensors).",
+    settings={
+        use_hardware_thresholds => "Warn if speed is below the min value provided by hardware. Default: true",
+    }
+)]
+pub struct Fans {
+    use_hardware_thresholds: bool,
+}
+
+impl Module for Fans {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Fans {
+            use_hardware_thresholds: settings.get("use_hardware_thresholds").and_then(|value| value.parse().ok()).unwrap_or(true),
+        }
+    }
+}
+
+impl MonitoringModule for Fans {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Fans"),
+            category: String::from("host"),
+            use_multivalue: true,
+            use_with_charts: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        sensors::connector_message(host, SensorKind::Fan)
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        sensors::process_readings(response, SensorKind::Fan, |reading| self.reading_data_point(reading))
+    }
+}
+
+impl Fans {
+    fn reading_data_point(&self, reading: &SensorReading) -> DataPoint {
+        let criticality = match sensors::hardware_threshold(reading.min, self.use_hardware_thresholds) {
+            Some(min) if reading.value < min => Criticality::Warning,
+            _ => Criticality::Normal,
+        };
+
+        let mut data_point = reading.data_point(format!("{:.0} RPM", reading.value), criticality);
+        data_point.description = reading.min.map(|min| format!("min {:.0} RPM", min)).unwrap_or_default();
+        data_point
+    }
+}
@@ -4,9 +4,11 @@
  */
 
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use lightkeeper_module::monitoring_module;
+use crate::enums::Criticality;
 use crate::error::LkError;
 use crate::module::connection::ResponseMessage;
 use crate::{
@@ -19,7 +21,7 @@ use crate::module::monitoring::*;
 #[monitoring_module(
     name="kernel",
     version="0.0.1",
-    description="Provides kernel version and architecture information.",
+    description="Provides kernel version and architecture information. Warns if a newer kernel has been installed.",
 )]
 pub struct Kernel;
 
@@ -44,20 +46,94 @@ impl MonitoringModule for Kernel {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+    fn get_connector_messages(&self, host: Host, _result: DataPoint) -> Result<Vec<String>, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            Ok(String::from("uname -r -m"))
+            // Every installed kernel has its own module directory.
+            Ok(vec![String::from("uname -r -m"), String::from("ls -1 /lib/modules")])
         }
         else {
             Err(LkError::unsupported_platform())
         }
     }
 
-    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
-        if response.is_error() {
-            return Err(response.message);
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _result: DataPoint) -> Result<DataPoint, String> {
+        let uname_response = responses.first().ok_or(String::from("No response"))?;
+        if uname_response.is_error() {
+            return Err(uname_response.message.clone());
         }
 
-        Ok(DataPoint::new(response.message.replace(" ", " (") + ")"))
+        let mut result = DataPoint::new(uname_response.message.replace(" ", " (") + ")");
+
+        // Not available on e.g. NixOS, so the check is skipped.
+        if let Some(modules_response) = responses.get(1).filter(|response| response.is_success()) {
+            let running = uname_response.message.split_whitespace().next().unwrap_or_default();
+            let installed = modules_response.message.lines()
+                .map(str::trim)
+                .filter(|version| version.starts_with(|character: char| character.is_ascii_digit()))
+                .collect::<Vec<_>>();
+
+            // Only kernels of the same flavor are compared, e.g. "-amd64" but not "-rt-amd64".
+            let newest_installed = installed.iter()
+                .filter(|version| flavor(version) == flavor(running))
+                .max_by(|first, second| compare_versions(first, second));
+
+            if let Some(newest_installed) = newest_installed.filter(|version| compare_versions(version, running) == Ordering::Greater) {
+                result.description = format!("Newer kernel installed: {}. Reboot required.", newest_installed);
+                result.criticality = Criticality::Warning;
+            }
+            // Some distributions remove the modules of the running kernel on upgrade.
+            else if !installed.is_empty() && !installed.contains(&running) {
+                result.description = String::from("Running kernel is no longer installed. Reboot required.");
+                result.criticality = Criticality::Warning;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Compares versions like "6.1.0-41-amd64" so that numeric parts are compared as numbers.
+fn compare_versions(first: &str, second: &str) -> Ordering {
+    let first_parts = version_parts(first);
+    let second_parts = version_parts(second);
+
+    for (first_part, second_part) in first_parts.iter().zip(second_parts.iter()) {
+        let ordering = match (first_part.parse::<u64>(), second_part.parse::<u64>()) {
+            (Ok(first_number), Ok(second_number)) => first_number.cmp(&second_number),
+            _ => first_part.cmp(second_part),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
     }
+
+    first_parts.len().cmp(&second_parts.len())
+}
+
+/// Version without the numbers, e.g. "6.1.0-41-amd64" -> "..--amd".
+fn flavor(version: &str) -> String {
+    version.chars().filter(|character| !character.is_ascii_digit()).collect()
+}
+
+/// Splits version into numeric and non-numeric parts, e.g. "6.1.0-41-amd64" -> ["6", "1", "0", "41", "amd", "64"].
+fn version_parts(version: &str) -> Vec<String> {
+    let mut parts = Vec::<String>::new();
+    let mut previous_is_digit = None;
+
+    for character in version.chars() {
+        if !character.is_ascii_alphanumeric() {
+            previous_is_digit = None;
+            continue;
+        }
+
+        let is_digit = character.is_ascii_digit();
+        match parts.last_mut() {
+            Some(part) if previous_is_digit == Some(is_digit) => part.push(character),
+            _ => parts.push(character.to_string()),
+        }
+        previous_is_digit = Some(is_digit);
+    }
+
+    parts
 }
\ No newline at end of file
@@ -20,6 +20,7 @@ use crate::module::monitoring::*;
     name="load",
     version="0.0.1",
     description="Provides information about average load (using uptime-command).",
+    default_timeout=30,
     settings={
         value_max => "Maximum value for the load average. Affects charts. Default: 20",
     }
@@ -21,6 +21,7 @@ use crate::module::monitoring::*;
     name="ram",
     version="0.0.1",
     description="Provides RAM usage information.",
+    default_timeout=30,
     settings={
         warning_threshold => "Warning threshold in percent. Default: 70",
         error_threshold => "Error threshold in percent. Default: 80",
@@ -0,0 +1,203 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::sh_single_quoted;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+
+const SECTION_PREFIX: &str = "=== ";
+
+#[monitoring_module(
+    name="linux-restart-required",
+    version="0.0.1",
+    description="Detects pending reboots and services that are still running with deleted (upgraded) libraries.",
+    uses_sudo=true,
+    settings={
+    }
+)]
+pub struct RestartRequired;
+
+impl Module for RestartRequired {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        RestartRequired { }
+    }
+}
+
+impl MonitoringModule for RestartRequired {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Restart required"),
+            category: String::from("host"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let script = format!(concat!(
+                // Debian and Ubuntu.
+                "if [ -f /var/run/reboot-required ]; then ",
+                    "echo \"{0}reboot-required\"; cat /var/run/reboot-required.pkgs 2>/dev/null; ",
+                "fi; ",
+                // RHEL and Fedora. Exit code 1 means that reboot is required.
+                "if command -v needs-restarting >/dev/null; then ",
+                    "echo \"{0}needs-restarting\"; needs-restarting -r >/dev/null 2>&1; echo $?; needs-restarting -s 2>/dev/null; ",
+                "fi; ",
+                "if [ -e /run/booted-system ]; then ",
+                    "echo \"{0}nixos\"; ",
+                    "for item in kernel initrd kernel-modules; do ",
+                        "echo \"$item $(readlink -f /run/booted-system/$item) $(readlink -f /run/current-system/$item)\"; ",
+                    "done; ",
+                "fi; ",
+                "echo \"{0}processes\"; ",
+                "for maps in /proc/[0-9]*/maps; do ",
+                    "pid=${{maps#/proc/}}; pid=${{pid%/maps}}; ",
+                    "grep -qs \"\\.so.* (deleted)$\" \"$maps\" || continue; ",
+                    "cgroup=$(grep -s -m1 -E \"^(0::|[0-9]+:name=systemd:)\" /proc/$pid/cgroup | cut -d: -f3); ",
+                    "echo \"$pid ${{cgroup:--}} $(cat /proc/$pid/comm 2>/dev/null)\"; ",
+                "done"
+            ), SECTION_PREFIX);
+            Ok(format!("sudo sh -c {}", sh_single_quoted(&script)))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        let mut reboot_reasons = Vec::<String>::new();
+        // From /var/run/reboot-required.pkgs.
+        let mut reboot_packages = Option::<Vec<String>>::None;
+        // Service name -> processes.
+        let mut services = BTreeMap::<String, Vec<String>>::new();
+        let mut other_processes = Vec::<String>::new();
+        let mut section = "";
+        let mut seen_processes_section = false;
+
+        for line in response.message.lines() {
+            let line = line.trim();
+
+            if let Some(name) = line.strip_prefix(SECTION_PREFIX) {
+                section = name;
+                match section {
+                    "reboot-required" => reboot_packages = Some(Vec::new()),
+                    "processes" => seen_processes_section = true,
+                    _ => {},
+                }
+                continue;
+            }
+            else if line.is_empty() {
+                continue;
+            }
+
+            match section {
+                // E.g. "linux-image-6.1.0-41-amd64"
+                "reboot-required" => reboot_packages.get_or_insert_with(Vec::new).push(line.to_string()),
+                "needs-restarting" => {
+                    if line == "1" {
+                        reboot_reasons.push(String::from("needs-restarting reports updated core packages"));
+                    }
+                    else if line.ends_with(".service") {
+                        services.entry(line.to_string()).or_default();
+                    }
+                },
+                // E.g. "kernel /nix/store/...-linux-6.6.58/bzImage /nix/store/...-linux-6.6.60/bzImage"
+                "nixos" => {
+                    let mut parts = line.split_whitespace();
+                    let item = parts.next().unwrap_or_default();
+                    if parts.next() != parts.next() {
+                        reboot_reasons.push(format!("NixOS {} changed", item));
+                    }
+                },
+                // E.g. "1234 /system.slice/nginx.service nginx"
+                "processes" => {
+                    let mut parts = line.splitn(3, ' ');
+                    let (Some(pid), Some(cgroup)) = (parts.next(), parts.next()) else {
+                        return Err(format!("Invalid response: {}", line));
+                    };
+                    let process = format!("{} ({})", parts.next().unwrap_or_default(), pid);
+
+                    match system_service(cgroup) {
+                        Some(service) => services.entry(service).or_default().push(process),
+                        None => other_processes.push(process),
+                    }
+                },
+                _ => return Err(format!("Invalid response: {}", line)),
+            }
+        }
+
+        match reboot_packages {
+            Some(packages) if !packages.is_empty() => reboot_reasons.insert(0, format!("Updated packages: {}", packages.join(", "))),
+            Some(_) => reboot_reasons.insert(0, String::from("/var/run/reboot-required exists")),
+            None => {},
+        }
+
+        // Section is always printed, so if it's missing, the script failed (e.g. sudo wasn't allowed).
+        if !seen_processes_section {
+            return Err(response.message);
+        }
+
+        let mut reboot = if reboot_reasons.is_empty() {
+            DataPoint::labeled_value_with_level(String::from("Reboot"), String::from("Not required"), Criticality::Normal)
+        }
+        else {
+            let mut data_point = DataPoint::labeled_value_with_level(String::from("Reboot"), String::from("Required"), Criticality::Warning);
+            data_point.description = reboot_reasons.join(" | ");
+            data_point
+        };
+        // Tags prevent service commands from showing on rows that aren't services.
+        reboot.tags = vec![String::from("Reboot")];
+        result.multivalue.push(reboot);
+
+        for (service, processes) in services {
+            let mut data_point = DataPoint::labeled_value_with_level(service.clone(), String::from("Restart required"), Criticality::Warning);
+            data_point.description = processes.join(", ");
+            data_point.command_params = vec![service];
+            result.multivalue.push(data_point);
+        }
+
+        if !other_processes.is_empty() {
+            let mut data_point = DataPoint::labeled_value_with_level(
+                String::from("Other processes"), format!("{} using deleted libraries", other_processes.len()), Criticality::Info
+            );
+            data_point.description = other_processes.join(", ");
+            data_point.tags = vec![String::from("Process")];
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+/// Returns the system service unit, e.g. "nginx.service" from "/system.slice/nginx.service".
+/// User services and session processes can't be restarted as system services, so they are ignored.
+fn system_service(cgroup: &str) -> Option<String> {
+    if !cgroup.starts_with("/system.slice/") {
+        return None;
+    }
+
+    cgroup.split('/').rfind(|part| part.ends_with(".service")).map(str::to_string)
+}
@@ -0,0 +1,345 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::{BTreeMap, HashMap};
+use serde_json;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::module::platform_info;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+const HWMON_PATH: &str = "/sys/class/hwmon/";
+
+#[monitoring_module(
+    name="linux-sensors",
+    version="0.0.1",
+    description="Provides temperatures from hardware sensors (using /sys/class/hwmon or lm-sensors).",
+    settings={
+        temperature_warning => "Warning threshold for temperatures in Celsius if hardware doesn't provide one. Default: 75",
+        temperature_critical => "Critical threshold for temperatures in Celsius if hardware doesn't provide one. Default: 90",
+        use_hardware_thresholds => "Use max and crit values provided by hardware when available. Default: true",
+    }
+)]
+pub struct Sensors {
+    temperature_warning: f32,
+    temperature_critical: f32,
+    use_hardware_thresholds: bool,
+}
+
+impl Module for Sensors {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Sensors {
+            temperature_warning: settings.get("temperature_warning").and_then(|value| value.parse().ok()).unwrap_or(75.0),
+            temperature_critical: settings.get("temperature_critical").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+            use_hardware_thresholds: settings.get("use_hardware_thresholds").and_then(|value| value.parse().ok()).unwrap_or(true),
+        }
+    }
+}
+
+impl MonitoringModule for Sensors {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Temperatures"),
+            category: String::from("host"),
+            use_multivalue: true,
+            use_with_charts: true,
+            charts_warning_level_setting: String::from("temperature_warning"),
+            charts_critical_level_setting: String::from("temperature_critical"),
+            charts_warning_value_default: Some(75.0),
+            charts_critical_value_default: Some(90.0),
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        connector_message(host, SensorKind::Temperature)
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        process_readings(response, SensorKind::Temperature, |reading| self.reading_data_point(reading))
+    }
+}
+
+impl Sensors {
+    fn reading_data_point(&self, reading: &SensorReading) -> DataPoint {
+        let warning = hardware_threshold(reading.max, self.use_hardware_thresholds).unwrap_or(self.temperature_warning);
+        let critical = hardware_threshold(reading.crit, self.use_hardware_thresholds).unwrap_or(self.temperature_critical);
+
+        let criticality = if reading.value >= critical {
+            Criticality::Critical
+        }
+        else if reading.value >= warning {
+            Criticality::Warning
+        }
+        else {
+            Criticality::Normal
+        };
+
+        let mut data_point = reading.data_point(format!("{:.1} °C", reading.value), criticality);
+        data_point.description = format!("high {:.1} °C | crit {:.1} °C", warning, critical);
+        data_point
+    }
+}
+
+/// Prints lines like "/sys/class/hwmon/hwmon0/temp1_input:45000". Some attributes can't be read so errors are ignored.
+pub(crate) fn connector_message(host: Host, kind: SensorKind) -> Result<String, LkError> {
+    if host.platform.os == platform_info::OperatingSystem::Linux {
+        Ok(format!(concat!(
+            "if ls {path}hwmon*/*_input >/dev/null 2>&1; then ",
+                "grep -H . {path}hwmon*/name {path}hwmon*/{prefix}*_* 2>/dev/null; ",
+            "elif command -v sensors >/dev/null; then sensors -j; ",
+            "else exit 127; fi"
+        ), path = HWMON_PATH, prefix = kind.attribute_prefix()))
+    }
+    else {
+        Err(LkError::unsupported_platform())
+    }
+}
+
+/// Parses either hwmon or lm-sensors output and creates data points for readings of the given kind.
+pub(crate) fn process_readings<F>(response: ResponseMessage, kind: SensorKind, to_data_point: F) -> Result<DataPoint, String>
+where
+    F: Fn(&SensorReading) -> DataPoint,
+{
+    let mut result = DataPoint::empty();
+
+    if response.is_command_not_found() {
+        result.multivalue.push(DataPoint::not_available("No sensors found"));
+        return Ok(result);
+    }
+
+    // grep returns an error if any of the files couldn't be read, so exit code is not checked for hwmon.
+    let readings = if response.message.starts_with(HWMON_PATH) {
+        parse_hwmon(&response.message)
+    }
+    else if response.is_success() {
+        parse_sensors_json(&response.message)?
+    }
+    else {
+        return Err(response.message);
+    };
+
+    result.multivalue = readings.iter()
+        .filter(|reading| reading.kind == kind)
+        .map(to_data_point)
+        .collect();
+
+    result.update_criticality_from_children();
+
+    Ok(result)
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SensorKind {
+    #[default]
+    Temperature,
+    Fan,
+    Voltage,
+}
+
+impl SensorKind {
+    /// From attribute names like "temp1_input". Returns the kind and the sensor number.
+    fn parse(attribute: &str) -> Option<(SensorKind, u32, &str)> {
+        let (sensor, item) = attribute.split_once('_')?;
+        let number_start = sensor.find(|character: char| character.is_ascii_digit())?;
+        let number = sensor[number_start..].parse().ok()?;
+
+        let kind = match &sensor[..number_start] {
+            "temp" => SensorKind::Temperature,
+            "fan" => SensorKind::Fan,
+            "in" => SensorKind::Voltage,
+            _ => return None,
+        };
+
+        Some((kind, number, item))
+    }
+
+    /// Prefix of hwmon attribute names.
+    fn attribute_prefix(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temp",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "in",
+        }
+    }
+
+    /// hwmon uses millidegrees and millivolts.
+    fn hwmon_divisor(&self) -> f32 {
+        match self {
+            SensorKind::Temperature | SensorKind::Voltage => 1000.0,
+            SensorKind::Fan => 1.0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SensorReading {
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub crit: Option<f32>,
+}
+
+impl SensorReading {
+    pub fn data_point(&self, value: String, criticality: Criticality) -> DataPoint {
+        let mut data_point = DataPoint::labeled_value_with_level(format!("{} {}", self.chip, self.label), value, criticality);
+        data_point.value_float = self.value;
+        data_point
+    }
+
+    fn set(&mut self, item: &str, value: f32) {
+        match item {
+            "input" => self.value = value,
+            "min" => self.min = Some(value),
+            "max" => self.max = Some(value),
+            "crit" => self.crit = Some(value),
+            _ => {},
+        }
+    }
+}
+
+/// Many boards report unconfigured limits as zeroes.
+pub(crate) fn hardware_threshold(threshold: Option<f32>, use_hardware_thresholds: bool) -> Option<f32> {
+    threshold.filter(|threshold| use_hardware_thresholds && *threshold > 0.0)
+}
+
+/// Chips are identified by hwmon directory or lm-sensors chip name. Sensors by kind and number.
+type ReadingKey = (String, SensorKind, u32);
+
+fn parse_hwmon(output: &str) -> Vec<SensorReading> {
+    let mut chip_names = BTreeMap::<String, String>::new();
+    let mut readings = BTreeMap::<ReadingKey, (SensorReading, bool)>::new();
+
+    for line in output.lines() {
+        let Some((path, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = path.trim_start_matches(HWMON_PATH).split('/');
+        let (Some(chip_id), Some(attribute)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if attribute == "name" {
+            chip_names.insert(chip_id.to_string(), value.trim().to_string());
+            continue;
+        }
+
+        let Some((kind, number, item)) = SensorKind::parse(attribute) else {
+            continue;
+        };
+
+        let key = (chip_id.to_string(), kind, number);
+        let (reading, has_input) = readings.entry(key).or_insert_with(|| {
+            let reading = SensorReading {
+                kind,
+                label: attribute.split('_').next().unwrap_or_default().to_string(),
+                ..Default::default()
+            };
+            (reading, false)
+        });
+
+        if item == "label" {
+            reading.label = value.trim().to_string();
+        }
+        else if let Ok(value) = value.trim().parse::<f32>() {
+            reading.set(item, value / kind.hwmon_divisor());
+            *has_input |= item == "input";
+        }
+    }
+
+    let readings = readings.into_iter()
+        .filter(|(_, (_, has_input))| *has_input)
+        .map(|((chip_id, _, _), (mut reading, _))| {
+            reading.chip = chip_names.get(&chip_id).cloned().unwrap_or(chip_id.clone());
+            (chip_id, reading)
+        })
+        .collect::<Vec<_>>();
+
+    sorted_with_unique_chips(readings)
+}
+
+/// Parses output of `sensors -j`, e.g. {"coretemp-isa-0000": {"Adapter": "ISA adapter", "Core 0": {"temp2_input": 45.0, "temp2_crit": 100.0}}}.
+fn parse_sensors_json(output: &str) -> Result<Vec<SensorReading>, String> {
+    let chips: serde_json::Map<String, serde_json::Value> = serde_json::from_str(output)
+        .map_err(|error| format!("Failed to parse output: {}", error))?;
+
+    let mut readings = BTreeMap::<ReadingKey, (String, SensorReading)>::new();
+
+    for (chip_id, features) in chips.iter() {
+        let Some(features) = features.as_object() else {
+            continue;
+        };
+
+        for (label, subfeatures) in features.iter() {
+            let Some(subfeatures) = subfeatures.as_object() else {
+                continue;
+            };
+
+            for (attribute, value) in subfeatures.iter() {
+                let (Some((kind, number, item)), Some(value)) = (SensorKind::parse(attribute), value.as_f64()) else {
+                    continue;
+                };
+
+                let (_, reading) = readings.entry((chip_id.clone(), kind, number)).or_insert_with(|| {
+                    let reading = SensorReading {
+                        // E.g. "coretemp" from "coretemp-isa-0000".
+                        chip: chip_id.split('-').next().unwrap_or(chip_id).to_string(),
+                        label: label.clone(),
+                        kind,
+                        ..Default::default()
+                    };
+                    (chip_id.clone(), reading)
+                });
+                reading.set(item, value as f32);
+            }
+        }
+    }
+
+    Ok(sorted_with_unique_chips(readings.into_values().collect()))
+}
+
+/// Sorts by kind and uses chip IDs instead of names if there are several chips with the same name, e.g. two NVMe drives.
+fn sorted_with_unique_chips(mut readings: Vec<(String, SensorReading)>) -> Vec<SensorReading> {
+    let mut chip_ids_by_name = HashMap::<String, Vec<String>>::new();
+    for (chip_id, reading) in readings.iter() {
+        let chip_ids = chip_ids_by_name.entry(reading.chip.clone()).or_default();
+        if !chip_ids.contains(chip_id) {
+            chip_ids.push(chip_id.clone());
+        }
+    }
+
+    // Stable sort keeps the sensor order within a chip.
+    readings.sort_by_key(|(chip_id, reading)| (reading.kind, chip_sort_key(chip_id)));
+
+    readings.into_iter().map(|(chip_id, mut reading)| {
+        if chip_ids_by_name.get(&reading.chip).is_some_and(|chip_ids| chip_ids.len() > 1) {
+            reading.chip = format!("{} ({})", reading.chip, chip_id);
+        }
+        reading
+    }).collect()
+}
+
+/// Sorts e.g. "hwmon2" before "hwmon10".
+fn chip_sort_key(chip_id: &str) -> (String, u64) {
+    let prefix = chip_id.trim_end_matches(|character: char| character.is_ascii_digit());
+    let number = chip_id[prefix.len()..].parse().unwrap_or_default();
+    (prefix.to_string(), number)
+}
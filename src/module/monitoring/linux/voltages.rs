@@ -0,0 +1,80 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use super::sensors::{self, SensorKind, SensorReading};
+
+#[monitoring_module(
+    name="linux-voltages",
+    version="0.0.1",
+    description="Provides voltages from hardware sensors (using /sys/class/hwmon or lm-sensors).",
+    settings={
+        use_hardware_thresholds => "Warn if voltage is outside the min and max values provided by hardware. Default: true",
+    }
+)]
+pub struct Voltages {
+    use_hardware_thresholds: bool,
+}
+
+impl Module for Voltages {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Voltages {
+            use_hardware_thresholds: settings.get("use_hardware_thresholds").and_then(|value| value.parse().ok()).unwrap_or(true),
+        }
+    }
+}
+
+impl MonitoringModule for Voltages {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Voltages"),
+            category: String::from("host"),
+            use_multivalue: true,
+            use_with_charts: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        sensors::connector_message(host, SensorKind::Voltage)
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        sensors::process_readings(response, SensorKind::Voltage, |reading| self.reading_data_point(reading))
+    }
+}
+
+impl Voltages {
+    fn reading_data_point(&self, reading: &SensorReading) -> DataPoint {
+        // Many boards report unconfigured limits as zeroes.
+        let (criticality, description) = match (reading.min, reading.max) {
+            (Some(min), Some(max)) if self.use_hardware_thresholds && max > min => {
+                let criticality = if reading.value < min || reading.value > max { Criticality::Warning } else { Criticality::Normal };
+                (criticality, format!("min {:.2} V | max {:.2} V", min, max))
+            },
+            _ => (Criticality::Normal, String::new()),
+        };
+
+        let mut data_point = reading.data_point(format!("{:.2} V", reading.value), criticality);
+        data_point.description = description;
+        data_point
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     error::LkError,
     frontend::DisplayOptions,
     frontend::DisplayStyle,
-    module::connection::ResponseMessage,
+    module::connection::{ResponseMessage, UnixSocketRequest},
     module::module::Module,
     module::MetadataSupport,
     module::ModuleSpecification,
@@ -51,6 +51,11 @@ pub trait MonitoringModule: BoxCloneableMonitor + MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
+    /// Used instead of connector messages if implemented. Should never panic.
+    fn get_socket_request(&self, _host: Host, _parent_result: DataPoint) -> Result<UnixSocketRequest, LkError> {
+        Err(LkError::not_implemented())
+    }
+
     fn process_response(&self, _host: Host, _response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
         Err(String::new())
     }
@@ -58,6 +63,11 @@ pub trait MonitoringModule: BoxCloneableMonitor + MetadataSupport + Module {
     fn process_responses(&self, _host: Host, _responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
         Err(String::new())
     }
+
+    /// Called after the responses are processed successfully. See `ConnectionModule::get_connection_details`.
+    fn process_connection_details(&self, _details: &HashMap<String, String>, data_point: DataPoint) -> DataPoint {
+        data_point
+    }
 }
 
 // Implemented by the macro.
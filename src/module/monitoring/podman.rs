@@ -2,7 +2,20 @@
  * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
+use crate::module::connection::UnixSocketRequest;
 
+/// Libpod API is used through this socket by default. Requires that podman.socket is enabled.
+pub const PODMAN_SOCKET_PATH: &str = "/run/podman/podman.sock";
+/// Prefix for libpod API paths. Oldest API version with the needed endpoints.
+pub const LIBPOD_API_PATH: &str = "/v4.0.0/libpod";
+
+/// Sudo is only used by the connector if the socket can't be opened directly.
+pub fn api_request(request: UnixSocketRequest, as_root: bool) -> UnixSocketRequest {
+    match as_root {
+        true => request.use_sudo(),
+        false => request,
+    }
+}
 
 pub mod containers;
 pub use containers::Containers;
@@ -9,12 +9,13 @@ use serde::Deserialize;
 use serde_json;
 
 use crate::error::LkError;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
 use crate::{ Host, enums::Criticality, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
-use crate::utils::ShellCommand;
 
 #[monitoring_module(
     name="podman-containers",
@@ -23,13 +24,16 @@ use crate::utils::ShellCommand;
     uses_sudo=true,
     settings={
       ignore_compose_managed => "Ignore containers that are managed by podman-compose. Default: true.",
-      as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
+      as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+      socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                      For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
     }
 )]
 pub struct Containers {
     // Ignore containers that are managed by podman-compose.
     ignore_compose_managed: bool,
     as_root: bool,
+    socket_path: String,
 }
 
 impl Module for Containers {
@@ -37,6 +41,7 @@ impl Module for Containers {
         Containers {
             ignore_compose_managed: settings.get("ignore_compose_managed").and_then(|value| Some(value == "true")).unwrap_or(true),
             as_root: settings.get("as_root").and_then(|value| Some(value == "true")).unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
         }
     }
 }
@@ -56,13 +61,10 @@ impl MonitoringModule for Containers {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = self.as_root;
-
+    fn get_socket_request(&self, host: Host, _result: DataPoint) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["podman", "ps", "-a", "--format", "json"]);
-            Ok(command.to_string())
+            let path = format!("{}/containers/json?all=true", LIBPOD_API_PATH);
+            Ok(api_request(UnixSocketRequest::get(&self.socket_path, &path), self.as_root))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -70,10 +72,14 @@ impl MonitoringModule for Containers {
     }
 
     fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
-        if response.return_code != 0 {
-            let result = DataPoint::value_with_level(String::from("Couldn't list Podman containers."), Criticality::Critical);
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            // Couldn't connect. Service or socket is probably not available.
+            let result = DataPoint::value_with_level(String::from("Couldn't connect to Podman API."), Criticality::Critical);
             return Ok(result);
         }
+        else if response.return_code != 0 {
+            return Err(api_error_message(&response));
+        }
 
         let mut rows: Vec<PodmanPsJsonRow> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
 
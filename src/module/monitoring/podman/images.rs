@@ -12,13 +12,14 @@ use serde_json;
 use chrono::Utc;
 
 use crate::error::LkError;
-use crate::module::connection::ResponseMessage;
+use crate::module::connection::{ResponseMessage, UnixSocketRequest};
+use crate::module::connection::unix_socket::{api_error_message, EXIT_CODE_CONNECT_FAILED};
+use crate::module::monitoring::podman::{api_request, LIBPOD_API_PATH, PODMAN_SOCKET_PATH};
 use crate::enums::Criticality;
 use crate::{ Host, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
-use crate::utils::ShellCommand;
 
 
 #[monitoring_module(
@@ -31,7 +32,9 @@ use crate::utils::ShellCommand;
         age_error_threshold => "Error threshold in days. Default: 365",
         age_critical_threshold => "Critical threshold in days. Default: 730",
         local_image_prefix => "Image name prefix indicating that image was built locally. Default: localhost",
-        as_root => "Run podman with sudo as root. Default: true. If false, run as the SSH user (rootless)."
+        as_root => "Use sudo if the socket can't be opened directly as the SSH user. Default: true. Set to false for rootless Podman.",
+        socket_path => "Path to the Podman API socket. Default: /run/podman/podman.sock. \
+                        For rootless Podman, use the socket of the user, e.g. /run/user/1000/podman/podman.sock."
     }
 )]
 pub struct Images {
@@ -40,6 +43,7 @@ pub struct Images {
     age_critical_threshold: i64,
     local_image_prefix: String,
     as_root: bool,
+    socket_path: String,
 }
 
 impl Module for Images {
@@ -50,6 +54,7 @@ impl Module for Images {
             age_critical_threshold: settings.get("age_critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(730),
             local_image_prefix: settings.get("local_image_prefix").unwrap_or(&String::from("localhost")).clone(),
             as_root: settings.get("as_root").and_then(|value| Some(value == "true")).unwrap_or(true),
+            socket_path: settings.get("socket_path").cloned().unwrap_or_else(|| String::from(PODMAN_SOCKET_PATH)),
         }
     }
 }
@@ -69,13 +74,10 @@ impl MonitoringModule for Images {
         }
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        let mut command = ShellCommand::new();
-        command.use_sudo = self.as_root;
-
+    fn get_socket_request(&self, host: Host, _result: DataPoint) -> Result<UnixSocketRequest, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["podman", "images", "--format", "json"]);
-            Ok(command.to_string())
+            let path = format!("{}/images/json", LIBPOD_API_PATH);
+            Ok(api_request(UnixSocketRequest::get(&self.socket_path, &path), self.as_root))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -83,10 +85,14 @@ impl MonitoringModule for Images {
     }
 
     fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
-        if response.return_code != 0 {
-            let result = DataPoint::value_with_level(String::from("Couldn't list Podman images."), Criticality::Critical);
+        if response.return_code == EXIT_CODE_CONNECT_FAILED {
+            // Couldn't connect. Service or socket is probably not available.
+            let result = DataPoint::value_with_level(String::from("Couldn't connect to Podman API."), Criticality::Critical);
             return Ok(result);
         }
+        else if response.return_code != 0 {
+            return Err(api_error_message(&response));
+        }
 
         let rows: Vec<PodmanImagesJsonRow> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
 
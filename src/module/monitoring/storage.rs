@@ -9,4 +9,16 @@ pub mod filesystem;
 pub use filesystem::Filesystem;
 
 pub mod cryptsetup;
-pub use cryptsetup::Cryptsetup;
\ No newline at end of file
+pub use cryptsetup::Cryptsetup;
+
+pub mod smart;
+pub use smart::Smart;
+
+pub mod mdadm;
+pub use mdadm::Mdadm;
+
+pub mod zfs;
+pub use zfs::Zfs;
+
+pub mod btrfs;
+pub use btrfs::Btrfs;
\ No newline at end of file
@@ -0,0 +1,215 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::sh_single_quoted;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+
+const SECTION_PREFIX: &str = "=== ";
+
+#[monitoring_module(
+    name="storage-btrfs",
+    version="0.0.1",
+    description="Provides degraded and scrub state, device errors and capacity of Btrfs filesystems.",
+    uses_sudo=true,
+    settings={
+        capacity_warning => "Warning threshold for used capacity in percent. Default: 80",
+        capacity_critical => "Critical threshold for used capacity in percent. Default: 90",
+    }
+)]
+pub struct Btrfs {
+    capacity_warning: f32,
+    capacity_critical: f32,
+}
+
+impl Module for Btrfs {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Btrfs {
+            capacity_warning: settings.get("capacity_warning").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            capacity_critical: settings.get("capacity_critical").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+        }
+    }
+}
+
+impl MonitoringModule for Btrfs {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Btrfs filesystems"),
+            category: String::from("storage"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            // Subvolumes of the same filesystem can be mounted many times, so only one mountpoint per filesystem is used.
+            let script = format!(concat!(
+                "command -v btrfs >/dev/null || exit 127; ",
+                "findmnt -n -l -t btrfs -o UUID,TARGET | sort -u -k1,1 | while read -r uuid target; do ",
+                    "echo \"{}$target\"; ",
+                    "btrfs filesystem show \"$target\"; ",
+                    "btrfs device stats \"$target\"; ",
+                    "btrfs filesystem usage -b \"$target\"; ",
+                    "btrfs scrub status \"$target\"; ",
+                "done"
+            ), SECTION_PREFIX);
+            Ok(format!("sudo sh -c {}", sh_single_quoted(&script)))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        if response.is_command_not_found() {
+            result.multivalue.push(DataPoint::not_available("btrfs not available"));
+            return Ok(result);
+        }
+
+        // Exit code is from the last command of the last filesystem, so it's not checked. Failing commands just leave values missing.
+        let mut filesystems = Vec::<BtrfsFilesystem>::new();
+        for line in response.message.lines() {
+            if let Some(mountpoint) = line.strip_prefix(SECTION_PREFIX) {
+                filesystems.push(BtrfsFilesystem {
+                    mountpoint: mountpoint.to_string(),
+                    ..Default::default()
+                });
+            }
+            else if let Some(filesystem) = filesystems.last_mut() {
+                filesystem.parse_line(line);
+            }
+            else if !line.trim().is_empty() {
+                return Err(format!("Invalid response: {}", line));
+            }
+        }
+
+        result.multivalue = filesystems.iter().map(|filesystem| self.filesystem_data_point(filesystem)).collect();
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+impl Btrfs {
+    fn filesystem_data_point(&self, filesystem: &BtrfsFilesystem) -> DataPoint {
+        let mut criticality = Criticality::Normal;
+        let mut value = String::from("OK");
+        let mut description = Vec::new();
+
+        if let Some(capacity) = filesystem.capacity() {
+            description.push(format!("{:.0} % used", capacity));
+
+            if capacity >= self.capacity_critical {
+                criticality = Criticality::Critical;
+            }
+            else if capacity >= self.capacity_warning {
+                criticality = Criticality::Warning;
+            }
+        }
+
+        if filesystem.scrub_status == "running" {
+            value = format!("Scrubbing {}", filesystem.scrub_progress).trim_end().to_string();
+        }
+
+        if filesystem.device_errors > 0 {
+            description.push(format!("{} device errors", filesystem.device_errors));
+            criticality = criticality.max(Criticality::Error);
+        }
+
+        if !filesystem.scrub_errors.is_empty() {
+            description.push(format!("scrub errors: {}", filesystem.scrub_errors));
+            criticality = criticality.max(Criticality::Error);
+        }
+        else if !filesystem.scrub_status.is_empty() {
+            description.push(format!("scrub {}", filesystem.scrub_status));
+        }
+
+        if filesystem.missing_devices {
+            value = String::from("Degraded");
+            criticality = Criticality::Critical;
+        }
+
+        let mut data_point = DataPoint::labeled_value_with_level(filesystem.mountpoint.clone(), value, criticality);
+        data_point.value_float = filesystem.capacity().unwrap_or_default();
+        data_point.description = description.join(" | ");
+        data_point.command_params = vec![filesystem.mountpoint.clone()];
+        data_point
+    }
+}
+
+#[derive(Default)]
+struct BtrfsFilesystem {
+    mountpoint: String,
+    missing_devices: bool,
+    /// Sum of all counters in `btrfs device stats`.
+    device_errors: u64,
+    device_size: Option<u64>,
+    used: Option<u64>,
+    /// E.g. "finished", "running" or "aborted".
+    scrub_status: String,
+    /// E.g. "56.21%".
+    scrub_progress: String,
+    /// E.g. "csum=2". Empty if there were no errors.
+    scrub_errors: String,
+}
+
+impl BtrfsFilesystem {
+    fn parse_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+
+        // From `btrfs filesystem show`, e.g. "*** Some devices missing" or "devid 2 size 0 used 0 path <missing disk> MISSING".
+        if trimmed.starts_with("*** Some devices missing") || (trimmed.starts_with("devid ") && trimmed.ends_with(" MISSING")) {
+            self.missing_devices = true;
+        }
+        // From `btrfs device stats`, e.g. "[/dev/sda1].write_io_errs    0".
+        else if trimmed.starts_with('[') && trimmed.contains("].") {
+            self.device_errors += trimmed.split_whitespace().last().and_then(|count| count.parse::<u64>().ok()).unwrap_or_default();
+        }
+        else if let Some((key, value)) = trimmed.split_once(':') {
+            let value = value.trim();
+            let first_value = value.split_whitespace().next().unwrap_or_default();
+
+            match key {
+                // From `btrfs filesystem usage -b`. "Used" is also in the per-profile sections but in a different format.
+                "Device size" => self.device_size = first_value.parse().ok(),
+                "Used" if self.used.is_none() => self.used = first_value.parse().ok(),
+                "Device missing" => self.missing_devices |= first_value.parse::<u64>().unwrap_or_default() > 0,
+                // From `btrfs scrub status`.
+                "Status" => self.scrub_status = value.to_string(),
+                "Bytes scrubbed" => {
+                    let progress = value.split_whitespace().find(|part| part.ends_with("%)")).unwrap_or_default();
+                    self.scrub_progress = progress.trim_matches(|character| character == '(' || character == ')').to_string();
+                },
+                "Error summary" if value != "no errors found" => self.scrub_errors = value.to_string(),
+                _ => {},
+            }
+        }
+    }
+
+    fn capacity(&self) -> Option<f32> {
+        match (self.used, self.device_size) {
+            (Some(used), Some(size)) if size > 0 => Some(used as f32 / size as f32 * 100.0),
+            _ => None,
+        }
+    }
+}
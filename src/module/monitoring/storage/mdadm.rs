@@ -0,0 +1,208 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+
+#[monitoring_module(
+    name="storage-mdadm",
+    version="0.0.1",
+    description="Provides state of Linux software RAID arrays (using /proc/mdstat).",
+    settings={
+    }
+)]
+pub struct Mdadm;
+
+impl Module for Mdadm {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        Mdadm { }
+    }
+}
+
+impl MonitoringModule for Mdadm {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("RAID arrays"),
+            category: String::from("storage"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            Ok(String::from("cat /proc/mdstat"))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        // File only exists if the md driver is loaded.
+        if response.is_error() {
+            result.multivalue.push(DataPoint::not_available("/proc/mdstat not available"));
+            return Ok(result);
+        }
+
+        let mut arrays = Vec::<MdArray>::new();
+
+        for line in response.message.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.starts_with("Personalities") || trimmed.starts_with("unused devices") || trimmed.is_empty() {
+                continue;
+            }
+            else if !line.starts_with(char::is_whitespace) {
+                // E.g. "md0 : active raid1 sdb1[1] sda1[0](F)"
+                let Some((name, details)) = line.split_once(" : ") else {
+                    return Err(format!("Invalid response: {}", line));
+                };
+                arrays.push(MdArray::parse(name.trim(), details));
+            }
+            else if let Some(array) = arrays.last_mut() {
+                array.parse_detail_line(trimmed);
+            }
+        }
+
+        result.multivalue = arrays.iter().map(MdArray::to_data_point).collect();
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+#[derive(Default)]
+struct MdArray {
+    name: String,
+    active: bool,
+    level: String,
+    members: Vec<String>,
+    failed_members: Vec<String>,
+    /// From e.g. "[2/1]".
+    devices_total: Option<u32>,
+    devices_up: Option<u32>,
+    /// E.g. "recovery" or "check" and the progress.
+    sync_action: Option<(String, String)>,
+}
+
+impl MdArray {
+    fn parse(name: &str, details: &str) -> MdArray {
+        let mut parts = details.split_whitespace().peekable();
+        let mut array = MdArray {
+            name: name.to_string(),
+            active: parts.next() == Some("active"),
+            ..Default::default()
+        };
+
+        // State can be followed by e.g. "(auto-read-only)".
+        if parts.peek().is_some_and(|part| part.starts_with('(')) {
+            parts.next();
+        }
+
+        // Inactive arrays have no level.
+        if parts.peek().is_some_and(|part| !part.contains('[')) {
+            array.level = parts.next().unwrap_or_default().to_string();
+        }
+
+        for member in parts {
+            let device = member.split('[').next().unwrap_or(member).to_string();
+            if member.ends_with("(F)") {
+                array.failed_members.push(device.clone());
+            }
+            array.members.push(device);
+        }
+
+        array
+    }
+
+    fn parse_detail_line(&mut self, line: &str) {
+        // E.g. "1953260544 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [U_U]"
+        if line.contains(" blocks") {
+            let counts = line.split_whitespace()
+                .filter_map(|part| part.strip_prefix('[')?.strip_suffix(']')?.split_once('/'))
+                .next();
+
+            if let Some((total, up)) = counts {
+                self.devices_total = total.parse().ok();
+                self.devices_up = up.parse().ok();
+            }
+        }
+        // E.g. "[==>..................]  recovery = 12.6% (123456/976630272) finish=100.2min speed=100000K/sec"
+        else if let Some((before, after)) = line.split_once(" = ") {
+            let action = before.split_whitespace().last().unwrap_or_default().to_string();
+            let progress = after.split_whitespace().next().unwrap_or_default().to_string();
+            self.sync_action = Some((action, progress));
+        }
+        // Pending or delayed resync is shown without progress.
+        else if let Some(action) = line.strip_prefix("resync=") {
+            self.sync_action = Some((String::from("resync"), action.to_string()));
+        }
+    }
+
+    fn is_degraded(&self) -> bool {
+        match (self.devices_total, self.devices_up) {
+            (Some(total), Some(up)) => up < total,
+            _ => !self.failed_members.is_empty(),
+        }
+    }
+
+    fn to_data_point(&self) -> DataPoint {
+        let (value, criticality) = if !self.active {
+            (String::from("Inactive"), Criticality::Critical)
+        }
+        else if let Some((action, progress)) = self.sync_action.as_ref().filter(|(action, _)| action == "recovery" || action == "resync" || action == "reshape") {
+            (format!("{} {}", capitalize(action), progress), if self.is_degraded() { Criticality::Error } else { Criticality::Warning })
+        }
+        else if self.is_degraded() {
+            (String::from("Degraded"), Criticality::Error)
+        }
+        else if let Some((action, progress)) = &self.sync_action {
+            // Scheduled "check" or "repair", i.e. scrubbing.
+            (format!("{} {}", capitalize(action), progress), Criticality::Normal)
+        }
+        else {
+            (String::from("OK"), Criticality::Normal)
+        };
+
+        let mut description = vec![self.level.clone()];
+        if let (Some(total), Some(up)) = (self.devices_total, self.devices_up) {
+            description.push(format!("{}/{} devices up", up, total));
+        }
+        if !self.failed_members.is_empty() {
+            description.push(format!("failed: {}", self.failed_members.join(", ")));
+        }
+
+        let mut data_point = DataPoint::labeled_value_with_level(self.name.clone(), value, criticality);
+        data_point.description = description.into_iter().filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" | ");
+        data_point.command_params = vec![format!("/dev/{}", self.name), self.name.clone()];
+        data_point
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
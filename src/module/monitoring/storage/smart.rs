@@ -0,0 +1,334 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use serde::Deserialize;
+use serde_json;
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::sh_single_quoted;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+
+/// ATA attributes whose normalized value tells the remaining life of an SSD. Vendors use different ones.
+const ATA_WEAR_ATTRIBUTES: [u32; 3] = [177, 231, 233];
+const ATA_REALLOCATED_SECTORS: u32 = 5;
+const ATA_PENDING_SECTORS: u32 = 197;
+
+#[monitoring_module(
+    name="storage-smart",
+    version="0.0.1",
+    description="Provides S.M.A.R.T. health of disks (using smartctl and nvme-cli).",
+    uses_sudo=true,
+    settings={
+        temperature_warning => "Warning threshold for disk temperature in Celsius. Default: 50",
+        temperature_critical => "Critical threshold for disk temperature in Celsius. Default: 60",
+        wear_warning => "Warning threshold for used SSD endurance in percent. Default: 80",
+        wear_critical => "Critical threshold for used SSD endurance in percent. Default: 95",
+    }
+)]
+pub struct Smart {
+    temperature_warning: f32,
+    temperature_critical: f32,
+    wear_warning: f32,
+    wear_critical: f32,
+}
+
+impl Module for Smart {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Smart {
+            temperature_warning: settings.get("temperature_warning").and_then(|value| value.parse().ok()).unwrap_or(50.0),
+            temperature_critical: settings.get("temperature_critical").and_then(|value| value.parse().ok()).unwrap_or(60.0),
+            wear_warning: settings.get("wear_warning").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            wear_critical: settings.get("wear_critical").and_then(|value| value.parse().ok()).unwrap_or(95.0),
+        }
+    }
+}
+
+impl MonitoringModule for Smart {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("S.M.A.R.T."),
+            category: String::from("storage"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            // Lines of `smartctl --scan` look like: /dev/sda -d sat # /dev/sda [SAT], ATA device
+            // nvme-cli is only needed with smartctl versions that don't report NVMe health.
+            let script = concat!(
+                "command -v smartctl >/dev/null || exit 127; ",
+                "smartctl --scan | while read -r device _ type _; do ",
+                    "smartctl --json -a -d \"$type\" \"$device\"; ",
+                    "case \"$device\" in ",
+                        "/dev/nvme*) command -v nvme >/dev/null && nvme smart-log -o json \"$device\" 2>/dev/null;; ",
+                    "esac; ",
+                "done"
+            );
+            Ok(format!("sudo sh -c {}", sh_single_quoted(script)))
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        if response.is_command_not_found() {
+            result.multivalue.push(DataPoint::not_available("smartctl not available"));
+            return Ok(result);
+        }
+        else if response.is_empty() {
+            return Ok(result);
+        }
+
+        // smartctl uses the exit code as a bit mask that is also set for e.g. past errors, so it's not checked.
+        // The output is a stream of JSON documents where nvme-cli output follows the related smartctl output.
+        let mut devices = Vec::<SmartctlOutput>::new();
+        for document in serde_json::Deserializer::from_str(&response.message).into_iter::<serde_json::Value>() {
+            let document = document.map_err(|error| format!("Failed to parse output: {}", error))?;
+
+            if document.get("smartctl").is_some() {
+                let device = serde_json::from_value(document).map_err(|error| format!("Failed to parse smartctl output: {}", error))?;
+                devices.push(device);
+            }
+            else if let Some(device) = devices.last_mut() {
+                let smart_log: NvmeCliSmartLog = serde_json::from_value(document)
+                    .map_err(|error| format!("Failed to parse nvme output: {}", error))?;
+
+                if device.nvme_smart_health_information_log.is_none() {
+                    device.nvme_smart_health_information_log = Some(smart_log.into());
+                }
+            }
+        }
+
+        result.multivalue = devices.iter().map(|device| self.device_data_point(device)).collect();
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+impl Smart {
+    fn device_data_point(&self, device: &SmartctlOutput) -> DataPoint {
+        let device_path = device.device.name.clone();
+        let short_name = device_path.rsplit('/').next().unwrap_or(&device_path).to_string();
+        let nvme_log = device.nvme_smart_health_information_log.as_ref();
+        let mut children = Vec::new();
+
+        let (health, health_level) = match (&device.smart_status, nvme_log.map(|log| log.critical_warning)) {
+            (_, Some(critical_warning)) if critical_warning != 0 => (format!("Critical warning 0x{:02x}", critical_warning), Criticality::Critical),
+            (Some(status), _) if !status.passed => (String::from("Failing"), Criticality::Critical),
+            (Some(_), _) => (String::from("OK"), Criticality::Normal),
+            // E.g. USB bridges often don't pass SMART commands through.
+            (None, _) => (String::from("Unknown"), Criticality::Info),
+        };
+        let mut health_point = DataPoint::labeled_value_with_level(String::from("Health"), health.clone(), health_level);
+        health_point.description = device.smartctl.error_messages().join(" | ");
+        children.push(health_point);
+
+        let temperature = device.temperature.as_ref().map(|temperature| temperature.current)
+            .or_else(|| nvme_log.and_then(|log| log.temperature));
+        if let Some(temperature) = temperature {
+            let mut data_point = DataPoint::labeled_value_with_level(
+                String::from("Temperature"),
+                format!("{} °C", temperature),
+                level(temperature as f32, self.temperature_warning, self.temperature_critical),
+            );
+            data_point.value_float = temperature as f32;
+            children.push(data_point);
+        }
+
+        let reallocated = device.ata_attribute(ATA_REALLOCATED_SECTORS).map(|attribute| attribute.raw.value)
+            .or(device.scsi_grown_defect_list);
+        if let Some(reallocated) = reallocated {
+            let criticality = if reallocated > 0 { Criticality::Warning } else { Criticality::Normal };
+            children.push(DataPoint::labeled_value_with_level(String::from("Reallocated sectors"), reallocated.to_string(), criticality));
+        }
+
+        if let Some(pending) = device.ata_attribute(ATA_PENDING_SECTORS).map(|attribute| attribute.raw.value) {
+            let criticality = if pending > 0 { Criticality::Error } else { Criticality::Normal };
+            children.push(DataPoint::labeled_value_with_level(String::from("Pending sectors"), pending.to_string(), criticality));
+        }
+
+        if let Some(media_errors) = nvme_log.and_then(|log| log.media_errors) {
+            let criticality = if media_errors > 0 { Criticality::Error } else { Criticality::Normal };
+            children.push(DataPoint::labeled_value_with_level(String::from("Media errors"), media_errors.to_string(), criticality));
+        }
+
+        if let Some(wear) = device.wear_level() {
+            let mut data_point = DataPoint::labeled_value_with_level(
+                String::from("Wear level"),
+                format!("{} %", wear),
+                level(wear as f32, self.wear_warning, self.wear_critical),
+            );
+            data_point.value_float = wear as f32;
+            children.push(data_point);
+        }
+
+        let mut data_point = DataPoint::labeled_value(short_name, health);
+        data_point.description = [&device.model_name, &device.serial_number].iter()
+            .filter_map(|value| value.as_deref())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        data_point.command_params = vec![device_path, device.device.device_type.clone()];
+        data_point.multivalue = children;
+        data_point.update_criticality_from_children();
+        data_point
+    }
+}
+
+fn level(value: f32, warning: f32, critical: f32) -> Criticality {
+    if value >= critical {
+        Criticality::Critical
+    }
+    else if value >= warning {
+        Criticality::Warning
+    }
+    else {
+        Criticality::Normal
+    }
+}
+
+/// Subset of the output of `smartctl --json -a`.
+#[derive(Deserialize)]
+struct SmartctlOutput {
+    smartctl: SmartctlInfo,
+    device: SmartctlDevice,
+    model_name: Option<String>,
+    serial_number: Option<String>,
+    smart_status: Option<SmartStatus>,
+    temperature: Option<SmartTemperature>,
+    ata_smart_attributes: Option<AtaSmartAttributes>,
+    nvme_smart_health_information_log: Option<NvmeHealthLog>,
+    scsi_grown_defect_list: Option<u64>,
+    scsi_percentage_used_endurance_indicator: Option<u64>,
+}
+
+impl SmartctlOutput {
+    fn ata_attribute(&self, id: u32) -> Option<&AtaAttribute> {
+        self.ata_smart_attributes.as_ref()?.table.iter().find(|attribute| attribute.id == id)
+    }
+
+    /// Used endurance in percent.
+    fn wear_level(&self) -> Option<u64> {
+        if let Some(percentage_used) = self.nvme_smart_health_information_log.as_ref().and_then(|log| log.percentage_used) {
+            return Some(percentage_used);
+        }
+        if let Some(percentage_used) = self.scsi_percentage_used_endurance_indicator {
+            return Some(percentage_used);
+        }
+
+        // Normalized values count down from 100.
+        ATA_WEAR_ATTRIBUTES.iter()
+            .filter_map(|id| self.ata_attribute(*id))
+            .map(|attribute| 100_u64.saturating_sub(attribute.value))
+            .max()
+    }
+}
+
+#[derive(Deserialize)]
+struct SmartctlInfo {
+    #[serde(default)]
+    messages: Vec<SmartctlMessage>,
+}
+
+impl SmartctlInfo {
+    fn error_messages(&self) -> Vec<&str> {
+        self.messages.iter()
+            .filter(|message| message.severity == "error")
+            .map(|message| message.string.as_str())
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct SmartctlMessage {
+    string: String,
+    #[serde(default)]
+    severity: String,
+}
+
+#[derive(Deserialize)]
+struct SmartctlDevice {
+    name: String,
+    #[serde(rename = "type", default)]
+    device_type: String,
+}
+
+#[derive(Deserialize)]
+struct SmartStatus {
+    passed: bool,
+}
+
+#[derive(Deserialize)]
+struct SmartTemperature {
+    current: i64,
+}
+
+#[derive(Deserialize)]
+struct AtaSmartAttributes {
+    table: Vec<AtaAttribute>,
+}
+
+#[derive(Deserialize)]
+struct AtaAttribute {
+    id: u32,
+    value: u64,
+    raw: AtaRawValue,
+}
+
+#[derive(Deserialize)]
+struct AtaRawValue {
+    value: u64,
+}
+
+#[derive(Deserialize)]
+struct NvmeHealthLog {
+    critical_warning: u64,
+    /// In Celsius.
+    temperature: Option<i64>,
+    percentage_used: Option<u64>,
+    media_errors: Option<u64>,
+}
+
+/// Output of `nvme smart-log -o json`.
+#[derive(Deserialize)]
+struct NvmeCliSmartLog {
+    critical_warning: u64,
+    /// In Kelvin.
+    temperature: Option<i64>,
+    #[serde(alias = "percentage_used")]
+    percent_used: Option<u64>,
+    media_errors: Option<u64>,
+}
+
+impl From<NvmeCliSmartLog> for NvmeHealthLog {
+    fn from(smart_log: NvmeCliSmartLog) -> Self {
+        NvmeHealthLog {
+            critical_warning: smart_log.critical_warning,
+            temperature: smart_log.temperature.map(|kelvin| kelvin - 273),
+            percentage_used: smart_log.percent_used,
+            media_errors: smart_log.media_errors,
+        }
+    }
+}
@@ -0,0 +1,245 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::ShellCommand;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+
+#[monitoring_module(
+    name="storage-zfs",
+    version="0.0.1",
+    description="Provides health, scrub and resilver state, errors and capacity of ZFS pools.",
+    settings={
+        capacity_warning => "Warning threshold for used capacity in percent. Default: 80",
+        capacity_critical => "Critical threshold for used capacity in percent. Default: 90",
+    }
+)]
+pub struct Zfs {
+    capacity_warning: f32,
+    capacity_critical: f32,
+}
+
+impl Module for Zfs {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Zfs {
+            capacity_warning: settings.get("capacity_warning").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            capacity_critical: settings.get("capacity_critical").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+        }
+    }
+}
+
+impl MonitoringModule for Zfs {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("ZFS pools"),
+            category: String::from("storage"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_messages(&self, host: Host, _parent_result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let list = ShellCommand::new_from(vec!["zpool", "list", "-H", "-p", "-o", "name,size,alloc,cap,health"]);
+            let status = ShellCommand::new_from(vec!["zpool", "status", "-p"]);
+            Ok(vec![list.to_string(), status.to_string()])
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        let list_response = responses.first().ok_or(String::from("No response"))?;
+        if list_response.is_command_not_found() {
+            result.multivalue.push(DataPoint::not_available("zpool not available"));
+            return Ok(result);
+        }
+        else if list_response.is_error() {
+            return Err(list_response.message.clone());
+        }
+
+        let statuses = match responses.get(1) {
+            Some(response) if response.is_success() => PoolStatus::parse_all(&response.message),
+            _ => HashMap::new(),
+        };
+
+        for line in list_response.message.lines().filter(|line| !line.is_empty()) {
+            let [name, size, allocated, capacity, health] = line.split('\t').collect::<Vec<_>>()[..] else {
+                return Err(format!("Invalid response: {}", line));
+            };
+
+            let size = size.parse::<u64>().map_err(|_| format!("Invalid size: {}", size))?;
+            let allocated = allocated.parse::<u64>().map_err(|_| format!("Invalid allocation: {}", allocated))?;
+            let capacity = capacity.trim_end_matches('%').parse::<f32>().map_err(|_| format!("Invalid capacity: {}", capacity))?;
+            let status = statuses.get(name);
+
+            let mut criticality = match health {
+                "ONLINE" => Criticality::Normal,
+                "DEGRADED" => Criticality::Error,
+                _ => Criticality::Critical,
+            };
+
+            let mut value = health.to_string();
+            let mut description = vec![format!("{} used of {} ({:.0} %)", format_bytes(allocated), format_bytes(size), capacity)];
+
+            if capacity >= self.capacity_critical {
+                criticality = criticality.max(Criticality::Critical);
+            }
+            else if capacity >= self.capacity_warning {
+                criticality = criticality.max(Criticality::Warning);
+            }
+
+            if let Some(status) = status {
+                if status.resilvering {
+                    value = format!("Resilvering {}", status.progress.clone().unwrap_or_default()).trim_end().to_string();
+                    criticality = criticality.max(Criticality::Warning);
+                }
+                else if status.scrubbing {
+                    value = format!("Scrubbing {}", status.progress.clone().unwrap_or_default()).trim_end().to_string();
+                }
+
+                if status.device_errors > 0 {
+                    description.push(format!("{} read, write or checksum errors", status.device_errors));
+                    criticality = criticality.max(Criticality::Error);
+                }
+                if status.data_errors {
+                    description.push(String::from("Data errors"));
+                    criticality = criticality.max(Criticality::Error);
+                }
+                if !status.scan.is_empty() {
+                    description.push(status.scan.clone());
+                }
+            }
+
+            let mut data_point = DataPoint::labeled_value_with_level(name.to_string(), value, criticality);
+            data_point.value_float = capacity;
+            data_point.description = description.join(" | ");
+            data_point.command_params = vec![name.to_string()];
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+
+        Ok(result)
+    }
+}
+
+const STATUS_SECTIONS: [&str; 10] = ["pool", "id", "state", "status", "action", "see", "scan", "remove", "config", "errors"];
+
+/// Relevant parts of `zpool status` for a pool.
+#[derive(Default)]
+struct PoolStatus {
+    /// First line of the "scan" section, e.g. "scrub repaired 0B in 00:10:01 with 0 errors on Sun Oct 11 00:34:02 2026".
+    scan: String,
+    scrubbing: bool,
+    resilvering: bool,
+    /// E.g. "16.20%".
+    progress: Option<String>,
+    /// Sum of READ, WRITE and CKSUM columns.
+    device_errors: u64,
+    data_errors: bool,
+}
+
+impl PoolStatus {
+    fn parse_all(output: &str) -> HashMap<String, PoolStatus> {
+        let mut pools = HashMap::new();
+        let mut current: Option<(String, PoolStatus)> = None;
+        let mut section: &str = "";
+
+        for line in output.lines() {
+            let trimmed = line.trim();
+
+            // Section headers are right-aligned with spaces, e.g. "  pool: tank". Other lines are indented with tabs.
+            let header = if line.starts_with('\t') {
+                None
+            }
+            else {
+                trimmed.split_once(':').filter(|(key, _)| STATUS_SECTIONS.contains(key))
+            };
+
+            if let Some((key, value)) = header {
+                section = key;
+
+                if key == "pool" {
+                    if let Some((name, status)) = current.take() {
+                        pools.insert(name, status);
+                    }
+                    current = Some((value.trim().to_string(), PoolStatus::default()));
+                }
+
+                let Some((_, status)) = current.as_mut() else {
+                    continue;
+                };
+
+                if section == "scan" {
+                    let value = value.trim();
+                    status.scan = value.to_string();
+                    status.scrubbing = value.starts_with("scrub in progress");
+                    status.resilvering = value.starts_with("resilver in progress");
+                }
+                else if section == "errors" {
+                    status.data_errors = value.trim() != "No known data errors";
+                }
+                continue;
+            }
+
+            let Some((_, status)) = current.as_mut() else {
+                continue;
+            };
+
+            match section {
+                // E.g. "300G resilvered, 16.20% done, 03:20:00 to go"
+                "scan" => {
+                    if let Some(progress) = trimmed.split(", ").find(|part| part.ends_with("% done")) {
+                        status.progress = Some(progress.trim_end_matches(" done").to_string());
+                    }
+                },
+                // E.g. "sdb     FAULTED      3   120     0  too many errors"
+                "config" => {
+                    let columns = trimmed.split_whitespace().collect::<Vec<_>>();
+                    if columns.len() >= 5 && columns[0] != "NAME" {
+                        status.device_errors += columns[2..5].iter().filter_map(|count| count.parse::<u64>().ok()).sum::<u64>();
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        if let Some((name, status)) = current.take() {
+            pools.insert(name, status);
+        }
+
+        pools
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "K", "M", "G", "T", "P"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
@@ -5,11 +5,12 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::error::*;
 use crate::module::connection::RequestResponse;
@@ -18,12 +19,23 @@ use crate::configuration::Hosts;
 use crate::module::connection::ResponseMessage;
 use crate::module::{monitoring::*, ModuleSpecification};
 use crate::module::ModuleFactory;
-use crate::host_manager::{StateUpdateMessage, HostManager};
+use crate::host_manager::{StateUpdateMessage, HostManager, HostStateCollection};
 use crate::connection_manager::{ ConnectorRequest, RequestType };
 
+pub mod command_batch;
+use command_batch::BatchedMonitor;
+
+pub mod scheduler;
+use scheduler::{ScheduledMonitor, Scheduler};
+
 /// Certificate monitor is implemented as a special internal host.
 pub const CERT_MONITOR_HOST_ID: &str = "_cert-monitor";
 
+/// How often the scheduler checks for monitors that are due for a refresh.
+const SCHEDULER_TICK: Duration = Duration::from_secs(1);
+/// Shortest allowed refresh interval in seconds.
+const MIN_REFRESH_INTERVAL: u64 = 1;
+
 
 // Default needs to be implemented because of Qt QObject requirements.
 #[derive(Default)]
@@ -45,6 +57,16 @@ pub struct MonitorManager {
     response_sender_prototype: Option<mpsc::Sender<RequestResponse>>,
     response_receiver: Option<mpsc::Receiver<RequestResponse>>,
     response_receiver_thread: Option<thread::JoinHandle<()>>,
+
+    /// Monitors with a refresh interval. Host name is the key.
+    schedule: HashMap<String, Vec<ScheduledMonitor>>,
+    /// Invocations started by the scheduler that haven't completed yet.
+    scheduled_invocations: Arc<Mutex<HashSet<u64>>>,
+    /// Monitors that bring the host down when at critical level. Host name is the key.
+    /// Extensions of a critical base monitor are included, since they carry the base monitor's result.
+    critical_monitors: Arc<Mutex<HashMap<String, HashSet<String>>>>,
+    scheduler_stop_sender: Option<mpsc::Sender<()>>,
+    scheduler_thread: Option<thread::JoinHandle<()>>,
 }
 
 impl MonitorManager {
@@ -61,10 +83,6 @@ impl MonitorManager {
         }
     }
 
-    fn next_invocation_id(&self) -> u64 {
-        self.invocation_id_counter.fetch_add(1, Ordering::SeqCst) + 1
-    }
-
     pub fn configure(&mut self,
                      hosts_config: &Hosts,
                      request_sender: mpsc::Sender<ConnectorRequest>,
@@ -76,6 +94,9 @@ impl MonitorManager {
         // Just clearing is not enough if the mutex is poisoned.
         self.monitors = Arc::new(Mutex::new(HashMap::new()));
         self.platform_info_providers = Arc::new(Mutex::new(HashMap::new()));
+        self.scheduled_invocations = Arc::new(Mutex::new(HashSet::new()));
+        self.critical_monitors = Arc::new(Mutex::new(HashMap::new()));
+        self.schedule = HashMap::new();
 
         self.request_sender = Some(request_sender);
         self.state_update_sender = Some(state_update_sender);
@@ -88,7 +109,7 @@ impl MonitorManager {
         let mut settings = HashMap::new();
         settings.insert("addresses".to_string(), hosts_config.certificate_monitors.join(","));
         let cert_monitor = internal::CertMonitor::new_monitoring_module(&settings);
-        self.add_monitor(CERT_MONITOR_HOST_ID.to_string(), cert_monitor, true);
+        self.add_monitor(CERT_MONITOR_HOST_ID.to_string(), cert_monitor, true, false);
 
         // Regular host monitoring.
         for (host_id, host_config) in hosts_config.hosts.iter() {
@@ -110,11 +131,62 @@ impl MonitorManager {
             let base_modules = new_monitors.iter().filter_map(|monitor| monitor.get_metadata_self().parent_module)
                                                   .collect::<Vec<_>>();
 
+            let is_configured_critical = |monitor_id: &String| {
+                host_config.effective.monitors.get(monitor_id)
+                    .and_then(|monitor_config| monitor_config.is_critical)
+                    .unwrap_or(false)
+            };
+
+            let host_critical_monitors = new_monitors.iter()
+                .filter(|monitor| {
+                    is_configured_critical(&monitor.get_module_spec().id) ||
+                    monitor.get_metadata_self().parent_module.is_some_and(|parent| is_configured_critical(&parent.id))
+                })
+                .map(|monitor| monitor.get_module_spec().id)
+                .collect::<HashSet<_>>();
+
+            // Extension monitors are refreshed together with their base monitor,
+            // so the shortest interval of the group is used.
+            let mut host_schedule = Vec::new();
+            for monitor in new_monitors.iter().filter(|monitor| monitor.get_metadata_self().parent_module.is_none()) {
+                let module_spec = monitor.get_module_spec();
+                let extension_ids = new_monitors.iter()
+                    .filter(|extension| extension.get_metadata_self().parent_module.as_ref() == Some(&module_spec))
+                    .map(|extension| extension.get_module_spec().id)
+                    .collect::<Vec<_>>();
+
+                let interval = std::iter::once(&module_spec.id).chain(extension_ids.iter())
+                    .filter_map(|monitor_id| host_config.effective.monitors.get(monitor_id))
+                    .filter(|monitor_config| monitor_config.enabled.unwrap_or(true))
+                    .filter_map(|monitor_config| monitor_config.interval)
+                    .min();
+
+                if let Some(mut interval) = interval {
+                    if interval < MIN_REFRESH_INTERVAL {
+                        log::warn!("[{}][{}] Refresh interval is too short, using {} second(s)", host_id, module_spec.id, MIN_REFRESH_INTERVAL);
+                        interval = MIN_REFRESH_INTERVAL;
+                    }
+
+                    let is_critical = std::iter::once(&module_spec.id).chain(extension_ids.iter())
+                        .any(|monitor_id| host_critical_monitors.contains(monitor_id));
+
+                    host_schedule.push(ScheduledMonitor::new(module_spec.id, interval, is_critical));
+                }
+            }
+
+            if !host_schedule.is_empty() {
+                log::debug!("[{}] Scheduled {} monitor(s) for periodic refresh", host_id, host_schedule.len());
+                self.schedule.insert(host_id.clone(), host_schedule);
+            }
+
             for monitor in new_monitors {
                 // Base modules won't get the initial NoData data point sent.
                 let is_base = base_modules.contains(&monitor.get_module_spec());
-                self.add_monitor(host_id.clone(), monitor, !is_base);
+                let is_critical = host_critical_monitors.contains(&monitor.get_module_spec().id);
+                self.add_monitor(host_id.clone(), monitor, !is_base, is_critical);
             }
+
+            self.critical_monitors.lock().unwrap().insert(host_id.clone(), host_critical_monitors);
         }
 
         let (sender, receiver) = mpsc::channel::<RequestResponse>();
@@ -123,6 +195,8 @@ impl MonitorManager {
     }
 
     pub fn stop(&mut self) {
+        self.stop_scheduler();
+
         if let Some(thread) = self.response_receiver_thread.take() {
             if let Err(_) = self.new_response_sender().send(RequestResponse::stop()) {
                 log::warn!("Couldn't stop thread, it may have already stopped.");
@@ -139,7 +213,7 @@ impl MonitorManager {
     }
 
     // Adds a monitor but only if a monitor with the same ID doesn't exist.
-    fn add_monitor(&mut self, host_id: String, monitor: Monitor, send_initial_value: bool) {
+    fn add_monitor(&mut self, host_id: String, monitor: Monitor, send_initial_value: bool, is_critical: bool) {
         let Ok(mut monitors) = self.monitors.lock() else {
             self.send_state_update(StateUpdateMessage::fatal_error());
             return;
@@ -159,6 +233,7 @@ impl MonitorManager {
                     module_spec: monitor.get_module_spec(),
                     data_point: Some(DataPoint::pending()),
                     invocation_id: 0,
+                    is_critical,
                     ..Default::default()
                 });
             }
@@ -193,10 +268,11 @@ impl MonitorManager {
     }
 
     /// Refreshes platform info and such in preparation for actual monitor refresh.
-    pub fn refresh_platform_info(&mut self, host_id: &str) {
+    /// Returns the invocation IDs of the refresh operations.
+    pub fn refresh_platform_info(&mut self, host_id: &str) -> Vec<u64> {
         let Ok(monitors) = self.monitors.lock() else {
             self.send_state_update(StateUpdateMessage::fatal_error());
-            return;
+            return Vec::new();
         };
 
         let platform_info_providers = self.platform_info_providers.lock().unwrap();
@@ -205,6 +281,7 @@ impl MonitorManager {
         let monitors_for_host = monitors.iter()
             .filter(|(host_id_key, _)| &host_id == host_id_key && !host_id_key.starts_with("_"));
 
+        let mut invocation_ids = Vec::new();
         for (host_name, monitor_collection) in monitors_for_host {
             let mut host = self.host_manager.borrow().get_host(host_name);
 
@@ -213,57 +290,66 @@ impl MonitorManager {
                 log::error!("Failed to resolve IP address for host {}: {}", host_name, error);
             }
 
-            for info_provider in platform_info_providers.values() {
-                // Executed only if required connector is used on the host.
-                if monitor_collection.values().all(|monitor|
-                    monitor.get_connector_spec().unwrap_or_default().id != info_provider.get_connector_spec().unwrap().id
-                ) {
-                    continue;
-                }
+            invocation_ids.extend(
+                Self::request_platform_info(&self.request_context(), &host, monitor_collection, &platform_info_providers)
+            );
+        }
 
-                let commands = match get_monitor_connector_messages(&host, &info_provider, &DataPoint::empty()) {
-                    Ok(messages) => messages,
-                    Err(error) => {
-                        log::error!("Monitor failed: {}", error);
-                        return;
-                    }
-                };
+        invocation_ids
+    }
 
-                if !host.settings.contains(&crate::host::HostSetting::UseSudo) && commands.iter().any(|msg| {
-                    // Checking command instead of relying to module metadata is more accurate.
-                    let trimmed = msg.trim_start();
-                    trimmed.starts_with("sudo ") || trimmed.starts_with("\"sudo\"")
-                }) {
-                    log::warn!("[{}][{}] Skipping, sudo required", host.name, info_provider.get_module_spec().id);
-                    return;
+    fn request_platform_info(context: &RequestContext,
+                             host: &Host,
+                             monitor_collection: &HashMap<String, Monitor>,
+                             platform_info_providers: &HashMap<String, Monitor>) -> Vec<u64> {
+
+        let mut invocation_ids = Vec::new();
+        for info_provider in platform_info_providers.values() {
+            // Executed only if required connector is used on the host.
+            if monitor_collection.values().all(|monitor|
+                monitor.get_connector_spec().unwrap_or_default().id != info_provider.get_connector_spec().unwrap().id
+            ) {
+                continue;
+            }
+
+            let request_type = match get_monitor_request_type(host, info_provider, &DataPoint::empty(), None, Vec::new()) {
+                Ok(request_type) => request_type,
+                Err(error) => {
+                    log::error!("Monitor failed: {}", error);
+                    return invocation_ids;
                 }
+            };
 
-                let invocation_id = self.next_invocation_id();
+            if host.privilege_escalation().is_none() && request_type.uses_sudo() {
+                log::warn!("[{}][{}] Skipping, sudo required", host.name, info_provider.get_module_spec().id);
+                return invocation_ids;
+            }
 
-                // Notify host state manager about new pending monitor invocation.
-                self.send_state_update(StateUpdateMessage {
-                    host_name: host.name.clone(),
-                    display_options: info_provider.get_display_options(),
-                    module_spec: info_provider.get_module_spec(),
-                    data_point: Some(DataPoint::pending()),
-                    invocation_id,
-                    ..Default::default()
-                });
+            let invocation_id = context.next_invocation_id();
 
-                self.send_connector_request(ConnectorRequest {
-                    connector_spec: info_provider.get_connector_spec(),
-                    source_id: info_provider.get_module_spec().id,
-                    host: host.clone(),
-                    invocation_id,
-                    response_sender: self.new_response_sender(),
-                    request_type: RequestType::MonitorCommand {
-                        parent_datapoint: None,
-                        extension_monitors: Vec::new(),
-                        commands: commands,
-                    },
-                });
-            }
+            // Notify host state manager about new pending monitor invocation.
+            context.send_state_update(StateUpdateMessage {
+                host_name: host.name.clone(),
+                display_options: info_provider.get_display_options(),
+                module_spec: info_provider.get_module_spec(),
+                data_point: Some(DataPoint::pending()),
+                invocation_id,
+                ..Default::default()
+            });
+
+            context.send_connector_request(ConnectorRequest {
+                connector_spec: info_provider.get_connector_spec(),
+                source_id: info_provider.get_module_spec().id,
+                host: host.clone(),
+                invocation_id,
+                response_sender: context.response_sender.clone(),
+                request_type,
+            });
+
+            invocation_ids.push(invocation_id);
         }
+
+        invocation_ids
     }
 
     pub fn get_all_host_categories(&self, host_id: &str) -> Vec<String> {
@@ -340,6 +426,16 @@ impl MonitorManager {
     }
 
     fn refresh_monitors(&self, host: Host, monitors: HashMap<&String, &Monitor>) -> Vec<u64> {
+        Self::request_monitor_refresh(&self.request_context(), host, monitors, None)
+    }
+
+    /// If `scheduled_invocations` is given, invocations that were actually sent are added to it.
+    fn request_monitor_refresh(context: &RequestContext,
+                               host: Host,
+                               monitors: HashMap<&String, &Monitor>,
+                               scheduled_invocations: Option<&Mutex<HashSet<u64>>>) -> Vec<u64> {
+
+
         if !host.platform.is_set() && monitors.values().any(|monitor| !monitor.is_internal()) {
             log::warn!("[{}] Refreshing monitors despite missing platform info", host.name);
         }
@@ -348,8 +444,9 @@ impl MonitorManager {
         let (extensions, bases): (Vec<&Monitor>, Vec<&Monitor>) = 
             monitors.values().partition(|monitor| monitor.get_metadata_self().parent_module.is_some());
 
-        let invocation_ids = (1..=bases.len()).map(|_| self.next_invocation_id()).collect::<Vec<_>>();
+        let invocation_ids = (1..=bases.len()).map(|_| context.next_invocation_id()).collect::<Vec<_>>();
         let mut id_iter = invocation_ids.iter();
+        let mut batch = Vec::new();
 
         for monitor in bases {
             let current_invocation_id = *id_iter.next().unwrap();
@@ -359,7 +456,7 @@ impl MonitorManager {
                 .map(|ext| ext.get_module_spec().id.clone()).collect();
 
             // Notify host state manager about new pending monitor invocation.
-            self.send_state_update(StateUpdateMessage {
+            context.send_state_update(StateUpdateMessage {
                 host_name: host.name.clone(),
                 display_options: monitor.get_display_options(),
                 module_spec: monitor.get_module_spec(),
@@ -368,12 +465,12 @@ impl MonitorManager {
                 ..Default::default()
             });
 
-            let messages = match get_monitor_connector_messages(&host, &monitor, &DataPoint::empty()) {
-                Ok(messages) => messages,
+            let request_type = match get_monitor_request_type(&host, &monitor, &DataPoint::empty(), None, extension_ids) {
+                Ok(request_type) => request_type,
                 Err(error) => {
                     log::error!("Monitor failed: {}", error);
 
-                    self.send_state_update(StateUpdateMessage {
+                    context.send_state_update(StateUpdateMessage {
                         host_name: host.name.clone(),
                         display_options: monitor.get_display_options(),
                         module_spec: monitor.get_module_spec(),
@@ -386,36 +483,114 @@ impl MonitorManager {
                 }
             };
 
-            if !host.settings.contains(&crate::host::HostSetting::UseSudo) && messages.iter().any(|msg| {
-                // Checking command instead of relying to module metadata is more accurate.
-                let trimmed = msg.trim_start();
-                trimmed.starts_with("sudo ") || trimmed.starts_with("\"sudo\"")
-            }) {
+            if host.privilege_escalation().is_none() && request_type.uses_sudo() {
                 log::warn!("[{}][{}] Skipping, sudo required", host.name, monitor.get_module_spec().id);
+
+                // Otherwise the invocation would stay pending.
+                context.send_state_update(StateUpdateMessage {
+                    host_name: host.name.clone(),
+                    display_options: monitor.get_display_options(),
+                    module_spec: monitor.get_module_spec(),
+                    invocation_id: current_invocation_id,
+                    ..Default::default()
+                });
                 continue;
             }
 
-            self.send_connector_request(ConnectorRequest {
-                connector_spec: monitor.get_connector_spec(),
-                source_id: monitor.get_module_spec().id,
+            if let Some(scheduled_invocations) = scheduled_invocations {
+                scheduled_invocations.lock().unwrap().insert(current_invocation_id);
+            }
+
+            let connector_spec = monitor.get_connector_spec();
+            let can_batch = host.batch_monitor_commands() && connector_spec.as_ref().is_some_and(|spec| spec.id == "ssh");
+
+            match request_type {
+                RequestType::MonitorCommand { extension_monitors, commands, .. }
+                    if can_batch && !commands.is_empty() && commands.iter().all(|command| command_batch::is_batchable(command)) => {
+
+                    batch.push((connector_spec, BatchedMonitor {
+                        monitor_id: monitor.get_module_spec().id,
+                        invocation_id: current_invocation_id,
+                        extension_monitors,
+                        commands,
+                    }));
+                },
+                request_type => context.send_connector_request(ConnectorRequest {
+                    connector_spec,
+                    source_id: monitor.get_module_spec().id,
+                    host: host.clone(),
+                    invocation_id: current_invocation_id,
+                    response_sender: context.response_sender.clone(),
+                    request_type,
+                }),
+            }
+        }
+
+        if let Some((connector_spec, _)) = batch.first() {
+            let connector_spec = connector_spec.clone();
+            Self::send_monitor_request(context, &host, connector_spec, batch.into_iter().map(|(_, monitor)| monitor).collect());
+        }
+
+        invocation_ids
+    }
+
+    /// Commands of multiple monitors are combined into one request.
+    fn send_monitor_request(context: &RequestContext,
+                            host: &Host,
+                            connector_spec: Option<ModuleSpecification>,
+                            mut monitors: Vec<BatchedMonitor>) {
+
+        let request = if monitors.len() == 1 {
+            let monitor = monitors.remove(0);
+            ConnectorRequest {
+                connector_spec,
+                source_id: monitor.monitor_id,
                 host: host.clone(),
-                invocation_id: current_invocation_id,
-                response_sender: self.new_response_sender(),
+                invocation_id: monitor.invocation_id,
+                response_sender: context.response_sender.clone(),
                 request_type: RequestType::MonitorCommand {
                     parent_datapoint: None,
-                    extension_monitors: extension_ids,
-                    commands: messages,
+                    extension_monitors: monitor.extension_monitors,
+                    commands: monitor.commands,
                 },
-            });
+            }
         }
+        else {
+            log::debug!("[{}] Running commands of {} monitors in one batch", host.name, monitors.len());
+            let delimiter = command_batch::new_delimiter();
 
-        invocation_ids
+            ConnectorRequest {
+                connector_spec,
+                source_id: command_batch::BATCH_SOURCE_ID.to_string(),
+                host: host.clone(),
+                invocation_id: monitors[0].invocation_id,
+                response_sender: context.response_sender.clone(),
+                request_type: RequestType::MonitorCommandBatch {
+                    commands: vec![command_batch::build_script(&delimiter, &monitors)],
+                    monitors,
+                    delimiter,
+                },
+            }
+        };
+
+        context.send_connector_request(request);
     }
 
-    fn send_connector_request(&self, request: ConnectorRequest) {
-        if let Err(error) = self.request_sender.as_ref().unwrap().send(request) {
-            log::error!("Failed to send connector request: {}", error);
-            self.send_state_update(StateUpdateMessage::fatal_error());
+    /// Handle for refreshing monitors from other threads.
+    pub(crate) fn refresher(&self) -> MonitorRefresher {
+        MonitorRefresher {
+            monitors: self.monitors.clone(),
+            host_states: self.host_manager.borrow().get_host_states(),
+            context: self.request_context(),
+        }
+    }
+
+    fn request_context(&self) -> RequestContext {
+        RequestContext {
+            request_sender: self.request_sender.as_ref().unwrap().clone(),
+            state_update_sender: self.state_update_sender.as_ref().unwrap().clone(),
+            response_sender: self.new_response_sender(),
+            invocation_id_counter: self.invocation_id_counter.clone(),
         }
     }
 
@@ -427,6 +602,66 @@ impl MonitorManager {
         }
     }
 
+    //
+    // SCHEDULING
+    //
+
+    /// Starts refreshing monitors that have an `interval` configured. Does nothing if there are none.
+    /// Should be called after `start_processing_responses`.
+    pub fn start_scheduler(&mut self) {
+        self.stop_scheduler();
+
+        let mut scheduler = self.new_scheduler(Instant::now());
+        if scheduler.is_empty() {
+            return;
+        }
+
+        let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            log::debug!("Started monitor scheduler");
+
+            // Runs until a stop message is received or the sender is dropped.
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(SCHEDULER_TICK) {
+                if let Err(error) = scheduler.tick(Instant::now()) {
+                    log::error!("Scheduler failed: {}", error);
+                    return;
+                }
+            }
+
+            log::debug!("Stopping scheduler thread");
+        });
+
+        self.scheduler_stop_sender = Some(stop_sender);
+        self.scheduler_thread = Some(thread);
+    }
+
+    /// Scheduler that isn't run automatically. `start_scheduler` runs one in a separate thread.
+    pub fn new_scheduler(&self, now: Instant) -> Scheduler {
+        Scheduler::new(
+            self.schedule.clone(),
+            self.monitors.clone(),
+            self.platform_info_providers.clone(),
+            self.host_manager.borrow().get_host_states(),
+            self.scheduled_invocations.clone(),
+            self.request_context(),
+            now,
+        )
+    }
+
+    fn stop_scheduler(&mut self) {
+        if let Some(thread) = self.scheduler_thread.take() {
+            if let Some(stop_sender) = self.scheduler_stop_sender.take() {
+                if stop_sender.send(()).is_err() {
+                    log::warn!("Couldn't stop scheduler thread, it may have already stopped.");
+                }
+            }
+
+            if thread.join().is_err() {
+                log::warn!("Scheduler thread had paniced");
+            }
+        }
+    }
+
     //
     // RESPONSE HANDLING
     //
@@ -435,10 +670,10 @@ impl MonitorManager {
         let thread = Self::_start_processing_responses(
             self.monitors.clone(),
             self.platform_info_providers.clone(),
-            self.request_sender.as_ref().unwrap().clone(),
-            self.state_update_sender.as_ref().unwrap().clone(),
-            self.response_sender_prototype.as_ref().unwrap().clone(),
+            self.request_context(),
             self.response_receiver.take().unwrap(),
+            self.scheduled_invocations.clone(),
+            self.critical_monitors.clone(),
         );
 
         self.response_receiver_thread = Some(thread);
@@ -447,14 +682,22 @@ impl MonitorManager {
     fn _start_processing_responses(
         monitors: Arc<Mutex<HashMap<String, HashMap<String, Monitor>>>>,
         platform_info_providers: Arc<Mutex<HashMap<String, Monitor>>>,
-        request_sender: mpsc::Sender<ConnectorRequest>,
-        state_update_sender: mpsc::Sender<StateUpdateMessage>,
-        response_sender: mpsc::Sender<RequestResponse>,
+        context: RequestContext,
         response_receiver: mpsc::Receiver<RequestResponse>,
+        scheduled_invocations: Arc<Mutex<HashSet<u64>>>,
+        critical_monitors: Arc<Mutex<HashMap<String, HashSet<String>>>>,
     ) -> thread::JoinHandle<()> {
 
         thread::spawn(move || {
             log::debug!("Started processing responses");
+            let RequestContext { request_sender, state_update_sender, response_sender, .. } = context;
+
+            // Lets the scheduler know that the invocation won't receive any more responses.
+            let finish_invocation = |invocation_id: u64| {
+                if let Ok(mut scheduled_invocations) = scheduled_invocations.lock() {
+                    scheduled_invocations.remove(&invocation_id);
+                }
+            };
 
             loop {
                 let response = match response_receiver.recv() {
@@ -469,6 +712,16 @@ impl MonitorManager {
                     break;
                 }
 
+                // Handled like separate responses.
+                if let RequestType::MonitorCommandBatch { .. } = response.request_type {
+                    for monitor_response in command_batch::split_response(response) {
+                        if let Err(error) = response_sender.send(monitor_response) {
+                            log::error!("Failed to split batched response: {}", error);
+                        }
+                    }
+                    continue;
+                }
+
                 let results_len = response.responses.len();
                 let (responses, errors): (Vec<_>, Vec<_>) =  response.responses.into_iter().partition(Result::is_ok);
                 let responses = responses.into_iter().map(Result::unwrap).collect::<Vec<_>>();
@@ -494,16 +747,19 @@ impl MonitorManager {
                 else {
                     // Can happen if the host or monitor was removed while command was in progress.
                     log::debug!("[{}][{}] Ignoring response for unknown host or monitor", response.host.name, monitor_id);
+                    finish_invocation(response.invocation_id);
                     continue;
                 };
                 
                 let (parent_datapoint, mut extension_monitors) = match response.request_type {
-                    RequestType::MonitorCommand { parent_datapoint, extension_monitors, .. } => {
+                    RequestType::MonitorCommand { parent_datapoint, extension_monitors, .. } |
+                    RequestType::MonitorSocketRequest { parent_datapoint, extension_monitors, .. } => {
                         (parent_datapoint, extension_monitors)
                     },
                     _ => {
                         log::warn!("[{}][{}] Ignoring invalid datapoint", response.host.name, monitor_id);
-                        continue; 
+                        finish_invocation(response.invocation_id);
+                        continue;
                     }
                 };
 
@@ -534,6 +790,10 @@ impl MonitorManager {
                     datapoint_result = Err(String::new());
                 }
 
+                if let Ok(data_point) = datapoint_result {
+                    datapoint_result = Ok(monitor.process_connection_details(&response.connection_details, data_point));
+                }
+
                 let new_data_point = match datapoint_result {
                     Ok(data_point) => {
                         log::debug!("[{}][{}] Data point received: {} {}", response.host.name, monitor_id, data_point.label, data_point);
@@ -560,20 +820,26 @@ impl MonitorManager {
                     let next_monitor = &monitors[&response.host.name][&next_monitor_id];
                     let next_parent_datapoint = parent_datapoint.unwrap_or_else(|| new_data_point.clone().unwrap());
 
-                    let messages = match get_monitor_connector_messages(&response.host, &next_monitor, &next_parent_datapoint) {
-                        Ok(messages) => {
-                            if !response.host.settings.contains(&crate::host::HostSetting::UseSudo) && messages.iter().any(|msg| {
-                                // checking command instead of relying to module metadata is more accurate
-                                let trimmed = msg.trim_start();
-                                trimmed.starts_with("sudo ") || trimmed.starts_with("\"sudo\"")
-                            }) {
+                    let request_type = get_monitor_request_type(
+                        &response.host,
+                        next_monitor,
+                        &next_parent_datapoint,
+                        Some(new_data_point.clone().unwrap()),
+                        extension_monitors,
+                    );
+
+                    let request_type = match request_type {
+                        Ok(request_type) => {
+                            if response.host.privilege_escalation().is_none() && request_type.uses_sudo() {
                                 log::warn!("[{}][{}] Skipping, sudo required", response.host.name, next_monitor.get_module_spec().id);
+                                finish_invocation(response.invocation_id);
                                 continue;
                             }
-                            messages
+                            request_type
                         },
                         Err(error1) => {
                             log::error!("[{}][{}] Monitor failed: {}", response.host.name, monitor_id, error1);
+                            finish_invocation(response.invocation_id);
 
                             if let Err(error2) = state_update_sender.send(StateUpdateMessage {
                                 host_name: response.host.name.clone(),
@@ -597,11 +863,7 @@ impl MonitorManager {
                         host: response.host.clone(),
                         invocation_id: response.invocation_id,
                         response_sender: response_sender.clone(),
-                        request_type: RequestType::MonitorCommand {
-                            parent_datapoint: Some(new_data_point.clone().unwrap()),
-                            extension_monitors: extension_monitors,
-                            commands: messages,
-                        },
+                        request_type,
                     }) {
                         log::error!("[{}][{}] Failed to send connector request: {}", response.host.name, next_monitor_id, error);
 
@@ -612,6 +874,12 @@ impl MonitorManager {
                     }
                 }
                 else {
+                    finish_invocation(response.invocation_id);
+
+                    let is_critical = critical_monitors.lock().ok()
+                        .and_then(|critical_monitors| critical_monitors.get(&response.host.name).map(|ids| ids.contains(monitor_id)))
+                        .unwrap_or(false);
+
                     if let Err(error) = state_update_sender.send(StateUpdateMessage {
                         host_name: response.host.name.clone(),
                         display_options: monitor.get_display_options(),
@@ -619,6 +887,7 @@ impl MonitorManager {
                         data_point: new_data_point,
                         errors: errors,
                         invocation_id: response.invocation_id,
+                        is_critical,
                         ..Default::default()
                     }) {
                         log::error!("Failed to send state update: {}", error);
@@ -633,6 +902,93 @@ impl MonitorManager {
 
 }
 
+/// Senders needed for refreshing monitors. Can be moved to other threads.
+#[derive(Clone)]
+struct RequestContext {
+    request_sender: mpsc::Sender<ConnectorRequest>,
+    state_update_sender: mpsc::Sender<StateUpdateMessage>,
+    response_sender: mpsc::Sender<RequestResponse>,
+    invocation_id_counter: Arc<AtomicU64>,
+}
+
+impl RequestContext {
+    fn next_invocation_id(&self) -> u64 {
+        self.invocation_id_counter.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn send_connector_request(&self, request: ConnectorRequest) {
+        if let Err(error) = self.request_sender.send(request) {
+            log::error!("Failed to send connector request: {}", error);
+            self.send_state_update(StateUpdateMessage::fatal_error());
+        }
+    }
+
+    fn send_state_update(&self, message: StateUpdateMessage) {
+        // If upstream state manager has crashed for some reason, there's nothing we can do to recover.
+        if let Err(error) = self.state_update_sender.send(message) {
+            log::error!("Failed to send state update: {}", error);
+            panic!("Failed to send state update: {}", error);
+        }
+    }
+}
+
+/// Thread-safe handle for refreshing monitors. Host data is read directly from host states.
+#[derive(Clone)]
+pub(crate) struct MonitorRefresher {
+    monitors: Arc<Mutex<HashMap<String, HashMap<String, Monitor>>>>,
+    host_states: Arc<Mutex<HostStateCollection>>,
+    context: RequestContext,
+}
+
+impl MonitorRefresher {
+    pub fn has_monitor(&self, host_id: &str, monitor_id: &str) -> bool {
+        self.monitors.lock().is_ok_and(|monitors| {
+            monitors.get(host_id).is_some_and(|host_monitors| host_monitors.contains_key(monitor_id))
+        })
+    }
+
+    /// Refreshes the monitor and its extensions. Returns the invocation IDs.
+    pub fn refresh(&self, host_id: &str, monitor_id: &str) -> Vec<u64> {
+        let Some(host) = self.host_states.lock().ok()
+            .and_then(|host_states| host_states.hosts.get(host_id).map(|state| state.host.clone())) else {
+            return Vec::new();
+        };
+
+        let Ok(monitors) = self.monitors.lock() else {
+            return Vec::new();
+        };
+
+        let Some(host_monitors) = monitors.get(host_id) else {
+            return Vec::new();
+        };
+
+        let monitors_to_refresh = host_monitors.iter()
+            .filter(|(id, monitor)| {
+                *id == monitor_id || monitor.get_metadata_self().parent_module.is_some_and(|parent| parent.id == monitor_id)
+            })
+            .collect::<HashMap<_, _>>();
+
+        MonitorManager::request_monitor_refresh(&self.context, host, monitors_to_refresh, None)
+    }
+}
+
+/// Socket request of the monitor if it has one, otherwise its connector messages.
+fn get_monitor_request_type(host: &Host,
+                            monitor: &Monitor,
+                            parent_result: &DataPoint,
+                            parent_datapoint: Option<DataPoint>,
+                            extension_monitors: Vec<String>) -> Result<RequestType, LkError> {
+
+    match monitor.get_socket_request(host.clone(), parent_result.clone()) {
+        Ok(request) => return Ok(RequestType::MonitorSocketRequest { extension_monitors, parent_datapoint, request }),
+        Err(error) if error.kind != ErrorKind::NotImplemented => return Err(error.set_source(monitor.get_module_spec().id)),
+        Err(_) => {},
+    }
+
+    let commands = get_monitor_connector_messages(host, monitor, parent_result)?;
+    Ok(RequestType::MonitorCommand { extension_monitors, parent_datapoint, commands })
+}
+
 /// NOTE: Panics are not handled gracefully since this runs in main UI thread.
 /// get_connector_message and get_connector_messages should never panic.
 fn get_monitor_connector_messages(host: &Host, monitor: &Monitor, parent_datapoint: &DataPoint) -> Result<Vec<String>, LkError> {
@@ -0,0 +1,123 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection_manager::RequestType;
+use crate::error::LkError;
+use crate::module::connection::{RequestResponse, ResponseMessage};
+use crate::utils::sh_single_quoted;
+
+//
+// Runs the commands of several monitors in one script to save round-trips. Each command's output is followed
+// by a delimiter line containing the exit code, so the output can be split back into separate responses.
+//
+
+/// Source ID of the combined request.
+pub const BATCH_SOURCE_ID: &str = "_monitor-batch";
+
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchedMonitor {
+    pub monitor_id: String,
+    pub invocation_id: u64,
+    pub extension_monitors: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+/// Only plain shell commands can be combined. Privileged commands are left out since a sudo password
+/// would have to be passed through stdin separately for each of them.
+pub fn is_batchable(message: &str) -> bool {
+    let trimmed = message.trim_start();
+
+    !trimmed.is_empty() &&
+    !trimmed.starts_with("sudo ") &&
+    !trimmed.starts_with("\"sudo\"")
+}
+
+/// Random, so that command output can't contain it by accident.
+pub fn new_delimiter() -> String {
+    format!("lightkeeper-batch-{:016x}", rand::random::<u64>())
+}
+
+/// Script is run with `sh` regardless of the login shell. Commands run in subshells so that one can't exit the script.
+pub fn build_script(delimiter: &str, monitors: &[BatchedMonitor]) -> String {
+    let mut script = String::new();
+
+    for (monitor_index, monitor) in monitors.iter().enumerate() {
+        for (command_index, command) in monitor.commands.iter().enumerate() {
+            script.push_str(&format!(
+                "(\n{}\n) 2>&1\nprintf '\\n%s %s\\n' '{} {} {}' \"$?\"\n",
+                command, delimiter, monitor_index, command_index
+            ));
+        }
+    }
+
+    format!("sh -c {}", sh_single_quoted(&script))
+}
+
+/// Splits the script output into responses for every command of every monitor.
+/// Commands without a delimiter line, e.g. if the script was interrupted, get an error.
+pub fn split_output(delimiter: &str, monitors: &[BatchedMonitor], output: &str) -> Vec<Vec<Result<ResponseMessage, LkError>>> {
+    let mut rest = output;
+    let mut results = Vec::new();
+
+    for (monitor_index, monitor) in monitors.iter().enumerate() {
+        let mut monitor_results = Vec::new();
+
+        for command_index in 0..monitor.commands.len() {
+            // The newline before the delimiter was added by the script.
+            let marker = format!("\n{} {} {} ", delimiter, monitor_index, command_index);
+
+            let Some(position) = rest.find(&marker) else {
+                monitor_results.push(Err(LkError::other("Output of batched command is incomplete")));
+                continue;
+            };
+
+            let message = &rest[..position];
+            let (return_code, remaining) = rest[position + marker.len()..].split_once('\n')
+                .unwrap_or((&rest[position + marker.len()..], ""));
+
+            monitor_results.push(match return_code.trim().parse::<i32>() {
+                Ok(return_code) => Ok(ResponseMessage::new(message.to_string(), return_code)),
+                Err(_) => Err(LkError::other_p("Invalid exit code in batched command output", return_code)),
+            });
+
+            rest = remaining;
+        }
+
+        results.push(monitor_results);
+    }
+
+    results
+}
+
+/// Converts a response to a batch request into responses to regular monitor requests.
+pub fn split_response(response: RequestResponse) -> Vec<RequestResponse> {
+    let RequestType::MonitorCommandBatch { monitors, delimiter, .. } = &response.request_type else {
+        return Vec::new();
+    };
+
+    let results = match response.responses.first() {
+        Some(Ok(message)) => split_output(delimiter, monitors, &message.message),
+        Some(Err(error)) => monitors.iter().map(|_| vec![Err(error.clone())]).collect(),
+        None => monitors.iter().map(|_| vec![Err(LkError::unexpected())]).collect(),
+    };
+
+    monitors.iter().zip(results)
+        .map(|(monitor, responses)| RequestResponse {
+            source_id: monitor.monitor_id.clone(),
+            host: response.host.clone(),
+            invocation_id: monitor.invocation_id,
+            request_type: RequestType::MonitorCommand {
+                extension_monitors: monitor.extension_monitors.clone(),
+                parent_datapoint: None,
+                commands: monitor.commands.clone(),
+            },
+            responses,
+            ..Default::default()
+        })
+        .collect()
+}
@@ -0,0 +1,198 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::enums::HostStatus;
+use crate::error::LkError;
+use crate::host_manager::{HostStateCollection, StateUpdateMessage};
+use crate::module::monitoring::Monitor;
+use super::{MonitorManager, RequestContext};
+
+/// Maximum random variation of a scheduled refresh time, as a fraction of the interval.
+const SCHEDULER_JITTER: f64 = 0.1;
+/// Upper limit for the backed-off refresh interval when a host is down.
+const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(3600);
+/// Scheduled invocations without a response after this long are considered lost.
+const STALE_INVOCATION_TIMEOUT: Duration = Duration::from_secs(600);
+/// How often to retry fetching platform info for hosts that haven't been initialized yet.
+const HOST_INIT_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+
+/// Refreshes monitors that have an `interval` configured. Current time is passed to `tick`,
+/// so the scheduler can be driven by a timer thread or manually.
+pub struct Scheduler {
+    schedule: HashMap<String, Vec<ScheduledMonitor>>,
+    monitors: Arc<Mutex<HashMap<String, HashMap<String, Monitor>>>>,
+    platform_info_providers: Arc<Mutex<HashMap<String, Monitor>>>,
+    host_states: Arc<Mutex<HostStateCollection>>,
+    /// Invocations started by the scheduler that haven't completed yet.
+    scheduled_invocations: Arc<Mutex<HashSet<u64>>>,
+    context: RequestContext,
+    next_platform_info_refresh: HashMap<String, Instant>,
+}
+
+impl Scheduler {
+    pub(super) fn new(
+        mut schedule: HashMap<String, Vec<ScheduledMonitor>>,
+        monitors: Arc<Mutex<HashMap<String, HashMap<String, Monitor>>>>,
+        platform_info_providers: Arc<Mutex<HashMap<String, Monitor>>>,
+        host_states: Arc<Mutex<HostStateCollection>>,
+        scheduled_invocations: Arc<Mutex<HashSet<u64>>>,
+        context: RequestContext,
+        now: Instant,
+    ) -> Self {
+        // Spread the initial refreshes a bit too.
+        for scheduled in schedule.values_mut().flatten() {
+            scheduled.next_refresh = now + scheduled.jitter();
+        }
+
+        Scheduler {
+            schedule,
+            monitors,
+            platform_info_providers,
+            host_states,
+            scheduled_invocations,
+            context,
+            next_platform_info_refresh: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schedule.is_empty()
+    }
+
+    /// Requests refreshes for monitors that are due at `now`. Returns the new invocation IDs.
+    pub fn tick(&mut self, now: Instant) -> Result<Vec<u64>, LkError> {
+        let mut invocation_ids = Vec::new();
+
+        for (host_id, scheduled_monitors) in self.schedule.iter_mut() {
+            let Some((host, host_status)) = self.host_states.lock().ok().and_then(|host_states| {
+                host_states.hosts.get(host_id)
+                    .map(|state| (state.host.clone(), state.status))
+            }) else {
+                continue;
+            };
+
+            let Ok(monitors) = self.monitors.lock() else {
+                self.context.send_state_update(StateUpdateMessage::fatal_error());
+                return Err(LkError::other("Monitors are unavailable"));
+            };
+
+            let Some(host_monitors) = monitors.get(host_id) else {
+                continue;
+            };
+
+            // Monitors can't be refreshed before platform info is available.
+            // Pending status means that platform info has already been requested.
+            if !host.platform.is_set() {
+                let next_refresh = self.next_platform_info_refresh.entry(host_id.clone()).or_insert(now);
+                if host_status != HostStatus::Pending && *next_refresh <= now {
+                    log::debug!("[{}] Scheduler is initializing host", host_id);
+                    *next_refresh = now + HOST_INIT_RETRY_INTERVAL;
+
+                    let platform_info_providers = self.platform_info_providers.lock().unwrap();
+                    invocation_ids.extend(
+                        MonitorManager::request_platform_info(&self.context, &host, host_monitors, &platform_info_providers)
+                    );
+                }
+                continue;
+            }
+
+            for scheduled in scheduled_monitors.iter_mut() {
+                if scheduled.next_refresh > now {
+                    continue;
+                }
+
+                // Avoid piling up invocations if the previous one hasn't finished yet.
+                if let Some((invocation_id, started)) = scheduled.pending {
+                    let mut scheduled_invocations = self.scheduled_invocations.lock().unwrap();
+                    if scheduled_invocations.contains(&invocation_id) {
+                        if now.duration_since(started) < STALE_INVOCATION_TIMEOUT {
+                            continue;
+                        }
+
+                        log::warn!("[{}][{}] Previous scheduled refresh didn't finish in time", host_id, scheduled.monitor_id);
+                        scheduled_invocations.remove(&invocation_id);
+                    }
+                    scheduled.pending = None;
+                }
+
+                // Non-critical monitors are refreshed less often when the host is down.
+                if host_status == HostStatus::Down && !scheduled.is_critical {
+                    scheduled.backoff_level = scheduled.backoff_level.saturating_add(1);
+                }
+                else {
+                    scheduled.backoff_level = 0;
+                }
+
+                scheduled.next_refresh = now + scheduled.next_interval();
+
+                let monitors_to_refresh = host_monitors.iter()
+                    .filter(|(monitor_id, monitor)| {
+                        **monitor_id == scheduled.monitor_id ||
+                        monitor.get_metadata_self().parent_module.is_some_and(|parent| parent.id == scheduled.monitor_id)
+                    })
+                    .collect::<HashMap<_, _>>();
+
+                let new_invocation_ids = MonitorManager::request_monitor_refresh(
+                    &self.context, host.clone(), monitors_to_refresh, Some(&self.scheduled_invocations)
+                );
+                if let Some(invocation_id) = new_invocation_ids.first() {
+                    scheduled.pending = Some((*invocation_id, now));
+                }
+                invocation_ids.extend(new_invocation_ids);
+            }
+        }
+
+        Ok(invocation_ids)
+    }
+}
+
+/// Base monitor (and its extensions) that is refreshed periodically.
+#[derive(Clone)]
+pub(super) struct ScheduledMonitor {
+    monitor_id: String,
+    /// Interval in seconds.
+    interval: u64,
+    is_critical: bool,
+    /// Set when the scheduler is created.
+    next_refresh: Instant,
+    /// Invocation ID and start time of the latest scheduled refresh.
+    pending: Option<(u64, Instant)>,
+    /// Increased on every refresh while the host is down.
+    backoff_level: u32,
+}
+
+impl ScheduledMonitor {
+    pub(super) fn new(monitor_id: String, interval: u64, is_critical: bool) -> Self {
+        ScheduledMonitor {
+            monitor_id,
+            interval,
+            is_critical,
+            next_refresh: Instant::now(),
+            pending: None,
+            backoff_level: 0,
+        }
+    }
+
+    fn next_interval(&self) -> Duration {
+        let interval = Duration::from_secs(self.interval);
+        let backed_off = interval.saturating_mul(2_u32.saturating_pow(self.backoff_level.min(16)));
+        let capped = if self.backoff_level > 0 { backed_off.min(MAX_BACKOFF_INTERVAL).max(interval) } else { interval };
+
+        capped + self.jitter()
+    }
+
+    /// Random delay so that monitors with the same interval don't all run at the same time.
+    fn jitter(&self) -> Duration {
+        let max_jitter = Duration::from_secs(self.interval).mul_f64(SCHEDULER_JITTER);
+        max_jitter.mul_f64(rand::rng().random::<f64>())
+    }
+}
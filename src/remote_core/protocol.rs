@@ -8,13 +8,22 @@ use std::io::{self, Read, Write};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::command_handler::CommandButtonData;
+use crate::audit_log::{AuditQuery, AuditRecord};
+use crate::command_handler::{BulkExecution, BulkRequest, CommandButtonData};
 use crate::configuration::CustomCommandConfig;
 use crate::frontend::frontend::VerificationRequest;
 use crate::frontend::{DisplayData, HostDisplayData};
+use crate::metrics::lmserver::Metric;
 
-pub const PROTOCOL_VERSION: u16 = 8;
+pub const PROTOCOL_VERSION: u16 = 15;
+/// Larger messages are split into several frames.
 pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+/// Limit for messages reassembled from frames. File contents are sent in chunks instead of one message.
+pub const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+/// Size of file contents sent in one message.
+pub const FILE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Set in the frame length when more frames of the same message follow.
+const CONTINUATION_FLAG: u32 = 1 << 31;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum ClientMessage {
@@ -27,6 +36,18 @@ pub enum ClientMessage {
         command_id: String,
         parameters: Vec<String>,
     },
+    ExecuteBulk {
+        request_id: u64,
+        request: BulkRequest,
+    },
+    GetBulkExecution {
+        request_id: u64,
+        bulk_id: u64,
+    },
+    CancelBulkExecution {
+        request_id: u64,
+        bulk_id: u64,
+    },
     CommandsForHost {
         request_id: u64,
         host_id: String,
@@ -87,11 +108,15 @@ pub enum ClientMessage {
         command_id: String,
         remote_file_path: String,
     },
-    WriteCachedFile {
+    /// Chunks are sent in order and each one is acknowledged with `WriteCachedFileResult`.
+    /// Offset 0 starts a new write and the cached file is replaced after the final chunk.
+    WriteCachedFileChunk {
         request_id: u64,
         host_id: String,
         remote_file_path: String,
-        contents: Vec<u8>,
+        offset: u64,
+        chunk: Vec<u8>,
+        is_final: bool,
     },
     RemoveCachedFile {
         request_id: u64,
@@ -119,6 +144,31 @@ pub enum ClientMessage {
         hosts_yml: String,
         groups_yml: String,
     },
+    /// Monitor and label are optional. Without them the whole host is silenced.
+    CreateSilence {
+        request_id: u64,
+        host_id: String,
+        monitor_id: Option<String>,
+        label: Option<String>,
+        duration_secs: u64,
+        comment: String,
+    },
+    CancelSilence {
+        request_id: u64,
+        silence_id: u64,
+    },
+    /// Start and end times are unix timestamps in seconds.
+    QueryMetrics {
+        request_id: u64,
+        host_id: String,
+        monitor_id: String,
+        start_time: i64,
+        end_time: i64,
+    },
+    QueryAuditLog {
+        request_id: u64,
+        query: AuditQuery,
+    },
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -130,6 +180,18 @@ pub enum ServerMessage {
         request_id: u64,
         invocation_id: u64,
     },
+    BulkExecutionStarted {
+        request_id: u64,
+        bulk_id: u64,
+    },
+    BulkExecution {
+        request_id: u64,
+        execution: Option<BulkExecution>,
+    },
+    BulkExecutionCancelled {
+        request_id: u64,
+        found: bool,
+    },
     CommandsForHost {
         request_id: u64,
         host_id: String,
@@ -197,19 +259,47 @@ pub enum ServerMessage {
     UpdateConfigOk {
         request_id: u64,
     },
+    SilenceCreated {
+        request_id: u64,
+        silence_id: u64,
+    },
+    SilenceCancelled {
+        request_id: u64,
+        found: bool,
+    },
+    /// Label as key.
+    Metrics {
+        request_id: u64,
+        metrics: HashMap<String, Vec<Metric>>,
+    },
+    /// Newest first.
+    AuditLog {
+        request_id: u64,
+        records: Vec<AuditRecord>,
+    },
 }
 
 pub fn read_message<T: DeserializeOwned, Reader: Read>(reader: &mut Reader) -> io::Result<T> {
     let mut length_buffer = [0_u8; 4];
-    reader.read_exact(&mut length_buffer)?;
+    let mut message_buffer = Vec::new();
 
-    let message_length = u32::from_be_bytes(length_buffer) as usize;
-    if message_length == 0 || message_length > MAX_FRAME_SIZE {
-        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid frame length"));
-    }
+    loop {
+        reader.read_exact(&mut length_buffer)?;
 
-    let mut message_buffer = vec![0_u8; message_length];
-    reader.read_exact(&mut message_buffer)?;
+        let header = u32::from_be_bytes(length_buffer);
+        let frame_length = (header & !CONTINUATION_FLAG) as usize;
+        if frame_length == 0 || frame_length > MAX_FRAME_SIZE || message_buffer.len() + frame_length > MAX_MESSAGE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid frame length"));
+        }
+
+        let frame_start = message_buffer.len();
+        message_buffer.resize(frame_start + frame_length, 0);
+        reader.read_exact(&mut message_buffer[frame_start..])?;
+
+        if header & CONTINUATION_FLAG == 0 {
+            break;
+        }
+    }
 
     bincode::deserialize(&message_buffer).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
 }
@@ -217,12 +307,21 @@ pub fn read_message<T: DeserializeOwned, Reader: Read>(reader: &mut Reader) -> i
 pub fn write_message<T: Serialize, Writer: Write>(writer: &mut Writer, message: &T) -> io::Result<()> {
     let serialized = bincode::serialize(message).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
 
-    if serialized.len() > MAX_FRAME_SIZE {
+    if serialized.len() > MAX_MESSAGE_SIZE {
         return Err(io::Error::new(io::ErrorKind::InvalidData, "Message is too large"));
     }
 
-    writer.write_all(&(serialized.len() as u32).to_be_bytes())?;
-    writer.write_all(&serialized)?;
+    let mut frames = serialized.chunks(MAX_FRAME_SIZE).peekable();
+    while let Some(frame) = frames.next() {
+        let mut header = frame.len() as u32;
+        if frames.peek().is_some() {
+            header |= CONTINUATION_FLAG;
+        }
+
+        writer.write_all(&header.to_be_bytes())?;
+        writer.write_all(frame)?;
+    }
+
     writer.flush()?;
     Ok(())
 }
@@ -11,6 +11,7 @@ use crate::configuration;
 use crate::error::LkError;
 use crate::file_handler;
 use crate::frontend;
+use crate::metrics::{MetricsExporter, MetricsManager};
 use crate::Configuration;
 use crate::CoreComponents;
 use crate::ModuleFactory;
@@ -18,6 +19,11 @@ use crate::ModuleFactory;
 pub struct CoreRuntime {
     pub core: CoreComponents,
     pub config_dir: String,
+    /// Records chart data for the whole time the core is running. Only used if charts are enabled.
+    pub metrics_manager: Option<MetricsManager>,
+    metrics_dir: Option<PathBuf>,
+    /// Serves monitoring data for Prometheus. Only used if the exporter is enabled.
+    pub exporter: Option<MetricsExporter>,
 }
 
 impl CoreRuntime {
@@ -38,7 +44,62 @@ impl CoreRuntime {
             log::info!("Initialized {} host(s)", host_ids.len());
         }
 
-        Ok(CoreRuntime { core, config_dir })
+        let mut runtime = CoreRuntime {
+            core,
+            config_dir,
+            metrics_manager: None,
+            metrics_dir: None,
+            exporter: None,
+        };
+
+        if main_config.preferences.show_charts {
+            let metrics_dir = file_handler::get_data_dir()?.join("metrics");
+            if let Err(error) = runtime.start_metrics(metrics_dir) {
+                log::error!("Failed to start metrics store. Charts will not be available: {}", error);
+            }
+        }
+
+        runtime.start_exporter(&main_config.exporter);
+        Ok(runtime)
+    }
+
+    /// Starts recording chart data into the given directory.
+    pub fn start_metrics(&mut self, metrics_dir: PathBuf) -> Result<(), LkError> {
+        let mut metrics_manager = MetricsManager::new_headless();
+        metrics_manager.start_service_in(metrics_dir.clone())?;
+        let recorder_sender = metrics_manager.start_recording()?;
+        self.core.host_manager.borrow_mut().add_observer(recorder_sender);
+
+        self.metrics_manager = Some(metrics_manager);
+        self.metrics_dir = Some(metrics_dir);
+        Ok(())
+    }
+
+    /// Errors are logged since the core is usable without the exporter.
+    fn start_exporter(&mut self, exporter_config: &configuration::Exporter) {
+        if !exporter_config.enabled {
+            return;
+        }
+
+        let reader = self.core.host_manager.borrow().display_data_reader();
+        match MetricsExporter::start(&exporter_config.listen_address, reader) {
+            Ok(exporter) => self.exporter = Some(exporter),
+            Err(error) => log::error!("Failed to start exporter: {}", error),
+        }
+    }
+
+    /// Re-initializes core components, e.g. after configuration changes. Metrics recording continues if it was enabled.
+    pub fn reinitialize(&mut self, main_config: &Configuration, hosts_config: &configuration::Hosts) -> Result<(), LkError> {
+        let module_factory = self.core.module_factory.clone();
+        self.stop();
+        self.core = crate::initialize_core(main_config, hosts_config, module_factory)?;
+
+        if let Some(metrics_dir) = self.metrics_dir.take() {
+            self.start_metrics(metrics_dir)?;
+        }
+
+        self.start_exporter(&main_config.exporter);
+        Ok(())
     }
 
     pub fn default_socket_path() -> Result<PathBuf, LkError> {
@@ -54,6 +115,15 @@ impl CoreRuntime {
     pub fn stop(&mut self) {
         self.core.command_handler.stop();
         self.core.monitor_manager.stop();
+        self.core.alert_manager.stop();
+        if let Some(mut exporter) = self.exporter.take() {
+            exporter.stop();
+        }
+        if let Some(mut metrics_manager) = self.metrics_manager.take() {
+            if let Err(error) = metrics_manager.stop() {
+                log::error!("Failed to stop metrics store: {}", error);
+            }
+        }
         self.core.host_manager.borrow_mut().stop();
         self.core.connection_manager.stop();
     }
@@ -105,6 +105,27 @@ fn handle_connected_client_loop(stream: &mut UnixStream, runtime: &mut CoreRunti
                     })?;
                 }
             },
+            ClientMessage::ExecuteBulk { request_id, request } => match runtime.core.command_handler.execute_bulk(request) {
+                Ok(bulk_id) => {
+                    session.send_message(&ServerMessage::BulkExecutionStarted { request_id, bulk_id })?;
+                }
+                Err(error) => {
+                    session.send_message(&ServerMessage::Error {
+                        request_id: Some(request_id),
+                        message: error.to_string(),
+                    })?;
+                }
+            },
+            ClientMessage::GetBulkExecution { request_id, bulk_id } => {
+                session.send_message(&ServerMessage::BulkExecution {
+                    request_id,
+                    execution: runtime.core.command_handler.get_bulk_execution(bulk_id),
+                })?;
+            }
+            ClientMessage::CancelBulkExecution { request_id, bulk_id } => {
+                let found = runtime.core.command_handler.cancel_bulk_execution(bulk_id);
+                session.send_message(&ServerMessage::BulkExecutionCancelled { request_id, found })?;
+            }
             ClientMessage::CommandsForHost { request_id, host_id } => {
                 session.send_message(&ServerMessage::CommandsForHost {
                     request_id,
@@ -216,15 +237,26 @@ fn handle_connected_client_loop(stream: &mut UnixStream, runtime: &mut CoreRunti
                     }
                 }
             }
-            ClientMessage::WriteCachedFile {
+            ClientMessage::WriteCachedFileChunk {
                 request_id,
                 host_id,
                 remote_file_path,
-                contents,
+                offset,
+                chunk,
+                is_final,
             } => {
                 let path = runtime.core.command_handler.cache_file_path_for_remote(&host_id, &remote_file_path);
-                runtime.core.command_handler.write_file(&path, contents);
-                session.send_message(&ServerMessage::WriteCachedFileResult { request_id })?;
+                match runtime.core.command_handler.write_file_chunk(&path, offset, &chunk, is_final) {
+                    Ok(()) => {
+                        session.send_message(&ServerMessage::WriteCachedFileResult { request_id })?;
+                    }
+                    Err(error) => {
+                        session.send_message(&ServerMessage::Error {
+                            request_id: Some(request_id),
+                            message: error.to_string(),
+                        })?;
+                    }
+                }
             }
             ClientMessage::RemoveCachedFile { request_id, host_id, remote_file_path } => {
                 let path = runtime.core.command_handler.cache_file_path_for_remote(&host_id, &remote_file_path);
@@ -313,10 +345,8 @@ fn handle_connected_client_loop(stream: &mut UnixStream, runtime: &mut CoreRunti
                             Configuration::write_main_config(&runtime.config_dir, &main)?;
                             Configuration::write_hosts_config(&runtime.config_dir, &hosts)?;
                             Configuration::write_groups_config(&runtime.config_dir, &groups)?;
-                            let module_factory = runtime.core.module_factory.clone();
-                            runtime.stop();
                             let (main_read, hosts_read, _groups) = Configuration::read(&runtime.config_dir)?;
-                            runtime.core = crate::initialize_core(&main_read, &hosts_read, module_factory)?;
+                            runtime.reinitialize(&main_read, &hosts_read)?;
                             Ok(())
                         })();
                         match update_result {
@@ -345,6 +375,71 @@ fn handle_connected_client_loop(stream: &mut UnixStream, runtime: &mut CoreRunti
                     }
                 }
             }
+            ClientMessage::CreateSilence {
+                request_id,
+                host_id,
+                monitor_id,
+                label,
+                duration_secs,
+                comment,
+            } => {
+                let result = runtime.core.host_manager.borrow().create_silence(&host_id, monitor_id, label, duration_secs, comment);
+                match result {
+                    Ok(silence_id) => {
+                        session.send_message(&ServerMessage::SilenceCreated { request_id, silence_id })?;
+                    }
+                    Err(error) => {
+                        session.send_message(&ServerMessage::Error {
+                            request_id: Some(request_id),
+                            message: error.to_string(),
+                        })?;
+                    }
+                }
+            }
+            ClientMessage::CancelSilence { request_id, silence_id } => {
+                let found = runtime.core.host_manager.borrow().cancel_silence(silence_id);
+                session.send_message(&ServerMessage::SilenceCancelled { request_id, found })?;
+            }
+            ClientMessage::QueryMetrics {
+                request_id,
+                host_id,
+                monitor_id,
+                start_time,
+                end_time,
+            } => {
+                let result = match runtime.metrics_manager.as_ref() {
+                    Some(metrics_manager) => metrics_manager.query_metrics(&host_id, &monitor_id, start_time, end_time),
+                    None => Err(LkError::other("Charts are not enabled on the core")),
+                };
+                match result {
+                    Ok(metrics) => {
+                        session.send_message(&ServerMessage::Metrics { request_id, metrics })?;
+                    }
+                    Err(error) => {
+                        session.send_message(&ServerMessage::Error {
+                            request_id: Some(request_id),
+                            message: error.to_string(),
+                        })?;
+                    }
+                }
+            }
+            ClientMessage::QueryAuditLog { request_id, query } => {
+                let result = match runtime.core.audit_log.as_ref() {
+                    Some(audit_log) => audit_log.query(&query),
+                    None => Err(LkError::other("Audit log is not enabled on the core")),
+                };
+                match result {
+                    Ok(records) => {
+                        session.send_message(&ServerMessage::AuditLog { request_id, records })?;
+                    }
+                    Err(error) => {
+                        session.send_message(&ServerMessage::Error {
+                            request_id: Some(request_id),
+                            message: error.to_string(),
+                        })?;
+                    }
+                }
+            }
         }
     }
 }
@@ -12,9 +12,12 @@ pub use version_number::VersionNumber;
 pub mod string_validation;
 
 pub mod shell_command;
-pub use shell_command::{sh_single_quoted, ShellCommand};
+pub use shell_command::{sh_single_quoted, PrivilegeEscalation, ShellCommand};
 
 pub mod error_message;
 pub use error_message::ErrorMessage;
 
 pub mod sha256;
+
+pub mod cron_schedule;
+pub use cron_schedule::CronSchedule;
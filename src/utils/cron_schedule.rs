@@ -0,0 +1,150 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use chrono::{Datelike, NaiveDate, TimeZone, Timelike};
+
+use crate::error::LkError;
+
+/// Cron-like schedule with 5 fields: minute, hour, day of month, month and day of week.
+/// Supports `*`, single values, ranges (`1-5`), lists (`1,3,5`) and steps (`*/15`, `0-30/10`).
+/// Day of week is 0-7 where both 0 and 7 are Sunday.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    /// Like in cron, if both day fields are restricted, either one matching is enough.
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(spec: &str) -> Result<Self, LkError> {
+        let fields = spec.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 5 {
+            return Err(LkError::invalid_parameter("Cron schedule must have 5 fields", spec));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // 7 is also Sunday.
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    /// Checks if the schedule matches the given time with minute precision.
+    pub fn matches<Tz: TimeZone>(&self, time: &chrono::DateTime<Tz>) -> bool {
+        self.date_matches(time.date_naive()) &&
+        self.minutes[time.minute() as usize] &&
+        self.hours[time.hour() as usize]
+    }
+
+    /// Returns the latest matching time that is at or before `time` but not before `earliest`.
+    /// Skips over non-matching days and hours instead of checking every minute.
+    pub fn latest_between<Tz: TimeZone>(&self, earliest: &chrono::DateTime<Tz>, time: &chrono::DateTime<Tz>) -> Option<chrono::DateTime<Tz>> {
+        let timezone = time.timezone();
+        let end = time.naive_local();
+        let mut date = end.date();
+
+        while date >= earliest.date_naive() {
+            if self.date_matches(date) {
+                let max_hour = if date == end.date() { end.hour() } else { 23 };
+
+                for hour in (0..=max_hour).rev().filter(|hour| self.hours[*hour as usize]) {
+                    let max_minute = if date == end.date() && hour == end.hour() { end.minute() } else { 59 };
+
+                    for minute in (0..=max_minute).rev().filter(|minute| self.minutes[*minute as usize]) {
+                        // Local times that don't exist because of DST changes are skipped.
+                        let Some(candidate) = date.and_hms_opt(hour, minute, 0)
+                            .and_then(|naive| timezone.from_local_datetime(&naive).earliest()) else {
+                            continue;
+                        };
+
+                        if candidate < *earliest {
+                            return None;
+                        }
+                        else if candidate <= *time {
+                            return Some(candidate);
+                        }
+                    }
+                }
+            }
+
+            date = date.pred_opt()?;
+        }
+
+        None
+    }
+
+    fn date_matches(&self, date: NaiveDate) -> bool {
+        let day_of_month_matches = self.days_of_month[date.day() as usize];
+        let day_of_week_matches = self.days_of_week[date.weekday().num_days_from_sunday() as usize];
+
+        let day_matches = if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month_matches || day_of_week_matches
+        }
+        else {
+            day_of_month_matches && day_of_week_matches
+        };
+
+        self.months[date.month() as usize] && day_matches
+    }
+}
+
+/// Returns a lookup table indexed by the value.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, LkError> {
+    let mut allowed = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<u32>().ok().filter(|step| *step > 0)
+                    .ok_or_else(|| LkError::invalid_parameter("Invalid step in cron schedule", part))?;
+                (range, step)
+            },
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        }
+        else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, max)?, parse_value(end, min, max)?)
+        }
+        else {
+            let value = parse_value(range, min, max)?;
+            // E.g. "5/10" means starting from 5.
+            if step > 1 { (value, max) } else { (value, value) }
+        };
+
+        if start > end {
+            return Err(LkError::invalid_parameter("Invalid range in cron schedule", part));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+
+    Ok(allowed)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, LkError> {
+    value.parse::<u32>().ok()
+        .filter(|value| *value >= min && *value <= max)
+        .ok_or_else(|| LkError::invalid_parameter("Invalid value in cron schedule", value))
+}
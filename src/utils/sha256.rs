@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::{fs, io, path::Path};
+
 use sha2::{Digest, Sha256};
 
 pub fn hash(contents: &[u8]) -> String {
@@ -12,3 +14,11 @@ pub fn hash(contents: &[u8]) -> String {
 
     hex::encode(hash)
 }
+
+/// Hashes a file without reading it to memory all at once.
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
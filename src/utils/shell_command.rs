@@ -6,12 +6,76 @@
 use std::collections::VecDeque;
 use std::process;
 
+use serde::{Deserialize, Serialize};
+
 /// For building command line commands correctly.
 pub struct ShellCommand {
     arguments: VecDeque<String>,
     piped_to: VecDeque<Vec<String>>,
     pub ignore_stderr: bool,
     pub use_sudo: bool,
+    /// Used with `use_sudo`.
+    pub escalation: PrivilegeEscalation,
+}
+
+/// How commands that require higher privileges are run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivilegeEscalation {
+    #[default]
+    Sudo,
+    Doas,
+    Run0,
+    Su,
+}
+
+impl PrivilegeEscalation {
+    /// Rewrites a command rendered with sudo (see `ShellCommand::use_sudo`) to use this method instead.
+    /// Commands not starting with sudo are returned as is.
+    pub fn rewrite(&self, command: &str) -> String {
+        let trimmed = command.trim_start();
+        let Some(rest) = trimmed.strip_prefix("\"sudo\" ").or_else(|| trimmed.strip_prefix("sudo ")) else {
+            return command.to_string();
+        };
+
+        match self {
+            PrivilegeEscalation::Sudo => command.to_string(),
+            // Non-interactive, fails instead of asking for a password.
+            PrivilegeEscalation::Doas => format!("\"doas\" \"-n\" {}", rest),
+            PrivilegeEscalation::Run0 => format!("\"run0\" {}", rest),
+            // Runs the whole command line, including pipes, as root.
+            PrivilegeEscalation::Su => format!("\"su\" \"root\" \"-c\" {}", sh_single_quoted(rest)),
+        }
+    }
+
+    /// For interactive commands. Not possible with su since it takes the command as a single argument.
+    pub fn interactive_prefix(&self) -> Option<&'static str> {
+        match self {
+            PrivilegeEscalation::Sudo => Some("sudo"),
+            PrivilegeEscalation::Doas => Some("doas"),
+            PrivilegeEscalation::Run0 => Some("run0"),
+            PrivilegeEscalation::Su => None,
+        }
+    }
+
+    /// For rsync's `--rsync-path`. Not possible with su since it takes the command as a single argument.
+    pub fn rsync_path(&self) -> Option<&'static str> {
+        match self {
+            PrivilegeEscalation::Sudo => Some("sudo rsync"),
+            PrivilegeEscalation::Doas => Some("doas -n rsync"),
+            PrivilegeEscalation::Run0 => Some("run0 rsync"),
+            PrivilegeEscalation::Su => None,
+        }
+    }
+
+    /// Command for an interactive root shell.
+    pub fn login_shell(&self) -> Vec<&'static str> {
+        match self {
+            PrivilegeEscalation::Sudo => vec!["sudo", "-i"],
+            PrivilegeEscalation::Doas => vec!["doas", "-s"],
+            PrivilegeEscalation::Run0 => vec!["run0"],
+            PrivilegeEscalation::Su => vec!["su", "-"],
+        }
+    }
 }
 
 impl ShellCommand {
@@ -21,6 +85,7 @@ impl ShellCommand {
             piped_to: VecDeque::new(),
             ignore_stderr: false,
             use_sudo: false,
+            escalation: PrivilegeEscalation::default(),
         }
     }
 
@@ -56,6 +121,11 @@ impl ShellCommand {
         self
     }
 
+    pub fn escalate_with(mut self, escalation: PrivilegeEscalation) -> Self {
+        self.escalation = escalation;
+        self
+    }
+
     pub fn pipe_to<IntoString>(&mut self, arguments: Vec<IntoString>) -> &mut Self
     where
         IntoString: Into<String>,
@@ -155,6 +225,10 @@ impl ToString for ShellCommand {
                 }
             }
 
+            if self.use_sudo {
+                command_string = self.escalation.rewrite(&command_string);
+            }
+
             command_string
         }
     }
@@ -113,3 +113,21 @@ pub fn ansi_to_rich_text(text: &str) -> String {
     })
     .into_owned()
 }
+
+/// Formats a byte count with binary units, e.g. "1.5 MiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -6,6 +6,7 @@ use lightkeeper::module::command::docker;
 use lightkeeper::module::command::docker::compose;
 use lightkeeper::module::platform_info::*;
 use lightkeeper::enums::Criticality;
+use lightkeeper::module::connection::UnixSocketRequest;
 
 use crate::{CommandTestHarness, StubSsh2};
 
@@ -13,7 +14,7 @@ use crate::{CommandTestHarness, StubSsh2};
 #[test]
 fn test_inspect() {
     let new_stub_ssh = |_settings: &HashMap<String, String>| {
-        StubSsh2::new(r#""sudo" "curl" "-s" "--unix-socket" "/var/run/docker.sock" "http://localhost/containers/test-container/json?all=true""#,
+        StubSsh2::new_socket(UnixSocketRequest::get("/var/run/docker.sock", "/containers/test-container/json").use_sudo(),
 r#"{
   "Id": "8001819398ea4b320c0604a26f0d0f15ebd0353bc76f113f1d9ac629a83458d8",
   "Created": "2025-11-16T10:49:42.466460495Z",
@@ -297,6 +298,68 @@ r#"{
     });
 }
 
+#[test]
+fn test_logs() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(
+            UnixSocketRequest::get("/var/run/docker.sock", "/containers/test-container/logs?follow=true&stdout=true&stderr=true&timestamps=true&tail=1000")
+                .use_sudo()
+                .demultiplex(),
+            "2025-12-01T10:00:00.000000000Z Starting service\n2025-12-01T10:00:01.000000000Z Service started", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (docker::Logs::get_metadata(), docker::Logs::new_command_module),
+    );
+
+    let module_id = docker::Logs::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec!["test-container".to_string()]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Normal);
+        assert!(result.message.starts_with("2025-12-01T10:00:00"));
+    });
+}
+
+#[test]
+fn test_events() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(UnixSocketRequest::get("/var/run/docker.sock", "/events").use_sudo(),
+            r#"{"Type":"container","Action":"start","Actor":{"ID":"8001819398ea4b32","Attributes":{"name":"service1"}},"time":1764583200}"#, 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (docker::Events::get_metadata(), docker::Events::new_command_module),
+    );
+
+    let module_id = docker::Events::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec![]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Normal);
+    });
+}
+
+#[test]
+fn test_format_events() {
+    let output = concat!(
+        r#"{"Type":"container","Action":"start","Actor":{"ID":"8001819398ea4b32","Attributes":{"name":"service1"}},"time":1764583200}"#, "\n",
+        r#"{"Type":"network","Action":"connect","Actor":{"ID":"a1b2c3d4e5f6a7b8c9"},"time":1764583201}"#, "\n",
+        r#"{"Type":"container","Act"#,
+    );
+
+    assert_eq!(
+        docker::events::format_events(output),
+        "2025-12-01T10:00:00Z container start service1\n\
+         2025-12-01T10:00:01Z network connect a1b2c3d4e5f6\n\
+         {\"Type\":\"container\",\"Act"
+    );
+}
+
 // Docker Compose command tests
 
 #[test]
@@ -1,3 +1,5 @@
 pub mod docker;
 pub mod packages;
-pub mod systemd;
\ No newline at end of file
+pub mod podman;
+pub mod systemd;
+pub mod storage;
\ No newline at end of file
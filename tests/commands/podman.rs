@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use lightkeeper::module::*;
+use lightkeeper::module::command::*;
+use lightkeeper::module::command::podman;
+use lightkeeper::module::platform_info::*;
+use lightkeeper::enums::Criticality;
+use lightkeeper::module::connection::UnixSocketRequest;
+
+use crate::{CommandTestHarness, StubSsh2};
+
+#[test]
+fn test_logs() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(
+            UnixSocketRequest::get("/run/podman/podman.sock", "/v4.0.0/libpod/containers/test-container/logs?follow=true&stdout=true&stderr=true&timestamps=true&tail=1000")
+                .use_sudo()
+                .demultiplex(),
+            "2025-12-01T10:00:00.000000000Z Starting service\n2025-12-01T10:00:01.000000000Z Service started", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (podman::Logs::get_metadata(), podman::Logs::new_command_module),
+    );
+
+    let module_id = podman::Logs::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec!["test-container".to_string()]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Normal);
+        assert!(result.message.starts_with("2025-12-01T10:00:00"));
+    });
+}
+
+#[test]
+fn test_logs_rootless() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(
+            UnixSocketRequest::get("/run/user/1000/podman/podman.sock", "/v4.0.0/libpod/containers/test-container/logs?follow=true&stdout=true&stderr=true&timestamps=true&tail=50")
+                .demultiplex(),
+            "2025-12-01T10:00:00.000000000Z Starting service", 0)
+    };
+
+    let new_logs = |_settings: &HashMap<String, String>| {
+        podman::Logs::new_command_module(&HashMap::from([
+            (String::from("as_root"), String::from("false")),
+            (String::from("socket_path"), String::from("/run/user/1000/podman/podman.sock")),
+            (String::from("tail"), String::from("50")),
+        ]))
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (podman::Logs::get_metadata(), new_logs),
+    );
+
+    let module_id = podman::Logs::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec!["test-container".to_string()]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Normal);
+    });
+}
+
+#[test]
+fn test_events() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(UnixSocketRequest::get("/run/podman/podman.sock", "/v4.0.0/libpod/events?stream=true").use_sudo(),
+            r#"{"Type":"container","Action":"died","Actor":{"ID":"8001819398ea4b32","Attributes":{"name":"service1"}},"time":1764583200}"#, 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (podman::Events::get_metadata(), podman::Events::new_command_module),
+    );
+
+    let module_id = podman::Events::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec![]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Normal);
+    });
+}
+
+#[test]
+fn test_events_connect_failed() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_socket(UnixSocketRequest::get("/run/podman/podman.sock", "/v4.0.0/libpod/events?stream=true").use_sudo(),
+            "", 7)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (podman::Events::get_metadata(), podman::Events::new_command_module),
+    );
+
+    let module_id = podman::Events::get_metadata().module_spec.id;
+
+    harness.execute_command(&module_id, vec![]);
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Error);
+        assert_eq!(result.message, "Couldn't connect to Podman API.");
+    });
+}
@@ -0,0 +1,181 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use lightkeeper::module::*;
+use lightkeeper::module::command::*;
+use lightkeeper::module::command::storage;
+use lightkeeper::module::platform_info::*;
+use lightkeeper::enums::Criticality;
+use lightkeeper::error::ErrorKind;
+
+use crate::{CommandTestHarness, StubSsh2};
+
+
+#[test]
+fn test_smart_selftest() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""sudo" "smartctl" "-d" "sat" "-t" "short" "/dev/sda""#,
+r#"smartctl 7.3 2022-02-28 r5338 [x86_64-linux-6.1.0-18-amd64] (local build)
+
+=== START OF OFFLINE IMMEDIATE AND SELF-TEST SECTION ===
+Sending command: "Execute SMART Short self-test routine immediately in off-line mode".
+Drive command "Execute SMART Short self-test routine immediately in off-line mode" successful.
+Testing has begun.
+Please wait 2 minutes for test to complete.
+Test will complete after Sat Oct 17 12:02:00 2026 EEST
+Use smartctl -X to abort test."#, 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::smart::SelfTest::get_metadata(), storage::smart::SelfTest::new_command_module),
+    );
+
+    let module_id = storage::smart::SelfTest::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["/dev/sda".to_string(), "sat".to_string(), "short".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Info);
+        assert_eq!(result.message, "Please wait 2 minutes for test to complete.\nTest will complete after Sat Oct 17 12:02:00 2026 EEST");
+    });
+}
+
+#[test]
+fn test_smart_selftest_log() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""sudo" "smartctl" "-d" "nvme" "-c" "-l" "selftest" "/dev/nvme0""#,
+r#"Self-test Log (NVMe Log 0x06)
+Self-test status: No self-test in progress
+Num  Test_Description  Status                       Power_on_Hours  Failing_LBA  NSID Seg SCT Code
+ 0   Short             Completed without error                3120            -     -   -   -    -"#, 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::smart::SelfTestLog::get_metadata(), storage::smart::SelfTestLog::new_command_module),
+    );
+
+    let module_id = storage::smart::SelfTestLog::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["/dev/nvme0".to_string(), "nvme".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert!(result.message.starts_with("```\nSelf-test Log"));
+        assert!(result.message.contains("Completed without error"));
+    });
+}
+
+#[test]
+fn test_smart_selftest_unknown_type() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""sudo" "smartctl" "-t" "long" "/dev/sdb""#, "Testing has begun.", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::smart::SelfTest::get_metadata(), storage::smart::SelfTest::new_command_module),
+    );
+
+    let module_id = storage::smart::SelfTest::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["/dev/sdb".to_string(), String::new(), "long".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Info);
+        assert_eq!(result.message, "Self-test started");
+    });
+}
+
+#[test]
+fn test_smart_selftest_missing_parameters() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_any("", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::smart::SelfTest::get_metadata(), storage::smart::SelfTest::new_command_module),
+    );
+
+    let module_id = storage::smart::SelfTest::get_metadata().module_spec.id.clone();
+
+    let error = harness.try_execute_command(&module_id, vec!["/dev/sda".to_string()]).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::InvalidParameter);
+    assert_eq!(error.parameter, Some(String::from("device type")));
+}
+
+#[test]
+fn test_zfs_scrub() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""sudo" "zpool" "scrub" "tank""#, "", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::zfs::Scrub::get_metadata(), storage::zfs::Scrub::new_command_module),
+    );
+
+    let module_id = storage::zfs::Scrub::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["tank".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Info);
+        assert_eq!(result.message, "Scrub started");
+    });
+}
+
+#[test]
+fn test_zfs_snapshot() {
+    // Snapshot name contains the current time.
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new_prefix(r#""sudo" "zfs" "snapshot" "-r" "tank@snapshot_2"#, "", 0)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::zfs::Snapshot::get_metadata(), storage::zfs::Snapshot::new_command_module),
+    );
+
+    let module_id = storage::zfs::Snapshot::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["tank".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Info);
+    });
+}
+
+#[test]
+fn test_mdadm_check_error() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""sudo" "mdadm" "--action=check" "/dev/md0""#,
+            "mdadm: Could not set action for /dev/md0 to check: Device or resource busy", 1)
+    };
+
+    let mut harness = CommandTestHarness::new_command_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::mdadm::Check::get_metadata(), storage::mdadm::Check::new_command_module),
+    );
+
+    let module_id = storage::mdadm::Check::get_metadata().module_spec.id.clone();
+
+    harness.execute_command(&module_id, vec!["/dev/md0".to_string(), "md0".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Error);
+        assert!(result.message.contains("Device or resource busy") || result.error.contains("Device or resource busy"));
+    });
+}
\ No newline at end of file
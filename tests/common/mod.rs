@@ -8,4 +8,6 @@ mod stub_tcp;
 pub use stub_tcp::StubTcp;
 
 mod stub_http;
-pub use stub_http::StubHttp;
\ No newline at end of file
+pub use stub_http::StubHttp;
+mod temp_path;
+pub use temp_path::temp_path;
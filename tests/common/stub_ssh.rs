@@ -25,6 +25,9 @@ const DEFAULT_PARTIAL_MESSAGE_SIZE: usize = 20;
 /// SSH connection module. Manages parallel SSH sessions internally.
 pub struct StubSsh2 {
     responses: HashMap<&'static str, ResponseMessage>,
+    /// Responses for commands starting with the prefix, e.g. when the command contains a timestamp.
+    prefix_responses: Vec<(&'static str, ResponseMessage)>,
+    socket_responses: Vec<(UnixSocketRequest, ResponseMessage)>,
     partial_message_size: usize,
     partial_responses: Arc<Mutex<HashMap<u64, ResponseMessage>>>,
 }
@@ -42,15 +45,69 @@ impl StubSsh2 {
         Box::new(ssh) as connection::Connector
     }
 
+    pub fn new_prefix(request_prefix: &'static str, response: &'static str, exit_code: i32) -> connection::Connector {
+        let mut ssh = StubSsh2::default();
+        ssh.add_prefix_response(request_prefix, response, exit_code);
+        Box::new(ssh) as connection::Connector
+    }
+
+    pub fn new_socket(request: UnixSocketRequest, response: &'static str, exit_code: i32) -> connection::Connector {
+        let mut ssh = StubSsh2::default();
+        ssh.add_socket_response(request, response, exit_code);
+        Box::new(ssh) as connection::Connector
+    }
+
     pub fn add_response(&mut self, request: &'static str, response: &'static str, exit_code: i32) {
         self.responses.insert(request, ResponseMessage::new(response.to_string(), exit_code));
     }
+
+    pub fn add_prefix_response(&mut self, request_prefix: &'static str, response: &'static str, exit_code: i32) {
+        self.prefix_responses.push((request_prefix, ResponseMessage::new(response.to_string(), exit_code)));
+    }
+
+    pub fn add_socket_response(&mut self, request: UnixSocketRequest, response: &'static str, exit_code: i32) {
+        self.socket_responses.push((request, ResponseMessage::new(response.to_string(), exit_code)));
+    }
+
+    fn find_socket_response(&self, request: &UnixSocketRequest) -> Result<ResponseMessage, LkError> {
+        self.socket_responses.iter().find(|(expected, _)| expected == request).map(|(_, response)| response)
+            .or_else(|| self.responses.get("_"))
+            .cloned()
+            .ok_or_else(|| LkError::other_p("No test response set up for socket request", &request.to_string()))
+    }
+
+    /// Returns the response in parts of `partial_message_size`.
+    fn start_partial_response(&self, response: ResponseMessage, invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        if response.message.len() > self.partial_message_size {
+            let response_message = response.message.clone();
+            let (partial_message, remaining_message) = response_message.split_at(self.partial_message_size);
+            self.partial_responses.lock().unwrap().insert(
+                invocation_id,
+                ResponseMessage::new(remaining_message.to_string(), response.return_code)
+            );
+
+            Ok(ResponseMessage::new_partial(partial_message.to_string()))
+        }
+        else {
+            Ok(ResponseMessage::new(strip_newline(&response.message), response.return_code))
+        }
+    }
+
+    fn find_response(&self, message: &str) -> Result<ResponseMessage, LkError> {
+        self.responses.get(message)
+            .or_else(|| self.prefix_responses.iter().find(|(prefix, _)| message.starts_with(prefix)).map(|(_, response)| response))
+            .or_else(|| self.responses.get("_"))
+            .cloned()
+            .ok_or_else(|| LkError::other_p("No test response set up for command", message))
+    }
 }
 
 impl Default for StubSsh2 {
     fn default() -> Self {
         StubSsh2 {
             responses: HashMap::new(),
+            prefix_responses: Vec::new(),
+            socket_responses: Vec::new(),
             partial_message_size: DEFAULT_PARTIAL_MESSAGE_SIZE,
             partial_responses: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -61,6 +118,8 @@ impl Module for StubSsh2 {
     fn new(_settings: &HashMap<String, String>) -> Self {
         StubSsh2 {
             responses: HashMap::new(),
+            prefix_responses: Vec::new(),
+            socket_responses: Vec::new(),
             partial_message_size: 20,
             partial_responses: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -69,43 +128,30 @@ impl Module for StubSsh2 {
 
 impl ConnectionModule for StubSsh2 {
     fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError> {
-        let response = match self.responses.get(message) {
-            Some(response) => response.clone(),
-            None => {
-                match self.responses.get("_") {
-                    Some(response) => response.clone(),
-                    None => return Err(LkError::other_p("No test response set up for command", message))
-                }
-            }
-        };
+        let response = self.find_response(message)?;
 
         Ok(response)
     }
 
     fn send_message_partial(&self, message: &str, invocation_id: u64) -> Result<ResponseMessage, LkError> {
-        let response = match self.responses.get(message) {
-            Some(response) => response.clone(),
-            None => {
-                match self.responses.get("_") {
-                    Some(response) => response.clone(),
-                    None => return Err(LkError::other_p("No test response set up for command", message))
-                }
-            }
-        };
+        let response = self.find_response(message)?;
+        self.start_partial_response(response, invocation_id)
+    }
 
-        if response.message.len() > self.partial_message_size {
-            let response_message = response.message.clone();
-            let (partial_message, remaining_message) = response_message.split_at(self.partial_message_size);
-            self.partial_responses.lock().unwrap().insert(
-                invocation_id,
-                ResponseMessage::new(remaining_message.to_string(), response.return_code)
-            );
+    fn send_socket_request(&self, request: &UnixSocketRequest) -> Result<ResponseMessage, LkError> {
+        self.find_socket_response(request)
+    }
 
-            return Ok(ResponseMessage::new_partial(partial_message.to_string()))
-        }
-        else {
-            Ok(ResponseMessage::new(strip_newline(&response.message), response.return_code))
-        }
+    fn send_socket_request_partial(&self, request: &UnixSocketRequest, invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        let response = self.find_socket_response(request)?;
+        self.start_partial_response(response, invocation_id)
+    }
+
+    /// Output is returned as binary data too, e.g. for file downloads.
+    fn send_message_binary(&self, command: &str, _stdin_data: &[u8]) -> Result<ResponseMessage, LkError> {
+        let mut response = self.find_response(command)?;
+        response.data = response.message.as_bytes().to_vec();
+        Ok(response)
     }
 
     fn receive_partial_response(&self, invocation_id: u64) -> Result<ResponseMessage, LkError> {
@@ -0,0 +1,15 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Path in the system temp directory that is unique to the test process. Anything left there by earlier runs is removed.
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lightkeeper-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
@@ -433,6 +433,13 @@ impl CommandTestHarness {
         self.wait_for_completion();
     }
 
+    /// For commands that are expected to fail before anything is sent.
+    fn try_execute_command(&mut self, command_id: &str, parameters: Vec<String>) -> Result<u64, lightkeeper::error::LkError> {
+        let result = self.command_manager.execute(&TEST_HOST_ID, &command_id, &parameters);
+        self.wait_for_completion();
+        result
+    }
+
     fn wait_for_completion(&self) {
         std::thread::sleep(std::time::Duration::from_millis(200));
     }
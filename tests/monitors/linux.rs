@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use lightkeeper::module::*;
+use lightkeeper::module::connection;
 use lightkeeper::module::monitoring::*;
 use lightkeeper::module::monitoring::linux;
 use lightkeeper::module::platform_info::*;
@@ -8,6 +9,59 @@ use lightkeeper::enums::Criticality;
 
 use crate::{MonitorTestHarness, StubSsh2};
 
+macro_rules! sensors_command {
+    ($prefix:literal) => {
+        concat!(
+            "if ls /sys/class/hwmon/hwmon*/*_input >/dev/null 2>&1; then ",
+            "grep -H . /sys/class/hwmon/hwmon*/name /sys/class/hwmon/hwmon*/", $prefix, "*_* 2>/dev/null; ",
+            "elif command -v sensors >/dev/null; then sensors -j; else exit 127; fi"
+        )
+    };
+}
+
+const SENSORS_JSON: &str = r#"{
+   "k10temp-pci-00c3":{
+      "Adapter": "PCI adapter",
+      "Tctl":{
+         "temp1_input": 95.500
+      }
+   },
+   "thinkpad-isa-0000":{
+      "Adapter": "ISA adapter",
+      "fan1":{
+         "fan1_input": 2900.000
+      },
+      "CPU":{
+         "temp1_input": 60.000,
+         "temp1_max": 85.000,
+         "temp1_crit": 98.000
+      }
+   }
+}"#;
+
+const RESTART_REQUIRED_COMMAND: &str = concat!(
+    "sudo sh -c '",
+    "if [ -f /var/run/reboot-required ]; then ",
+    "echo \"=== reboot-required\"; cat /var/run/reboot-required.pkgs 2>/dev/null; ",
+    "fi; ",
+    "if command -v needs-restarting >/dev/null; then ",
+    "echo \"=== needs-restarting\"; needs-restarting -r >/dev/null 2>&1; echo $?; needs-restarting -s 2>/dev/null; ",
+    "fi; ",
+    "if [ -e /run/booted-system ]; then ",
+    "echo \"=== nixos\"; ",
+    "for item in kernel initrd kernel-modules; do ",
+    "echo \"$item $(readlink -f /run/booted-system/$item) $(readlink -f /run/current-system/$item)\"; ",
+    "done; ",
+    "fi; ",
+    "echo \"=== processes\"; ",
+    "for maps in /proc/[0-9]*/maps; do ",
+    "pid=${maps#/proc/}; pid=${pid%/maps}; ",
+    "grep -qs \"\\.so.* (deleted)$\" \"$maps\" || continue; ",
+    "cgroup=$(grep -s -m1 -E \"^(0::|[0-9]+:name=systemd:)\" /proc/$pid/cgroup | cut -d: -f3); ",
+    "echo \"$pid ${cgroup:--} $(cat /proc/$pid/comm 2>/dev/null)\"; ",
+    "done'"
+);
+
 
 
 #[test]
@@ -48,7 +102,10 @@ r#"[{"ifindex":1,"ifname":"lo","flags":["LOOPBACK","UP","LOWER_UP"],"mtu":65536,
 #[test]
 fn test_kernel() {
     let new_stub_ssh = |_settings: &HashMap<String, String>| {
-        StubSsh2::new("uname -r -m", "6.1.0-41-amd64 x86_64", 0)
+        let mut ssh = StubSsh2::default();
+        ssh.add_response("uname -r -m", "6.1.0-41-amd64 x86_64", 0);
+        ssh.add_response("ls -1 /lib/modules", "6.1.0-9-amd64\n6.1.0-41-amd64\n6.1.0-42-rt-amd64", 0);
+        Box::new(ssh) as connection::Connector
     };
 
     let mut harness = MonitorTestHarness::new_monitor_tester(
@@ -61,10 +118,143 @@ fn test_kernel() {
 
     harness.verify_next_datapoint(&linux::Kernel::get_metadata().module_spec.id, |datapoint| {
         let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.value, "6.1.0-41-amd64 (x86_64)");
         assert_eq!(datapoint.criticality, Criticality::Normal);
     });
 }
 
+#[test]
+fn test_kernel_newer_installed() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        ssh.add_response("uname -r -m", "6.1.0-9-amd64 x86_64", 0);
+        ssh.add_response("ls -1 /lib/modules", "6.1.0-9-amd64\n6.1.0-41-amd64", 0);
+        Box::new(ssh) as connection::Connector
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Kernel::get_metadata(), linux::Kernel::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Kernel::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.criticality, Criticality::Warning);
+        assert_eq!(datapoint.description, "Newer kernel installed: 6.1.0-41-amd64. Reboot required.");
+    });
+}
+
+#[test]
+fn test_restart_required() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(RESTART_REQUIRED_COMMAND,
+r#"=== reboot-required
+linux-image-6.1.0-41-amd64
+libc6
+=== processes
+812 /system.slice/nginx.service nginx
+813 /system.slice/nginx.service nginx
+940 /system.slice/system-getty.slice/getty@tty1.service agetty
+1502 /user.slice/user-1000.slice/session-3.scope tmux: server
+"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::RestartRequired::get_metadata(), linux::RestartRequired::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::RestartRequired::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.criticality, Criticality::Warning);
+
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("Reboot", "Required", Criticality::Warning),
+            ("getty@tty1.service", "Restart required", Criticality::Warning),
+            ("nginx.service", "Restart required", Criticality::Warning),
+            ("Other processes", "1 using deleted libraries", Criticality::Info),
+        ]);
+
+        assert_eq!(datapoint.multivalue[0].description, "Updated packages: linux-image-6.1.0-41-amd64, libc6");
+        assert_eq!(datapoint.multivalue[0].tags, vec![String::from("Reboot")]);
+        assert_eq!(datapoint.multivalue[2].description, "nginx (812), nginx (813)");
+        assert_eq!(datapoint.multivalue[2].command_params, vec![String::from("nginx.service")]);
+        assert_eq!(datapoint.multivalue[3].description, "tmux: server (1502)");
+    });
+}
+
+#[test]
+fn test_restart_required_needs_restarting() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(RESTART_REQUIRED_COMMAND,
+r#"=== needs-restarting
+1
+sshd.service
+chronyd.service
+=== processes
+1022 /system.slice/sshd.service sshd
+"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Fedora, "40"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::RestartRequired::get_metadata(), linux::RestartRequired::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::RestartRequired::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("Reboot", "Required", Criticality::Warning),
+            ("chronyd.service", "Restart required", Criticality::Warning),
+            ("sshd.service", "Restart required", Criticality::Warning),
+        ]);
+
+        assert_eq!(datapoint.multivalue[0].description, "needs-restarting reports updated core packages");
+        assert_eq!(datapoint.multivalue[1].command_params, vec![String::from("chronyd.service")]);
+        assert_eq!(datapoint.multivalue[2].description, "sshd (1022)");
+    });
+}
+
+#[test]
+fn test_restart_required_nixos() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(RESTART_REQUIRED_COMMAND,
+r#"=== nixos
+kernel /nix/store/a-linux-6.6.58/bzImage /nix/store/b-linux-6.6.60/bzImage
+initrd /nix/store/c-initrd/initrd /nix/store/c-initrd/initrd
+kernel-modules /nix/store/d-modules /nix/store/e-modules
+=== processes
+"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::NixOS, "24.05"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::RestartRequired::get_metadata(), linux::RestartRequired::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::RestartRequired::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.multivalue.len(), 1);
+        assert_eq!(datapoint.multivalue[0].value, "Required");
+        assert_eq!(datapoint.multivalue[0].description, "NixOS kernel changed | NixOS kernel-modules changed");
+    });
+}
+
 #[test]
 fn test_load() {
     let new_stub_ssh = |_settings: &HashMap<String, String>| {
@@ -168,6 +358,205 @@ Swap:              0           0           0
     });
 }
 
+#[test]
+fn test_cpu() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new("grep '^cpu' /proc/stat; sleep 1; grep '^cpu' /proc/stat",
+r#"cpu  1000 0 500 8000 100 0 0 0 0 0
+cpu0 500 0 250 4000 50 0 0 0 0 0
+cpu1 500 0 250 4000 50 0 0 0 0 0
+cpu  1300 0 600 8500 150 0 0 50 0 0
+cpu0 750 0 300 4150 75 0 0 25 0 0
+cpu1 550 0 300 4350 75 0 0 25 0 0
+"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Cpu::get_metadata(), linux::Cpu::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Cpu::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.criticality, Criticality::Normal);
+        assert_eq!(datapoint.multivalue.len(), 3);
+
+        let total = &datapoint.multivalue[0];
+        assert_eq!(total.label, "Total");
+        assert_eq!(total.value, "45 %");
+        assert_eq!(total.value_float as i32, 45);
+        assert_eq!(total.description, "user 30.0 % | system 10.0 % | iowait 5.0 % | steal 5.0 %");
+
+        assert_eq!(datapoint.multivalue[1].label, "Core 0");
+        assert_eq!(datapoint.multivalue[1].value_float as i32, 65);
+        assert_eq!(datapoint.multivalue[2].label, "Core 1");
+        assert_eq!(datapoint.multivalue[2].value_float as i32, 25);
+    });
+}
+
+#[test]
+fn test_sensors_hwmon() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(sensors_command!("temp"),
+r#"/sys/class/hwmon/hwmon0/name:coretemp
+/sys/class/hwmon/hwmon0/temp1_crit:100000
+/sys/class/hwmon/hwmon0/temp1_crit_alarm:0
+/sys/class/hwmon/hwmon0/temp1_input:82000
+/sys/class/hwmon/hwmon0/temp1_label:Package id 0
+/sys/class/hwmon/hwmon0/temp1_max:80000
+/sys/class/hwmon/hwmon1/name:nvme
+/sys/class/hwmon/hwmon1/temp1_input:41900
+/sys/class/hwmon/hwmon1/temp1_label:Composite
+/sys/class/hwmon/hwmon10/name:nvme
+/sys/class/hwmon/hwmon10/temp1_input:43900
+/sys/class/hwmon/hwmon10/temp1_label:Composite
+/sys/class/hwmon/hwmon2/name:nvme
+/sys/class/hwmon/hwmon2/temp1_input:39900
+/sys/class/hwmon/hwmon2/temp1_label:Composite
+/sys/class/hwmon/hwmon3/name:nct6775"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Sensors::get_metadata(), linux::Sensors::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Sensors::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("coretemp Package id 0", "82.0 °C", Criticality::Warning),
+            ("nvme (hwmon1) Composite", "41.9 °C", Criticality::Normal),
+            ("nvme (hwmon2) Composite", "39.9 °C", Criticality::Normal),
+            ("nvme (hwmon10) Composite", "43.9 °C", Criticality::Normal),
+        ]);
+        assert_eq!(datapoint.multivalue[0].value_float, 82.0);
+        assert_eq!(datapoint.multivalue[0].description, "high 80.0 °C | crit 100.0 °C");
+        // No hardware thresholds, so defaults from settings are used.
+        assert_eq!(datapoint.multivalue[1].description, "high 75.0 °C | crit 90.0 °C");
+        assert_eq!(datapoint.criticality, Criticality::Warning);
+    });
+}
+
+#[test]
+fn test_fans_hwmon() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(sensors_command!("fan"),
+r#"/sys/class/hwmon/hwmon0/name:coretemp
+/sys/class/hwmon/hwmon3/name:nct6775
+/sys/class/hwmon/hwmon3/fan1_input:1205
+/sys/class/hwmon/hwmon3/fan1_min:300
+/sys/class/hwmon/hwmon3/fan2_input:0
+/sys/class/hwmon/hwmon3/fan2_min:300"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Fans::get_metadata(), linux::Fans::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Fans::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("nct6775 fan1", "1205 RPM", Criticality::Normal),
+            ("nct6775 fan2", "0 RPM", Criticality::Warning),
+        ]);
+        assert_eq!(datapoint.multivalue[0].value_float, 1205.0);
+        assert_eq!(datapoint.multivalue[0].description, "min 300 RPM");
+    });
+}
+
+#[test]
+fn test_voltages_hwmon() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(sensors_command!("in"),
+r#"/sys/class/hwmon/hwmon3/name:nct6775
+/sys/class/hwmon/hwmon3/in0_input:1040
+/sys/class/hwmon/hwmon3/in0_max:1744
+/sys/class/hwmon/hwmon3/in0_min:0
+/sys/class/hwmon/hwmon3/in1_input:1000
+/sys/class/hwmon/hwmon3/in1_max:0
+/sys/class/hwmon/hwmon3/in1_min:0
+/sys/class/hwmon/hwmon3/intrusion0_alarm:1"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Voltages::get_metadata(), linux::Voltages::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Voltages::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("nct6775 in0", "1.04 V", Criticality::Normal),
+            ("nct6775 in1", "1.00 V", Criticality::Normal),
+        ]);
+        assert_eq!(datapoint.multivalue[0].description, "min 0.00 V | max 1.74 V");
+    });
+}
+
+#[test]
+fn test_sensors_json() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(sensors_command!("temp"), SENSORS_JSON, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Sensors::get_metadata(), linux::Sensors::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Sensors::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("k10temp Tctl", "95.5 °C", Criticality::Critical),
+            ("thinkpad CPU", "60.0 °C", Criticality::Normal),
+        ]);
+        assert_eq!(datapoint.criticality, Criticality::Critical);
+    });
+}
+
+#[test]
+fn test_fans_json() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(sensors_command!("fan"), SENSORS_JSON, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (linux::Fans::get_metadata(), linux::Fans::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&linux::Fans::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|value| (value.label.as_str(), value.value.as_str(), value.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("thinkpad fan1", "2900 RPM", Criticality::Normal),
+        ]);
+    });
+}
+
 #[test]
 fn test_uptime() {
     let new_stub_ssh = |_settings: &HashMap<String, String>| {
@@ -220,12 +609,17 @@ fn test_invalid_responses() {
         PlatformInfo::linux(Flavor::Debian, "12.0"),
         (StubSsh2::get_metadata(), new_stub_ssh),
         vec![
+            (linux::Cpu::get_metadata(), linux::Cpu::new_monitoring_module),
+            (linux::Fans::get_metadata(), linux::Fans::new_monitoring_module),
             (linux::Interface::get_metadata(), linux::Interface::new_monitoring_module),
             (linux::Kernel::get_metadata(), linux::Kernel::new_monitoring_module),
             (linux::Load::get_metadata(), linux::Load::new_monitoring_module),
             (linux::Package::get_metadata(), linux::Package::new_monitoring_module),
             (linux::Ram::get_metadata(), linux::Ram::new_monitoring_module),
+            (linux::RestartRequired::get_metadata(), linux::RestartRequired::new_monitoring_module),
+            (linux::Sensors::get_metadata(), linux::Sensors::new_monitoring_module),
             (linux::Uptime::get_metadata(), linux::Uptime::new_monitoring_module),
+            (linux::Voltages::get_metadata(), linux::Voltages::new_monitoring_module),
             (linux::Who::get_metadata(), linux::Who::new_monitoring_module),
         ],
     );
@@ -234,6 +628,14 @@ fn test_invalid_responses() {
 
     // Monitors shouldn't return data points on errors.
     // There should be only the initial NoData datapoint available.
+    harness.verify_next_datapoint(&linux::Cpu::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
+    harness.verify_next_datapoint(&linux::Fans::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
     harness.verify_next_datapoint(&linux::Interface::get_metadata().module_spec.id, |datapoint| {
         assert_eq!(datapoint.is_none(), true);
     });
@@ -254,10 +656,22 @@ fn test_invalid_responses() {
         assert_eq!(datapoint.is_none(), true);
     });
 
+    harness.verify_next_datapoint(&linux::RestartRequired::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
+    harness.verify_next_datapoint(&linux::Sensors::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
     harness.verify_next_datapoint(&linux::Uptime::get_metadata().module_spec.id, |datapoint| {
         assert_eq!(datapoint.is_none(), true);
     });
 
+    harness.verify_next_datapoint(&linux::Voltages::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
     harness.verify_next_datapoint(&linux::Who::get_metadata().module_spec.id, |datapoint| {
         assert_eq!(datapoint.is_none(), true);
     });
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use lightkeeper::module::*;
+use lightkeeper::module::connection;
 use lightkeeper::module::monitoring::*;
 use lightkeeper::module::monitoring::storage;
 use lightkeeper::module::platform_info::*;
@@ -147,6 +148,295 @@ r#"  PV|Attr|PSize|PFree
     });
 }
 
+#[test]
+fn test_smart() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(concat!(
+            r#"sudo sh -c 'command -v smartctl >/dev/null || exit 127; smartctl --scan | while read -r device _ type _; do "#,
+            r#"smartctl --json -a -d "$type" "$device"; case "$device" in /dev/nvme*) command -v nvme >/dev/null && "#,
+            r#"nvme smart-log -o json "$device" 2>/dev/null;; esac; done'"#),
+r#"{
+  "smartctl": { "version": [7, 3], "exit_status": 64 },
+  "device": { "name": "/dev/sda", "type": "sat", "protocol": "ATA" },
+  "model_name": "WDC WD40EFRX",
+  "serial_number": "WD-1234",
+  "smart_status": { "passed": true },
+  "temperature": { "current": 38 },
+  "ata_smart_attributes": {
+    "table": [
+      { "id": 5, "name": "Reallocated_Sector_Ct", "value": 200, "raw": { "value": 8, "string": "8" } },
+      { "id": 197, "name": "Current_Pending_Sector", "value": 200, "raw": { "value": 0, "string": "0" } }
+    ]
+  }
+}
+{
+  "smartctl": { "version": [6, 6], "exit_status": 4 },
+  "device": { "name": "/dev/nvme0", "type": "nvme", "protocol": "NVMe" },
+  "model_name": "Samsung SSD 970 EVO",
+  "smart_status": { "passed": true }
+}
+{
+  "critical_warning" : 0,
+  "temperature" : 329,
+  "avail_spare" : 100,
+  "spare_thresh" : 10,
+  "percent_used" : 85,
+  "media_errors" : 0
+}
+{
+  "smartctl": {
+    "version": [7, 3],
+    "messages": [ { "string": "/dev/sdb: Unknown USB bridge", "severity": "error" } ],
+    "exit_status": 1
+  },
+  "device": { "name": "/dev/sdb", "type": "scsi", "protocol": "SCSI" }
+}"#, 4)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::Smart::get_metadata(), storage::Smart::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&storage::Smart::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.multivalue.len(), 3);
+        assert_eq!(datapoint.criticality, Criticality::Warning);
+
+        let sda = &datapoint.multivalue[0];
+        assert_eq!(sda.label, "sda");
+        assert_eq!(sda.value, "OK");
+        assert_eq!(sda.description, "WDC WD40EFRX | WD-1234");
+        assert_eq!(sda.command_params, vec!["/dev/sda", "sat"]);
+        assert_eq!(sda.criticality, Criticality::Warning);
+        let values = sda.multivalue.iter().map(|child| (child.label.as_str(), child.value.as_str(), child.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("Health", "OK", Criticality::Normal),
+            ("Temperature", "38 °C", Criticality::Normal),
+            ("Reallocated sectors", "8", Criticality::Warning),
+            ("Pending sectors", "0", Criticality::Normal),
+        ]);
+
+        // Older smartctl without NVMe health, so nvme-cli output is used.
+        let nvme = &datapoint.multivalue[1];
+        assert_eq!(nvme.label, "nvme0");
+        assert_eq!(nvme.criticality, Criticality::Warning);
+        let values = nvme.multivalue.iter().map(|child| (child.label.as_str(), child.value.as_str(), child.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("Health", "OK", Criticality::Normal),
+            ("Temperature", "56 °C", Criticality::Warning),
+            ("Media errors", "0", Criticality::Normal),
+            ("Wear level", "85 %", Criticality::Warning),
+        ]);
+
+        let sdb = &datapoint.multivalue[2];
+        assert_eq!(sdb.value, "Unknown");
+        assert_eq!(sdb.criticality, Criticality::Info);
+        assert_eq!(sdb.multivalue[0].description, "/dev/sdb: Unknown USB bridge");
+    });
+}
+
+#[test]
+fn test_mdadm() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new("cat /proc/mdstat",
+r#"Personalities : [raid1] [raid6] [raid5] [raid4]
+md0 : active raid1 sdb1[1] sda1[0]
+      976630464 blocks super 1.2 [2/2] [UU]
+      [=>...................]  check =  8.5% (83012352/976630464) finish=72.1min speed=206511K/sec
+      bitmap: 0/8 pages [0KB], 65536KB chunk
+
+md1 : active raid5 sdc1[3] sdd1[1](F) sde1[0]
+      1953260544 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [U_U]
+
+md2 : active raid1 sdg1[2] sdf1[0]
+      976630464 blocks super 1.2 [2/1] [U_]
+      [==>..................]  recovery = 12.6% (123456/976630272) finish=100.2min speed=100000K/sec
+
+md127 : inactive sdh[0](S)
+      976630464 blocks super 1.2
+
+unused devices: <none>"#, 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::Mdadm::get_metadata(), storage::Mdadm::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&storage::Mdadm::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        let values = datapoint.multivalue.iter().map(|array| (array.label.as_str(), array.value.as_str(), array.criticality)).collect::<Vec<_>>();
+        assert_eq!(values, vec![
+            ("md0", "Check 8.5%", Criticality::Normal),
+            ("md1", "Degraded", Criticality::Error),
+            ("md2", "Recovery 12.6%", Criticality::Error),
+            ("md127", "Inactive", Criticality::Critical),
+        ]);
+        assert_eq!(datapoint.multivalue[1].description, "raid5 | 2/3 devices up | failed: sdd1");
+        assert_eq!(datapoint.multivalue[1].command_params, vec!["/dev/md1", "md1"]);
+        assert_eq!(datapoint.criticality, Criticality::Critical);
+    });
+}
+
+#[test]
+fn test_zfs() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        ssh.add_response(r#""zpool" "list" "-H" "-p" "-o" "name,size,alloc,cap,health""#,
+            "backup\t4000787030016\t3600708327014\t90\tONLINE\ntank\t3985729650688\t1992864825344\t50\tDEGRADED", 0);
+        ssh.add_response(r#""zpool" "status" "-p""#,
+"  pool: backup
+ state: ONLINE
+  scan: scrub repaired 0B in 00:10:01 with 0 errors on Sun Oct 11 00:34:02 2026
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\tbackup      ONLINE       0     0     0
+\t  sdc       ONLINE       0     0     0
+
+errors: No known data errors
+
+  pool: tank
+ state: DEGRADED
+status: One or more devices are faulted in response to persistent errors.
+\tSufficient replicas exist for the pool to continue functioning in a
+\tdegraded state.
+action: Replace the faulted device, or use 'zpool clear' to mark the device
+\trepaired.
+  scan: resilver in progress since Sun Oct 18 10:00:00 2026
+\t1.23T scanned at 500M/s, 600G issued at 250M/s, 3.62T total
+\t300G resilvered, 16.20% done, 03:20:00 to go
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        DEGRADED     0     0     0
+\t  mirror-0  DEGRADED     0     0     0
+\t    sda     ONLINE       0     0     0
+\t    sdb     FAULTED      3   120     0  too many errors
+
+errors: No known data errors", 0);
+        Box::new(ssh) as connection::Connector
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::Zfs::get_metadata(), storage::Zfs::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&storage::Zfs::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.multivalue.len(), 2);
+
+        let backup = &datapoint.multivalue[0];
+        assert_eq!(backup.label, "backup");
+        assert_eq!(backup.value, "ONLINE");
+        assert_eq!(backup.criticality, Criticality::Critical);
+        assert_eq!(backup.description, "3.3 T used of 3.6 T (90 %) | scrub repaired 0B in 00:10:01 with 0 errors on Sun Oct 11 00:34:02 2026");
+
+        let tank = &datapoint.multivalue[1];
+        assert_eq!(tank.value, "Resilvering 16.20%");
+        assert_eq!(tank.criticality, Criticality::Error);
+        assert!(tank.description.contains("123 read, write or checksum errors"));
+        assert_eq!(tank.command_params, vec!["tank"]);
+    });
+}
+
+#[test]
+fn test_btrfs() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(concat!(
+            r#"sudo sh -c 'command -v btrfs >/dev/null || exit 127; findmnt -n -l -t btrfs -o UUID,TARGET | sort -u -k1,1 | while read -r uuid target; do "#,
+            r#"echo "=== $target"; btrfs filesystem show "$target"; btrfs device stats "$target"; btrfs filesystem usage -b "$target"; "#,
+            r#"btrfs scrub status "$target"; done'"#),
+"=== /
+Label: 'missing-backups'  uuid: 0b5c7e5e-8d0a-4c4e-9b57-3a7a6d1a2b3c
+\tTotal devices 1 FS bytes used 20.00GiB
+\tdevid    1 size 100.00GiB used 25.00GiB path /dev/sda2
+
+[/dev/sda2].write_io_errs    0
+[/dev/sda2].read_io_errs     0
+[/dev/sda2].flush_io_errs    0
+[/dev/sda2].corruption_errs  0
+[/dev/sda2].generation_errs  0
+Overall:
+    Device size:\t\t        107374182400
+    Device allocated:\t\t         26843545600
+    Device unallocated:\t\t         80530636800
+    Device missing:\t\t                   0
+    Used:\t\t\t         21474836480
+    Free (estimated):\t\t         84825604096\t(min: 44560285696)
+    Data ratio:\t\t\t                1.00
+
+Data,single: Size:24696061952, Used:20937965568 (84.78%)
+   /dev/sda2\t24696061952
+
+UUID:             0b5c7e5e-8d0a-4c4e-9b57-3a7a6d1a2b3c
+Scrub started:    Sun Oct 18 09:00:00 2026
+Status:           running
+Duration:         0:05:00
+Time left:        0:15:00
+Bytes scrubbed:   5.00GiB  (25.00%)
+Rate:             17.07MiB/s
+Error summary:    no errors found
+=== /data
+Label: 'data'  uuid: 5d1f0c3a-1111-2222-3333-444455556666
+\tTotal devices 2 FS bytes used 1.50TiB
+\tdevid    1 size 2.00TiB used 1.60TiB path /dev/sdb
+\t*** Some devices missing
+
+[/dev/sdb].write_io_errs    0
+[/dev/sdb].read_io_errs     0
+[/dev/sdb].flush_io_errs    0
+[/dev/sdb].corruption_errs  4
+[/dev/sdb].generation_errs  0
+UUID:             5d1f0c3a-1111-2222-3333-444455556666
+Scrub started:    Sun Oct 11 03:00:00 2026
+Status:           finished
+Duration:         2:10:00
+Total to scrub:   3.00TiB
+Rate:             200.00MiB/s
+Error summary:    csum=4
+  Corrected:      4
+  Uncorrectable:  0
+  Unverified:     0", 0)
+    };
+
+    let mut harness = MonitorTestHarness::new_monitor_tester(
+        PlatformInfo::linux(Flavor::Debian, "12.0"),
+        (StubSsh2::get_metadata(), new_stub_ssh),
+        (storage::Btrfs::get_metadata(), storage::Btrfs::new_monitoring_module),
+    );
+
+    harness.refresh_monitors();
+
+    harness.verify_next_datapoint(&storage::Btrfs::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.multivalue.len(), 2);
+
+        let root = &datapoint.multivalue[0];
+        assert_eq!(root.label, "/");
+        assert_eq!(root.value, "Scrubbing 25.00%");
+        assert_eq!(root.criticality, Criticality::Normal);
+        assert_eq!(root.description, "20 % used | scrub running");
+
+        let data = &datapoint.multivalue[1];
+        assert_eq!(data.value, "Degraded");
+        assert_eq!(data.criticality, Criticality::Critical);
+        assert_eq!(data.description, "4 device errors | scrub errors: csum=4");
+        assert_eq!(data.command_params, vec!["/data"]);
+    });
+}
+
 #[test]
 /// Test handling of invalid responses of all storage-category monitors.
 fn test_invalid_responses() {
@@ -163,6 +453,10 @@ fn test_invalid_responses() {
             (storage::lvm::LogicalVolume::get_metadata(), storage::lvm::LogicalVolume::new_monitoring_module),
             (storage::lvm::VolumeGroup::get_metadata(), storage::lvm::VolumeGroup::new_monitoring_module),
             (storage::lvm::PhysicalVolume::get_metadata(), storage::lvm::PhysicalVolume::new_monitoring_module),
+            (storage::Smart::get_metadata(), storage::Smart::new_monitoring_module),
+            (storage::Mdadm::get_metadata(), storage::Mdadm::new_monitoring_module),
+            (storage::Zfs::get_metadata(), storage::Zfs::new_monitoring_module),
+            (storage::Btrfs::get_metadata(), storage::Btrfs::new_monitoring_module),
         ],
     );
 
@@ -175,6 +469,12 @@ fn test_invalid_responses() {
         assert!(datapoint.multivalue.is_empty());
     });
 
+    // Returns data instead of an error since /proc/mdstat is missing without the md driver.
+    harness.verify_next_datapoint(&storage::Mdadm::get_metadata().module_spec.id, |datapoint| {
+        let datapoint = datapoint.expect("Should have datapoint");
+        assert_eq!(datapoint.multivalue[0].criticality, Criticality::NotAvailable);
+    });
+
     harness.verify_next_datapoint(&storage::Cryptsetup::get_metadata().module_spec.id, |datapoint| {
         assert_eq!(datapoint.is_none(), true);
     });
@@ -190,5 +490,16 @@ fn test_invalid_responses() {
     harness.verify_next_datapoint(&storage::lvm::PhysicalVolume::get_metadata().module_spec.id, |datapoint| {
         assert_eq!(datapoint.is_none(), true);
     });
-}
 
+    harness.verify_next_datapoint(&storage::Smart::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
+    harness.verify_next_datapoint(&storage::Zfs::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+
+    harness.verify_next_datapoint(&storage::Btrfs::get_metadata().module_spec.id, |datapoint| {
+        assert_eq!(datapoint.is_none(), true);
+    });
+}
@@ -0,0 +1,247 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::net::TcpListener;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use lightkeeper::alerting::{sinks, Alert, AlertKind, AlertManager, AlertTracker};
+use lightkeeper::configuration::{AlertSinkConfig, Alerting, Configuration, SmtpSecurity};
+use lightkeeper::enums::Criticality;
+use lightkeeper::frontend::{DisplayOptions, HostDisplayData, UIUpdate};
+use lightkeeper::module::monitoring::{DataPoint, MonitoringData};
+
+use crate::temp_path;
+
+fn data_point(criticality: Criticality) -> DataPoint {
+    DataPoint::value_with_level("value".to_string(), criticality)
+}
+
+fn multivalue(children: Vec<(&str, Criticality)>) -> DataPoint {
+    let mut parent = DataPoint::empty();
+    parent.multivalue = children.into_iter()
+        .map(|(label, criticality)| DataPoint::labeled_value_with_level(label.to_string(), "value".to_string(), criticality))
+        .collect();
+    parent
+}
+
+#[test]
+fn alerts_only_on_transitions() {
+    let mut tracker = AlertTracker::new(&Alerting::default());
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    assert!(tracker.process("host", "load", &data_point(Criticality::Normal), now).is_empty());
+
+    let alerts = tracker.process("host", "load", &data_point(Criticality::Warning), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Firing);
+    assert_eq!(alerts[0].previous_criticality, Criticality::Normal);
+
+    // Deduplicated.
+    assert!(tracker.process("host", "load", &data_point(Criticality::Warning), now).is_empty());
+    // NoData doesn't change the state.
+    assert!(tracker.process("host", "load", &data_point(Criticality::NoData), now).is_empty());
+
+    let alerts = tracker.process("host", "load", &data_point(Criticality::Critical), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Firing);
+    assert_eq!(alerts[0].previous_criticality, Criticality::Warning);
+
+    let alerts = tracker.process("host", "load", &data_point(Criticality::Normal), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Resolved);
+    assert_eq!(alerts[0].previous_criticality, Criticality::Critical);
+
+    assert!(tracker.process("host", "load", &data_point(Criticality::Normal), now).is_empty());
+}
+
+#[test]
+fn alerts_are_repeated_after_interval() {
+    let config = Alerting {
+        repeat_interval: 60,
+        send_resolved: false,
+        ..Default::default()
+    };
+    let mut tracker = AlertTracker::new(&config);
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(tracker.process("host", "ram", &data_point(Criticality::Error), now).len(), 1);
+    assert!(tracker.process("host", "ram", &data_point(Criticality::Error), now + Duration::seconds(30)).is_empty());
+
+    let alerts = tracker.process("host", "ram", &data_point(Criticality::Error), now + Duration::seconds(61));
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Repeated);
+
+    // Resolved messages are disabled.
+    assert!(tracker.process("host", "ram", &data_point(Criticality::Normal), now + Duration::seconds(90)).is_empty());
+}
+
+#[test]
+fn multivalue_labels_are_tracked_separately() {
+    let mut tracker = AlertTracker::new(&Alerting::default());
+    let now = Utc::now();
+
+    let alerts = tracker.process("host", "filesystem", &multivalue(vec![("/", Criticality::Normal), ("/home", Criticality::Critical)]), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].label, "/home");
+
+    let alerts = tracker.process("host", "filesystem", &multivalue(vec![("/", Criticality::Warning), ("/home", Criticality::Critical)]), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].label, "/");
+
+    // Other hosts are independent.
+    assert_eq!(tracker.process("other", "filesystem", &multivalue(vec![("/", Criticality::Warning)]), now).len(), 1);
+
+    // Labels that disappear are resolved.
+    let alerts = tracker.process("host", "filesystem", &multivalue(vec![("/", Criticality::Normal)]), now);
+    assert_eq!(alerts.len(), 2);
+    assert!(alerts.iter().all(|alert| alert.kind == AlertKind::Resolved));
+}
+
+#[test]
+fn labels_are_kept_over_errors() {
+    let mut tracker = AlertTracker::new(&Alerting::default());
+    let now = Utc::now();
+
+    let alerts = tracker.process("host", "docker-containers", &multivalue(vec![("web", Criticality::Critical), ("db", Criticality::Normal)]), now);
+    assert_eq!(alerts.len(), 1);
+
+    // Failed refresh without the container list.
+    let alerts = tracker.process("host", "docker-containers", &data_point(Criticality::Error), now);
+    assert!(alerts.iter().all(|alert| alert.label.is_empty()));
+    assert!(tracker.process("host", "docker-containers", &data_point(Criticality::NoData), now).is_empty());
+
+    // Only the error is resolved on recovery, the container alert is still active.
+    let alerts = tracker.process("host", "docker-containers", &multivalue(vec![("web", Criticality::Critical), ("db", Criticality::Normal)]), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Resolved);
+    assert_eq!(alerts[0].label, "");
+
+    let alerts = tracker.process("host", "docker-containers", &multivalue(vec![("db", Criticality::Normal)]), now);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, AlertKind::Resolved);
+    assert_eq!(alerts[0].label, "web");
+}
+
+#[test]
+fn command_sink_receives_alert_details() {
+    let output_path = temp_path("alert-command");
+
+    let sink = AlertSinkConfig::Command {
+        command: "sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            format!("echo \"$LIGHTKEEPER_ALERT_KIND $LIGHTKEEPER_ALERT_HOST $LIGHTKEEPER_ALERT_CRITICALITY\" > {}", output_path.display()),
+        ],
+    };
+
+    let alert = Alert {
+        kind: AlertKind::Firing,
+        host_name: "test-host".to_string(),
+        monitor_id: "load".to_string(),
+        label: String::new(),
+        criticality: Criticality::Critical,
+        previous_criticality: Criticality::Normal,
+        value: "10.0".to_string(),
+        description: String::new(),
+        time: Utc::now(),
+    };
+
+    sinks::send(&sink, &alert).unwrap();
+
+    let mut contents = String::new();
+    for _ in 0..50 {
+        contents = std::fs::read_to_string(&output_path).unwrap_or_default();
+        if !contents.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let _ = std::fs::remove_file(&output_path);
+    assert_eq!(contents.trim(), "firing test-host Critical");
+}
+
+#[test]
+fn hanging_sink_doesnt_block_other_sinks() {
+    let output_path = temp_path("alert-hanging");
+
+    // Accepts connections but never sends the SMTP greeting.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        let _connections = listener.incoming().collect::<Vec<_>>();
+    });
+
+    let config = Alerting {
+        sinks: vec![
+            AlertSinkConfig::Smtp {
+                server: "127.0.0.1".to_string(),
+                port: Some(port),
+                security: SmtpSecurity::None,
+                username: String::new(),
+                password: String::new(),
+                from: "lightkeeper@example.com".to_string(),
+                to: vec!["admin@example.com".to_string()],
+            },
+            AlertSinkConfig::Command {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), format!("echo \"$LIGHTKEEPER_ALERT_MONITOR\" >> {}", output_path.display())],
+            },
+        ],
+        ..Default::default()
+    };
+
+    let mut manager = AlertManager::new();
+    manager.configure(&config);
+    manager.start_processing_updates();
+
+    let sender = manager.new_update_sender();
+    for monitor_id in ["load", "ram"] {
+        let mut display_data = HostDisplayData::default();
+        display_data.host_state.host.name = "test-host".to_string();
+
+        let mut monitoring_data = MonitoringData::new(monitor_id.to_string(), DisplayOptions::default());
+        monitoring_data.values.push_back(data_point(Criticality::Critical));
+        display_data.new_monitoring_data = Some((1, monitoring_data));
+
+        sender.send(UIUpdate::Host(display_data)).unwrap();
+    }
+
+    let mut contents = String::new();
+    for _ in 0..50 {
+        contents = std::fs::read_to_string(&output_path).unwrap_or_default();
+        if contents.lines().count() == 2 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    manager.stop();
+    let _ = std::fs::remove_file(&output_path);
+    assert_eq!(contents.lines().collect::<Vec<_>>(), vec!["load", "ram"]);
+}
+
+#[test]
+fn alerting_config_is_parsed() {
+    let config: Configuration = serde_yaml::from_str(r#"
+alerting:
+  repeat_interval: 3600
+  sinks:
+    - type: webhook
+      url: "https://example.com/hook"
+    - type: smtp
+      server: smtp.example.com
+      from: lightkeeper@example.com
+      to: ["admin@example.com"]
+    - type: dbus
+"#).unwrap();
+
+    assert_eq!(config.alerting.min_criticality, Criticality::Warning);
+    assert_eq!(config.alerting.repeat_interval, 3600);
+    assert!(config.alerting.send_resolved);
+    assert_eq!(config.alerting.sinks.len(), 3);
+    assert_eq!(config.alerting.sinks[2], AlertSinkConfig::Dbus);
+}
@@ -0,0 +1,88 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use chrono::{TimeZone, Utc};
+
+use lightkeeper::audit_log::{AuditLogger, AuditQuery, AuditRecord, AuditRequestType};
+use lightkeeper::configuration;
+
+use crate::temp_path;
+
+fn record(time_sec: i64, host: &str, request_type: AuditRequestType, target: &str) -> AuditRecord {
+    AuditRecord {
+        time: Utc.timestamp_opt(time_sec, 0).unwrap(),
+        user: "tester".to_string(),
+        host: host.to_string(),
+        connector: "ssh".to_string(),
+        source_id: "uptime".to_string(),
+        invocation_id: time_sec as u64,
+        batched_monitors: Vec::new(),
+        request_type,
+        target: target.to_string(),
+        return_code: Some(0),
+        error: None,
+        duration_ms: 5,
+    }
+}
+
+#[test]
+fn append_and_query() {
+    let dir = temp_path("audit-query");
+    let logger = AuditLogger::new(dir.clone(), &configuration::AuditLog::default()).unwrap();
+
+    logger.append(&record(100, "host1", AuditRequestType::Monitor, "uptime")).unwrap();
+    logger.append(&record(200, "host2", AuditRequestType::Command, "sudo systemctl restart nginx")).unwrap();
+    logger.append(&record(300, "host1", AuditRequestType::Download, "/etc/hosts")).unwrap();
+
+    let all = logger.query(&AuditQuery::default()).unwrap();
+    let times = all.iter().map(|record| record.time.timestamp()).collect::<Vec<_>>();
+    assert_eq!(times, vec![300, 200, 100]);
+    assert_eq!(all[0], record(300, "host1", AuditRequestType::Download, "/etc/hosts"));
+
+    let by_host = logger.query(&AuditQuery { host: Some("host1".to_string()), ..Default::default() }).unwrap();
+    assert_eq!(by_host.len(), 2);
+
+    let by_target = logger.query(&AuditQuery { target_contains: Some("NGINX".to_string()), ..Default::default() }).unwrap();
+    assert_eq!(by_target.len(), 1);
+    assert_eq!(by_target[0].host, "host2");
+
+    let by_type = logger.query(&AuditQuery { request_type: Some(AuditRequestType::Monitor), ..Default::default() }).unwrap();
+    assert_eq!(by_type.len(), 1);
+
+    let by_time = logger.query(&AuditQuery { start_time: Some(150), end_time: Some(300), ..Default::default() }).unwrap();
+    assert_eq!(by_time.len(), 2);
+
+    let limited = logger.query(&AuditQuery { limit: 1, ..Default::default() }).unwrap();
+    assert_eq!(limited.len(), 1);
+    assert_eq!(limited[0].time.timestamp(), 300);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rotation_keeps_newest_files() {
+    let dir = temp_path("audit-rotation");
+    let config = configuration::AuditLog {
+        enabled: true,
+        max_file_size: 0,
+        max_files: 2,
+    };
+    let logger = AuditLogger::new(dir.clone(), &config).unwrap();
+
+    // With zero size limit, every record goes to a new file.
+    for time in [100, 200, 300] {
+        logger.append(&record(time, "host1", AuditRequestType::Command, "reboot")).unwrap();
+    }
+
+    assert!(dir.join("audit.jsonl").exists());
+    assert!(dir.join("audit.1.jsonl").exists());
+    assert!(!dir.join("audit.2.jsonl").exists());
+
+    let times = logger.query(&AuditQuery::default()).unwrap()
+        .iter().map(|record| record.time.timestamp()).collect::<Vec<_>>();
+    assert_eq!(times, vec![300, 200]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
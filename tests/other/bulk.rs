@@ -0,0 +1,168 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use lightkeeper::backend::{CommandBackend, LocalCommandBackend};
+use lightkeeper::command_handler::{bulk, BulkExecution, BulkRequest, HostExecutionState, RolloutStrategy};
+use lightkeeper::configuration::{self, get_default_main_config};
+use lightkeeper::module::command::{systemd, CommandModule};
+use lightkeeper::module::connection::Connector;
+use lightkeeper::module::platform_info::{Flavor, PlatformInfo};
+use lightkeeper::module::MetadataSupport;
+use lightkeeper::{HostSetting, ModuleFactory};
+
+use crate::StubSsh2;
+
+fn hosts(count: usize) -> Vec<String> {
+    (1..=count).map(|index| format!("host{}", index)).collect()
+}
+
+fn batches(execution: &BulkExecution) -> Vec<usize> {
+    execution.hosts.iter().map(|host| host.batch).collect()
+}
+
+#[test]
+fn rollout_strategies_define_batches() {
+    let new_execution = |strategy| {
+        BulkExecution::new(1, BulkRequest { strategy, ..Default::default() }, hosts(5))
+    };
+
+    assert_eq!(batches(&new_execution(RolloutStrategy::Parallel)), vec![0, 0, 0, 0, 0]);
+    assert_eq!(batches(&new_execution(RolloutStrategy::Serial)), vec![0, 1, 2, 3, 4]);
+    assert_eq!(batches(&new_execution(RolloutStrategy::Canary { hosts: 2 })), vec![0, 0, 1, 1, 1]);
+    assert_eq!(batches(&new_execution(RolloutStrategy::Percentage { percent: 40 })), vec![0, 0, 1, 1, 2]);
+    // Batch size is always at least one host.
+    assert_eq!(batches(&new_execution(RolloutStrategy::Percentage { percent: 1 })), vec![0, 1, 2, 3, 4]);
+
+    let mut execution = new_execution(RolloutStrategy::Serial);
+    assert_eq!(execution.current_batch(), Some(0));
+    execution.stop();
+    assert!(execution.is_finished());
+    assert_eq!(execution.count(HostExecutionState::Skipped), 5);
+}
+
+#[test]
+fn rollout_strategy_from_string() {
+    assert_eq!("parallel".parse::<RolloutStrategy>().unwrap(), RolloutStrategy::Parallel);
+    assert_eq!("serial".parse::<RolloutStrategy>().unwrap(), RolloutStrategy::Serial);
+    assert_eq!("canary:2".parse::<RolloutStrategy>().unwrap(), RolloutStrategy::Canary { hosts: 2 });
+    assert_eq!("percent:25".parse::<RolloutStrategy>().unwrap(), RolloutStrategy::Percentage { percent: 25 });
+
+    for invalid in ["", "canary", "canary:0", "percent:101", "serial:1", "random"] {
+        assert!(invalid.parse::<RolloutStrategy>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn resolve_hosts_by_name_and_group() {
+    let mut web = configuration::HostSettings::default();
+    web.groups = vec![String::from("web")];
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([
+            (String::from("db1"), configuration::HostSettings::default()),
+            (String::from("web1"), web.clone()),
+            (String::from("web2"), web),
+        ]),
+        ..Default::default()
+    };
+
+    let resolve = |host_ids: &[&str], groups: &[&str]| bulk::resolve_hosts(
+        &hosts_config,
+        &host_ids.iter().map(|host_id| host_id.to_string()).collect::<Vec<_>>(),
+        &groups.iter().map(|group| group.to_string()).collect::<Vec<_>>(),
+    );
+
+    assert_eq!(resolve(&[], &["web"]).unwrap(), vec!["web1", "web2"]);
+    // Hosts given by name come first, e.g. for canaries.
+    assert_eq!(resolve(&["web2", "db1"], &["web"]).unwrap(), vec!["web2", "db1", "web1"]);
+    assert!(resolve(&[], &[]).is_err());
+    assert!(resolve(&["unknown"], &[]).is_err());
+    assert!(resolve(&[], &["unknown"]).is_err());
+}
+
+#[test]
+fn serial_rollout_stops_on_failure() {
+    // Hosts with the "fail" connector setting fail to start the service.
+    let new_stub_ssh = |settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        let exit_status = if settings.contains_key("fail") { 1 } else { 0 };
+        let output = if exit_status == 0 { "" } else { "Failed to start test-service.service" };
+        ssh.add_response(r#""sudo" "systemctl" "start" "test-service.service""#, output, exit_status);
+        Box::new(ssh) as Connector
+    };
+
+    let command_id = systemd::service::Start::get_metadata().module_spec.id.clone();
+    let mut hosts_config = configuration::Hosts::default();
+
+    for (host_id, fail) in [("host1", false), ("host2", true), ("host3", false)] {
+        let mut connector_config = configuration::ConnectorConfig::default();
+        if fail {
+            connector_config.settings.insert(String::from("fail"), String::from("true"));
+        }
+
+        let mut host_settings = configuration::HostSettings::default();
+        host_settings.address = "127.0.0.1".to_string();
+        host_settings.groups = vec![String::from("web")];
+        host_settings.effective.host_settings = vec![HostSetting::UseSudo];
+        host_settings.effective.connectors.insert(StubSsh2::get_metadata().module_spec.id.clone(), connector_config);
+        host_settings.effective.commands.insert(
+            command_id.clone(),
+            configuration::CommandConfig {
+                version: "0.0.1".to_string(),
+                ..Default::default()
+            },
+        );
+
+        hosts_config.hosts.insert(host_id.to_string(), host_settings);
+        hosts_config.predefined_platforms.insert(host_id.to_string(), PlatformInfo::linux(Flavor::Debian, "12.0"));
+    }
+
+    let module_factory = ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![],
+        vec![(systemd::service::Start::get_metadata(), systemd::service::Start::new_command_module)],
+    );
+
+    let core = lightkeeper::initialize_core(&get_default_main_config(), &hosts_config, Arc::new(module_factory)).unwrap();
+    let host_manager = core.host_manager.clone();
+    let mut connection_manager = core.connection_manager;
+    let mut backend = LocalCommandBackend::new(core.command_handler, core.monitor_manager);
+
+    let request = BulkRequest {
+        command_id: command_id.clone(),
+        parameters: vec![String::from("test-service.service")],
+        groups: vec![String::from("web")],
+        strategy: RolloutStrategy::Serial,
+        stop_on_failure: true,
+        ..Default::default()
+    };
+
+    assert!(backend.execute_bulk(BulkRequest { groups: vec![String::from("db")], ..request.clone() }).is_err());
+
+    let bulk_id = backend.execute_bulk(request).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let execution = loop {
+        let execution = backend.bulk_execution(bulk_id).unwrap().unwrap();
+        if execution.is_finished() || Instant::now() > deadline {
+            break execution;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    backend.stop();
+    host_manager.borrow_mut().stop();
+    connection_manager.stop();
+
+    let states = execution.hosts.iter().map(|host| (host.host_id.as_str(), host.state)).collect::<BTreeMap<_, _>>();
+    assert_eq!(states["host1"], HostExecutionState::Succeeded);
+    assert_eq!(states["host2"], HostExecutionState::Failed);
+    assert_eq!(states["host3"], HostExecutionState::Skipped);
+    assert!(execution.stopped);
+    assert!(execution.hosts[0].result.is_some());
+    assert!(!backend.cancel_bulk_execution(bulk_id + 1).unwrap());
+}
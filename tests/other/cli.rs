@@ -0,0 +1,209 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use lightkeeper::command_handler::{BulkRequest, RolloutStrategy};
+use lightkeeper::configuration::{self, get_default_main_config};
+use lightkeeper::enums::Criticality;
+use lightkeeper::frontend::{cli, DisplayData, DisplayOptions, HostDisplayData};
+use lightkeeper::maintenance::Silence;
+use lightkeeper::module::command::{systemd, CommandModule, CommandResult};
+use lightkeeper::module::connection::Connector;
+use lightkeeper::module::monitoring::linux::Load;
+use lightkeeper::module::monitoring::{DataPoint, MonitoringData, MonitoringModule};
+use lightkeeper::module::platform_info::{Flavor, PlatformInfo};
+use lightkeeper::module::MetadataSupport;
+use lightkeeper::{HostSetting, ModuleFactory};
+
+use crate::StubSsh2;
+
+fn host_with_monitor(monitor_id: &str, criticality: Criticality) -> HostDisplayData {
+    let mut host = HostDisplayData::default();
+    let mut monitoring_data = MonitoringData::new(monitor_id.to_string(), DisplayOptions::default());
+    monitoring_data.values.push_back(DataPoint::value_with_level(String::from("value"), criticality));
+    host.host_state.monitor_data.insert(monitor_id.to_string(), monitoring_data);
+    host
+}
+
+#[test]
+fn exit_code_follows_worst_criticality() {
+    let mut display_data = DisplayData::new();
+    display_data.hosts.insert(String::from("a"), host_with_monitor("ping", Criticality::NoData));
+    display_data.hosts.insert(String::from("b"), host_with_monitor("load", Criticality::Warning));
+    display_data.hosts.insert(String::from("c"), host_with_monitor("ping", Criticality::NotAvailable));
+
+    let all_hosts = vec![String::from("a"), String::from("b"), String::from("c")];
+    let report = cli::build_report(&display_data, &all_hosts, |_| (true, Vec::new()));
+    assert_eq!(report.criticality, Criticality::Warning);
+    assert_eq!(report.exit_code(), cli::EXIT_WARNING);
+
+    let report = cli::build_report(&display_data, &[String::from("a")], |_| (true, Vec::new()));
+    assert_eq!(report.exit_code(), cli::EXIT_UNKNOWN);
+
+    let report = cli::build_report(&display_data, &[String::from("c")], |_| (true, Vec::new()));
+    assert_eq!(report.exit_code(), cli::EXIT_OK);
+
+    // Silenced monitors don't affect the exit code.
+    let mut critical = host_with_monitor("ping", Criticality::Critical);
+    critical.host_state.silences.push(Silence {
+        id: 1,
+        host_name: String::from("d"),
+        monitors: Vec::new(),
+        labels: Vec::new(),
+        starts_at: Utc::now(),
+        ends_at: Utc::now() + chrono::Duration::hours(1),
+        comment: String::new(),
+    });
+    display_data.hosts.insert(String::from("d"), critical);
+    let report = cli::build_report(&display_data, &[String::from("d")], |_| (true, Vec::new()));
+    assert_eq!(report.exit_code(), cli::EXIT_OK);
+    assert!(cli::render_table(&report).contains("Critical (silenced)"));
+}
+
+#[test]
+fn refresh_hosts_waits_for_platform_info_and_monitors() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        ssh.add_response("cat /etc/os-release", "ID=debian\nVERSION_ID=\"12.0\"\n", 0);
+        ssh.add_response("uname -m", "x86_64", 0);
+        ssh.add_response("uptime", " 10:00:00 up 1 day,  1 user,  load average: 0.50, 0.40, 0.30", 0);
+        Box::new(ssh) as Connector
+    };
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    host_settings.effective.monitors.insert(
+        Load::get_metadata().module_spec.id.clone(),
+        configuration::MonitorConfig {
+            version: "0.0.1".to_string(),
+            ..Default::default()
+        },
+    );
+    host_settings.effective.connectors.insert(
+        StubSsh2::get_metadata().module_spec.id.clone(),
+        configuration::ConnectorConfig::default(),
+    );
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([(String::from("server1"), host_settings)]),
+        ..Default::default()
+    };
+
+    let module_factory = ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![(Load::get_metadata(), Load::new_monitoring_module)],
+        vec![],
+    );
+
+    let mut core = lightkeeper::initialize_core(&get_default_main_config(), &hosts_config, Arc::new(module_factory)).unwrap();
+    let report = cli::refresh_hosts(&mut core, &[String::from("server1")], Duration::from_secs(10)).unwrap();
+
+    core.monitor_manager.stop();
+    core.host_manager.borrow_mut().stop();
+    core.connection_manager.stop();
+
+    let host = &report.hosts[0];
+    assert!(host.completed);
+    assert_eq!(host.monitors[0].id, "load");
+    assert_eq!(host.monitors[0].value, "0.50, 0.40, 0.30");
+    assert_eq!(report.exit_code(), cli::EXIT_OK);
+
+    let table = cli::render_table(&report);
+    assert!(table.starts_with("HOST"));
+    assert!(table.contains("server1  up"));
+}
+
+#[test]
+fn output_follower_returns_complete_lines() {
+    let mut follower = cli::OutputFollower::new();
+
+    let partial = CommandResult::new_partial("<span style=\"color: red\">first</span>\nsec", 10);
+    assert_eq!(follower.new_output("a", &partial), "first\n");
+    // Other hosts are followed separately.
+    assert_eq!(follower.new_output("b", &partial), "first\n");
+
+    let partial = CommandResult::new_partial("<span style=\"color: red\">first</span>\nsecond\nthi", 50);
+    assert_eq!(follower.new_output("a", &partial), "second\n");
+
+    let final_result = CommandResult::new_info("<span style=\"color: red\">first</span>\nsecond\nthird");
+    assert_eq!(follower.new_output("a", &final_result), "third");
+    assert_eq!(follower.new_output("a", &final_result), "");
+}
+
+#[test]
+fn execute_command_on_hosts() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        ssh.add_response(r#""sudo" "systemctl" "start" "test-service.service""#, "", 0);
+        Box::new(ssh) as Connector
+    };
+
+    let command_id = systemd::service::Start::get_metadata().module_spec.id.clone();
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    host_settings.effective.host_settings = vec![HostSetting::UseSudo];
+    host_settings.effective.connectors.insert(
+        StubSsh2::get_metadata().module_spec.id.clone(),
+        configuration::ConnectorConfig::default(),
+    );
+    host_settings.effective.commands.insert(
+        command_id.clone(),
+        configuration::CommandConfig {
+            version: "0.0.1".to_string(),
+            ..Default::default()
+        },
+    );
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([
+            (String::from("server1"), host_settings.clone()),
+            (String::from("server2"), host_settings),
+        ]),
+        predefined_platforms: BTreeMap::from([
+            (String::from("server1"), PlatformInfo::linux(Flavor::Debian, "12.0")),
+            (String::from("server2"), PlatformInfo::linux(Flavor::Debian, "12.0")),
+        ]),
+        ..Default::default()
+    };
+
+    let module_factory = ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![],
+        vec![(systemd::service::Start::get_metadata(), systemd::service::Start::new_command_module)],
+    );
+
+    let host_ids = vec![String::from("server1"), String::from("server2")];
+    let mut core = lightkeeper::initialize_core(&get_default_main_config(), &hosts_config, Arc::new(module_factory)).unwrap();
+
+    assert!(cli::resolve_command(&core, &host_ids, "no-such-command").is_err());
+
+    let request = BulkRequest {
+        command_id: command_id.clone(),
+        parameters: vec![String::from("test-service.service")],
+        host_ids: host_ids.clone(),
+        strategy: RolloutStrategy::Serial,
+        ..Default::default()
+    };
+
+    let mut result_count = 0;
+    let outcomes = cli::execute_command(&mut core, &request, Duration::from_secs(10), |_, _| result_count += 1).unwrap();
+
+    core.command_handler.stop();
+    core.host_manager.borrow_mut().stop();
+    core.connection_manager.stop();
+
+    assert_eq!(outcomes.len(), 2);
+    assert!(result_count >= 2);
+    for outcome in outcomes.iter() {
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        assert_eq!(outcome.result.as_ref().unwrap().criticality, Criticality::Info);
+    }
+    assert_eq!(cli::outcomes_exit_code(&outcomes), cli::EXIT_OK);
+}
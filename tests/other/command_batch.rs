@@ -0,0 +1,125 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::process::Command;
+
+use lightkeeper::connection_manager::RequestType;
+use lightkeeper::error::LkError;
+use lightkeeper::module::connection::{RequestResponse, ResponseMessage};
+use lightkeeper::monitor_manager::command_batch::{self, BatchedMonitor};
+
+fn batched_monitor(monitor_id: &str, invocation_id: u64, commands: Vec<&str>) -> BatchedMonitor {
+    BatchedMonitor {
+        monitor_id: monitor_id.to_string(),
+        invocation_id,
+        extension_monitors: Vec::new(),
+        commands: commands.into_iter().map(String::from).collect(),
+    }
+}
+
+/// Runs the script like the remote host would.
+fn run_script(script: &str) -> String {
+    let output = Command::new("sh").arg("-c").arg(script).output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn batchable_commands() {
+    assert!(command_batch::is_batchable("\"uptime\""));
+    assert!(!command_batch::is_batchable(""));
+    assert!(!command_batch::is_batchable("\"sudo\" \"ss\" \"-tulpn\""));
+    assert!(!command_batch::is_batchable("sudo ls"));
+}
+
+#[test]
+fn script_output_is_split_per_command() {
+    let monitors = vec![
+        batched_monitor("uptime", 1, vec!["echo 'up 5 days'"]),
+        // Output without a trailing newline, stderr and a non-zero exit code.
+        batched_monitor("multi", 2, vec!["printf 'a\\nb'", "echo error >&2; exit 3"]),
+        batched_monitor("empty", 3, vec!["true"]),
+        // Exiting in a command doesn't stop the rest.
+        batched_monitor("quote", 4, vec!["exit 1", "echo \"it's\""]),
+    ];
+
+    let delimiter = command_batch::new_delimiter();
+    let output = run_script(&command_batch::build_script(&delimiter, &monitors));
+    let results = command_batch::split_output(&delimiter, &monitors, &output);
+
+    let results = results.into_iter()
+        .map(|responses| responses.into_iter().map(|response| {
+            let response = response.unwrap();
+            (response.message, response.return_code)
+        }).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    assert_eq!(results, vec![
+        vec![("up 5 days\n".to_string(), 0)],
+        vec![("a\nb".to_string(), 0), ("error\n".to_string(), 3)],
+        vec![(String::new(), 0)],
+        vec![(String::new(), 1), ("it's\n".to_string(), 0)],
+    ]);
+}
+
+#[test]
+fn incomplete_output() {
+    let monitors = vec![
+        batched_monitor("first", 1, vec!["echo 1"]),
+        batched_monitor("second", 2, vec!["echo 2"]),
+    ];
+
+    let delimiter = command_batch::new_delimiter();
+    let output = format!("1\n\n{} 0 0 0\n2", delimiter);
+    let results = command_batch::split_output(&delimiter, &monitors, &output);
+
+    assert_eq!(results[0][0].as_ref().unwrap().message, "1\n");
+    assert!(results[1][0].is_err());
+}
+
+#[test]
+fn response_is_split_into_monitor_responses() {
+    let mut first = batched_monitor("first", 11, vec!["echo 1"]);
+    first.extension_monitors = vec!["extension".to_string()];
+    let monitors = vec![first, batched_monitor("second", 12, vec!["echo 2"])];
+
+    let response = RequestResponse {
+        source_id: command_batch::BATCH_SOURCE_ID.to_string(),
+        invocation_id: 11,
+        request_type: RequestType::MonitorCommandBatch {
+            monitors,
+            delimiter: "delimiter".to_string(),
+            commands: Vec::new(),
+        },
+        responses: vec![Err(LkError::other("Connection lost"))],
+        ..Default::default()
+    };
+
+    let split = command_batch::split_response(response);
+    assert_eq!(split.len(), 2);
+    assert_eq!(split[0].source_id, "first");
+    assert_eq!(split[0].invocation_id, 11);
+    assert_eq!(split[1].source_id, "second");
+    assert_eq!(split[1].invocation_id, 12);
+    assert!(split.iter().all(|response| response.responses[0].is_err()));
+
+    match &split[0].request_type {
+        RequestType::MonitorCommand { extension_monitors, .. } => assert_eq!(extension_monitors, &vec!["extension".to_string()]),
+        _ => panic!("Invalid request type"),
+    }
+
+    let ok = RequestResponse {
+        request_type: RequestType::MonitorCommandBatch {
+            monitors: vec![batched_monitor("a", 1, vec!["x"]), batched_monitor("b", 2, vec!["y"])],
+            delimiter: "d".to_string(),
+            commands: Vec::new(),
+        },
+        responses: vec![Ok(ResponseMessage::new("x\n\nd 0 0 0\ny\n\nd 1 0 2\n".to_string(), 0))],
+        ..Default::default()
+    };
+
+    let split = command_batch::split_response(ok);
+    assert_eq!(split[1].responses[0].as_ref().unwrap().message, "y\n");
+    assert_eq!(split[1].responses[0].as_ref().unwrap().return_code, 2);
+}
@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use lightkeeper::connection_manager::{ConnectionState, ConnectionTracker, RequestStart};
+use lightkeeper::error::{ErrorKind, LkError};
+
+fn tracker(backoff_base_ms: u64) -> ConnectionTracker {
+    ConnectionTracker::new(Duration::from_millis(backoff_base_ms), Duration::from_secs(60))
+}
+
+#[test]
+fn successful_connection() {
+    let tracker = tracker(1000);
+    let (sender, receiver) = mpsc::channel();
+    tracker.set_state_update_sender(sender);
+
+    assert_eq!(tracker.get("host1"), ConnectionState::NotConnected);
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Probe);
+    assert_eq!(tracker.get("host1"), ConnectionState::Connecting);
+    tracker.end_request("host1", true, None);
+    assert_eq!(tracker.get("host1"), ConnectionState::Connected);
+
+    // Already connected, so no need to connect again.
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Connected);
+    // Command errors don't affect the connection state.
+    tracker.end_request("host1", false, Some(&LkError::other("Command failed")));
+    assert_eq!(tracker.get("host1"), ConnectionState::Connected);
+
+    let updates = receiver.try_iter().map(|update| (update.host_name, update.connection_state.unwrap())).collect::<Vec<_>>();
+    assert_eq!(updates, vec![
+        ("host1".to_string(), ConnectionState::Connecting),
+        ("host1".to_string(), ConnectionState::Connected),
+    ]);
+}
+
+#[test]
+fn failures_back_off_exponentially() {
+    let tracker = tracker(200);
+    let error = LkError::new(ErrorKind::ConnectionFailed, "Connection refused");
+
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Probe);
+    tracker.end_request("host1", true, Some(&error));
+
+    match tracker.get("host1") {
+        ConnectionState::Backoff { retry_at, error } => {
+            assert_eq!(error, "Connection refused");
+            assert!(retry_at <= Utc::now() + chrono::Duration::milliseconds(200));
+        },
+        state => panic!("Unexpected state: {:?}", state),
+    }
+
+    // Fails immediately during backoff.
+    let result = tracker.begin_request("host1");
+    assert_eq!(result.unwrap_err().kind, ErrorKind::ConnectionFailed);
+
+    thread::sleep(Duration::from_millis(250));
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Probe);
+    tracker.end_request("host1", true, Some(&error));
+
+    // Second failure doubles the delay.
+    match tracker.get("host1") {
+        ConnectionState::Backoff { retry_at, .. } => assert!(retry_at > Utc::now() + chrono::Duration::milliseconds(250)),
+        state => panic!("Unexpected state: {:?}", state),
+    }
+
+    // Host is unaffected.
+    assert_eq!(tracker.begin_request("host2").unwrap(), RequestStart::Probe);
+}
+
+#[test]
+fn authentication_and_host_key_failures() {
+    let tracker = tracker(1000);
+
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Probe);
+    tracker.end_request("host1", true, Some(&LkError::new(ErrorKind::AuthenticationFailed, "Permission denied")));
+    assert!(matches!(tracker.get("host1"), ConnectionState::AuthFailed { .. }));
+    assert_eq!(tracker.begin_request("host1").unwrap_err().kind, ErrorKind::AuthenticationFailed);
+
+    assert_eq!(tracker.begin_request("host2").unwrap(), RequestStart::Probe);
+    tracker.end_request("host2", true, Some(&LkError::host_key_unverified("ssh", "Host key changed", "key")));
+    assert_eq!(tracker.get("host2"), ConnectionState::HostKeyMismatch);
+    // No backoff since the user can verify the key at any time.
+    assert_eq!(tracker.begin_request("host2").unwrap(), RequestStart::Probe);
+}
+
+#[test]
+fn only_one_request_connects_at_a_time() {
+    let tracker = tracker(1000);
+    let (sender, receiver) = mpsc::channel();
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::Probe);
+
+    // Doesn't block. The request is parked until the probe finishes.
+    assert_eq!(tracker.begin_request("host1").unwrap(), RequestStart::ProbeInProgress);
+    assert!(tracker.wait_for_probe("host1", Box::new(move || sender.send(()).unwrap())).is_none());
+    // Other hosts are unaffected.
+    assert_eq!(tracker.begin_request("host2").unwrap(), RequestStart::Probe);
+
+    let waiting = tracker.end_request("host1", true, Some(&LkError::new(ErrorKind::ConnectionFailed, "Timed out")));
+    assert_eq!(waiting.len(), 1);
+    waiting.into_iter().for_each(|job| job());
+    receiver.try_recv().unwrap();
+
+    assert_eq!(tracker.begin_request("host1").unwrap_err().kind, ErrorKind::ConnectionFailed);
+    // Probe has already finished, so the job is returned right away.
+    assert!(tracker.wait_for_probe("host1", Box::new(|| ())).is_some());
+}
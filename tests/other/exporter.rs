@@ -0,0 +1,83 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use lightkeeper::configuration::Exporter;
+use lightkeeper::enums::Criticality;
+use lightkeeper::frontend::{DisplayData, DisplayOptions, HostDisplayData};
+use lightkeeper::host_manager::HostManager;
+use lightkeeper::metrics::{exporter, MetricsExporter};
+use lightkeeper::module::monitoring::{DataPoint, MonitoringData};
+
+fn http_get(exporter: &MetricsExporter, path: &str) -> String {
+    let mut stream = TcpStream::connect(exporter.local_address()).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn render_monitor_data() {
+    let mut host = HostDisplayData::default();
+
+    let mut load = MonitoringData::new(String::from("load"), DisplayOptions::default());
+    let mut old = DataPoint::new("0.1");
+    old.value_float = 0.1;
+    let mut latest = DataPoint::new("1.5");
+    latest.value_float = 1.5;
+    load.values.push_back(old);
+    load.values.push_back(latest);
+    host.host_state.monitor_data.insert(String::from("load"), load);
+
+    let mut filesystems = MonitoringData::new(String::from("filesystem"), DisplayOptions::default());
+    let mut parent = DataPoint::value_with_level(String::from("90 %"), Criticality::Warning);
+    let mut child = DataPoint::labeled_value_with_level(String::from("/mnt/\"data\""), String::from("90 %"), Criticality::Warning)
+        .with_tags(vec![String::from("ext4"), String::from("ro")]);
+    child.value_float = 90.0;
+    parent.multivalue.push(child);
+    filesystems.values.push_back(parent);
+    host.host_state.monitor_data.insert(String::from("filesystem"), filesystems);
+
+    let mut display_data = DisplayData::new();
+    display_data.hosts.insert(String::from("server1"), host);
+
+    let output = exporter::render(&display_data);
+    assert!(output.contains("# TYPE lightkeeper_monitor_value gauge\n"));
+    assert!(output.contains("lightkeeper_monitor_value{host=\"server1\",monitor=\"load\",label=\"\",tags=\"\"} 1.5\n"));
+    assert!(!output.contains("} 0.1\n"));
+    assert!(output.contains(
+        "lightkeeper_monitor_value{host=\"server1\",monitor=\"filesystem\",label=\"/mnt/\\\"data\\\"\",tags=\"ext4,ro\"} 90\n"
+    ));
+    // Multivalue parent is not exported as a separate series.
+    assert!(!output.contains("monitor=\"filesystem\",label=\"\""));
+    assert!(output.contains("lightkeeper_monitor_criticality{host=\"server1\",monitor=\"filesystem\"} 4\n"));
+    assert!(output.contains("lightkeeper_monitor_criticality{host=\"server1\",monitor=\"load\"} 1\n"));
+}
+
+#[test]
+fn serve_metrics_over_http() {
+    let host_manager = HostManager::new();
+    let mut exporter = MetricsExporter::start("127.0.0.1:0", host_manager.display_data_reader()).unwrap();
+
+    let response = http_get(&exporter, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+    assert!(response.contains("# TYPE lightkeeper_monitor_criticality gauge\n"));
+
+    assert!(http_get(&exporter, "/").starts_with("HTTP/1.1 404"));
+
+    exporter.stop();
+    assert!(TcpStream::connect(exporter.local_address()).is_err());
+}
+
+#[test]
+fn exporter_is_disabled_by_default() {
+    let config: Exporter = serde_yaml::from_str("enabled: true").unwrap();
+    assert_eq!(config.listen_address, Exporter::default().listen_address);
+    assert!(!Exporter::default().enabled);
+}
@@ -0,0 +1,235 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use lightkeeper::alerting::AlertManager;
+use lightkeeper::configuration::{self, AlertSinkConfig, Alerting, ConfigGroup, MaintenanceWindow};
+use lightkeeper::enums::{Criticality, HostStatus};
+use lightkeeper::frontend::{DisplayOptions, HostDisplayData, UIUpdate};
+use lightkeeper::host_manager::{HostManager, StateUpdateMessage};
+use lightkeeper::maintenance::{ScheduledWindow, Silence};
+use lightkeeper::module::ModuleSpecification;
+use lightkeeper::module::monitoring::{DataPoint, MonitoringData};
+use lightkeeper::utils::CronSchedule;
+
+use crate::temp_path;
+
+#[test]
+fn cron_schedule_matching() {
+    // 2026-01-05 is a Monday.
+    let monday_night = Utc.with_ymd_and_hms(2026, 1, 5, 3, 30, 0).unwrap();
+
+    assert!(CronSchedule::parse("30 3 * * *").unwrap().matches(&monday_night));
+    assert!(CronSchedule::parse("*/15 0-5 * * 1-5").unwrap().matches(&monday_night));
+    assert!(CronSchedule::parse("0,30 3 * 1 1").unwrap().matches(&monday_night));
+    assert!(!CronSchedule::parse("30 3 * * 0,6").unwrap().matches(&monday_night));
+    // Either day field is enough when both are restricted.
+    assert!(CronSchedule::parse("30 3 1 * 1").unwrap().matches(&monday_night));
+    // 7 is also Sunday.
+    assert!(CronSchedule::parse("30 3 * * 7").unwrap().matches(&(monday_night - Duration::days(1))));
+
+    assert!(CronSchedule::parse("30 3 * *").is_err());
+    assert!(CronSchedule::parse("60 3 * * *").is_err());
+    assert!(CronSchedule::parse("*/0 3 * * *").is_err());
+    assert!(CronSchedule::parse("5-1 3 * * *").is_err());
+}
+
+#[test]
+fn recurring_window_is_active_for_duration() {
+    let window = ScheduledWindow::new(&MaintenanceWindow {
+        schedule: String::from("0 2 * * 6"),
+        duration: 120,
+        ..Default::default()
+    }).unwrap();
+
+    // 2026-01-10 is a Saturday.
+    let start = Utc.with_ymd_and_hms(2026, 1, 10, 2, 0, 0).unwrap();
+    assert_eq!(window.active_period(&start), Some((start, start + Duration::minutes(120))));
+    assert!(window.active_period(&(start + Duration::minutes(119))).is_some());
+    assert!(window.active_period(&(start + Duration::minutes(120))).is_none());
+    assert!(window.active_period(&(start - Duration::minutes(1))).is_none());
+    assert!(window.active_period(&(start - Duration::days(1))).is_none());
+}
+
+#[test]
+fn recurring_window_spanning_days() {
+    let window = ScheduledWindow::new(&MaintenanceWindow {
+        schedule: String::from("0 22 * * 5"),
+        duration: 3 * 24 * 60,
+        ..Default::default()
+    }).unwrap();
+
+    // 2026-01-09 is a Friday.
+    let start = Utc.with_ymd_and_hms(2026, 1, 9, 22, 0, 0).unwrap();
+    assert_eq!(window.active_period(&(start + Duration::hours(50))), Some((start, start + Duration::days(3))));
+    assert!(window.active_period(&(start + Duration::days(3))).is_none());
+    assert!(window.active_period(&(start - Duration::minutes(1))).is_none());
+}
+
+#[test]
+fn cron_schedule_latest_match() {
+    let schedule = CronSchedule::parse("*/20 8-16 * * 1-5").unwrap();
+
+    // 2026-01-10 is a Saturday, so the latest match is on Friday.
+    let saturday = Utc.with_ymd_and_hms(2026, 1, 10, 12, 5, 0).unwrap();
+    let friday_evening = Utc.with_ymd_and_hms(2026, 1, 9, 16, 40, 0).unwrap();
+    assert_eq!(schedule.latest_between(&(saturday - Duration::days(2)), &saturday), Some(friday_evening));
+    assert_eq!(schedule.latest_between(&(saturday - Duration::hours(12)), &saturday), None);
+
+    let friday_noon = Utc.with_ymd_and_hms(2026, 1, 9, 12, 19, 0).unwrap();
+    assert_eq!(schedule.latest_between(&friday_noon, &friday_noon), None);
+    assert_eq!(schedule.latest_between(&(friday_noon - Duration::hours(1)), &friday_noon), Some(friday_noon - Duration::minutes(19)));
+}
+
+#[test]
+fn one_off_window_validation() {
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 20, 0, 0).unwrap().fixed_offset();
+    let window = ScheduledWindow::new(&MaintenanceWindow {
+        start: Some(start),
+        end: Some(start + Duration::hours(1)),
+        ..Default::default()
+    }).unwrap();
+
+    assert!(window.active_period(&(start + Duration::minutes(30))).is_some());
+    assert!(window.active_period(&(start + Duration::hours(2))).is_none());
+
+    assert!(ScheduledWindow::new(&MaintenanceWindow { start: Some(start), ..Default::default() }).is_err());
+    assert!(ScheduledWindow::new(&MaintenanceWindow { schedule: String::from("0 2 * * *"), ..Default::default() }).is_err());
+}
+
+#[test]
+fn maintenance_windows_are_parsed_from_groups() {
+    let group: ConfigGroup = serde_yaml::from_str(r#"
+maintenance_windows:
+  - schedule: "0 3 * * 0"
+    duration: 60
+    monitors: ["ping"]
+  - start: "2026-01-31T22:00:00+02:00"
+    end: "2026-02-01T02:00:00+02:00"
+    comment: "Hardware upgrade"
+"#).unwrap();
+
+    assert_eq!(group.maintenance_windows.len(), 2);
+    assert!(group.maintenance_windows.iter().all(|window| ScheduledWindow::new(window).is_ok()));
+}
+
+#[test]
+fn silenced_critical_monitor_does_not_bring_host_down() {
+    let mut host_state = HostDisplayData::default().host_state;
+    host_state.is_initialized = true;
+
+    let mut ping = MonitoringData::new(String::from("ping"), DisplayOptions::default());
+    ping.is_critical = true;
+    ping.values.push_back(DataPoint::value_with_level(String::from("timeout"), Criticality::Critical));
+    host_state.monitor_data.insert(String::from("ping"), ping);
+
+    host_state.update_status();
+    assert_eq!(host_state.status, HostStatus::Down);
+
+    let now = Utc::now();
+    let mut silence = Silence {
+        id: 1,
+        host_name: String::new(),
+        monitors: vec![String::from("ping")],
+        labels: vec![String::from("eth0")],
+        starts_at: now,
+        ends_at: now + Duration::hours(1),
+        comment: String::new(),
+    };
+
+    // Label-specific silence doesn't cover the whole monitor.
+    host_state.silences = vec![silence.clone()];
+    host_state.update_status();
+    assert_eq!(host_state.status, HostStatus::Down);
+    assert!(host_state.is_silenced("ping", "eth0"));
+
+    silence.labels.clear();
+    host_state.silences = vec![silence];
+    host_state.update_status();
+    assert_eq!(host_state.status, HostStatus::Up);
+    assert!(!host_state.is_silenced("load", ""));
+    // Criticality is still shown.
+    assert_eq!(host_state.monitor_data["ping"].values.back().unwrap().criticality, Criticality::Critical);
+}
+
+#[test]
+fn host_status_and_alerts_follow_silence_end() {
+    let output_path = temp_path("silence-end");
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([("test-host".to_string(), host_settings)]),
+        ..Default::default()
+    };
+
+    let mut host_manager = HostManager::new();
+    host_manager.configure(&hosts_config);
+
+    let mut alert_manager = AlertManager::new();
+    alert_manager.configure(&Alerting {
+        sinks: vec![AlertSinkConfig::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), format!("echo \"$LIGHTKEEPER_ALERT_KIND\" >> {}", output_path.display())],
+        }],
+        ..Default::default()
+    });
+    alert_manager.start_processing_updates();
+    host_manager.add_observer(alert_manager.new_update_sender());
+
+    let (ui_sender, ui_receiver) = mpsc::channel();
+    host_manager.add_observer(ui_sender);
+    host_manager.start_receiving_updates();
+
+    host_manager.create_silence("test-host", None, None, 2, String::new()).unwrap();
+
+    let state_update_sender = host_manager.new_state_update_sender();
+    for (invocation_id, data_point) in [(0, DataPoint::pending()), (1, DataPoint::value_with_level("timeout".to_string(), Criticality::Critical))] {
+        state_update_sender.send(StateUpdateMessage {
+            host_name: "test-host".to_string(),
+            module_spec: ModuleSpecification::monitor("ping", "0.0.1"),
+            data_point: Some(data_point),
+            invocation_id,
+            is_critical: true,
+            ..Default::default()
+        }).unwrap();
+    }
+
+    let mut next_host_update = || loop {
+        if let UIUpdate::Host(display_data) = ui_receiver.recv_timeout(std::time::Duration::from_secs(15)).unwrap() {
+            break display_data;
+        }
+    };
+
+    let display_data = std::iter::repeat_with(&mut next_host_update)
+        .find(|display_data| display_data.new_monitoring_data.is_some())
+        .unwrap();
+    assert_ne!(display_data.host_state.status, HostStatus::Down);
+
+    // Host goes down after the silence ends, without any new data.
+    let display_data = std::iter::repeat_with(&mut next_host_update)
+        .find(|display_data| display_data.host_state.status == HostStatus::Down)
+        .unwrap();
+    assert!(display_data.new_monitoring_data.is_none());
+    assert!(display_data.host_state.silences.is_empty());
+
+    let mut contents = String::new();
+    for _ in 0..50 {
+        contents = std::fs::read_to_string(&output_path).unwrap_or_default();
+        if !contents.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    alert_manager.stop();
+    host_manager.stop();
+    let _ = std::fs::remove_file(&output_path);
+    assert_eq!(contents.trim(), "firing");
+}
@@ -0,0 +1,205 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::os::unix::fs::MetadataExt;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use lightkeeper::frontend::UIUpdate;
+use lightkeeper::metrics::lmserver::Metric;
+use lightkeeper::metrics::{self, MetricsManager, MetricsStore, RetentionPolicy};
+
+use crate::temp_path;
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn metric(time_sec: i64, label: &str, value: f32) -> Metric {
+    Metric {
+        time: time_sec * 1000,
+        label: label.to_string(),
+        value,
+    }
+}
+
+#[test]
+fn insert_and_query() {
+    let dir = temp_path("metrics-query");
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+
+    store.insert("host/1", "load", &[metric(100, "", 1.0), metric(200, "", 2.0)]).unwrap();
+    store.insert("host/1", "load", &[metric(300, "", 3.0), metric(300, "cpu0", 0.5)]).unwrap();
+    store.insert("other", "load", &[metric(100, "", 9.0)]).unwrap();
+
+    let result = store.query("host/1", "load", 150, 300).unwrap();
+    let values = result[""].iter().map(|metric| metric.value).collect::<Vec<_>>();
+    assert_eq!(values, vec![2.0, 3.0]);
+    assert_eq!(result["cpu0"].len(), 1);
+
+    assert!(store.query("host/1", "unknown", 0, 1000).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn compaction_downsamples_and_expires() {
+    let dir = temp_path("metrics-compact");
+    let retention = RetentionPolicy {
+        raw_retention: DAY,
+        rollup_interval: 3600,
+        rollup_retention: 10 * DAY,
+    };
+    let store = MetricsStore::new(dir.clone(), retention).unwrap();
+    let now = 100 * DAY;
+
+    store.insert("host", "ping", &[
+        // Expired completely.
+        metric(now - 20 * DAY, "", 100.0),
+        // Downsampled into one hourly average.
+        metric(now - 5 * DAY, "", 10.0),
+        metric(now - 5 * DAY + 60, "", 20.0),
+        // Kept as is.
+        metric(now - 60, "", 5.0),
+    ]).unwrap();
+
+    store.compact(now).unwrap();
+
+    let result = store.query("host", "ping", 0, now).unwrap();
+    let values = result[""].iter().map(|metric| metric.value).collect::<Vec<_>>();
+    assert_eq!(values, vec![15.0, 5.0]);
+    assert_eq!(result[""][0].time, (now - 5 * DAY) * 1000);
+
+    // Compacting again doesn't change anything.
+    store.compact(now).unwrap();
+    assert_eq!(store.query("host", "ping", 0, now).unwrap()[""].len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn interval_crossing_cutoff_is_downsampled_once() {
+    let dir = temp_path("metrics-compact-twice");
+    let retention = RetentionPolicy {
+        raw_retention: DAY,
+        rollup_interval: 3600,
+        rollup_retention: 10 * DAY,
+    };
+    let store = MetricsStore::new(dir.clone(), retention).unwrap();
+    let now = 100 * DAY + 1800;
+
+    // Same interval, on both sides of the unaligned cutoff.
+    store.insert("host", "ping", &[metric(99 * DAY + 600, "", 10.0), metric(99 * DAY + 3000, "", 20.0)]).unwrap();
+
+    // Nothing to downsample yet, so the segment isn't rewritten.
+    let segment = dir.join("host").join("ping.seg");
+    let inode = std::fs::metadata(&segment).unwrap().ino();
+    store.compact(now).unwrap();
+    assert_eq!(std::fs::metadata(&segment).unwrap().ino(), inode);
+    assert_eq!(store.query("host", "ping", 0, now).unwrap()[""].len(), 2);
+
+    store.compact(now + 3600).unwrap();
+    store.compact(now + 7200).unwrap();
+
+    let result = store.query("host", "ping", 0, now).unwrap();
+    assert_eq!(result[""].len(), 1);
+    assert_eq!(result[""][0].time, 99 * DAY * 1000);
+    assert_eq!(result[""][0].value, 15.0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn truncated_record_is_ignored() {
+    let dir = temp_path("metrics-truncated");
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+    store.insert("host", "ram", &[metric(100, "", 1.0), metric(200, "", 2.0)]).unwrap();
+
+    let segment = dir.join("host").join("ram.seg");
+    let contents = std::fs::read(&segment).unwrap();
+    std::fs::write(&segment, &contents[..contents.len() - 3]).unwrap();
+
+    assert_eq!(store.query("host", "ram", 0, 1000).unwrap()[""].len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn append_after_truncated_record() {
+    let dir = temp_path("metrics-append-truncated");
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+    store.insert("host", "ram", &[metric(100, "", 1.0), metric(200, "", 2.0)]).unwrap();
+
+    // Partial record left by a crash.
+    let segment = dir.join("host").join("ram.seg");
+    let contents = std::fs::read(&segment).unwrap();
+    std::fs::write(&segment, &contents[..contents.len() - 3]).unwrap();
+
+    // Also after restart.
+    store.insert("host", "ram", &[metric(300, "", 3.0)]).unwrap();
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+    store.insert("host", "ram", &[metric(400, "", 4.0)]).unwrap();
+
+    let values = store.query("host", "ram", 0, 1000).unwrap()[""].iter().map(|metric| metric.value).collect::<Vec<_>>();
+    assert_eq!(values, vec![1.0, 3.0, 4.0]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn append_after_truncated_header() {
+    let dir = temp_path("metrics-append-header");
+    std::fs::create_dir_all(dir.join("host")).unwrap();
+    std::fs::write(dir.join("host").join("ram.seg"), b"LK").unwrap();
+
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+    assert!(store.query("host", "ram", 0, 1000).unwrap().is_empty());
+
+    store.insert("host", "ram", &[metric(100, "", 1.0)]).unwrap();
+    assert_eq!(store.query("host", "ram", 0, 1000).unwrap()[""].len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn long_label_is_cut_on_char_boundary() {
+    let dir = temp_path("metrics-long-label");
+    let store = MetricsStore::new(dir.clone(), RetentionPolicy::default()).unwrap();
+
+    // 4-byte characters don't divide evenly into the maximum length.
+    let label = "😀".repeat(u16::MAX as usize / 4 + 1);
+    store.insert("host", "load", &[metric(100, &label, 1.0)]).unwrap();
+
+    let result = store.query("host", "load", 0, 1000).unwrap();
+    let stored_label = result.keys().next().unwrap();
+    assert_eq!(stored_label.len(), u16::MAX as usize / 4 * 4);
+    assert!(label.starts_with(stored_label.as_str()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn manager_responds_with_chart_updates() {
+    let dir = temp_path("metrics-manager");
+    let (sender, receiver) = mpsc::channel();
+    let mut manager = MetricsManager::new(sender);
+    manager.start_service_in(dir.clone()).unwrap();
+
+    let now = chrono::Utc::now().timestamp();
+    manager.insert_metrics("host", "load", &[metrics::Metric { time: now * 1000, label: String::new(), value: 1.5 }]).unwrap();
+    let query_id = manager.get_metrics("host", "load", now - 60, now + 60).unwrap();
+
+    let response = loop {
+        match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            UIUpdate::Chart(response) if response.request_id == query_id => break response,
+            _ => continue,
+        }
+    };
+
+    assert!(response.errors.is_empty());
+    assert_eq!(response.metrics[""][0].value, 1.5);
+
+    manager.stop().unwrap();
+    assert!(!manager.is_running());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1 +1,16 @@
 pub mod invocation_id;
+pub mod scheduler;
+pub mod alerting;
+pub mod maintenance;
+pub mod metrics_store;
+pub mod exporter;
+pub mod cli;
+pub mod bulk;
+pub mod runbook;
+pub mod ssh;
+pub mod privilege_escalation;
+pub mod unix_socket;
+pub mod audit_log;
+pub mod connection_state;
+pub mod command_batch;
+pub mod request_limits;
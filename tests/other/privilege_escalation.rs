@@ -0,0 +1,78 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+
+use lightkeeper::configuration;
+use lightkeeper::enums::Criticality;
+use lightkeeper::module::command::{systemd, CommandModule};
+use lightkeeper::module::platform_info::{Flavor, PlatformInfo};
+use lightkeeper::module::*;
+use lightkeeper::utils::{PrivilegeEscalation, ShellCommand};
+use lightkeeper::{HostSetting, ModuleFactory};
+
+use crate::{CommandTestHarness, StubSsh2, TEST_HOST_ID};
+
+#[test]
+fn shell_command_wrappers() {
+    let render = |escalation: PrivilegeEscalation| {
+        let mut command = ShellCommand::new_from(vec!["systemctl", "stop", "it's.service"]).use_sudo().escalate_with(escalation);
+        command.pipe_to(vec!["head", "-n", "1"]);
+        command.to_string()
+    };
+
+    assert_eq!(render(PrivilegeEscalation::Sudo), r#""sudo" "systemctl" "stop" "it's.service" | "head" "-n" "1""#);
+    assert_eq!(render(PrivilegeEscalation::Doas), r#""doas" "-n" "systemctl" "stop" "it's.service" | "head" "-n" "1""#);
+    assert_eq!(render(PrivilegeEscalation::Run0), r#""run0" "systemctl" "stop" "it's.service" | "head" "-n" "1""#);
+    assert_eq!(
+        render(PrivilegeEscalation::Su),
+        r#""su" "root" "-c" '"systemctl" "stop" "it'\''s.service" | "head" "-n" "1"'"#
+    );
+
+    // Commands rendered by modules without sudo are left as they are.
+    assert_eq!(PrivilegeEscalation::Doas.rewrite(r#""systemctl" "status""#), r#""systemctl" "status""#);
+    assert_eq!(PrivilegeEscalation::Doas.rewrite("sudo sh -c 'id'"), r#""doas" "-n" sh -c 'id'"#);
+    assert_eq!(PrivilegeEscalation::Su.rsync_path(), None);
+    assert_eq!(PrivilegeEscalation::Doas.login_shell(), vec!["doas", "-s"]);
+}
+
+#[test]
+fn commands_use_host_privilege_escalation() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        StubSsh2::new(r#""doas" "-n" "systemctl" "stop" "test-service.service""#, "", 0)
+    };
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    host_settings.effective.host_settings = vec![HostSetting::UseDoas];
+    host_settings.effective.connectors.insert(StubSsh2::get_metadata().module_spec.id.clone(), Default::default());
+    host_settings.effective.commands.insert(
+        systemd::service::Stop::get_metadata().module_spec.id.clone(),
+        configuration::CommandConfig {
+            version: "0.0.1".to_string(),
+            ..Default::default()
+        },
+    );
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([(TEST_HOST_ID.to_string(), host_settings)]),
+        predefined_platforms: BTreeMap::from([(TEST_HOST_ID.to_string(), PlatformInfo::linux(Flavor::Debian, "12.0"))]),
+        ..Default::default()
+    };
+
+    let module_factory = ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![],
+        vec![(systemd::service::Stop::get_metadata(), systemd::service::Stop::new_command_module)],
+    );
+
+    let mut harness = CommandTestHarness::new(hosts_config, module_factory);
+    let module_id = systemd::service::Stop::get_metadata().module_spec.id.clone();
+    harness.execute_command(&module_id, vec!["test-service.service".to_string()]);
+
+    harness.verify_next_command_result(&module_id, |result| {
+        assert_eq!(result.criticality, Criticality::Info);
+    });
+}
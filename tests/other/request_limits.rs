@@ -0,0 +1,403 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lightkeeper::audit_log::{AuditLogger, AuditQuery};
+use lightkeeper::configuration::{self, MonitorConfig};
+use lightkeeper::connection_manager::request_limits::{HostQueue, RequestLimits, Watchdog, REQUEST_TIMEOUT_SETTING};
+use lightkeeper::connection_manager::{ConnectionManager, ConnectorRequest, RequestType};
+use lightkeeper::error::{ErrorKind, LkError};
+use lightkeeper::module::connection::*;
+use lightkeeper::module::*;
+use lightkeeper::monitor_manager::command_batch::{BatchedMonitor, BATCH_SOURCE_ID};
+use lightkeeper::ModuleFactory;
+
+use crate::temp_path;
+use lightkeeper_module::connection_module;
+
+#[connection_module(
+    name="hanging",
+    version="0.0.1",
+    description="Never responds until interrupted.",
+    settings={
+    }
+)]
+pub struct HangingConnector {
+    /// Interrupted invocation IDs.
+    interrupted: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Module for HangingConnector {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        HangingConnector {
+            interrupted: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl ConnectionModule for HangingConnector {
+    fn send_message(&self, _message: &str) -> Result<ResponseMessage, LkError> {
+        Err(LkError::other("Not interruptible"))
+    }
+
+    fn send_message_interruptible(&self, _message: &str, invocation_id: u64) -> Result<ResponseMessage, LkError> {
+        while !self.interrupted.lock().unwrap().contains(&invocation_id) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        Err(LkError::other("Command was interrupted"))
+    }
+
+    fn interrupt(&self, invocation_id: u64) -> Result<(), LkError> {
+        self.interrupted.lock().unwrap().push(invocation_id);
+        Ok(())
+    }
+}
+
+#[connection_module(
+    name="slow-connect",
+    version="0.0.1",
+    description="Takes a while to connect and then fails.",
+    settings={
+    }
+)]
+pub struct SlowConnector {
+    connect_delay: Duration,
+}
+
+impl Module for SlowConnector {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        SlowConnector {
+            connect_delay: Duration::ZERO,
+        }
+    }
+}
+
+impl ConnectionModule for SlowConnector {
+    fn connect(&self) -> Result<(), LkError> {
+        if self.connect_delay.is_zero() {
+            Ok(())
+        }
+        else {
+            thread::sleep(self.connect_delay);
+            Err(LkError::new(ErrorKind::ConnectionFailed, "Connection timed out"))
+        }
+    }
+
+    fn send_message(&self, _message: &str) -> Result<ResponseMessage, LkError> {
+        Ok(ResponseMessage::new_success(String::new()))
+    }
+}
+
+#[test]
+fn host_queue_limits_concurrent_jobs() {
+    let queue = HostQueue::new(2);
+    let (sender, receiver) = mpsc::channel();
+    let job = |id: u32| -> Box<dyn FnOnce() + Send> {
+        let sender = sender.clone();
+        Box::new(move || sender.send(id).unwrap())
+    };
+
+    assert!(queue.submit("host1", job(1)).is_some());
+    assert!(queue.submit("host1", job(2)).is_some());
+    assert!(queue.submit("host1", job(3)).is_none());
+    assert!(queue.submit("host1", job(4)).is_none());
+    // Other hosts are unaffected.
+    assert!(queue.submit("host2", job(5)).is_some());
+
+    assert_eq!(queue.running("host1"), 2);
+    assert_eq!(queue.queued("host1"), 2);
+
+    // Queued jobs are returned in order and keep the slot reserved.
+    queue.finish("host1").unwrap()();
+    queue.finish("host1").unwrap()();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!(queue.running("host1"), 2);
+
+    assert!(queue.finish("host1").is_none());
+    assert!(queue.finish("host1").is_none());
+    assert_eq!(queue.running("host1"), 0);
+
+    let unlimited = HostQueue::new(0);
+    for id in 0..10 {
+        assert!(unlimited.submit("host1", job(id)).is_some());
+    }
+}
+
+#[test]
+fn watchdog_runs_action_on_timeout() {
+    let watchdog = Arc::new(Watchdog::default());
+    let thread = watchdog.start();
+    let (sender, receiver) = mpsc::channel();
+
+    let expiring = {
+        let sender = sender.clone();
+        watchdog.watch(Duration::from_millis(50), move || sender.send("expired").unwrap())
+    };
+    let finished = watchdog.watch(Duration::from_millis(100), move || sender.send("finished").unwrap());
+    drop(finished);
+
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), "expired");
+    assert!(expiring.is_expired());
+    assert_eq!(expiring.error().kind, ErrorKind::TimedOut);
+
+    // Dropped guards are not run.
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+    watchdog.stop();
+    thread.join().unwrap();
+}
+
+#[test]
+fn module_timeouts_override_default() {
+    let monitor = |timeout: &str| MonitorConfig {
+        settings: HashMap::from([(REQUEST_TIMEOUT_SETTING.to_string(), timeout.to_string())]),
+        ..Default::default()
+    };
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.effective.monitors.insert("package".to_string(), monitor("900"));
+    host_settings.effective.monitors.insert("logs".to_string(), monitor("0"));
+    host_settings.effective.monitors.insert("invalid".to_string(), monitor("soon"));
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([("host1".to_string(), host_settings)]),
+        ..Default::default()
+    };
+
+    let mut limits = RequestLimits::default();
+    limits.set_config(&configuration::Connections {
+        default_timeout: 60,
+        ..Default::default()
+    });
+    limits.set_module_timeouts(&hosts_config);
+
+    assert_eq!(limits.timeout("host1", &["uptime"]), Some(Duration::from_secs(60)));
+    assert_eq!(limits.timeout("host1", &["invalid"]), Some(Duration::from_secs(60)));
+    assert_eq!(limits.timeout("host1", &["package"]), Some(Duration::from_secs(900)));
+    assert_eq!(limits.timeout("host2", &["package"]), Some(Duration::from_secs(60)));
+    assert_eq!(limits.timeout("host1", &["logs"]), None);
+    // Batched monitors get the longest timeout.
+    assert_eq!(limits.timeout("host1", &["uptime", "package"]), Some(Duration::from_secs(900)));
+    assert_eq!(limits.timeout("host1", &["uptime", "logs"]), None);
+
+    limits.set_config(&configuration::Connections {
+        default_timeout: 0,
+        ..Default::default()
+    });
+    assert_eq!(limits.timeout("host1", &["uptime"]), None);
+}
+
+#[test]
+fn module_default_timeouts() {
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.effective.commands.insert("linux-packages-update-all".to_string(), configuration::CommandConfig {
+        settings: HashMap::from([(REQUEST_TIMEOUT_SETTING.to_string(), "7200".to_string())]),
+        ..Default::default()
+    });
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([("host1".to_string(), host_settings)]),
+        ..Default::default()
+    };
+
+    let mut limits = RequestLimits::default();
+    limits.set_config(&configuration::Connections {
+        default_timeout: 60,
+        ..Default::default()
+    });
+    limits.set_module_timeouts(&hosts_config);
+    limits.set_module_default_timeouts(&ModuleFactory::new());
+
+    assert_eq!(limits.timeout("host2", &["linux-packages-update-all"]), Some(Duration::from_secs(3600)));
+    assert_eq!(limits.timeout("host2", &["uptime"]), Some(Duration::from_secs(30)));
+    // Modules without their own default use the global default.
+    assert_eq!(limits.timeout("host2", &["filesystem"]), Some(Duration::from_secs(60)));
+    // Module setting overrides the module's default.
+    assert_eq!(limits.timeout("host1", &["linux-packages-update-all"]), Some(Duration::from_secs(7200)));
+    assert_eq!(limits.timeout("host2", &["uptime", "filesystem"]), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn zero_concurrent_requests_is_rejected() {
+    let mut config = configuration::Connections::default();
+    assert!(config.validate().is_ok());
+
+    config.max_concurrent_requests = 0;
+    assert!(config.validate().is_err());
+
+    // Per-host limit of 0 means no limit.
+    let config = configuration::Connections {
+        max_concurrent_requests_per_host: 0,
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn hanging_request_times_out() {
+    let connector_spec = HangingConnector::get_metadata().module_spec;
+    let module_factory = ModuleFactory::new_with(
+        vec![(HangingConnector::get_metadata(), HangingConnector::new_connection_module)],
+        vec![],
+        vec![],
+    );
+
+    let mut connection_manager = ConnectionManager::new(Arc::new(module_factory));
+    connection_manager.configure(&configuration::Hosts::default());
+    connection_manager.set_limits(&configuration::Connections {
+        default_timeout: 1,
+        ..Default::default()
+    });
+    connection_manager.inject_test_connector("", connector_spec.clone(), HangingConnector::new_connection_module(&HashMap::new()));
+    connection_manager.start_processing_requests();
+
+    let (response_sender, response_receiver) = mpsc::channel();
+    connection_manager.new_request_sender().send(ConnectorRequest {
+        connector_spec: Some(connector_spec),
+        source_id: "package".to_string(),
+        // Host with an empty name.
+        host: Default::default(),
+        invocation_id: 1,
+        request_type: RequestType::Command { commands: vec!["dnf check-update".to_string(), "true".to_string()] },
+        response_sender,
+    }).unwrap();
+
+    let response = response_receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(response.responses.len(), 1);
+
+    let error = response.responses[0].as_ref().unwrap_err();
+    assert_eq!(error.kind, ErrorKind::TimedOut);
+    assert_eq!(error.to_string(), "hanging: Request timed out after 1 seconds");
+
+    connection_manager.stop();
+}
+
+#[test]
+fn connecting_hosts_dont_block_workers() {
+    let connector_spec = SlowConnector::get_metadata().module_spec;
+    let module_factory = ModuleFactory::new_with(
+        vec![(SlowConnector::get_metadata(), SlowConnector::new_connection_module)],
+        vec![],
+        vec![],
+    );
+
+    let mut connection_manager = ConnectionManager::new(Arc::new(module_factory));
+    connection_manager.configure(&configuration::Hosts::default());
+    connection_manager.set_limits(&configuration::Connections {
+        max_concurrent_requests: 3,
+        max_concurrent_requests_per_host: 3,
+        ..Default::default()
+    });
+
+    for host_name in ["unreachable1", "unreachable2"] {
+        let connector = Box::new(SlowConnector { connect_delay: Duration::from_secs(3) }) as Connector;
+        connection_manager.inject_test_connector(host_name, connector_spec.clone(), connector);
+    }
+    connection_manager.inject_test_connector("reachable", connector_spec.clone(), Box::new(SlowConnector::new(&HashMap::new())));
+    connection_manager.start_processing_requests();
+
+    let (response_sender, response_receiver) = mpsc::channel();
+    let request_sender = connection_manager.new_request_sender();
+    let host_names = ["unreachable1", "unreachable2", "unreachable1", "unreachable2", "unreachable1", "unreachable2", "reachable"];
+    for (invocation_id, host_name) in host_names.into_iter().enumerate() {
+        let mut request = ConnectorRequest {
+            connector_spec: Some(connector_spec.clone()),
+            source_id: "uptime".to_string(),
+            host: Default::default(),
+            invocation_id: invocation_id as u64,
+            request_type: RequestType::Command { commands: vec!["uptime".to_string()] },
+            response_sender: response_sender.clone(),
+        };
+        request.host.name = host_name.to_string();
+        request_sender.send(request).unwrap();
+    }
+
+    // Requests waiting for the other hosts to connect don't occupy the remaining worker.
+    let started = Instant::now();
+    let response = response_receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert_eq!(response.host.name, "reachable");
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // Waiting requests fail once the connection attempts have failed.
+    let responses = (0..6).map(|_| response_receiver.recv_timeout(Duration::from_secs(10)).unwrap()).collect::<Vec<_>>();
+    for response in responses {
+        assert_eq!(response.responses[0].as_ref().unwrap_err().kind, ErrorKind::ConnectionFailed);
+    }
+
+    connection_manager.stop();
+}
+
+#[test]
+fn batched_monitors_can_be_interrupted() {
+    let connector_spec = HangingConnector::get_metadata().module_spec;
+    let module_factory = ModuleFactory::new_with(
+        vec![(HangingConnector::get_metadata(), HangingConnector::new_connection_module)],
+        vec![],
+        vec![],
+    );
+
+    let audit_dir = temp_path("batch-interrupt");
+    let audit_log = Arc::new(AuditLogger::new(audit_dir.clone(), &configuration::AuditLog::default()).unwrap());
+
+    let mut connection_manager = ConnectionManager::new(Arc::new(module_factory));
+    connection_manager.configure(&configuration::Hosts::default());
+    connection_manager.set_audit_log(Some(audit_log.clone()));
+    connection_manager.inject_test_connector("", connector_spec.clone(), HangingConnector::new_connection_module(&HashMap::new()));
+    connection_manager.start_processing_requests();
+
+    let batched_monitor = |monitor_id: &str, invocation_id: u64| BatchedMonitor {
+        monitor_id: monitor_id.to_string(),
+        invocation_id,
+        extension_monitors: Vec::new(),
+        commands: vec!["true".to_string()],
+    };
+
+    let (response_sender, response_receiver) = mpsc::channel();
+    let request_sender = connection_manager.new_request_sender();
+    request_sender.send(ConnectorRequest {
+        connector_spec: Some(connector_spec.clone()),
+        source_id: BATCH_SOURCE_ID.to_string(),
+        host: Default::default(),
+        invocation_id: 1,
+        request_type: RequestType::MonitorCommandBatch {
+            monitors: vec![batched_monitor("uptime", 1), batched_monitor("kernel", 2)],
+            delimiter: "delimiter".to_string(),
+            commands: vec!["true".to_string()],
+        },
+        response_sender: response_sender.clone(),
+    }).unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+
+    // Interrupt aimed at the second monitor stops the whole batch.
+    request_sender.send(ConnectorRequest {
+        connector_spec: None,
+        source_id: "kernel".to_string(),
+        host: Default::default(),
+        invocation_id: 3,
+        request_type: RequestType::Interrupt { invocation_id: 2 },
+        response_sender,
+    }).unwrap();
+
+    let responses = (0..2).map(|_| response_receiver.recv_timeout(Duration::from_secs(5)).unwrap()).collect::<Vec<_>>();
+    let batch_response = responses.iter().find(|response| response.invocation_id == 1).unwrap();
+    assert_eq!(batch_response.responses[0].as_ref().unwrap_err().message, "Command was interrupted");
+
+    // Batched monitors are listed in the audit log and can be searched for.
+    let query = AuditQuery {
+        source_id: Some("kernel".to_string()),
+        ..Default::default()
+    };
+    let records = audit_log.query(&query).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].batched_monitors, vec!["uptime".to_string(), "kernel".to_string()]);
+
+    connection_manager.stop();
+    let _ = std::fs::remove_dir_all(&audit_dir);
+}
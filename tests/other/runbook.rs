@@ -0,0 +1,145 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use lightkeeper::command_handler::{RunState, StepState};
+use lightkeeper::configuration::{self, get_default_main_config, Configuration, Runbooks};
+use lightkeeper::enums::Criticality;
+use lightkeeper::frontend::cli;
+use lightkeeper::module::command::{systemd, CommandModule};
+use lightkeeper::module::connection::Connector;
+use lightkeeper::module::monitoring::linux::Load;
+use lightkeeper::module::monitoring::MonitoringModule;
+use lightkeeper::module::platform_info::{Flavor, PlatformInfo};
+use lightkeeper::module::MetadataSupport;
+use lightkeeper::{HostSetting, ModuleFactory};
+
+use crate::StubSsh2;
+
+const TEST_RUNBOOKS: &str = r#"
+runbooks:
+  restart:
+    steps:
+      - name: "Stop service"
+        command: systemd-service-stop
+        parameters: ["test-service.service"]
+        rollback:
+          - command: systemd-service-start
+            parameters: ["test-service.service"]
+      - name: "Wait for load"
+        wait_for:
+          monitors: ["load"]
+          refresh_interval: 1
+        timeout: 10
+      - name: "Start broken service"
+        command: systemd-service-start
+        parameters: ["broken.service"]
+      - name: "Never run"
+        command: systemd-service-start
+        parameters: ["test-service.service"]
+  unknown-monitor:
+    steps:
+      - name: "Wait"
+        wait_for:
+          monitors: ["ping"]
+"#;
+
+#[test]
+fn example_runbooks_are_valid() {
+    let runbooks = serde_yaml::from_str::<Runbooks>(configuration::EXAMPLE_RUNBOOKS_CONFIG).unwrap();
+    let runbook = &runbooks.runbooks["update-and-reboot"];
+    assert_eq!(runbook.steps.len(), 4);
+    assert_eq!(runbook.steps[0].rollback[0].command, "docker-compose-start");
+    assert_eq!(runbook.steps[1].timeout, 300);
+
+    let condition = runbook.steps[3].wait_for.as_ref().unwrap();
+    assert_eq!(condition.monitors, vec![String::from("ping"), String::from("systemd-service")]);
+    assert_eq!(condition.criticality, Criticality::Normal);
+
+    // Runbooks are optional.
+    let missing = Configuration::read_runbooks("/nonexistent/lightkeeper").unwrap();
+    assert!(missing.runbooks.is_empty());
+}
+
+#[test]
+fn failed_step_is_rolled_back() {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| {
+        let mut ssh = StubSsh2::default();
+        ssh.add_response("uptime", " 10:00:00 up 1 day,  1 user,  load average: 0.50, 0.40, 0.30", 0);
+        ssh.add_response(r#""sudo" "systemctl" "stop" "test-service.service""#, "", 0);
+        ssh.add_response(r#""sudo" "systemctl" "start" "test-service.service""#, "", 0);
+        ssh.add_response(r#""sudo" "systemctl" "start" "broken.service""#, "Failed to start broken.service", 1);
+        Box::new(ssh) as Connector
+    };
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    host_settings.effective.host_settings = vec![HostSetting::UseSudo];
+    host_settings.effective.connectors.insert(StubSsh2::get_metadata().module_spec.id.clone(), Default::default());
+    host_settings.effective.monitors.insert(
+        Load::get_metadata().module_spec.id.clone(),
+        configuration::MonitorConfig {
+            version: "0.0.1".to_string(),
+            ..Default::default()
+        },
+    );
+    for command_id in [systemd::service::Start::get_metadata().module_spec.id, systemd::service::Stop::get_metadata().module_spec.id] {
+        host_settings.effective.commands.insert(
+            command_id,
+            configuration::CommandConfig {
+                version: "0.0.1".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([(String::from("server1"), host_settings)]),
+        predefined_platforms: BTreeMap::from([(String::from("server1"), PlatformInfo::linux(Flavor::Debian, "12.0"))]),
+        ..Default::default()
+    };
+
+    let module_factory = ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![(Load::get_metadata(), Load::new_monitoring_module)],
+        vec![
+            (systemd::service::Start::get_metadata(), systemd::service::Start::new_command_module),
+            (systemd::service::Stop::get_metadata(), systemd::service::Stop::new_command_module),
+        ],
+    );
+
+    let runbooks = serde_yaml::from_str::<Runbooks>(TEST_RUNBOOKS).unwrap();
+    let host_ids = vec![String::from("server1")];
+    let mut core = lightkeeper::initialize_core(&get_default_main_config(), &hosts_config, Arc::new(module_factory)).unwrap();
+
+    let unknown_monitor = core.command_handler.run_runbook("unknown-monitor", &runbooks.runbooks["unknown-monitor"], "server1", &core.monitor_manager);
+    assert!(unknown_monitor.is_err());
+
+    let mut follower = cli::RunbookFollower::new();
+    let mut lines = Vec::new();
+    let result = cli::run_runbook(&mut core, &host_ids, "restart", &runbooks.runbooks["restart"], Duration::from_secs(20), |run| {
+        lines.extend(follower.new_lines(run));
+    });
+
+    core.command_handler.stop();
+    core.monitor_manager.stop();
+    core.host_manager.borrow_mut().stop();
+    core.connection_manager.stop();
+
+    let outcomes = result.unwrap();
+    let run = outcomes[0].run.as_ref().unwrap();
+    let step_states = run.steps.iter().map(|step| step.state).collect::<Vec<_>>();
+    assert_eq!(step_states, vec![StepState::Succeeded, StepState::Succeeded, StepState::Failed, StepState::Skipped]);
+    assert_eq!(run.steps[1].message, "load: Normal");
+    assert_eq!(run.rollback.len(), 1);
+    assert_eq!(run.rollback[0].name, "Stop service");
+    assert_eq!(run.rollback[0].state, StepState::Succeeded);
+    assert_eq!(run.state, RunState::RolledBack);
+    assert_eq!(cli::runbook_outcomes_exit_code(&outcomes), cli::EXIT_CRITICAL);
+    assert!(lines.contains(&String::from("Rollback \"Stop service\": succeeded")));
+}
@@ -0,0 +1,304 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::AtomicU64;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use lightkeeper::configuration;
+use lightkeeper::connection_manager::ConnectorRequest;
+use lightkeeper::enums::HostStatus;
+use lightkeeper::frontend::{HostDisplayData, UIUpdate};
+use lightkeeper::host_manager::HostManager;
+use lightkeeper::module::*;
+use lightkeeper::module::connection::{RequestResponse, ResponseMessage};
+use lightkeeper::module::connection::unix_socket::EXIT_CODE_CONNECT_FAILED;
+use lightkeeper::module::monitoring;
+use lightkeeper::module::monitoring::MonitoringModule;
+use lightkeeper::module::platform_info::*;
+use lightkeeper::monitor_manager::MonitorManager;
+use lightkeeper::monitor_manager::scheduler::Scheduler;
+use lightkeeper::HostSetting;
+
+use crate::{StubSsh2, TEST_HOST_ID};
+
+const KERNEL_OUTPUT: &str = "6.1.0-41-amd64 x86_64";
+const UPTIME_OUTPUT: &str = "10:00:00 up 5 days,  2:00,  1 user,  load average: 0.00, 0.00, 0.00";
+
+/// Answers connector requests itself instead of a connection manager, so tests decide when
+/// invocations finish. The scheduler is driven with explicit times.
+struct SchedulerTest {
+    host_manager: Rc<RefCell<HostManager>>,
+    monitor_manager: MonitorManager,
+    request_receiver: mpsc::Receiver<ConnectorRequest>,
+    ui_update_receiver: mpsc::Receiver<UIUpdate>,
+    scheduler: Scheduler,
+    start: Instant,
+}
+
+impl SchedulerTest {
+    fn new(hosts_config: configuration::Hosts) -> Self {
+        let host_manager = Rc::new(RefCell::new(HostManager::new()));
+        host_manager.borrow_mut().configure(&hosts_config);
+
+        let (ui_update_sender, ui_update_receiver) = mpsc::channel();
+        host_manager.borrow_mut().add_observer(ui_update_sender);
+
+        let mut monitor_manager = MonitorManager::new(
+            host_manager.clone(),
+            Arc::new(new_module_factory()),
+            Arc::new(AtomicU64::new(0)),
+        );
+        let (request_sender, request_receiver) = mpsc::channel();
+        monitor_manager.configure(&hosts_config, request_sender, host_manager.borrow().new_state_update_sender());
+
+        host_manager.borrow_mut().start_receiving_updates();
+        monitor_manager.start_processing_responses();
+
+        let start = Instant::now();
+        let scheduler = monitor_manager.new_scheduler(start);
+
+        SchedulerTest {
+            host_manager,
+            monitor_manager,
+            request_receiver,
+            ui_update_receiver,
+            scheduler,
+            start,
+        }
+    }
+
+    /// Runs the scheduler at `seconds` after start and returns the requests it sent.
+    fn tick(&mut self, seconds: u64) -> Vec<ConnectorRequest> {
+        let invocation_ids = self.scheduler.tick(self.start + Duration::from_secs(seconds)).unwrap();
+        let requests = self.request_receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(requests.len(), invocation_ids.len());
+        requests
+    }
+
+    /// Responds to requests and waits until the results have been processed.
+    fn respond(&self, requests: Vec<ConnectorRequest>, uptime_output: &str) -> HostDisplayData {
+        self.respond_with(requests, |request| {
+            let output = if request.source_id == uptime_id() { uptime_output } else { KERNEL_OUTPUT };
+            vec![ResponseMessage::new(output.to_string(), 0)]
+        })
+    }
+
+    /// Also responds to follow-up requests of extension monitors.
+    fn respond_with<F>(&self, requests: Vec<ConnectorRequest>, responses: F) -> HostDisplayData
+    where
+        F: Fn(&ConnectorRequest) -> Vec<ResponseMessage>,
+    {
+        let send_response = |request: ConnectorRequest| {
+            let response = RequestResponse::new(&request, responses(&request).into_iter().map(Ok).collect());
+            request.response_sender.send(response).unwrap();
+        };
+
+        let mut pending = requests.iter().map(|request| request.invocation_id).collect::<HashSet<_>>();
+        requests.into_iter().for_each(send_response);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            self.request_receiver.try_iter().for_each(send_response);
+
+            assert!(Instant::now() < deadline, "Timed out waiting for monitoring data");
+            let Ok(update) = self.ui_update_receiver.recv_timeout(Duration::from_millis(50)) else {
+                continue;
+            };
+
+            if let UIUpdate::Host(display_data) = update {
+                if let Some((invocation_id, _)) = display_data.new_monitoring_data.as_ref() {
+                    pending.remove(invocation_id);
+                }
+
+                if pending.is_empty() {
+                    return display_data;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SchedulerTest {
+    fn drop(&mut self) {
+        self.monitor_manager.stop();
+        self.host_manager.borrow_mut().stop();
+    }
+}
+
+fn docker_images_id() -> String {
+    monitoring::docker::Images::get_metadata().module_spec.id
+}
+
+fn kernel_id() -> String {
+    monitoring::linux::Kernel::get_metadata().module_spec.id
+}
+
+fn uptime_id() -> String {
+    monitoring::linux::Uptime::get_metadata().module_spec.id
+}
+
+fn source_ids(requests: &[ConnectorRequest]) -> Vec<String> {
+    requests.iter().map(|request| request.source_id.clone()).collect()
+}
+
+fn new_monitor_config(interval: Option<u64>, is_critical: Option<bool>) -> configuration::MonitorConfig {
+    configuration::MonitorConfig {
+        version: "0.0.1".to_string(),
+        interval,
+        is_critical,
+        ..Default::default()
+    }
+}
+
+fn new_hosts_config(monitors: Vec<(String, configuration::MonitorConfig)>) -> configuration::Hosts {
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "127.0.0.1".to_string();
+    host_settings.effective.host_settings = vec![HostSetting::UseSudo];
+    host_settings.effective.connectors.insert(
+        StubSsh2::get_metadata().module_spec.id.clone(),
+        configuration::ConnectorConfig::default(),
+    );
+    host_settings.effective.monitors.extend(monitors);
+
+    configuration::Hosts {
+        hosts: BTreeMap::from([(TEST_HOST_ID.to_string(), host_settings)]),
+        predefined_platforms: BTreeMap::from([(
+            TEST_HOST_ID.to_string(),
+            PlatformInfo::linux(Flavor::Debian, "12.0"),
+        )]),
+        ..Default::default()
+    }
+}
+
+fn new_module_factory() -> ModuleFactory {
+    let new_stub_ssh = |_settings: &HashMap<String, String>| StubSsh2::new("uname -r -m", KERNEL_OUTPUT, 0);
+
+    ModuleFactory::new_with(
+        vec![(StubSsh2::get_metadata(), new_stub_ssh)],
+        vec![
+            (monitoring::linux::Kernel::get_metadata(), monitoring::linux::Kernel::new_monitoring_module),
+            (monitoring::linux::Uptime::get_metadata(), monitoring::linux::Uptime::new_monitoring_module),
+            (monitoring::docker::Images::get_metadata(), monitoring::docker::Images::new_monitoring_module),
+            (monitoring::docker::ImageUpdates::get_metadata(), monitoring::docker::ImageUpdates::new_monitoring_module),
+        ],
+        vec![],
+    )
+}
+
+#[test]
+fn monitors_with_interval_are_refreshed_periodically() {
+    let mut test = SchedulerTest::new(new_hosts_config(vec![(kernel_id(), new_monitor_config(Some(10), None))]));
+
+    // Initial refresh is jittered by at most a tenth of the interval.
+    let requests = test.tick(2);
+    assert_eq!(source_ids(&requests), vec![kernel_id()]);
+    test.respond(requests, UPTIME_OUTPUT);
+
+    assert!(test.tick(5).is_empty(), "Monitor shouldn't be refreshed before the interval has passed");
+
+    let requests = test.tick(14);
+    assert_eq!(source_ids(&requests), vec![kernel_id()]);
+    test.respond(requests, UPTIME_OUTPUT);
+}
+
+#[test]
+fn monitors_without_interval_are_not_scheduled() {
+    let mut test = SchedulerTest::new(new_hosts_config(vec![(kernel_id(), new_monitor_config(None, None))]));
+
+    assert!(test.scheduler.is_empty());
+    assert!(test.tick(3600).is_empty());
+}
+
+#[test]
+fn pending_refresh_is_not_started_again() {
+    let mut test = SchedulerTest::new(new_hosts_config(vec![(kernel_id(), new_monitor_config(Some(10), None))]));
+
+    let requests = test.tick(2);
+    assert_eq!(requests.len(), 1);
+
+    // Due again, but the previous invocation hasn't received a response yet.
+    assert!(test.tick(13).is_empty());
+    assert!(test.tick(100).is_empty());
+
+    test.respond(requests, UPTIME_OUTPUT);
+    assert_eq!(source_ids(&test.tick(101)), vec![kernel_id()]);
+
+    // Invocations that never finish are given up on eventually.
+    assert!(test.tick(500).is_empty());
+    assert_eq!(source_ids(&test.tick(702)), vec![kernel_id()]);
+}
+
+#[test]
+fn refreshes_back_off_while_host_is_down() {
+    let mut test = SchedulerTest::new(new_hosts_config(vec![
+        (uptime_id(), new_monitor_config(Some(10), Some(true))),
+        (kernel_id(), new_monitor_config(Some(10), None)),
+    ]));
+
+    let requests = test.tick(2);
+    assert_eq!(requests.len(), 2);
+    let display_data = test.respond(requests, "garbage");
+    assert_eq!(display_data.host_state.status, HostStatus::Down);
+
+    // Next Kernel refresh is delayed to 2 intervals (+ jitter).
+    let requests = test.tick(14);
+    assert!(source_ids(&requests).contains(&kernel_id()));
+    test.respond(requests, "garbage");
+
+    // Critical monitors keep their interval.
+    let requests = test.tick(26);
+    assert_eq!(source_ids(&requests), vec![uptime_id()]);
+    test.respond(requests, "garbage");
+
+    // Then 4 intervals.
+    let requests = test.tick(36);
+    assert!(source_ids(&requests).contains(&kernel_id()));
+    test.respond(requests, "garbage");
+
+    // Host recovers.
+    let requests = test.tick(50);
+    assert_eq!(source_ids(&requests), vec![uptime_id()]);
+    let display_data = test.respond(requests, UPTIME_OUTPUT);
+    assert_ne!(display_data.host_state.status, HostStatus::Down);
+
+    let requests = test.tick(70);
+    assert!(!source_ids(&requests).contains(&kernel_id()));
+    test.respond(requests, UPTIME_OUTPUT);
+
+    // Normal interval is used again after the next refresh.
+    let requests = test.tick(78);
+    assert!(source_ids(&requests).contains(&kernel_id()));
+    test.respond(requests, UPTIME_OUTPUT);
+
+    let requests = test.tick(90);
+    assert!(source_ids(&requests).contains(&kernel_id()));
+    test.respond(requests, UPTIME_OUTPUT);
+}
+
+#[test]
+fn critical_base_monitor_with_extension_brings_host_down() {
+    let mut test = SchedulerTest::new(new_hosts_config(vec![
+        (docker_images_id(), new_monitor_config(Some(10), Some(true))),
+        (monitoring::docker::ImageUpdates::get_metadata().module_spec.id, new_monitor_config(None, None)),
+    ]));
+
+    let requests = test.tick(2);
+    assert_eq!(source_ids(&requests), vec![docker_images_id()]);
+
+    // Base monitor doesn't get its own data, the result is stored under the extension.
+    let display_data = test.respond_with(requests, |request| {
+        if request.source_id == docker_images_id() {
+            vec![ResponseMessage::new(String::new(), EXIT_CODE_CONNECT_FAILED)]
+        }
+        else {
+            Vec::new()
+        }
+    });
+    assert_eq!(display_data.host_state.status, HostStatus::Down);
+}
@@ -0,0 +1,404 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use lightkeeper::configuration::{self, get_default_main_config};
+use lightkeeper::enums::Criticality;
+use lightkeeper::module::connection::ssh::{self, ssh_config, Certificate, CertificateStatus};
+use lightkeeper::module::connection::ssh::sudo::SudoPassword;
+use lightkeeper::module::Module;
+use lightkeeper::module::monitoring::{DataPoint, MonitoringModule};
+use lightkeeper::module::monitoring::internal::platform_info_ssh;
+use lightkeeper::module::monitoring::network::TcpConnect;
+use lightkeeper::host_manager::HostManager;
+use lightkeeper::utils::ShellCommand;
+use lightkeeper::ModuleFactory;
+
+use crate::temp_path;
+
+fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+const ED25519_CERTIFICATE: &str = "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIJcHjPI4vMVE9c9NwDhVk6Wyu+mIRY5+0n3VxVbDIDnWAAAAICaaSxDmcrihx2IcqwTnPlaEprRlI89KokUEPiehkJTVAAAAAAAAACoAAAABAAAADmRlcGxveUBleGFtcGxlAAAAEwAAAAZkZXBsb3kAAAAFYWRtaW4AAAAAaVW5AAAAAABpfpeAAAAAAAAAAIIAAAAVcGVybWl0LVgxMS1mb3J3YXJkaW5nAAAAAAAAABdwZXJtaXQtYWdlbnQtZm9yd2FyZGluZwAAAAAAAAAWcGVybWl0LXBvcnQtZm9yd2FyZGluZwAAAAAAAAAKcGVybWl0LXB0eQAAAAAAAAAOcGVybWl0LXVzZXItcmMAAAAAAAAAAAAAADMAAAALc3NoLWVkMjU1MTkAAAAgdpgiuFOFtjKrcs44Ed/0wXnQ9l4UW1yeCWfx9zJzcR8AAABTAAAAC3NzaC1lZDI1NTE5AAAAQL9WDXr3Jfwi2/kQhGPzqyw3EQvPRz5Hsvx/lYEy1TGBBSP0goCXEp0ykG/rSoeCzvdtDHIc2UZdEZEDy/27kwY= user";
+const ECDSA_CERTIFICATE: &str = "ecdsa-sha2-nistp256-cert-v01@openssh.com AAAAKGVjZHNhLXNoYTItbmlzdHAyNTYtY2VydC12MDFAb3BlbnNzaC5jb20AAAAgx8xC+MROblPP+h+SNP0lxcvKmkCjUJvfNvxvikFhCDkAAAAIbmlzdHAyNTYAAABBBEkyxixEqcbHCoCQ7IxYzPMylWTymn1r8NWwj3MgTFDMUqJptuXUIpXOGupexGOa4xhEkdaSR1FvxW5X97tjycUAAAAAAAAAAAAAAAEAAAAJZWNkc2Eta2V5AAAACAAAAARyb290AAAAAAAAAAD//////////wAAAAAAAACCAAAAFXBlcm1pdC1YMTEtZm9yd2FyZGluZwAAAAAAAAAXcGVybWl0LWFnZW50LWZvcndhcmRpbmcAAAAAAAAAFnBlcm1pdC1wb3J0LWZvcndhcmRpbmcAAAAAAAAACnBlcm1pdC1wdHkAAAAAAAAADnBlcm1pdC11c2VyLXJjAAAAAAAAAAAAAAAzAAAAC3NzaC1lZDI1NTE5AAAAIHaYIrhThbYyq3LOOBHf9MF50PZeFFtcngln8fcyc3EfAAAAUwAAAAtzc2gtZWQyNTUxOQAAAECRAme2uO5Xauz7wbHi7o7iT3sLlrXn2OGOREhCnDxiXGnm16uoW1Cw5AVmOewnENenkV7a1ICX+2DfWTLGUJ8K user";
+
+#[test]
+fn parse_jump_hosts() {
+    let host_settings = settings(&[
+        ("jump_hosts", "bastion, admin@10.0.0.1:2222, [fe80::1]:2200"),
+        ("username", "deploy"),
+        ("password", "target-secret"),
+        ("private_key_path", "/keys/target"),
+        ("jump_host_2_private_key_path", "/keys/internal"),
+        ("jump_host_2_password", "hop-secret"),
+    ]);
+
+    let jump_hosts = ssh::parse_jump_hosts(&host_settings).unwrap();
+    assert_eq!(jump_hosts.len(), 3);
+
+    assert_eq!(jump_hosts[0].address, "bastion");
+    assert_eq!(jump_hosts[0].port, 22);
+    assert_eq!(jump_hosts[0].credentials.username, "deploy");
+    assert_eq!(jump_hosts[0].credentials.private_key_path.as_deref(), Some("/keys/target"));
+    // Password of the target is never used for jump hosts.
+    assert_eq!(jump_hosts[0].credentials.password, None);
+
+    assert_eq!(jump_hosts[1].address, "10.0.0.1");
+    assert_eq!(jump_hosts[1].port, 2222);
+    assert_eq!(jump_hosts[1].credentials.username, "admin");
+    assert_eq!(jump_hosts[1].credentials.private_key_path.as_deref(), Some("/keys/internal"));
+    assert_eq!(jump_hosts[1].credentials.password.as_deref(), Some("hop-secret"));
+
+    assert_eq!(jump_hosts[2].address, "fe80::1");
+    assert_eq!(jump_hosts[2].port, 2200);
+    assert_eq!(jump_hosts[2].to_proxy_jump(), "deploy@[fe80::1]:2200");
+
+    assert!(ssh::parse_jump_hosts(&HashMap::new()).unwrap().is_empty());
+    assert!(ssh::parse_jump_hosts(&settings(&[("jump_hosts", "bastion:ssh")])).is_err());
+    assert!(ssh::parse_jump_hosts(&settings(&[("jump_hosts", "user@:22")])).is_err());
+}
+
+#[test]
+fn terminal_uses_jump_hosts() {
+    let mut connector_config = configuration::ConnectorConfig::default();
+    connector_config.settings = settings(&[
+        ("username", "deploy"),
+        ("jump_hosts", "bastion1,admin@bastion2:2222"),
+        ("jump_host_2_private_key_path", "/keys/internal"),
+    ]);
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.address = "10.1.0.5".to_string();
+    host_settings.effective.connectors.insert(String::from("ssh"), connector_config);
+
+    let mut hosts_config = configuration::Hosts::default();
+    hosts_config.hosts.insert(String::from("internal"), host_settings);
+
+    let mut core = lightkeeper::initialize_core(&get_default_main_config(), &hosts_config, Arc::new(ModuleFactory::new())).unwrap();
+    let command = core.command_handler.open_remote_text_editor(&String::from("internal"), "/etc/hosts");
+
+    core.command_handler.stop();
+    core.monitor_manager.stop();
+    core.host_manager.borrow_mut().stop();
+    core.connection_manager.stop();
+
+    let arguments = command.to_vec();
+    let proxy_index = arguments.iter().position(|argument| argument.starts_with("ProxyCommand=")).unwrap();
+    assert_eq!(arguments[proxy_index - 1], "-o");
+    assert!(proxy_index < arguments.iter().position(|argument| argument == "10.1.0.5").unwrap());
+
+    // Connects through the last hop, which is reached through the earlier ones.
+    let proxy_command = &arguments[proxy_index];
+    assert!(proxy_command.ends_with(" -W '[%h]:%p' lightkeeper-jump-2"));
+    let config_path = proxy_command.split('\'').nth(1).unwrap();
+    let config = fs::read_to_string(config_path).unwrap();
+    assert!(config.contains("Host lightkeeper-jump-2\n    HostName bastion2\n    Port 2222\n    User \"admin\""));
+    assert!(config.contains("IdentityFile \"/keys/internal\""));
+    assert!(config.contains("ProxyJump lightkeeper-jump-1"));
+}
+
+#[test]
+fn jump_hosts_to_ssh_config() {
+    let host_settings = settings(&[
+        ("jump_hosts", "bastion,admin@[fe80::1]:2200"),
+        ("username", "deploy"),
+        ("private_key_path", "/keys/target"),
+        ("jump_host_2_private_key_path", "/keys/internal key"),
+        ("jump_host_2_certificate_path", "/keys/internal-cert.pub"),
+        ("custom_known_hosts_path", "/config/known_hosts"),
+        ("verify_host_key", "false"),
+    ]);
+
+    let jump_hosts = ssh::parse_jump_hosts(&host_settings).unwrap();
+    assert_eq!(
+        ssh::jump_hosts_to_ssh_config(&jump_hosts, &host_settings),
+        "Host lightkeeper-jump-1\n\
+         \x20   HostName bastion\n\
+         \x20   Port 22\n\
+         \x20   User \"deploy\"\n\
+         \x20   IdentitiesOnly yes\n\
+         \x20   UserKnownHostsFile \"/config/known_hosts\"\n\
+         \x20   IdentityFile \"/keys/target\"\n\
+         \x20   StrictHostKeyChecking no\n\
+         \n\
+         Host lightkeeper-jump-2\n\
+         \x20   HostName fe80::1\n\
+         \x20   Port 2200\n\
+         \x20   User \"admin\"\n\
+         \x20   IdentitiesOnly yes\n\
+         \x20   UserKnownHostsFile \"/config/known_hosts\"\n\
+         \x20   IdentityFile \"/keys/internal key\"\n\
+         \x20   CertificateFile \"/keys/internal-cert.pub\"\n\
+         \x20   StrictHostKeyChecking no\n\
+         \x20   ProxyJump lightkeeper-jump-1\n"
+    );
+}
+
+#[test]
+fn resolve_ssh_config() {
+    let dir = temp_path("ssh-config");
+    fs::create_dir_all(&dir).unwrap();
+    let key_path = dir.join("id_web");
+    fs::write(&key_path, "").unwrap();
+    fs::create_dir_all(dir.join("config.d")).unwrap();
+    fs::write(dir.join("config.d").join("bastion.conf"), "Host bastion\n  HostName 192.0.2.10\n  User jump\n  Port 2200\n").unwrap();
+    fs::write(dir.join("config"), format!(
+        "Include config.d/*.conf\n\
+         \n\
+         Host web? !web9\n\
+         \tHostName %h.example.com\n\
+         \tUser=deploy\n\
+         \tIdentityFile \"{}/id_%r_missing\"\n\
+         \tIdentityFile {}\n\
+         \tProxyJump bastion,admin@other:2022\n\
+         \n\
+         Match host web1 exec true\n\
+         \tUser ignored\n\
+         \n\
+         Host *\n\
+         \tUser fallback\n\
+         \tPort 2222\n\
+         \tIdentitiesOnly yes\n",
+        dir.to_string_lossy(), key_path.to_string_lossy(),
+    )).unwrap();
+
+    let config_path = dir.join("config");
+    let resolved = ssh_config::resolve(&config_path, "web1").unwrap();
+    assert_eq!(resolved.host_name.as_deref(), Some("web1.example.com"));
+    assert_eq!(resolved.user.as_deref(), Some("deploy"));
+    assert_eq!(resolved.port, Some(2222));
+    assert_eq!(resolved.identity_files, vec![
+        format!("{}/id_deploy_missing", dir.to_string_lossy()),
+        key_path.to_string_lossy().to_string(),
+    ]);
+    assert_eq!(resolved.identities_only, Some(true));
+
+    // Negated pattern.
+    let resolved = ssh_config::resolve(&config_path, "web9").unwrap();
+    assert_eq!(resolved.host_name.as_deref(), Some("web9"));
+    assert_eq!(resolved.user.as_deref(), Some("fallback"));
+
+    // Explicit settings take precedence.
+    let mut connector_settings = settings(&[
+        ("use_ssh_config", "true"),
+        ("ssh_config_path", &config_path.to_string_lossy()),
+        ("port", "22"),
+    ]);
+    ssh_config::apply("web1", false, &mut connector_settings);
+    assert_eq!(connector_settings["host_name"], "web1.example.com");
+    assert_eq!(connector_settings["username"], "deploy");
+    assert_eq!(connector_settings["port"], "22");
+    assert_eq!(connector_settings["private_key_path"], key_path.to_string_lossy());
+    assert_eq!(connector_settings["identities_only"], "true");
+    assert_eq!(connector_settings["jump_hosts"], "jump@192.0.2.10:2200,admin@other:2022");
+
+    let jump_hosts = ssh::parse_jump_hosts(&connector_settings).unwrap();
+    assert_eq!(jump_hosts[1].port, 2022);
+
+    // Host address is used instead of HostName.
+    let mut connector_settings = settings(&[("use_ssh_config", "true"), ("ssh_config_path", &config_path.to_string_lossy())]);
+    ssh_config::apply("web1", true, &mut connector_settings);
+    assert!(!connector_settings.contains_key("host_name"));
+
+    // Disabled by default.
+    let mut connector_settings = settings(&[("ssh_config_path", &config_path.to_string_lossy())]);
+    ssh_config::apply("web1", false, &mut connector_settings);
+    assert_eq!(connector_settings.len(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn alias_only_host_uses_ssh_config_address() {
+    let dir = temp_path("ssh-config-alias");
+    fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("config");
+    fs::write(&config_path, "Host web1\n  HostName 192.0.2.20\n  Port 2222\n").unwrap();
+
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.effective.connectors.insert(String::from("ssh"), configuration::ConnectorConfig {
+        settings: settings(&[("use_ssh_config", "true"), ("ssh_config_path", &config_path.to_string_lossy())]),
+    });
+    host_settings.effective.monitors.insert(String::from("tcp-connect"), configuration::MonitorConfig::default());
+    configuration::Configuration::apply_ssh_config("web1", &mut host_settings);
+
+    assert_eq!(host_settings.effective_address(), (String::from("192.0.2.20"), String::new()));
+    // Not stored in the host configuration itself.
+    assert!(configuration::HostSettings::is_default_address(&host_settings.address));
+
+    let hosts_config = configuration::Hosts {
+        hosts: BTreeMap::from([(String::from("web1"), host_settings.clone())]),
+        ..Default::default()
+    };
+    let mut host_manager = HostManager::new();
+    host_manager.configure(&hosts_config);
+    let host = host_manager.get_host("web1");
+
+    // Default critical monitor checks the SSH port of the ssh_config host.
+    let tcp_connect = TcpConnect::new(&host_settings.effective.monitors["tcp-connect"].settings);
+    assert_eq!(tcp_connect.get_connector_message(host, DataPoint::empty()).unwrap(), "192.0.2.20:2222");
+
+    // Host names are resolved later like FQDNs.
+    fs::write(&config_path, "Host web1\n  HostName web1.example.com\n").unwrap();
+    let mut host_settings = configuration::HostSettings::default();
+    host_settings.effective.connectors.insert(String::from("ssh"), configuration::ConnectorConfig {
+        settings: settings(&[("use_ssh_config", "true"), ("ssh_config_path", &config_path.to_string_lossy())]),
+    });
+    configuration::Configuration::apply_ssh_config("web1", &mut host_settings);
+    assert_eq!(host_settings.effective_address().1, "web1.example.com");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn parse_certificates() {
+    let certificate = Certificate::from_public_key_line(ED25519_CERTIFICATE).unwrap();
+    assert_eq!(certificate.key_type, "ssh-ed25519-cert-v01@openssh.com");
+    assert_eq!(certificate.serial, 42);
+    assert_eq!(certificate.key_id, "deploy@example");
+    assert_eq!(certificate.principals, vec![String::from("deploy"), String::from("admin")]);
+    assert_eq!(certificate.valid_after, "2026-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+    assert_eq!(certificate.valid_before, Some("2026-02-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()));
+    assert_eq!(certificate.lifetime(), Some(Duration::days(31)));
+
+    let certificate = Certificate::from_public_key_line(ECDSA_CERTIFICATE).unwrap();
+    assert_eq!(certificate.key_id, "ecdsa-key");
+    assert_eq!(certificate.principals, vec![String::from("root")]);
+    assert_eq!(certificate.valid_before, None);
+
+    // Plain public keys are not certificates.
+    assert!(Certificate::from_public_key_line("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHaYIrhThbYyq3LOOBHf9MF50PZeFFtcngln8fcyc3Ef ca").is_err());
+    assert!(Certificate::from_public_key_line(&ED25519_CERTIFICATE[..200]).is_err());
+}
+
+#[test]
+fn certificate_expiry_data_point() {
+    let valid_before = "2026-02-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+    let status = CertificateStatus {
+        key_id: String::from("deploy@example"),
+        source: String::from("ssh-agent (user)"),
+        valid_before: Some(valid_before),
+        warning_threshold: Duration::hours(24),
+    };
+
+    // Passed from the connector to the platform info monitor as connection details.
+    let details = status.to_details();
+    let status = CertificateStatus::from_details(&details).unwrap();
+    assert_eq!(status.valid_before, Some(valid_before));
+    assert_eq!(status.warning_threshold, Duration::hours(24));
+    assert!(CertificateStatus::from_details(&HashMap::new()).is_none());
+
+    let monitor = platform_info_ssh::PlatformInfoSsh::new(&HashMap::new());
+    let data_point = monitor.process_connection_details(&details, DataPoint::empty());
+    assert!(data_point.multivalue.iter().any(|data| data.label == "ssh_certificate"));
+    let data_point = monitor.process_connection_details(&HashMap::new(), DataPoint::empty());
+    assert!(data_point.multivalue.is_empty());
+
+    let data_point = platform_info_ssh::certificate_data_point(&status, valid_before - Duration::days(2));
+    assert_eq!(data_point.criticality, Criticality::Normal);
+    assert_eq!(data_point.value, "Expires 2026-02-01 00:00:00 UTC");
+    assert_eq!(data_point.description, "deploy@example (ssh-agent (user))");
+
+    let data_point = platform_info_ssh::certificate_data_point(&status, valid_before - Duration::hours(2));
+    assert_eq!(data_point.criticality, Criticality::Warning);
+
+    let data_point = platform_info_ssh::certificate_data_point(&status, valid_before + Duration::seconds(1));
+    assert_eq!(data_point.criticality, Criticality::Critical);
+}
+
+/// Runs the command locally with stderr merged to stdout, like the SSH channel does.
+struct LocalChannel {
+    child: process::Child,
+}
+
+impl LocalChannel {
+    fn new(command: &str, path: &Path, nopasswd: bool) -> Self {
+        let mut process = process::Command::new("sh");
+        process.arg("-c").arg(format!("exec 2>&1; {}", command))
+            .env("PATH", format!("{}:{}", path.display(), std::env::var("PATH").unwrap_or_default()))
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped());
+
+        if nopasswd {
+            process.env("NOPASSWD", "1");
+        }
+
+        LocalChannel { child: process.spawn().unwrap() }
+    }
+}
+
+impl Read for LocalChannel {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.child.stdout.as_mut().unwrap().read(buffer)
+    }
+}
+
+impl Write for LocalChannel {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.child.stdin.as_mut().unwrap().write(buffer)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.child.stdin.as_mut().unwrap().flush()
+    }
+}
+
+#[test]
+fn sudo_password_is_not_passed_to_command() {
+    let dir = temp_path("sudo-password");
+    fs::create_dir_all(&dir).unwrap();
+
+    // Prompts like `sudo -S -p` unless NOPASSWD is set.
+    let sudo_path = dir.join("sudo");
+    fs::write(&sudo_path, concat!(
+        "#!/bin/sh\n",
+        "[ \"$1 $2 $3\" = \"-S -k -p\" ] || exit 1\n",
+        "prompt=\"$4\"; shift 4\n",
+        "if [ -z \"$NOPASSWD\" ]; then\n",
+        "  printf '%s' \"$prompt\" >&2; read -r password\n",
+        "  [ \"$password\" = \"secret\" ] || { echo 'Sorry, try again.' >&2; printf '%s' \"$prompt\" >&2; exit 1; }\n",
+        "fi\n",
+        "exec \"$@\"\n",
+    )).unwrap();
+    fs::set_permissions(&sudo_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let payload = b"secret\nfirst line\n\x00\xff".to_vec();
+    let upload = |password: &str, nopasswd: bool| {
+        let target_path = dir.join("uploaded");
+        let _ = fs::remove_file(&target_path);
+
+        let sudo_password = SudoPassword::new(password);
+        let command = ShellCommand::new_from(vec!["tee", &target_path.to_string_lossy()]).use_sudo().to_string();
+        let mut channel = LocalChannel::new(&sudo_password.wrap_command(&command).unwrap(), &dir, nopasswd);
+
+        let result = sudo_password.authenticate(&mut channel).map(|_| {
+            channel.write_all(&payload).unwrap();
+            drop(channel.child.stdin.take());
+            channel.child.wait().unwrap();
+            fs::read(&target_path).unwrap()
+        });
+        let _ = channel.child.kill();
+        let _ = channel.child.wait();
+        result
+    };
+
+    assert_eq!(upload("secret", true).unwrap(), payload);
+    assert_eq!(upload("secret", false).unwrap(), payload);
+    assert!(upload("wrong", false).is_err());
+
+    // Commands without sudo are run as they are.
+    assert!(SudoPassword::new("secret").wrap_command("\"tee\" \"/tmp/file\"").is_none());
+
+    let _ = fs::remove_dir_all(&dir);
+}
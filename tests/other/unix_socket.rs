@@ -0,0 +1,94 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2026 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use lightkeeper::module::connection::unix_socket::HttpResponseDecoder;
+use lightkeeper::module::connection::UnixSocketRequest;
+use lightkeeper::utils::PrivilegeEscalation;
+
+#[test]
+fn request_serialization() {
+    let request = UnixSocketRequest::post("/var/run/docker.sock", "/containers/test/restart")
+        .use_sudo()
+        .escalate_with(PrivilegeEscalation::Doas)
+        .body("{\n  \"key\": \"value\"\n}");
+
+    // Part of connector requests, which are serializable.
+    let serialized = serde_json::to_string(&request).unwrap();
+    assert_eq!(serde_json::from_str::<UnixSocketRequest>(&serialized).unwrap(), request);
+
+    // Used in logs and audit records.
+    assert_eq!(request.to_string(), "POST /var/run/docker.sock /containers/test/restart");
+}
+
+#[test]
+fn curl_fallback_command() {
+    let request = UnixSocketRequest::delete("/var/run/docker.sock", "/images/nginx:latest").use_sudo().escalate_with(PrivilegeEscalation::Doas);
+    assert_eq!(
+        request.to_command().to_string(),
+        r#""doas" "-n" "curl" "-s" "-i" "--raw" "-N" "--unix-socket" "/var/run/docker.sock" "-X" "DELETE" "http://localhost/images/nginx:latest""#
+    );
+
+    let http = String::from_utf8(UnixSocketRequest::get("/run/podman/podman.sock", "/v4.0.0/libpod/images/json").to_http()).unwrap();
+    assert_eq!(http, "GET /v4.0.0/libpod/images/json HTTP/1.1\r\nHost: localhost\r\nUser-Agent: lightkeeper\r\nConnection: close\r\n\r\n");
+}
+
+#[test]
+fn decodes_content_length_response() {
+    let raw = b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 37\r\n\r\n{\"message\":\"No such container: test\"}";
+    let response = HttpResponseDecoder::decode_response(raw, false).unwrap();
+
+    assert_eq!(response.return_code, 404);
+    assert_eq!(response.message, r#"{"message":"No such container: test"}"#);
+
+    let empty = HttpResponseDecoder::decode_response(b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 204 No Content\r\n\r\n", false).unwrap();
+    assert_eq!(empty.return_code, 0);
+    assert_eq!(empty.message, "");
+
+    assert!(HttpResponseDecoder::decode_response(b"curl: (7) Failed to connect", false).is_err());
+}
+
+#[test]
+fn decodes_chunked_response_incrementally() {
+    let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\n\r\n";
+    let mut decoder = HttpResponseDecoder::new(false);
+
+    // Split at every possible position to make sure partial reads are handled.
+    let mut body = Vec::new();
+    for byte in raw.iter() {
+        body.extend(decoder.decode(&[*byte]).unwrap());
+    }
+
+    assert!(decoder.is_complete());
+    assert_eq!(decoder.return_code(), 0);
+    assert_eq!(String::from_utf8(body).unwrap(), "hello, world");
+}
+
+#[test]
+fn demultiplexes_stream_frames() {
+    let mut payload = Vec::new();
+    payload.extend([1, 0, 0, 0, 0, 0, 0, 6]);
+    payload.extend(b"line1\n");
+    payload.extend([2, 0, 0, 0, 0, 0, 0, 6]);
+    payload.extend(b"error\n");
+
+    let mut raw = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/vnd.docker.multiplexed-stream\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n",
+        payload.len()
+    ).into_bytes();
+    raw.extend(&payload);
+    raw.extend(b"\r\n0\r\n\r\n");
+
+    let mut decoder = HttpResponseDecoder::new(true);
+    let (first, second) = raw.split_at(raw.len() / 2 + 3);
+    let mut body = decoder.decode(first).unwrap();
+    body.extend(decoder.decode(second).unwrap());
+
+    assert!(decoder.is_complete());
+    assert_eq!(String::from_utf8(body).unwrap(), "line1\nerror\n");
+
+    // Containers with TTY don't multiplex the output.
+    let tty = HttpResponseDecoder::decode_response(b"HTTP/1.1 200 OK\r\n\r\nplain log line\n", true).unwrap();
+    assert_eq!(tty.message, "plain log line\n");
+}
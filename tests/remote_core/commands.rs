@@ -11,12 +11,15 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use lightkeeper::backend::{CommandBackend, ConfigBackend, RemoteCommandBackend, RemoteConfigBackend, RemoteCoreClient};
+use lightkeeper::command_handler::{BulkRequest, HostExecutionState};
 use lightkeeper::configuration::{self, get_default_main_config, Configuration, Groups};
 use lightkeeper::frontend::{HostDisplayData, UIUpdate};
 use lightkeeper::module::command::internal::custom_command::CustomCommand;
+use lightkeeper::module::command::internal::FileBrowserEdit;
 use lightkeeper::module::command::systemd;
 use lightkeeper::module::command::CommandModule;
 use lightkeeper::module::connection::Connector;
+use lightkeeper::module::monitoring::linux::Load;
 use lightkeeper::module::monitoring::os::Os;
 use lightkeeper::module::monitoring::systemd::service::Service;
 use lightkeeper::module::monitoring::MonitoringModule;
@@ -24,10 +27,12 @@ use lightkeeper::module::platform_info::{Flavor, PlatformInfo};
 use lightkeeper::module::MetadataSupport;
 use lightkeeper::remote_core::runtime::CoreRuntime;
 use lightkeeper::remote_core::server::run_remote_client_session;
+use lightkeeper::metrics::MetricsManager;
+use lightkeeper::remote_core::protocol::FILE_CHUNK_SIZE;
 use lightkeeper::HostSetting;
 use lightkeeper::ModuleFactory;
 
-use crate::{StubSsh2, StubTcp};
+use crate::{temp_path, StubSsh2, StubTcp};
 
 const TEST_HOST: &str = "test-host";
 
@@ -54,6 +59,7 @@ fn stub_ssh_factory() -> ModuleFactory {
     let new_stub_ssh = move |_settings: &HashMap<String, String>| -> Connector {
         let mut ssh = StubSsh2::default();
         ssh.add_response(BUSCTL_LIST_UNITS, BUSCTL_JSON, 0);
+        ssh.add_response("uptime", " 10:00:00 up 1 day,  1 user,  load average: 0.50, 0.40, 0.30", 0);
         ssh.add_response("_", STUB_SSH_FALLBACK, 0);
         Box::new(ssh) as Connector
     };
@@ -65,6 +71,7 @@ fn stub_ssh_factory() -> ModuleFactory {
         ],
         vec![
             (Os::get_metadata(), Os::new_monitoring_module),
+            (Load::get_metadata(), Load::new_monitoring_module),
             (Service::get_metadata(), Service::new_monitoring_module),
         ],
         vec![(
@@ -99,6 +106,14 @@ fn stub_hosts() -> configuration::Hosts {
             ..Default::default()
         },
     );
+    host_settings.overrides.monitors.insert(
+        Load::get_metadata().module_spec.id.clone(),
+        configuration::MonitorConfig {
+            version: "0.0.1".to_string(),
+            settings: HashMap::new(),
+            ..Default::default()
+        },
+    );
     host_settings.overrides.monitors.insert(
         Service::get_metadata().module_spec.id.clone(),
         configuration::MonitorConfig {
@@ -152,10 +167,18 @@ fn temp_config_dir_for_remote_core() -> (String, configuration::Configuration, c
 
 fn with_remote_core_session(
     client_body: impl FnOnce(RemoteCommandBackend, RemoteConfigBackend, mpsc::Receiver<UIUpdate>) + Send + 'static,
+) {
+    with_remote_core_session_setup(|_runtime| {}, client_body)
+}
+
+fn with_remote_core_session_setup(
+    setup: impl FnOnce(&mut CoreRuntime),
+    client_body: impl FnOnce(RemoteCommandBackend, RemoteConfigBackend, mpsc::Receiver<UIUpdate>) + Send + 'static,
 ) {
     let (config_dir, main_config, hosts) = temp_config_dir_for_remote_core();
     let factory = Arc::new(stub_ssh_factory());
     let mut runtime = CoreRuntime::new_with_module_factory(&main_config, &hosts, factory, config_dir).unwrap();
+    setup(&mut runtime);
 
     let (client_stream, server) = UnixStream::pair().unwrap();
     let session_active = Arc::new(Mutex::new(false));
@@ -253,6 +276,44 @@ fn remote_core_execute_command() {
     });
 }
 
+#[test]
+fn remote_core_execute_bulk() {
+    init_log();
+
+    let custom_id = CustomCommand::get_metadata().module_spec.id.clone();
+
+    with_remote_core_session(move |mut backend, _cfg, _ui_rx| {
+        let request = BulkRequest {
+            command_id: custom_id.clone(),
+            parameters: vec!["echo test-service".to_string()],
+            host_ids: vec![TEST_HOST.to_string()],
+            ..Default::default()
+        };
+
+        assert!(backend
+            .execute_bulk(BulkRequest { host_ids: vec!["no-such-host".to_string()], ..request.clone() })
+            .is_err());
+
+        let bulk_id = backend.execute_bulk(request).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(8);
+        let execution = loop {
+            let execution = backend.bulk_execution(bulk_id).unwrap().unwrap();
+            if execution.is_finished() || Instant::now() > deadline {
+                break execution;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        assert_eq!(execution.count(HostExecutionState::Succeeded), 1);
+        let result = execution.hosts[0].result.as_ref().unwrap();
+        assert!(result.message.contains("stub-line-from-fake-ssh"));
+        assert!(backend.cancel_bulk_execution(bulk_id).unwrap());
+        assert!(backend.bulk_execution(bulk_id + 1).unwrap().is_none());
+
+        backend.stop();
+    });
+}
+
 #[test]
 fn remote_core_all_host_categories() {
     init_log();
@@ -339,22 +400,38 @@ fn remote_core_download_editable_file() {
 fn remote_core_write_cache_and_upload() {
     init_log();
 
-    let systemd_start_id = systemd::service::Start::get_metadata().module_spec.id.clone();
+    let edit_id = FileBrowserEdit::get_metadata().module_spec.id.clone();
 
-    with_remote_core_session(move |mut backend, _cfg, ui_rx| {
-        backend.download_editable_file(TEST_HOST, &systemd_start_id, "test-service").unwrap();
+    let (path_tx, path_rx) = mpsc::channel();
+    let setup = move |runtime: &mut CoreRuntime| {
+        path_tx.send(runtime.core.command_handler.cache_file_path_for_remote(TEST_HOST, "test-service")).unwrap();
+    };
+
+    with_remote_core_session_setup(setup, move |mut backend, _cfg, ui_rx| {
+        backend.download_editable_file(TEST_HOST, &edit_id, "test-service").unwrap();
 
         recv_host_until(&ui_rx, TEST_HOST, |d| {
             d.host_state
                 .command_results
-                .get(&systemd_start_id)
+                .get(&edit_id)
                 .is_some_and(|result| result.progress == 100)
         });
 
-        backend.write_cached_file(TEST_HOST, "test-service", b"new-bytes".to_vec()).unwrap();
-        let invocation_id = backend.upload_file_from_cache(TEST_HOST, &systemd_start_id, "test-service").unwrap();
+        // Spans several chunks.
+        let contents = (0..FILE_CHUNK_SIZE * 2 + 100).map(|index| (index % 251) as u8).collect::<Vec<_>>();
+        backend.write_cached_file(TEST_HOST, "test-service", contents.clone()).unwrap();
+
+        let cache_path = path_rx.recv().unwrap();
+        assert!(std::fs::read(&cache_path).unwrap() == contents);
+        assert!(!backend.has_cached_file_changed(TEST_HOST, "test-service", b"stub-line-from-fake-ssh-connector-output\n").unwrap());
+
+        let invocation_id = backend.upload_file_from_cache(TEST_HOST, &edit_id, "test-service").unwrap();
         assert!(invocation_id > 0);
 
+        backend.remove_cached_file(TEST_HOST, "test-service").unwrap();
+        // File has to be downloaded before it can be written.
+        assert!(backend.write_cached_file(TEST_HOST, "test-service", b"new-bytes".to_vec()).is_err());
+
         backend.stop();
     });
 }
@@ -390,3 +467,131 @@ fn remote_core_get_update_config() {
         backend.stop();
     });
 }
+
+#[test]
+fn remote_core_create_and_cancel_silence() {
+    init_log();
+
+    let os_id = Os::get_metadata().module_spec.id.clone();
+
+    with_remote_core_session(move |mut backend, _cfg, ui_rx| {
+        let silence_id = backend
+            .create_silence(TEST_HOST, Some(os_id.clone()), None, 3600, "Patching".to_string())
+            .unwrap();
+        assert!(silence_id > 0);
+
+        let display = recv_host_until(&ui_rx, TEST_HOST, |d| !d.host_state.silences.is_empty());
+        assert!(display.host_state.is_silenced(&os_id, ""));
+        assert!(!display.host_state.is_silenced("other-monitor", ""));
+        assert_eq!(display.host_state.silences[0].comment, "Patching");
+
+        assert!(backend.create_silence("no-such-host", None, None, 60, String::new()).is_err());
+
+        assert!(backend.cancel_silence(silence_id).unwrap());
+        recv_host_until(&ui_rx, TEST_HOST, |d| d.host_state.silences.is_empty());
+        assert!(!backend.cancel_silence(silence_id).unwrap());
+
+        backend.stop();
+    });
+}
+
+#[test]
+fn remote_core_records_and_queries_metrics() {
+    init_log();
+
+    let load_id = Load::get_metadata().module_spec.id.clone();
+    let metrics_dir = temp_path("core-metrics");
+    let setup_dir = metrics_dir.clone();
+
+    with_remote_core_session_setup(
+        move |runtime| runtime.start_metrics(setup_dir).unwrap(),
+        move |mut backend, _cfg, ui_rx| {
+            backend.refresh_monitors_of_category(TEST_HOST, "host").unwrap();
+            recv_host_until(&ui_rx, TEST_HOST, |d| {
+                d.new_monitoring_data.as_ref().is_some_and(|(_, data)| data.monitor_id == load_id)
+            });
+
+            let now = chrono::Utc::now().timestamp();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let metrics = loop {
+                let metrics = backend.query_metrics(TEST_HOST, &load_id, now - 60, now + 60).unwrap();
+                if !metrics.is_empty() || Instant::now() > deadline {
+                    break metrics;
+                }
+                thread::sleep(Duration::from_millis(100));
+            };
+
+            assert_eq!(metrics[""][0].value, 0.5);
+
+            backend.stop();
+        },
+    );
+
+    let _ = std::fs::remove_dir_all(&metrics_dir);
+}
+
+#[test]
+fn remote_core_charts_use_core_metrics() {
+    init_log();
+
+    let load_id = Load::get_metadata().module_spec.id.clone();
+    let metrics_dir = temp_path("core-charts");
+    let setup_dir = metrics_dir.clone();
+
+    with_remote_core_session_setup(
+        move |runtime| runtime.start_metrics(setup_dir).unwrap(),
+        move |mut backend, _cfg, ui_rx| {
+            backend.refresh_monitors_of_category(TEST_HOST, "host").unwrap();
+            recv_host_until(&ui_rx, TEST_HOST, |d| {
+                d.new_monitoring_data.as_ref().is_some_and(|(_, data)| data.monitor_id == load_id)
+            });
+
+            // Desktop's own store is not started, so data can only come from the core.
+            let (chart_tx, chart_rx) = mpsc::channel();
+            let mut metrics_manager = MetricsManager::new(chart_tx);
+
+            let now = chrono::Utc::now().timestamp();
+            let deadline = Instant::now() + Duration::from_secs(5);
+            let response = loop {
+                let invocation_id = metrics_manager.get_backend_metrics(&backend, TEST_HOST, &load_id, now - 60, now + 60).unwrap();
+                let response = match chart_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                    UIUpdate::Chart(response) => response,
+                    _ => panic!("Expected chart data"),
+                };
+                assert_eq!(response.request_id, invocation_id);
+
+                if !response.metrics.is_empty() || Instant::now() > deadline {
+                    break response;
+                }
+                thread::sleep(Duration::from_millis(100));
+            };
+
+            assert!(response.errors.is_empty());
+            assert_eq!(response.metrics[""][0].value, 0.5);
+
+            backend.stop();
+        },
+    );
+
+    let _ = std::fs::remove_dir_all(&metrics_dir);
+}
+
+#[test]
+fn remote_core_metrics_disabled() {
+    init_log();
+
+    with_remote_core_session(move |mut backend, _cfg, _ui_rx| {
+        assert!(backend.query_metrics(TEST_HOST, "load", 0, 1).is_err());
+
+        // Core's error is shown instead of falling back to the desktop's store.
+        let (chart_tx, chart_rx) = mpsc::channel();
+        let mut metrics_manager = MetricsManager::new(chart_tx);
+        metrics_manager.get_backend_metrics(&backend, TEST_HOST, "load", 0, 1).unwrap();
+        match chart_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            UIUpdate::Chart(response) => assert!(!response.errors.is_empty()),
+            _ => panic!("Expected chart data"),
+        }
+
+        backend.stop();
+    });
+}
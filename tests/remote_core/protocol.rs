@@ -3,8 +3,9 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use lightkeeper::audit_log::{AuditQuery, AuditRequestType};
 use lightkeeper::remote_core::protocol::{
-    read_message, write_message, ClientMessage, ServerMessage, PROTOCOL_VERSION,
+    read_message, write_message, ClientMessage, ServerMessage, MAX_FRAME_SIZE, PROTOCOL_VERSION,
 };
 
 #[test]
@@ -88,3 +89,73 @@ fn write_cached_file_result_server_roundtrip() {
         _ => panic!("Invalid message"),
     }
 }
+
+#[test]
+fn query_audit_log_roundtrip() {
+    let mut buffer = Vec::new();
+    let message = ClientMessage::QueryAuditLog {
+        request_id: 12,
+        query: AuditQuery {
+            host: Some("h1".to_string()),
+            request_type: Some(AuditRequestType::Command),
+            limit: 50,
+            ..Default::default()
+        },
+    };
+
+    write_message(&mut buffer, &message).unwrap();
+
+    let decoded: ClientMessage = read_message(&mut buffer.as_slice()).unwrap();
+    match decoded {
+        ClientMessage::QueryAuditLog { request_id, query } => {
+            assert_eq!(request_id, 12);
+            assert_eq!(query.host.as_deref(), Some("h1"));
+            assert_eq!(query.request_type, Some(AuditRequestType::Command));
+            assert_eq!(query.start_time, None);
+            assert_eq!(query.limit, 50);
+        }
+        _ => panic!("Invalid message"),
+    }
+}
+
+#[test]
+fn large_message_is_split_into_frames() {
+    let mut buffer = Vec::new();
+    let contents = (0..MAX_FRAME_SIZE * 2 + 100).map(|index| (index % 251) as u8).collect::<Vec<_>>();
+    let message = ClientMessage::WriteCachedFileChunk {
+        request_id: 13,
+        host_id: "h1".to_string(),
+        remote_file_path: "/var/log/large.log".to_string(),
+        offset: 0,
+        chunk: contents.clone(),
+        is_final: true,
+    };
+
+    write_message(&mut buffer, &message).unwrap();
+
+    // Continuation bit is set for all but the last frame.
+    let first_header = u32::from_be_bytes(buffer[..4].try_into().unwrap());
+    assert_eq!(first_header, (1 << 31) | MAX_FRAME_SIZE as u32);
+    let second_header_start = 4 + MAX_FRAME_SIZE;
+    let second_header = u32::from_be_bytes(buffer[second_header_start..second_header_start + 4].try_into().unwrap());
+    assert_eq!(second_header, (1 << 31) | MAX_FRAME_SIZE as u32);
+
+    let decoded: ClientMessage = read_message(&mut buffer.as_slice()).unwrap();
+    match decoded {
+        ClientMessage::WriteCachedFileChunk { request_id, chunk: decoded_contents, .. } => {
+            assert_eq!(request_id, 13);
+            assert!(decoded_contents == contents);
+        }
+        _ => panic!("Invalid message"),
+    }
+}
+
+#[test]
+fn oversized_frame_is_rejected() {
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&(MAX_FRAME_SIZE as u32 + 1).to_be_bytes());
+    buffer.extend_from_slice(&[0_u8; 8]);
+
+    let result = read_message::<ClientMessage, _>(&mut buffer.as_slice());
+    assert!(result.is_err());
+}